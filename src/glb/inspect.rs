@@ -1,8 +1,15 @@
 use std::path::PathBuf;
 
+use crate::mesh::format::{self, MeshFormat};
+use crate::mesh::gltf_compression;
+
 /// Inspect GLB/glTF file structure
-pub fn inspect_glb(path: &PathBuf, as_json: bool) -> Result<(), Box<dyn std::error::Error>> {
-    let (document, _buffers, _images) = gltf::import(path)?;
+pub fn inspect_glb(path: &PathBuf, format: Option<MeshFormat>, as_json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let detected = format::detect(path, format)?;
+    if detected != MeshFormat::Gltf {
+        return Err(format!("inspect-glb reads glTF/GLB files, not {}", detected.name()).into());
+    }
+    let (document, _buffers, _images) = gltf_compression::import(path)?;
 
     if as_json {
         // JSON output
@@ -105,6 +112,16 @@ fn print_node_tree(node: &gltf::Node, prefix: &str, is_last: bool) {
             mesh.name().unwrap_or("<unnamed>"),
             mesh.primitives().count()
         );
+        for primitive in mesh.primitives() {
+            if let Some(compression) = gltf_compression::primitive_compression(&primitive) {
+                println!(
+                    "{}  Primitive {}: {}",
+                    prop_prefix,
+                    primitive.index(),
+                    compression
+                );
+            }
+        }
     }
 
     // Recursively print children
@@ -185,6 +202,12 @@ fn build_node_json(node: &gltf::Node) -> Result<serde_json::Value, Box<dyn std::
             serde_json::json!({
                 "name": mesh.name().unwrap_or("<unnamed>"),
                 "primitive_count": mesh.primitives().count(),
+                "primitives": mesh
+                    .primitives()
+                    .map(|p| serde_json::json!({
+                        "compression": gltf_compression::primitive_compression(&p),
+                    }))
+                    .collect::<Vec<_>>(),
             }),
         );
     }
@@ -1,5 +1,5 @@
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 mod glb;
 mod mesh;
@@ -7,6 +7,8 @@ mod remote;
 mod rpc;
 mod viewer;
 
+use mesh::stdio::progress;
+
 #[derive(Parser)]
 #[command(name = "msh")]
 #[command(about = "A CLI tool for 3D mesh processing", long_about = None)]
@@ -20,17 +22,25 @@ struct Cli {
 enum RemeshCommands {
     /// Incremental remeshing (edge-based operations)
     Incremental {
-        /// Input mesh file (.obj or .glb)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         input: PathBuf,
 
-        /// Output mesh file (.obj)
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
         #[arg(short, long)]
         out: PathBuf,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
 
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
         /// Number of remeshing iterations (default: 10)
         #[arg(short, long, default_value_t = 10)]
         iterations: u32,
@@ -38,21 +48,41 @@ enum RemeshCommands {
         /// Target edge length for remeshing (default: 0.01)
         #[arg(short, long, default_value_t = 0.01)]
         target_edge_length: f32,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
     },
 
     /// Voxel-based remeshing (converts to SDF then remeshes)
     Voxel {
-        /// Input mesh file (.obj or .glb)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         input: PathBuf,
 
-        /// Output mesh file (.obj)
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
         #[arg(short, long)]
         out: PathBuf,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
 
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
         /// Voxel size (controls output resolution, default: 0.01)
         #[arg(short, long, default_value_t = 0.01)]
         size: f32,
@@ -60,6 +90,18 @@ enum RemeshCommands {
         /// Meshing method
         #[arg(short = 'M', long, default_value = "manifold")]
         method: VoxelMethodArg,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
     },
 }
 
@@ -80,22 +122,185 @@ impl From<VoxelMethodArg> for mesh::VoxelMethod {
     }
 }
 
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum SmoothMethodArg {
+    /// Uniform Laplacian: every neighbour counts the same
+    Uniform,
+    /// Cotangent-weighted Laplacian, less sensitive to uneven triangles
+    Cotangent,
+    /// Taubin lambda/mu smoothing, which doesn't shrink the mesh
+    Taubin,
+}
+
+impl From<SmoothMethodArg> for mesh::SmoothMethod {
+    fn from(arg: SmoothMethodArg) -> Self {
+        match arg {
+            SmoothMethodArg::Uniform => mesh::SmoothMethod::Uniform,
+            SmoothMethodArg::Cotangent => mesh::SmoothMethod::Cotangent,
+            SmoothMethodArg::Taubin => mesh::SmoothMethod::Taubin,
+        }
+    }
+}
+
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum SchemeArg {
+    /// Loop subdivision: smooth limit surface
+    Loop,
+    /// Split at edge midpoints without changing the shape
+    Midpoint,
+}
+
+impl From<SchemeArg> for mesh::SubdivisionScheme {
+    fn from(arg: SchemeArg) -> Self {
+        match arg {
+            SchemeArg::Loop => mesh::SubdivisionScheme::Loop,
+            SchemeArg::Midpoint => mesh::SubdivisionScheme::Midpoint,
+        }
+    }
+}
+
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum FixMethodArg {
+    /// Patch each hole on its own, keeping the rest of the mesh as it is
+    Local,
+    /// Voxel-remesh the whole mesh (closes anything, but loses UVs, colors and sharp features)
+    Voxel,
+}
+
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum TriangulationArg {
+    /// Smallest total patch area
+    MinArea,
+    /// Smallest largest dihedral angle, then area
+    MinDihedral,
+}
+
+impl From<TriangulationArg> for mesh::HoleTriangulation {
+    fn from(arg: TriangulationArg) -> Self {
+        match arg {
+            TriangulationArg::MinArea => mesh::HoleTriangulation::MinArea,
+            TriangulationArg::MinDihedral => mesh::HoleTriangulation::MinDihedral,
+        }
+    }
+}
+
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum UnitArg {
+    Micron,
+    Millimeter,
+    Centimeter,
+    Inch,
+    Foot,
+    Meter,
+}
+
+impl From<UnitArg> for mesh::ModelUnit {
+    fn from(arg: UnitArg) -> Self {
+        match arg {
+            UnitArg::Micron => mesh::ModelUnit::Micron,
+            UnitArg::Millimeter => mesh::ModelUnit::Millimeter,
+            UnitArg::Centimeter => mesh::ModelUnit::Centimeter,
+            UnitArg::Inch => mesh::ModelUnit::Inch,
+            UnitArg::Foot => mesh::ModelUnit::Foot,
+            UnitArg::Meter => mesh::ModelUnit::Meter,
+        }
+    }
+}
+
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum FormatArg {
+    Obj,
+    Gltf,
+    Glb,
+    #[value(name = "3mf")]
+    ThreeMf,
+    Fbx,
+    Dae,
+    Usda,
+    Stl,
+    Ply,
+    Bvh,
+}
+
+impl From<FormatArg> for mesh::MeshFormat {
+    fn from(arg: FormatArg) -> Self {
+        match arg {
+            FormatArg::Obj => mesh::MeshFormat::Obj,
+            FormatArg::Gltf | FormatArg::Glb => mesh::MeshFormat::Gltf,
+            FormatArg::ThreeMf => mesh::MeshFormat::ThreeMf,
+            FormatArg::Fbx => mesh::MeshFormat::Fbx,
+            FormatArg::Dae => mesh::MeshFormat::Collada,
+            FormatArg::Usda => mesh::MeshFormat::Usda,
+            FormatArg::Stl => mesh::MeshFormat::Stl,
+            FormatArg::Ply => mesh::MeshFormat::Ply,
+            FormatArg::Bvh => mesh::MeshFormat::Bvh,
+        }
+    }
+}
+
+impl FormatArg {
+    fn extension(&self) -> &'static str {
+        match self {
+            FormatArg::Obj => "obj",
+            FormatArg::Gltf => "gltf",
+            FormatArg::Glb => "glb",
+            FormatArg::ThreeMf => "3mf",
+            FormatArg::Fbx => "fbx",
+            FormatArg::Dae => "dae",
+            FormatArg::Usda => "usda",
+            FormatArg::Stl => "stl",
+            FormatArg::Ply => "ply",
+            FormatArg::Bvh => "bvh",
+        }
+    }
+}
+
+/// Build write options; writing to stdout (-) moves progress output to stderr
+/// and needs a format, which defaults to the input's
+fn write_options(
+    input: &Path,
+    out: &Path,
+    load_options: &mesh::LoadOptions,
+    out_format: Option<FormatArg>,
+    ascii: bool,
+    unit: UnitArg,
+) -> mesh::WriteOptions {
+    let format = if mesh::stdio::is_std(out) {
+        mesh::stdio::reserve_stdout();
+        out_format.map(|f| f.extension().to_string()).or_else(|| {
+            let input_format = mesh::format::detect(input, load_options.format).ok()?;
+            input_format.output_extension().map(String::from)
+        })
+    } else {
+        None
+    };
+    mesh::WriteOptions { ascii, unit: unit.into(), format }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Remesh a mesh file (fixes then incrementally remeshes, or use subcommands for specific methods)
     Remesh {
-        /// Input mesh file (.obj or .glb)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         #[arg(required_unless_present = "command")]
         input: Option<PathBuf>,
 
-        /// Output mesh file (.obj)
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
         #[arg(short, long, required_unless_present = "command")]
         out: Option<PathBuf>,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
 
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
         /// Number of incremental remeshing iterations (default: 10)
         #[arg(short, long, default_value_t = 10)]
         iterations: u32,
@@ -116,35 +321,75 @@ enum Commands {
         #[arg(long, default_value_t = false)]
         no_fix: bool,
 
+        /// Keep only the N largest connected components
+        #[arg(long, value_name = "N")]
+        keep_largest: Option<usize>,
+
+        /// Remove connected components with fewer faces
+        #[arg(long)]
+        min_faces: Option<usize>,
+
+        /// Remove connected components enclosing less volume
+        #[arg(long)]
+        min_volume: Option<f32>,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+
         #[command(subcommand)]
         command: Option<RemeshCommands>,
     },
 
     /// Display mesh statistics
     Stats {
-        /// Input mesh file (.obj or .glb)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         input: PathBuf,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
     },
 
     /// View mesh in a 3D viewer
     View {
-        /// Input mesh file (.obj or .glb) - optional when using --remote
+        /// Input mesh file in any supported format, detected from its contents (see --format) - optional when using --remote
         #[cfg(feature = "remote")]
         #[arg(required_unless_present = "remote")]
         input: Option<PathBuf>,
 
-        /// Input mesh file (.obj or .glb)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         #[cfg(not(feature = "remote"))]
         input: PathBuf,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
 
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
         /// Enable remote control via JSON-RPC (requires 'remote' feature)
         #[cfg(feature = "remote")]
         #[arg(long)]
@@ -209,22 +454,34 @@ enum Commands {
         /// Skip auto-centering the mesh at the origin
         #[arg(long)]
         no_center: bool,
+
+        /// Paint self-intersecting faces red
+        #[arg(long)]
+        highlight_intersections: bool,
     },
 
     /// Render mesh to PNG without opening a window
     #[command(allow_negative_numbers = true, allow_hyphen_values = true)]
     Render {
-        /// Input mesh file (.obj, .glb, or .3mf)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         input: PathBuf,
 
         /// Output PNG file
         #[arg(short, long)]
         out: PathBuf,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
 
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
         /// Image width in pixels (default: 800)
         #[arg(long, default_value_t = 800)]
         width: u32,
@@ -316,45 +573,459 @@ enum Commands {
 
     /// Check if mesh is manifold (watertight)
     Check {
-        /// Input mesh file (.obj or .glb)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         input: PathBuf,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Also list pairs of faces that intersect each other
+        #[arg(long)]
+        intersections: bool,
     },
 
     /// Fix holes in mesh automatically
     Fix {
-        /// Input mesh file (.obj or .glb)
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
         input: PathBuf,
 
-        /// Output mesh file (.obj)
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
         #[arg(short, long)]
         out: PathBuf,
 
-        /// Mesh name (required if GLB contains multiple meshes)
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
         #[arg(short, long)]
         mesh: Option<String>,
 
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// How to close holes
+        #[arg(long, value_enum, default_value = "voxel")]
+        method: FixMethodArg,
+
         /// Voxel size for remeshing (default: 0.01)
         #[arg(short, long, default_value_t = 0.01)]
         voxel_size: f32,
 
-        /// Merge vertices closer than this distance before fixing (default: 0.0001)
+        /// What local hole triangulation minimises
+        #[arg(long, value_enum, default_value = "min-dihedral")]
+        triangulation: TriangulationArg,
+
+        /// Split local patches down to the density of the surrounding mesh
+        #[arg(long)]
+        refine: bool,
+
+        /// Refine local patches and blend them into the surrounding curvature
+        #[arg(long)]
+        fair: bool,
+
+        /// Leave holes with more boundary edges than this open (local method)
+        #[arg(long, default_value_t = 250)]
+        max_hole_edges: usize,
+
+        /// Also cap the outer border of open surfaces (local method)
+        #[arg(long)]
+        fill_border: bool,
+
+        /// Merge vertices closer than this distance before fixing (voxel method; default: 0.0001)
+        #[arg(short, long, default_value_t = 0.0001)]
+        tolerance: f32,
+
+        /// Skip vertex merging step (voxel method)
+        #[arg(long, default_value_t = false)]
+        no_merge: bool,
+
+        /// Keep only the N largest connected components
+        #[arg(long, value_name = "N")]
+        keep_largest: Option<usize>,
+
+        /// Remove connected components with fewer faces
+        #[arg(long)]
+        min_faces: Option<usize>,
+
+        /// Remove connected components enclosing less volume
+        #[arg(long)]
+        min_volume: Option<f32>,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+    },
+
+    /// Convert a mesh to another format, keeping the attributes both formats support
+    Convert {
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
+        input: PathBuf,
+
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
+        out: PathBuf,
+
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
+        #[arg(short, long)]
+        mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Treat input as Z-up and convert to Y-up (for OpenSCAD, CAD tools)
+        #[arg(long)]
+        z_up: bool,
+
+        /// Uniform scale applied to the geometry, skeleton and animations
+        #[arg(long)]
+        scale: Option<f32>,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+    },
+
+    /// Subdivide a triangle mesh, interpolating UVs and colors
+    Subdivide {
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
+        input: PathBuf,
+
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
+        #[arg(short, long)]
+        mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Subdivision scheme
+        #[arg(long, value_enum, default_value = "loop")]
+        scheme: SchemeArg,
+
+        /// Number of levels; each multiplies the face count by four
+        #[arg(short, long, default_value_t = 1)]
+        levels: usize,
+
+        /// Keep edges sharper than this dihedral angle (degrees) as creases
+        #[arg(long)]
+        crease_angle: Option<f32>,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+    },
+
+    /// List connected components, remove small islands or split them into files
+    Components {
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
+        input: PathBuf,
+
+        /// Write the components that pass the filters to one mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+
+        /// Write each kept component to its own file; {} is replaced by the component number
+        #[arg(long, value_name = "PATTERN")]
+        split: Option<String>,
+
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
+        #[arg(short, long)]
+        mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Keep only the N largest connected components
+        #[arg(long, value_name = "N")]
+        keep_largest: Option<usize>,
+
+        /// Remove connected components with fewer faces
+        #[arg(long)]
+        min_faces: Option<usize>,
+
+        /// Remove connected components enclosing less volume
+        #[arg(long)]
+        min_volume: Option<f32>,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+    },
+
+    /// Make face winding consistent and turn normals outward
+    Orient {
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
+        input: PathBuf,
+
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
+        #[arg(short, long)]
+        mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Only make winding consistent; don't turn components outward
+        #[arg(long)]
+        keep_direction: bool,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+    },
+
+    /// Reduce a mesh to a face budget by quadric edge collapse
+    Decimate {
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
+        input: PathBuf,
+
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
+        #[arg(short, long)]
+        mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Number of faces to reduce the mesh to
+        #[arg(long, required_unless_present = "ratio", conflicts_with = "ratio")]
+        target_faces: Option<usize>,
+
+        /// Fraction of the input's faces to keep, in (0, 1] (e.g. 0.25)
+        #[arg(long)]
+        ratio: Option<f32>,
+
+        /// Keep boundary vertices in place
+        #[arg(long)]
+        lock_boundary: bool,
+
+        /// Keep UV seams intact (seam vertices stay in place with their UVs)
+        #[arg(long)]
+        preserve_seams: bool,
+
+        /// Keep borders between face colors intact
+        #[arg(long)]
+        preserve_color_borders: bool,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+    },
+
+    /// Smooth a noisy mesh with Laplacian or Taubin smoothing
+    Smooth {
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
+        input: PathBuf,
+
+        /// Output mesh file (.obj, .stl, .ply, .glb, .gltf, .3mf or .usda), or - for stdout
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
+        #[arg(short, long)]
+        mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Smoothing method
+        #[arg(long, value_enum, default_value = "taubin")]
+        method: SmoothMethodArg,
+
+        /// Number of smoothing iterations (a lambda/mu pair each for Taubin)
+        #[arg(short, long, default_value_t = 10)]
+        iterations: usize,
+
+        /// Step towards the neighbour average, in (0, 1]
+        #[arg(long, default_value_t = 0.5)]
+        lambda: f32,
+
+        /// Taubin's inflating step, between -1 and -lambda
+        #[arg(long, default_value_t = -0.53, allow_negative_numbers = true)]
+        mu: f32,
+
+        /// Keep boundary vertices in place instead of sliding them along the boundary
+        #[arg(long)]
+        lock_boundary: bool,
+
+        /// Keep edges sharper than this dihedral angle (degrees) from being smoothed away
+        #[arg(long)]
+        feature_angle: Option<f32>,
+
+        /// Merge vertices closer than this distance before smoothing (default: 0.0001)
         #[arg(short, long, default_value_t = 0.0001)]
         tolerance: f32,
 
         /// Skip vertex merging step
         #[arg(long, default_value_t = false)]
         no_merge: bool,
+
+        /// Write ASCII instead of binary (STL/PLY output only)
+        #[arg(long)]
+        ascii: bool,
+
+        /// Unit for 3MF coordinates, converted from meters (3MF output only)
+        #[arg(long, default_value = "millimeter")]
+        unit: UnitArg,
+
+        /// Output format when writing to stdout (-); defaults to the input's format
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
+    },
+
+    /// Generate a chain of decimated LOD meshes, written as glTF linked with MSFT_lod
+    Lod {
+        /// Input mesh file in any supported format, detected from its contents (see --format), or - for stdin
+        input: PathBuf,
+
+        /// Output glTF file (.glb or .gltf), or - for stdout
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Mesh name (required if GLB contains multiple meshes; selects an o/g group in OBJ)
+        #[arg(short, long)]
+        mesh: Option<String>,
+
+        /// Load a whole glTF scene (default scene if no index), applying node transforms
+        #[arg(long, num_args = 0..=1, value_name = "INDEX", conflicts_with = "mesh")]
+        scene: Option<Option<usize>>,
+
+        /// Input format, overriding detection from the file contents and extension
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
+        /// Number of levels, including the full-detail mesh [default: 4]
+        #[arg(long)]
+        levels: Option<usize>,
+
+        /// Fraction of the input's faces kept by each level, finest first
+        /// [default: halving per level, e.g. 1,0.5,0.25,0.125]
+        #[arg(long, value_delimiter = ',')]
+        ratios: Option<Vec<f32>>,
+
+        /// Keep boundary vertices in place
+        #[arg(long)]
+        lock_boundary: bool,
+
+        /// Keep UV seams intact (seam vertices stay in place with their UVs)
+        #[arg(long)]
+        preserve_seams: bool,
+
+        /// Keep borders between face colors intact
+        #[arg(long)]
+        preserve_color_borders: bool,
+
+        /// Output format when writing to stdout (-) [default: glb]
+        #[arg(long, value_enum)]
+        out_format: Option<FormatArg>,
     },
 
     /// Inspect GLB/glTF file structure and contents
     InspectGlb {
-        /// Input GLB/glTF file
+        /// Input GLB/glTF file, or - for stdin
         input: PathBuf,
 
+        /// Input format, overriding detection from the file contents and extension (gltf or glb)
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+
         /// Output as JSON instead of tree format
         #[arg(long)]
         json: bool,
@@ -601,27 +1272,43 @@ fn main() {
             input,
             out,
             mesh,
+            scene,
+            format,
             iterations,
             target_edge_length,
             voxel_size,
             tolerance,
             no_fix,
+            keep_largest,
+            min_faces,
+            min_volume,
+            ascii,
+            unit,
+            out_format,
             command,
         } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
             match command {
                 Some(RemeshCommands::Incremental {
                     input,
                     out,
                     mesh,
+                    scene,
+                    format,
                     iterations,
                     target_edge_length,
+                    ascii,
+                    unit,
+                    out_format,
                 }) => {
+                    let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
                     if let Err(e) = mesh::remesh_incremental(
                         &input,
                         &out,
-                        mesh.as_deref(),
+                        &load_options,
                         iterations,
                         target_edge_length,
+                        &write_options(&input, &out, &load_options, out_format, ascii, unit),
                     ) {
                         eprintln!("Error during incremental remeshing: {}", e);
                         std::process::exit(1);
@@ -631,12 +1318,23 @@ fn main() {
                     input,
                     out,
                     mesh,
+                    scene,
+                    format,
                     size,
                     method,
+                    ascii,
+                    unit,
+                    out_format,
                 }) => {
-                    if let Err(e) =
-                        mesh::remesh_voxel(&input, &out, mesh.as_deref(), size, method.into())
-                    {
+                    let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+                    if let Err(e) = mesh::remesh_voxel(
+                        &input,
+                        &out,
+                        &load_options,
+                        size,
+                        method.into(),
+                        &write_options(&input, &out, &load_options, out_format, ascii, unit),
+                    ) {
                         eprintln!("Error during voxel remeshing: {}", e);
                         std::process::exit(1);
                     }
@@ -648,12 +1346,14 @@ fn main() {
                     if let Err(e) = mesh::remesh_pipeline(
                         &input,
                         &out,
-                        mesh.as_deref(),
+                        &load_options,
                         voxel_size,
                         tolerance,
                         no_fix,
                         iterations,
                         target_edge_length,
+                        &mesh::ComponentFilter { keep_largest, min_faces, min_volume },
+                        &write_options(&input, &out, &load_options, out_format, ascii, unit),
                     ) {
                         eprintln!("Error during remeshing pipeline: {}", e);
                         std::process::exit(1);
@@ -661,8 +1361,9 @@ fn main() {
                 }
             }
         }
-        Commands::Stats { input, mesh } => {
-            if let Err(e) = mesh::show_stats(&input, mesh.as_deref()) {
+        Commands::Stats { input, mesh, scene, format } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            if let Err(e) = mesh::show_stats(&input, &load_options) {
                 eprintln!("Error reading mesh stats: {}", e);
                 std::process::exit(1);
             }
@@ -670,6 +1371,8 @@ fn main() {
         Commands::View {
             input,
             mesh,
+            scene,
+            format,
             #[cfg(feature = "remote")]
             remote,
             no_vsync,
@@ -687,7 +1390,9 @@ fn main() {
             animation,
             scale,
             no_center,
+            highlight_intersections,
         } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
             use viewer::state::{ProjectionMode, ShadingMode, RenderPreset, ViewerState};
 
             // Build initial render state from CLI flags
@@ -744,14 +1449,14 @@ fn main() {
                 }
             };
 
-            // Handle BVH motion capture files (parse-only for now)
+            // Handle BVH motion capture files (detected from contents, or --format bvh)
             #[cfg(feature = "remote")]
             let input_path: Option<&PathBuf> = input.as_ref();
             #[cfg(not(feature = "remote"))]
             let input_path: Option<&PathBuf> = Some(&input);
 
             if let Some(path) = input_path {
-                if path.extension().and_then(|s| s.to_str()) == Some("bvh") {
+                if mesh::format::detect(path, load_options.format).ok() == Some(mesh::MeshFormat::Bvh) {
                     if let Err(e) = viewer::view_bvh(path, no_vsync, build_state) {
                         eprintln!("Error viewing BVH: {}", e);
                         std::process::exit(1);
@@ -763,13 +1468,13 @@ fn main() {
             #[cfg(feature = "remote")]
             {
                 if remote {
-                    if let Err(e) = viewer::view_mesh_with_rpc(input.as_ref(), mesh.as_deref(), no_vsync, z_up, build_state) {
+                    if let Err(e) = viewer::view_mesh_with_rpc(input.as_ref(), &load_options, no_vsync, z_up, build_state) {
                         eprintln!("Error viewing mesh: {}", e);
                         std::process::exit(1);
                     }
                 } else {
                     let input_ref = input.as_ref().expect("input required when not using --remote");
-                    if let Err(e) = viewer::view_mesh_with_bvh(input_ref, mesh.as_deref(), no_vsync, z_up, bvh.as_ref(), animation.as_deref(), scale, no_center, build_state) {
+                    if let Err(e) = viewer::view_mesh_with_bvh(input_ref, &load_options, no_vsync, z_up, bvh.as_ref(), animation.as_deref(), scale, no_center, highlight_intersections, build_state) {
                         eprintln!("Error viewing mesh: {}", e);
                         std::process::exit(1);
                     }
@@ -777,7 +1482,7 @@ fn main() {
             }
             #[cfg(not(feature = "remote"))]
             {
-                if let Err(e) = viewer::view_mesh_with_bvh(&input, mesh.as_deref(), no_vsync, z_up, bvh.as_ref(), animation.as_deref(), scale, no_center, build_state) {
+                if let Err(e) = viewer::view_mesh_with_bvh(&input, &load_options, no_vsync, z_up, bvh.as_ref(), animation.as_deref(), scale, no_center, highlight_intersections, build_state) {
                     eprintln!("Error viewing mesh: {}", e);
                     std::process::exit(1);
                 }
@@ -787,6 +1492,8 @@ fn main() {
             input,
             out,
             mesh,
+            scene,
+            format,
             width,
             height,
             projection,
@@ -810,6 +1517,7 @@ fn main() {
             scale,
             no_center,
         } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
             use viewer::state::{ProjectionMode, ShadingMode, RenderPreset, ViewerState};
             use mesh::animation::frame_to_time;
             use mesh::loader::load_mesh_with_colors;
@@ -866,6 +1574,9 @@ fn main() {
             };
 
             let out_str = out.to_string_lossy().to_string();
+            if mesh::stdio::is_std(&out) {
+                mesh::stdio::reserve_stdout();
+            }
 
             // Determine if we're doing animation/sprite-sheet rendering
             let has_animation_args = frame.is_some() || frames.is_some();
@@ -873,17 +1584,17 @@ fn main() {
             if !has_animation_args && !sprite_sheet && angles == 1 {
                 // --- Original single-image render path ---
                 if let Err(e) = viewer::headless::render_to_file(
-                    &input, &out_str, mesh.as_deref(), width, height, z_up, camera_pos, camera_target, None, None, scale, no_center, build_state,
+                    &input, &out_str, &load_options, width, height, z_up, camera_pos, camera_target, None, None, scale, no_center, build_state,
                 ) {
                     eprintln!("Error rendering: {}", e);
                     std::process::exit(1);
                 }
-                println!("Rendered to {}", out_str);
+                progress!("Rendered to {}", out_str);
             } else {
                 // --- Animation / sprite-sheet render path ---
 
                 // Load mesh to inspect animations
-                let mesh_data = match load_mesh_with_colors(&input, mesh.as_deref()) {
+                let mesh_data = match load_mesh_with_colors(&input, &load_options) {
                     Ok(m) => m,
                     Err(e) => {
                         eprintln!("Error loading mesh: {}", e);
@@ -971,7 +1682,7 @@ fn main() {
                     if let Err(e) = viewer::headless::render_to_file(
                         &input,
                         &out_str,
-                        mesh.as_deref(),
+                        &load_options,
                         width,
                         height,
                         z_up,
@@ -986,7 +1697,7 @@ fn main() {
                         eprintln!("Error rendering: {}", e);
                         std::process::exit(1);
                     }
-                    println!("Rendered frame {} (t={:.3}s) to {}", frame_start, anim_time, out_str);
+                    progress!("Rendered frame {} (t={:.3}s) to {}", frame_start, anim_time, out_str);
                 } else if sprite_sheet {
                     // Sprite sheet atlas mode
                     let config = viewer::sprite_sheet::SpriteSheetConfig {
@@ -1001,32 +1712,17 @@ fn main() {
                     match viewer::sprite_sheet::render_sprite_sheet(
                         &input,
                         &config,
-                        mesh.as_deref(),
+                        &load_options,
                         z_up,
                         build_state,
                     ) {
                         Ok((atlas_data, atlas_w, atlas_h)) => {
-                            // Save the atlas PNG
-                            if let Some(parent) = std::path::Path::new(&out_str).parent() {
-                                if !parent.as_os_str().is_empty() {
-                                    if let Err(e) = std::fs::create_dir_all(parent) {
-                                        eprintln!("Error creating output directory: {}", e);
-                                        std::process::exit(1);
-                                    }
-                                }
-                            }
-                            if let Err(e) = image::save_buffer(
-                                &out_str,
-                                &atlas_data,
-                                atlas_w,
-                                atlas_h,
-                                image::ColorType::Rgba8,
-                            ) {
+                            if let Err(e) = viewer::headless::save_png(&out_str, &atlas_data, atlas_w, atlas_h) {
                                 eprintln!("Error saving sprite sheet: {}", e);
                                 std::process::exit(1);
                             }
                             let tile_count = frame_count * angles as usize;
-                            println!(
+                            progress!(
                                 "Rendered {} tiles ({} angles x {} frames) to {}",
                                 tile_count, angles, frame_count, out_str
                             );
@@ -1038,6 +1734,10 @@ fn main() {
                     }
                 } else {
                     // Individual frames mode (--frames and/or --angles without --sprite-sheet)
+                    if mesh::stdio::is_std(&out) {
+                        eprintln!("Error: rendering individual frames writes several files; pass a path instead of -");
+                        std::process::exit(1);
+                    }
                     let config = viewer::sprite_sheet::SpriteSheetConfig {
                         tile_width: width,
                         tile_height: height,
@@ -1051,7 +1751,7 @@ fn main() {
                         &input,
                         &out_str,
                         &config,
-                        mesh.as_deref(),
+                        &load_options,
                         z_up,
                         build_state,
                     ) {
@@ -1066,8 +1766,224 @@ fn main() {
                 }
             }
         }
-        Commands::Check { input, mesh } => {
-            if let Err(e) = mesh::check_manifold(&input, mesh.as_deref()) {
+        Commands::Convert {
+            input,
+            out,
+            mesh,
+            scene,
+            format,
+            z_up,
+            scale,
+            ascii,
+            unit,
+            out_format,
+        } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            if let Err(e) = mesh::convert(
+                &input,
+                &out,
+                &load_options,
+                z_up,
+                scale,
+                &write_options(&input, &out, &load_options, out_format, ascii, unit),
+            ) {
+                eprintln!("Error converting mesh: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Subdivide {
+            input,
+            out,
+            mesh,
+            scene,
+            format,
+            scheme,
+            levels,
+            crease_angle,
+            ascii,
+            unit,
+            out_format,
+        } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            let options = mesh::SubdivideOptions { scheme: scheme.into(), levels, crease_angle };
+            if let Err(e) = mesh::subdivide(
+                &input,
+                &out,
+                &load_options,
+                &options,
+                &write_options(&input, &out, &load_options, out_format, ascii, unit),
+            ) {
+                eprintln!("Error subdividing mesh: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Components {
+            input,
+            out,
+            split,
+            mesh,
+            scene,
+            format,
+            keep_largest,
+            min_faces,
+            min_volume,
+            ascii,
+            unit,
+            out_format,
+        } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            let filter = mesh::ComponentFilter { keep_largest, min_faces, min_volume };
+            if filter.is_active() && out.is_none() && split.is_none() {
+                eprintln!("Warning: nothing is written without --out or --split; only listing components");
+            }
+            let write_options = match &out {
+                Some(out) => write_options(&input, out, &load_options, out_format, ascii, unit),
+                None => mesh::WriteOptions { ascii, unit: unit.into(), format: None },
+            };
+            if let Err(e) = mesh::components(
+                &input,
+                out.as_ref(),
+                split.as_deref(),
+                &load_options,
+                &filter,
+                &write_options,
+            ) {
+                eprintln!("Error analyzing components: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Orient {
+            input,
+            out,
+            mesh,
+            scene,
+            format,
+            keep_direction,
+            ascii,
+            unit,
+            out_format,
+        } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            if let Err(e) = mesh::orient(
+                &input,
+                &out,
+                &load_options,
+                keep_direction,
+                &write_options(&input, &out, &load_options, out_format, ascii, unit),
+            ) {
+                eprintln!("Error orienting mesh: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Decimate {
+            input,
+            out,
+            mesh,
+            scene,
+            format,
+            target_faces,
+            ratio,
+            lock_boundary,
+            preserve_seams,
+            preserve_color_borders,
+            ascii,
+            unit,
+            out_format,
+        } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            let target = match (target_faces, ratio) {
+                (Some(faces), _) => mesh::DecimateTarget::Faces(faces),
+                (None, Some(ratio)) => mesh::DecimateTarget::Ratio(ratio),
+                (None, None) => unreachable!("clap requires --target-faces or --ratio"),
+            };
+            let options = mesh::DecimateOptions { target, lock_boundary, preserve_seams, preserve_color_borders };
+            if let Err(e) = mesh::decimate(
+                &input,
+                &out,
+                &load_options,
+                &options,
+                &write_options(&input, &out, &load_options, out_format, ascii, unit),
+            ) {
+                eprintln!("Error decimating mesh: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Smooth {
+            input,
+            out,
+            mesh,
+            scene,
+            format,
+            method,
+            iterations,
+            lambda,
+            mu,
+            lock_boundary,
+            feature_angle,
+            tolerance,
+            no_merge,
+            ascii,
+            unit,
+            out_format,
+        } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            let options = mesh::SmoothOptions {
+                method: method.into(),
+                iterations,
+                lambda,
+                mu,
+                lock_boundary,
+                feature_angle,
+            };
+            if let Err(e) = mesh::smooth(
+                &input,
+                &out,
+                &load_options,
+                &options,
+                tolerance,
+                no_merge,
+                &write_options(&input, &out, &load_options, out_format, ascii, unit),
+            ) {
+                eprintln!("Error smoothing mesh: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Lod {
+            input,
+            out,
+            mesh,
+            scene,
+            format,
+            levels,
+            ratios,
+            lock_boundary,
+            preserve_seams,
+            preserve_color_borders,
+            out_format,
+        } => {
+            let ratios = match (levels, ratios) {
+                (Some(levels), Some(ratios)) if levels != ratios.len() => {
+                    eprintln!("Error: --levels {} doesn't match the {} values given to --ratios", levels, ratios.len());
+                    std::process::exit(1);
+                }
+                (_, Some(ratios)) => ratios,
+                (levels, None) => mesh::lod::default_ratios(levels.unwrap_or(4)),
+            };
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            let options = mesh::LodOptions { ratios, lock_boundary, preserve_seams, preserve_color_borders };
+            if mesh::stdio::is_std(&out) {
+                mesh::stdio::reserve_stdout();
+            }
+            let write_options =
+                mesh::WriteOptions { format: out_format.map(|f| f.extension().to_string()), ..Default::default() };
+            if let Err(e) = mesh::lod(&input, &out, &load_options, &options, &write_options) {
+                eprintln!("Error generating LODs: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Check { input, mesh, scene, format, intersections } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            if let Err(e) = mesh::check_manifold(&input, &load_options, intersections) {
                 eprintln!("Error checking mesh: {}", e);
                 std::process::exit(1);
             }
@@ -1076,24 +1992,52 @@ fn main() {
             input,
             out,
             mesh,
+            scene,
+            format,
+            method,
             voxel_size,
+            triangulation,
+            refine,
+            fair,
+            max_hole_edges,
+            fill_border,
             tolerance,
             no_merge,
+            keep_largest,
+            min_faces,
+            min_volume,
+            ascii,
+            unit,
+            out_format,
         } => {
+            let load_options = mesh::LoadOptions { mesh_name: mesh, scene, format: format.map(Into::into) };
+            let method = match method {
+                FixMethodArg::Voxel => mesh::FixMethod::Voxel,
+                FixMethodArg::Local => mesh::FixMethod::Local(mesh::HoleFillOptions {
+                    triangulation: triangulation.into(),
+                    refine,
+                    fair,
+                    max_edges: max_hole_edges,
+                    fill_border,
+                }),
+            };
             if let Err(e) = mesh::fix_holes(
                 &input,
                 &out,
-                mesh.as_deref(),
+                &load_options,
+                &method,
                 voxel_size,
                 tolerance,
                 no_merge,
+                &mesh::ComponentFilter { keep_largest, min_faces, min_volume },
+                &write_options(&input, &out, &load_options, out_format, ascii, unit),
             ) {
                 eprintln!("Error fixing mesh: {}", e);
                 std::process::exit(1);
             }
         }
-        Commands::InspectGlb { input, json } => {
-            if let Err(e) = glb::inspect_glb(&input, json) {
+        Commands::InspectGlb { input, format, json } => {
+            if let Err(e) = glb::inspect_glb(&input, format.map(Into::into), json) {
                 eprintln!("Error inspecting GLB: {}", e);
                 std::process::exit(1);
             }
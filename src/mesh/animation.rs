@@ -15,13 +15,16 @@ pub enum AnimationProperty {
     Translation,
     Rotation,
     Scale,
+    /// Morph target weights of the mesh
+    MorphWeights,
 }
 
 /// A single keyframe with optional cubic spline tangents
 #[derive(Debug, Clone)]
 pub struct Keyframe {
     pub time: f32,
-    /// 3 floats for Translation/Scale, 4 for Rotation (quaternion XYZW)
+    /// 3 floats for Translation/Scale, 4 for Rotation (quaternion XYZW),
+    /// one per morph target for MorphWeights
     pub value: Vec<f32>,
     /// In-tangent (CubicSpline only)
     pub in_tangent: Option<Vec<f32>>,
@@ -29,10 +32,10 @@ pub struct Keyframe {
     pub out_tangent: Option<Vec<f32>>,
 }
 
-/// A single animated channel targeting one joint's property
+/// A single animated channel targeting one joint's property (or the mesh's morph weights)
 #[derive(Debug, Clone)]
 pub struct AnimationChannel {
-    /// Index into the skeleton's joint list (NOT the glTF node index)
+    /// Index into the skeleton's joint list (NOT the glTF node index); unused for MorphWeights
     pub joint_index: usize,
     pub property: AnimationProperty,
     pub interpolation: Interpolation,
@@ -53,14 +56,18 @@ pub struct AnimationClip {
 /// `joint_node_indices` is the list of node indices from the skin's joints array,
 /// used to map channel target nodes to joint indices. If None, channels that
 /// target nodes not in a skin are skipped.
+///
+/// `morph_node_index` is the node instancing the loaded mesh; its `weights`
+/// channels become MorphWeights channels. Other weights channels are skipped.
 pub fn extract_animations(
     document: &gltf::Document,
     buffers: &[gltf::buffer::Data],
     joint_node_indices: Option<&[usize]>,
+    morph_node_index: Option<usize>,
 ) -> Vec<AnimationClip> {
     document
         .animations()
-        .map(|anim| extract_one_animation(&anim, buffers, joint_node_indices))
+        .map(|anim| extract_one_animation(&anim, buffers, joint_node_indices, morph_node_index))
         .collect()
 }
 
@@ -68,6 +75,7 @@ fn extract_one_animation(
     anim: &gltf::Animation<'_>,
     buffers: &[gltf::buffer::Data],
     joint_node_indices: Option<&[usize]>,
+    morph_node_index: Option<usize>,
 ) -> AnimationClip {
     let name = anim.name().map(String::from);
     let mut duration: f32 = 0.0;
@@ -77,20 +85,27 @@ fn extract_one_animation(
         let target = channel.target();
         let node_index = target.node().index();
 
-        // Map node index to joint index
-        let joint_index = match joint_node_indices {
-            Some(joints) => match joints.iter().position(|&n| n == node_index) {
-                Some(idx) => idx,
-                None => continue, // node is not a joint, skip
-            },
-            None => node_index, // no skin info, use node index as-is
-        };
-
         let property = match target.property() {
             gltf::animation::Property::Translation => AnimationProperty::Translation,
             gltf::animation::Property::Rotation => AnimationProperty::Rotation,
             gltf::animation::Property::Scale => AnimationProperty::Scale,
-            gltf::animation::Property::MorphTargetWeights => continue, // not supported
+            gltf::animation::Property::MorphTargetWeights => AnimationProperty::MorphWeights,
+        };
+
+        // Map node index to joint index
+        let joint_index = if property == AnimationProperty::MorphWeights {
+            if morph_node_index != Some(node_index) {
+                continue; // weights of a mesh we did not load
+            }
+            0
+        } else {
+            match joint_node_indices {
+                Some(joints) => match joints.iter().position(|&n| n == node_index) {
+                    Some(idx) => idx,
+                    None => continue, // node is not a joint, skip
+                },
+                None => node_index, // no skin info, use node index as-is
+            }
         };
 
         let sampler = channel.sampler();
@@ -124,6 +139,22 @@ fn extract_one_animation(
         let component_count = match property {
             AnimationProperty::Translation | AnimationProperty::Scale => 3,
             AnimationProperty::Rotation => 4,
+            AnimationProperty::MorphWeights => 1,
+        };
+        // Weights are read as scalars; regroup into one vector per output element
+        let elements_per_key = if is_cubic { 3 } else { 1 };
+        let raw_values = if property == AnimationProperty::MorphWeights {
+            let output_count = timestamps.len() * elements_per_key;
+            let target_count = raw_values.len().checked_div(output_count).unwrap_or(0);
+            if target_count == 0 {
+                continue;
+            }
+            raw_values
+                .chunks_exact(target_count)
+                .map(|chunk| chunk.iter().flatten().copied().collect())
+                .collect()
+        } else {
+            raw_values
         };
 
         let keyframes = build_keyframes(
@@ -154,7 +185,7 @@ fn read_output_values(outputs: ReadOutputs<'_>) -> Vec<Vec<f32>> {
         ReadOutputs::Translations(iter) => iter.map(|v| v.to_vec()).collect(),
         ReadOutputs::Rotations(iter) => iter.into_f32().map(|v| v.to_vec()).collect(),
         ReadOutputs::Scales(iter) => iter.map(|v| v.to_vec()).collect(),
-        ReadOutputs::MorphTargetWeights(_) => Vec::new(), // not supported
+        ReadOutputs::MorphTargetWeights(iter) => iter.into_f32().map(|w| vec![w]).collect(),
     }
 }
 
@@ -392,6 +423,22 @@ pub fn evaluate_animation(
         .collect()
 }
 
+/// Evaluate a clip's morph weight channels at `time`, starting from the
+/// mesh's default weights. Returns one weight per morph target.
+pub fn evaluate_morph_weights(clip: &AnimationClip, default_weights: &[f32], time: f32) -> Vec<f32> {
+    let mut weights = default_weights.to_vec();
+    for channel in &clip.channels {
+        if channel.property != AnimationProperty::MorphWeights {
+            continue;
+        }
+        let sampled = sample_channel(channel, time);
+        for (w, s) in weights.iter_mut().zip(sampled) {
+            *w = s;
+        }
+    }
+    weights
+}
+
 /// Convert a frame index to a time within an animation clip.
 /// `frame` is the zero-based frame index, `total_frames` is the total number of frames.
 /// Returns `frame as f32 / total_frames as f32 * clip.duration`.
@@ -447,4 +494,23 @@ mod tests {
         );
         assert_eq!(keyframes[1].value, vec![4.0, 5.0, 6.0]);
     }
+
+    #[test]
+    fn test_evaluate_morph_weights() {
+        let clip = AnimationClip {
+            name: None,
+            channels: vec![AnimationChannel {
+                joint_index: 0,
+                property: AnimationProperty::MorphWeights,
+                interpolation: Interpolation::Step,
+                keyframes: build_keyframes(&[0.0, 1.0], &[vec![0.0, 1.0], vec![1.0, 0.0]], 1, false),
+            }],
+            duration: 1.0,
+        };
+        assert_eq!(evaluate_morph_weights(&clip, &[0.3, 0.3], 0.5), vec![0.0, 1.0]);
+        assert_eq!(evaluate_morph_weights(&clip, &[0.3, 0.3], 2.0), vec![1.0, 0.0]);
+        // Clips without weight channels keep the defaults
+        let empty = AnimationClip { name: None, channels: Vec::new(), duration: 0.0 };
+        assert_eq!(evaluate_morph_weights(&empty, &[0.3, 0.7], 0.5), vec![0.3, 0.7]);
+    }
 }
@@ -0,0 +1,1503 @@
+//! Collada (.dae) reader.
+//!
+//! The document is parsed into an element tree and resolved by id. One mesh
+//! instance of the visual scene (picked with `--mesh` when there are several)
+//! is loaded from `library_geometries` (`triangles`, `polylist` and
+//! `polygons`, fan-triangulated) with its first UV set, normals and vertex
+//! colors. Bound `library_materials` effects supply diffuse colors per face
+//! and the first diffuse texture. A `library_controllers` skin becomes the
+//! skeleton, and `library_animations` (split by `library_animation_clips`
+//! when present) become animation clips, sampled at their key times.
+//!
+//! Coordinates are converted to meters and Y-up from the `<asset>` unit and
+//! `up_axis`; joint matrices are converted the same way so skins stay valid.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use nalgebra::{Matrix3, Matrix4, Point3, Rotation3, Unit, Vector3};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use super::animation::{
+    AnimationChannel, AnimationClip, AnimationProperty, Interpolation, Keyframe,
+};
+use super::loader::{MeshWithColors, TextureData};
+use super::skeleton::{Joint, JointTransform, Skeleton};
+use super::stdio::{self, progress};
+
+/// Color used for faces without a bound material
+const DEFAULT_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+
+/// Diffuse color and texture image file of a material
+type Material = ([f32; 4], Option<String>);
+
+/// Material symbol of each triangle, as named by its primitive
+type FaceMaterials = Vec<Option<String>>;
+
+// --- Element tree ---
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Id referenced by a `#id` URL attribute
+    fn url(&self, name: &str) -> Option<&str> {
+        self.attr(name).map(|u| u.trim_start_matches('#'))
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// First descendant (depth first) with the given name
+    fn find(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find_map(|c| {
+            if c.name == name {
+                Some(c)
+            } else {
+                c.find(name)
+            }
+        })
+    }
+
+    fn floats(&self) -> Vec<f64> {
+        self.text
+            .split_whitespace()
+            .filter_map(|v| v.parse().ok())
+            .collect()
+    }
+
+    fn ints(&self) -> Vec<i64> {
+        self.text
+            .split_whitespace()
+            .filter_map(|v| v.parse().ok())
+            .collect()
+    }
+
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Element)) {
+        f(self);
+        for child in &self.children {
+            child.visit(f);
+        }
+    }
+}
+
+fn parse_document(xml: &str) -> Result<Element, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack = vec![Element::default()];
+
+    let open = |e: &quick_xml::events::BytesStart| Element {
+        name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+        attrs: e
+            .attributes()
+            .flatten()
+            .map(|a| {
+                (
+                    String::from_utf8_lossy(a.key.local_name().as_ref()).into_owned(),
+                    a.unescape_value()
+                        .map(|v| v.into_owned())
+                        .unwrap_or_else(|_| String::from_utf8_lossy(&a.value).into_owned()),
+                )
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) => stack.push(open(e)),
+            Ok(Event::Empty(ref e)) => {
+                let element = open(e);
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(element);
+                }
+            }
+            Ok(Event::End(_)) => {
+                let element = stack.pop().ok_or("Unbalanced Collada XML")?;
+                stack
+                    .last_mut()
+                    .ok_or("Unbalanced Collada XML")?
+                    .children
+                    .push(element);
+            }
+            Ok(Event::Text(t)) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(
+                        &t.unescape()
+                            .map_err(|e| format!("XML parse error: {}", e))?,
+                    );
+                }
+            }
+            Ok(Event::CData(c)) => {
+                if let Some(current) = stack.last_mut() {
+                    current
+                        .text
+                        .push_str(&String::from_utf8_lossy(&c.into_inner()));
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parse error: {}", e).into()),
+            _ => {}
+        }
+    }
+
+    let root = stack
+        .pop()
+        .and_then(|r| r.children.into_iter().find(|c| c.name == "COLLADA"));
+    root.ok_or_else(|| "Not a Collada file (missing <COLLADA> root)".into())
+}
+
+// --- Document ---
+
+/// A node of the instantiated visual scene, in depth-first order
+struct SceneNode<'a> {
+    element: &'a Element,
+    parent: Option<usize>,
+    world: Matrix4<f64>,
+}
+
+struct Document<'a> {
+    ids: HashMap<&'a str, &'a Element>,
+    nodes: Vec<SceneNode<'a>>,
+    /// Conversion to meters and Y-up, applied to points and conjugated onto joint matrices
+    correction: Matrix4<f64>,
+}
+
+impl<'a> Document<'a> {
+    fn new(root: &'a Element) -> Self {
+        let mut ids = HashMap::new();
+        root.visit(&mut |e| {
+            if let Some(id) = e.attr("id") {
+                ids.insert(id, e);
+            }
+        });
+
+        let asset = root.child("asset");
+        let meter = asset
+            .and_then(|a| a.child("unit"))
+            .and_then(|u| u.attr("meter"))
+            .and_then(|m| m.parse::<f64>().ok())
+            .unwrap_or(1.0);
+        let axis = match asset
+            .and_then(|a| a.child("up_axis"))
+            .map(|u| u.text.trim())
+        {
+            // Z up: (x, y, z) -> (x, z, -y)
+            Some("Z_UP") => Matrix3::new(1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0),
+            // X up: (x, y, z) -> (-y, x, z)
+            Some("X_UP") => Matrix3::new(0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0),
+            _ => Matrix3::identity(),
+        };
+        let correction = Matrix4::new_scaling(meter) * axis.to_homogeneous();
+
+        let mut doc = Document {
+            ids,
+            nodes: Vec::new(),
+            correction,
+        };
+        let scene = root
+            .child("scene")
+            .and_then(|s| s.child("instance_visual_scene"))
+            .and_then(|i| i.url("url"))
+            .and_then(|id| doc.ids.get(id).copied())
+            .or_else(|| {
+                root.child("library_visual_scenes")
+                    .and_then(|l| l.child("visual_scene"))
+            });
+        if let Some(scene) = scene {
+            for node in scene.children_named("node") {
+                doc.add_node(node, None, 0);
+            }
+        }
+        doc
+    }
+
+    fn add_node(&mut self, element: &'a Element, parent: Option<usize>, depth: usize) {
+        // Guard against instance_node cycles
+        if depth > 256 {
+            return;
+        }
+        let parent_world = parent
+            .map(|p| self.nodes[p].world)
+            .unwrap_or_else(Matrix4::identity);
+        let index = self.nodes.len();
+        self.nodes.push(SceneNode {
+            element,
+            parent,
+            world: parent_world * node_local(element, &HashMap::new()),
+        });
+        for child in &element.children {
+            match child.name.as_str() {
+                "node" => self.add_node(child, Some(index), depth + 1),
+                "instance_node" => {
+                    if let Some(&target) = child.url("url").and_then(|id| self.ids.get(id)) {
+                        self.add_node(target, Some(index), depth + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get(&self, url: Option<&str>) -> Option<&'a Element> {
+        url.and_then(|id| self.ids.get(id).copied())
+    }
+
+    /// Express a scene-space matrix in corrected (meters, Y-up) space
+    fn conjugate(&self, m: &Matrix4<f64>) -> Matrix4<f64> {
+        self.correction
+            * m
+            * self
+                .correction
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity)
+    }
+
+    /// Float data and stride of a `<source>`
+    fn source(&self, url: Option<&str>) -> Option<(Vec<f64>, usize)> {
+        let source = self.get(url)?;
+        let data = source.child("float_array")?.floats();
+        let stride = source
+            .find("accessor")
+            .and_then(|a| a.attr("stride"))
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1usize)
+            .max(1);
+        Some((data, stride))
+    }
+
+    /// Names or ids listed by a `<source>`
+    fn names(&self, url: Option<&str>) -> Vec<String> {
+        self.get(url)
+            .and_then(|s| s.child("Name_array").or_else(|| s.child("IDREF_array")))
+            .map(|a| a.text.split_whitespace().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Diffuse color and texture of a material, through its effect
+    fn material(&self, id: &str) -> Option<Material> {
+        let effect = self.get(self.ids.get(id)?.child("instance_effect")?.url("url"))?;
+        let profile = effect.child("profile_COMMON")?;
+        let technique = profile.child("technique")?;
+        let shading = technique
+            .children
+            .iter()
+            .find(|c| matches!(c.name.as_str(), "phong" | "lambert" | "blinn" | "constant"))?;
+        let diffuse = shading
+            .child("diffuse")
+            .or_else(|| shading.child("emission"))?;
+        if let Some(color) = diffuse.child("color") {
+            let c = color.floats();
+            let channel = |i: usize, default: f64| c.get(i).copied().unwrap_or(default) as f32;
+            return Some((
+                [
+                    channel(0, 0.8),
+                    channel(1, 0.8),
+                    channel(2, 0.8),
+                    channel(3, 1.0),
+                ],
+                None,
+            ));
+        }
+        let sampler = diffuse.child("texture")?.attr("texture")?;
+        Some(([1.0, 1.0, 1.0, 1.0], self.texture_image(profile, sampler)))
+    }
+
+    /// Follow a `<texture texture=...>` reference through sampler and surface params to an image file
+    fn texture_image(&self, profile: &Element, sampler: &str) -> Option<String> {
+        let param = |sid: &str| {
+            profile
+                .children_named("newparam")
+                .find(|p| p.attr("sid") == Some(sid))
+        };
+        let image_id = match param(sampler).and_then(|p| p.child("sampler2D")) {
+            Some(sampler2d) => match sampler2d.child("instance_image") {
+                Some(instance) => instance.url("url").map(String::from),
+                None => {
+                    let surface = sampler2d
+                        .child("source")
+                        .map(|s| s.text.trim().to_string())?;
+                    param(&surface)?
+                        .find("init_from")
+                        .map(|i| i.text.trim().to_string())
+                }
+            },
+            None => Some(sampler.to_string()),
+        }?;
+        let image = self.ids.get(image_id.as_str())?;
+        let init = image.child("init_from")?;
+        let file = init.child("ref").unwrap_or(init).text.trim();
+        Some(percent_decode(file.trim_start_matches("file://")))
+    }
+
+    /// Scene node referenced by a skin joint name: sid within the skeleton roots, then sid, id or name anywhere
+    fn joint_node(&self, name: &str, roots: &[usize]) -> Option<usize> {
+        let in_roots = |i: usize| {
+            let mut current = Some(i);
+            while let Some(c) = current {
+                if roots.contains(&c) {
+                    return true;
+                }
+                current = self.nodes[c].parent;
+            }
+            false
+        };
+        let by = |key: &str, scoped: bool| {
+            self.nodes
+                .iter()
+                .position(|n| n.element.attr(key) == Some(name))
+                .filter(|&i| !scoped || in_roots(i))
+        };
+        by("sid", true)
+            .or_else(|| by("sid", false))
+            .or_else(|| by("id", false))
+            .or_else(|| by("name", false))
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(v) = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        {
+            out.push(v);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Local matrix of a `<node>` from its transform elements, in document order.
+/// `overrides` replaces the values of elements by sid (for animation).
+fn node_local(node: &Element, overrides: &HashMap<String, Vec<f64>>) -> Matrix4<f64> {
+    let mut m = Matrix4::identity();
+    for element in &node.children {
+        let v = match element.attr("sid").and_then(|sid| overrides.get(sid)) {
+            Some(values) => values.clone(),
+            None => element.floats(),
+        };
+        let get = |i: usize, default: f64| v.get(i).copied().unwrap_or(default);
+        let op = match element.name.as_str() {
+            "matrix" if v.len() == 16 => Matrix4::from_row_slice(&v),
+            "translate" => {
+                Matrix4::new_translation(&Vector3::new(get(0, 0.0), get(1, 0.0), get(2, 0.0)))
+            }
+            "scale" => Matrix4::new_nonuniform_scaling(&Vector3::new(
+                get(0, 1.0),
+                get(1, 1.0),
+                get(2, 1.0),
+            )),
+            "rotate" => {
+                let axis = Vector3::new(get(0, 0.0), get(1, 0.0), get(2, 1.0));
+                if axis.norm() == 0.0 {
+                    continue;
+                }
+                Rotation3::from_axis_angle(&Unit::new_normalize(axis), get(3, 0.0).to_radians())
+                    .to_homogeneous()
+            }
+            _ => continue,
+        };
+        m *= op;
+    }
+    m
+}
+
+fn to_f32(m: &Matrix4<f64>) -> [[f32; 4]; 4] {
+    m.cast::<f32>().into()
+}
+
+// --- Geometry ---
+
+/// One `<input>` of a primitive
+struct Input<'a> {
+    semantic: &'a str,
+    source: Option<&'a str>,
+    offset: usize,
+    set: usize,
+}
+
+fn inputs(element: &Element) -> Vec<Input<'_>> {
+    element
+        .children_named("input")
+        .map(|i| Input {
+            semantic: i.attr("semantic").unwrap_or(""),
+            source: i.url("source"),
+            offset: i.attr("offset").and_then(|o| o.parse().ok()).unwrap_or(0),
+            set: i.attr("set").and_then(|s| s.parse().ok()).unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Build positions, triangles, per-corner UVs/normals and vertex colors from a
+/// `<geometry>`. Also returns the material symbol of each triangle.
+fn read_geometry(
+    doc: &Document,
+    geometry: &Element,
+    transform: &Matrix4<f64>,
+) -> Result<(MeshWithColors, FaceMaterials), Box<dyn std::error::Error>> {
+    let name = geometry.attr("id").unwrap_or("");
+    let mesh_el = geometry
+        .child("mesh")
+        .ok_or_else(|| format!("Collada geometry '{}' is not a polygon mesh", name))?;
+    let vertices = mesh_el
+        .child("vertices")
+        .ok_or("Collada mesh without <vertices>")?;
+    let vertex_inputs = inputs(vertices);
+    let vertex_source = |semantic: &str| {
+        vertex_inputs
+            .iter()
+            .find(|i| i.semantic == semantic)
+            .and_then(|i| doc.source(i.source))
+    };
+    let (points, point_stride) =
+        vertex_source("POSITION").ok_or("Collada mesh without POSITION data")?;
+    let vertex_normals = vertex_source("NORMAL");
+    let vertex_uvs = vertex_source("TEXCOORD");
+    let vertex_colors = vertex_source("COLOR");
+
+    let normal_matrix = transform
+        .fixed_view::<3, 3>(0, 0)
+        .try_inverse()
+        .unwrap_or_else(Matrix3::identity)
+        .transpose();
+    let flip = transform.fixed_view::<3, 3>(0, 0).determinant() < 0.0;
+
+    let mut mesh = MeshWithColors {
+        positions: points
+            .chunks(point_stride)
+            .filter(|p| p.len() >= 3)
+            .map(|p| {
+                let v = transform.transform_point(&Point3::new(p[0], p[1], p[2]));
+                [v.x as f32, v.y as f32, v.z as f32]
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let mut materials = Vec::new();
+    let mut uvs: Vec<[[f32; 2]; 3]> = Vec::new();
+    let mut normals: Vec<[[f32; 3]; 3]> = Vec::new();
+    let mut has_uvs = false;
+    let mut has_normals = false;
+    let mut color_sums: Vec<([f32; 4], u32)> = Vec::new();
+
+    let element_at = |data: &(Vec<f64>, usize), i: usize| -> Option<Vec<f64>> {
+        data.0.get(i * data.1..(i + 1) * data.1).map(|s| s.to_vec())
+    };
+
+    for primitive in &mesh_el.children {
+        let polygons: Vec<Vec<i64>> = match primitive.name.as_str() {
+            "triangles" => {
+                let p = primitive.child("p").map(Element::ints).unwrap_or_default();
+                vec![p]
+            }
+            "polylist" | "polygons" => primitive.children_named("p").map(Element::ints).collect(),
+            "lines" | "linestrips" | "trifans" | "tristrips" => {
+                eprintln!(
+                    "Warning: Collada <{}> primitives are not supported; skipping",
+                    primitive.name
+                );
+                continue;
+            }
+            _ => continue,
+        };
+        let prim_inputs = inputs(primitive);
+        let stride = prim_inputs.iter().map(|i| i.offset + 1).max().unwrap_or(1);
+        let find = |semantic: &str| {
+            prim_inputs
+                .iter()
+                .filter(|i| i.semantic == semantic)
+                .min_by_key(|i| i.set)
+        };
+        let vertex_offset = find("VERTEX")
+            .map(|i| i.offset)
+            .ok_or("Collada primitive without VERTEX input")?;
+        let normal_input = find("NORMAL").and_then(|i| Some((i.offset, doc.source(i.source)?)));
+        let uv_input = find("TEXCOORD").and_then(|i| Some((i.offset, doc.source(i.source)?)));
+        let color_input = find("COLOR").and_then(|i| Some((i.offset, doc.source(i.source)?)));
+        let material = primitive.attr("material").map(String::from);
+
+        // Corner lists: one polygon per <p>, or split by <vcount>
+        let polygons: Vec<Vec<i64>> = match (primitive.name.as_str(), primitive.child("vcount")) {
+            ("polylist", Some(vcount)) => {
+                let flat = polygons.concat();
+                let mut out = Vec::new();
+                let mut start = 0;
+                for count in vcount.ints() {
+                    let end = start + count.max(0) as usize * stride;
+                    out.push(
+                        flat.get(start..end)
+                            .ok_or("Collada <p> is shorter than <vcount> requires")?
+                            .to_vec(),
+                    );
+                    start = end;
+                }
+                out
+            }
+            ("triangles", _) => polygons
+                .concat()
+                .chunks_exact(3 * stride)
+                .map(|c| c.to_vec())
+                .collect(),
+            _ => polygons,
+        };
+
+        for corners in polygons {
+            let corners: Vec<&[i64]> = corners.chunks_exact(stride).collect();
+            if corners.len() < 3 {
+                continue;
+            }
+            let position = |c: &[i64]| {
+                usize::try_from(c[vertex_offset])
+                    .ok()
+                    .filter(|&v| v < mesh.positions.len())
+            };
+            if corners.iter().any(|c| position(c).is_none()) {
+                return Err(
+                    format!("Collada geometry '{}' references a missing vertex", name).into(),
+                );
+            }
+            for k in 1..corners.len() - 1 {
+                let mut tri = [corners[0], corners[k], corners[k + 1]];
+                if flip {
+                    tri.swap(1, 2);
+                }
+                mesh.face_indices
+                    .push(tri.map(|c| position(c).unwrap_or(0) as u32));
+                materials.push(material.clone());
+
+                uvs.push(tri.map(|c| {
+                    let uv = match &uv_input {
+                        Some((offset, data)) => element_at(data, c[*offset] as usize),
+                        None => vertex_uvs
+                            .as_ref()
+                            .and_then(|d| element_at(d, c[vertex_offset] as usize)),
+                    };
+                    has_uvs |= uv.is_some();
+                    uv.map(|uv| [uv[0] as f32, 1.0 - uv.get(1).copied().unwrap_or(0.0) as f32])
+                        .unwrap_or([0.0; 2])
+                }));
+                normals.push(tri.map(|c| {
+                    let n = match &normal_input {
+                        Some((offset, data)) => element_at(data, c[*offset] as usize),
+                        None => vertex_normals
+                            .as_ref()
+                            .and_then(|d| element_at(d, c[vertex_offset] as usize)),
+                    };
+                    has_normals |= n.is_some();
+                    n.filter(|n| n.len() >= 3)
+                        .map(|n| {
+                            let n = (normal_matrix * Vector3::new(n[0], n[1], n[2])).normalize();
+                            [n.x as f32, n.y as f32, n.z as f32]
+                        })
+                        .unwrap_or([0.0; 3])
+                }));
+            }
+            // Per-corner colors are averaged onto their vertices
+            if let Some((offset, data)) = &color_input {
+                color_sums.resize(mesh.positions.len(), ([0.0; 4], 0));
+                for c in &corners {
+                    if let (Some(v), Some(color)) =
+                        (position(c), element_at(data, c[*offset] as usize))
+                    {
+                        let (sum, n) = &mut color_sums[v];
+                        for (k, s) in sum.iter_mut().enumerate() {
+                            *s += color.get(k).copied().unwrap_or(1.0) as f32;
+                        }
+                        *n += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    if has_uvs {
+        mesh.corner_texcoords = uvs;
+    }
+    if has_normals {
+        mesh.corner_normals = normals;
+    }
+    if !color_sums.is_empty() {
+        mesh.vertex_colors = color_sums
+            .iter()
+            .map(|(sum, n)| {
+                if *n > 0 {
+                    sum.map(|s| s / *n as f32)
+                } else {
+                    DEFAULT_COLOR
+                }
+            })
+            .collect();
+    } else if let Some(data) = vertex_colors {
+        mesh.vertex_colors = (0..mesh.positions.len())
+            .map(|v| {
+                let c = element_at(&data, v).unwrap_or_default();
+                std::array::from_fn(|k| c.get(k).copied().unwrap_or(1.0) as f32)
+            })
+            .collect();
+    }
+    Ok((mesh, materials))
+}
+
+/// Apply bound material colors per face and load the first diffuse texture
+fn apply_materials(
+    mesh: &mut MeshWithColors,
+    doc: &Document,
+    instance: &Element,
+    face_materials: &[Option<String>],
+    path: &Path,
+) {
+    // instance_material symbol -> material id
+    let bindings: HashMap<&str, &str> = instance
+        .child("bind_material")
+        .and_then(|b| b.child("technique_common"))
+        .map(|t| {
+            t.children_named("instance_material")
+                .filter_map(|m| Some((m.attr("symbol")?, m.url("target")?)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut resolved: HashMap<&str, Option<Material>> = HashMap::new();
+    let mut texture_file = None;
+    let mut any = false;
+    let colors: Vec<[f32; 4]> = face_materials
+        .iter()
+        .map(|symbol| {
+            let Some(symbol) = symbol.as_deref() else {
+                return DEFAULT_COLOR;
+            };
+            let material = resolved
+                .entry(symbol)
+                .or_insert_with(|| doc.material(bindings.get(symbol).copied().unwrap_or(symbol)));
+            match material {
+                Some((color, texture)) => {
+                    any = true;
+                    if texture_file.is_none() {
+                        texture_file = texture.clone();
+                    }
+                    *color
+                }
+                None => DEFAULT_COLOR,
+            }
+        })
+        .collect();
+    if any {
+        mesh.face_colors = colors;
+    }
+
+    if let Some(file) = texture_file {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let texture_path = dir.join(&file);
+        match image::open(&texture_path) {
+            Ok(img) => {
+                let rgba = img.to_rgba8();
+                mesh.texture = Some(TextureData {
+                    width: rgba.width(),
+                    height: rgba.height(),
+                    pixels: rgba.into_raw(),
+                });
+            }
+            Err(e) => eprintln!("Warning: failed to load texture {:?}: {}", texture_path, e),
+        }
+    }
+}
+
+// --- Loading ---
+
+/// Load one mesh instance from a Collada file, with its skin and animations
+pub fn load_collada(
+    path: &Path,
+    mesh_name: Option<&str>,
+) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let xml = stdio::read_to_string(path)?;
+    let root = parse_document(&xml)?;
+    let doc = Document::new(&root);
+
+    // (scene node, instance element, name) of every geometry or controller instance
+    let mut instances: Vec<(usize, &Element, String)> = Vec::new();
+    for (index, node) in doc.nodes.iter().enumerate() {
+        for instance in &node.element.children {
+            if matches!(
+                instance.name.as_str(),
+                "instance_geometry" | "instance_controller"
+            ) {
+                let name = node
+                    .element
+                    .attr("name")
+                    .or(node.element.attr("id"))
+                    .unwrap_or("<unnamed>");
+                instances.push((index, instance, name.to_string()));
+            }
+        }
+    }
+    if instances.is_empty() {
+        return Err("Collada file contains no mesh instances in its visual scene".into());
+    }
+    let mesh_list = || {
+        instances
+            .iter()
+            .map(|(_, _, n)| n.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let (node_index, instance, name) = match (instances.len(), mesh_name) {
+        (1, None) => &instances[0],
+        (_, None) => {
+            return Err(format!(
+                "Collada file contains {} meshes. Please specify one with --mesh <name>.\nAvailable meshes: {}",
+                instances.len(),
+                mesh_list()
+            )
+            .into());
+        }
+        (_, Some(wanted)) => instances
+            .iter()
+            .find(|(_, _, n)| n == wanted)
+            .ok_or_else(|| {
+                format!(
+                    "Mesh '{}' not found in Collada file.\nAvailable meshes: {}",
+                    wanted,
+                    mesh_list()
+                )
+            })?,
+    };
+    progress!("Loading mesh: {}", name);
+
+    let target = doc
+        .get(instance.url("url"))
+        .ok_or_else(|| format!("Collada instance of '{}' has an unresolved url", name))?;
+    let (geometry, skin) = match target.name.as_str() {
+        "geometry" => (target, None),
+        "controller" => {
+            let (skin, source) = match (target.child("skin"), target.child("morph")) {
+                (Some(skin), _) => (Some(skin), skin.url("source")),
+                (None, Some(morph)) => {
+                    eprintln!(
+                        "Warning: Collada morph controllers are not supported; loading the base mesh"
+                    );
+                    (None, morph.url("source"))
+                }
+                (None, None) => (None, None),
+            };
+            let geometry = doc
+                .get(source)
+                .filter(|g| g.name == "geometry")
+                .ok_or("Collada controller does not reference a geometry")?;
+            (geometry, skin)
+        }
+        other => return Err(format!("Unexpected Collada instance target <{}>", other).into()),
+    };
+
+    // Skinned vertices are placed by the bind shape matrix and joints; static ones by their node
+    let transform = match skin {
+        Some(skin) => {
+            let bind_shape = skin
+                .child("bind_shape_matrix")
+                .map(Element::floats)
+                .unwrap_or_default();
+            let bind_shape = if bind_shape.len() == 16 {
+                Matrix4::from_row_slice(&bind_shape)
+            } else {
+                Matrix4::identity()
+            };
+            doc.correction * bind_shape
+        }
+        None => doc.correction * doc.nodes[*node_index].world,
+    };
+    let (mut mesh, face_materials) = read_geometry(&doc, geometry, &transform)?;
+    apply_materials(&mut mesh, &doc, instance, &face_materials, path);
+
+    if let Some(skin) = skin {
+        let (skeleton, node_to_joint) = read_skin(&mut mesh, &doc, skin, instance)?;
+        let clip_name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|_| !stdio::is_std(path))
+            .unwrap_or("animation");
+        mesh.animations = read_animations(&root, &doc, &skeleton, &node_to_joint, clip_name);
+        progress!(
+            "Loaded skeleton with {} joints, {} skinned vertices",
+            skeleton.joints.len(),
+            mesh.joint_indices.len()
+        );
+        if !mesh.animations.is_empty() {
+            progress!(
+                "Loaded {} animation clip(s): {}",
+                mesh.animations.len(),
+                mesh.animations
+                    .iter()
+                    .map(|a| a.name.as_deref().unwrap_or("<unnamed>"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        mesh.skeleton = Some(skeleton);
+    }
+
+    Ok(mesh)
+}
+
+/// Build the skeleton (skin joints plus their ancestors) and per-vertex weights.
+/// Returns the skeleton and the joint index of each scene node in it.
+fn read_skin(
+    mesh: &mut MeshWithColors,
+    doc: &Document,
+    skin: &Element,
+    instance: &Element,
+) -> Result<(Skeleton, HashMap<usize, usize>), Box<dyn std::error::Error>> {
+    let joints_el = skin
+        .child("joints")
+        .ok_or("Collada skin without <joints>")?;
+    let joint_inputs = inputs(joints_el);
+    let input = |semantic: &str| joint_inputs.iter().find(|i| i.semantic == semantic);
+    let names = doc.names(input("JOINT").and_then(|i| i.source));
+    let inverse_binds: Vec<Matrix4<f64>> = input("INV_BIND_MATRIX")
+        .and_then(|i| doc.source(i.source))
+        .map(|(data, _)| data.chunks_exact(16).map(Matrix4::from_row_slice).collect())
+        .unwrap_or_default();
+
+    let roots: Vec<usize> = instance
+        .children_named("skeleton")
+        .filter_map(|s| {
+            let id = s.text.trim().trim_start_matches('#');
+            doc.nodes
+                .iter()
+                .position(|n| n.element.attr("id") == Some(id))
+        })
+        .collect();
+    let skin_nodes: Vec<Option<usize>> = names
+        .iter()
+        .map(|name| {
+            let node = doc.joint_node(name, &roots);
+            if node.is_none() {
+                eprintln!(
+                    "Warning: Collada skin joint '{}' not found in the scene",
+                    name
+                );
+            }
+            node
+        })
+        .collect();
+
+    // Skin joints and every ancestor, in scene (depth-first) order so parents come first
+    let mut needed: HashSet<usize> = HashSet::new();
+    for &node in skin_nodes.iter().flatten() {
+        let mut current = Some(node);
+        while let Some(c) = current {
+            if !needed.insert(c) {
+                break;
+            }
+            current = doc.nodes[c].parent;
+        }
+    }
+    let mut node_to_joint = HashMap::new();
+    let mut joints = Vec::new();
+    for (node_index, node) in doc.nodes.iter().enumerate() {
+        if !needed.contains(&node_index) {
+            continue;
+        }
+        let index = joints.len();
+        node_to_joint.insert(node_index, index);
+        let local = doc.conjugate(&node_local(node.element, &HashMap::new()));
+        let (translation, rotation, scale) = JointTransform::Matrix(to_f32(&local)).decompose();
+        let inverse_bind = skin_nodes
+            .iter()
+            .position(|&n| n == Some(node_index))
+            .and_then(|k| inverse_binds.get(k))
+            .map(|m| doc.conjugate(m))
+            .unwrap_or_else(|| {
+                doc.conjugate(&node.world)
+                    .try_inverse()
+                    .unwrap_or_else(Matrix4::identity)
+            });
+        joints.push(Joint {
+            index,
+            node_index,
+            name: node
+                .element
+                .attr("name")
+                .or(node.element.attr("id"))
+                .map(String::from),
+            parent: node.parent.and_then(|p| node_to_joint.get(&p).copied()),
+            inverse_bind_matrix: to_f32(&inverse_bind),
+            local_transform: JointTransform::Decomposed {
+                translation,
+                rotation,
+                scale,
+            },
+        });
+    }
+    if joints.is_empty() {
+        return Err("Collada skin joints could not be found in the scene".into());
+    }
+
+    // Influences: (joint, weight) pairs per vertex, reduced to the strongest four
+    let weights_el = skin
+        .child("vertex_weights")
+        .ok_or("Collada skin without <vertex_weights>")?;
+    let weight_inputs = inputs(weights_el);
+    let stride = weight_inputs
+        .iter()
+        .map(|i| i.offset + 1)
+        .max()
+        .unwrap_or(2);
+    let joint_offset = weight_inputs
+        .iter()
+        .find(|i| i.semantic == "JOINT")
+        .map_or(0, |i| i.offset);
+    let weight_input = weight_inputs.iter().find(|i| i.semantic == "WEIGHT");
+    let weight_offset = weight_input.map_or(1, |i| i.offset);
+    let weight_values = weight_input
+        .and_then(|i| doc.source(i.source))
+        .map(|(d, _)| d)
+        .unwrap_or_default();
+    let counts = weights_el
+        .child("vcount")
+        .map(Element::ints)
+        .unwrap_or_default();
+    let v = weights_el.child("v").map(Element::ints).unwrap_or_default();
+
+    let mut cursor = 0usize;
+    for vertex in 0..mesh.positions.len() {
+        let count = counts.get(vertex).copied().unwrap_or(0).max(0) as usize;
+        let mut influences: Vec<(u16, f32)> = Vec::with_capacity(count);
+        for k in 0..count {
+            let base = cursor + k * stride;
+            let (Some(&j), Some(&w)) = (v.get(base + joint_offset), v.get(base + weight_offset))
+            else {
+                break;
+            };
+            // Joint -1 binds to the bind shape itself
+            let joint = usize::try_from(j)
+                .ok()
+                .and_then(|j| skin_nodes.get(j).copied().flatten())
+                .and_then(|n| node_to_joint.get(&n));
+            let weight = usize::try_from(w)
+                .ok()
+                .and_then(|w| weight_values.get(w))
+                .copied()
+                .unwrap_or(0.0);
+            if let Some(&joint) = joint {
+                influences.push((joint as u16, weight as f32));
+            }
+        }
+        cursor += count * stride;
+        influences.sort_by(|a, b| b.1.total_cmp(&a.1));
+        influences.truncate(4);
+        let total: f32 = influences.iter().map(|(_, w)| w).sum();
+        let mut ji = [0u16; 4];
+        let mut jw = [0.0f32; 4];
+        for (k, &(j, w)) in influences.iter().enumerate() {
+            ji[k] = j;
+            jw[k] = if total > 0.0 { w / total } else { 0.0 };
+        }
+        mesh.joint_indices.push(ji);
+        mesh.joint_weights.push(jw);
+    }
+
+    Ok((Skeleton { joints }, node_to_joint))
+}
+
+// --- Animation ---
+
+/// One animation `<channel>` with its sampler data
+struct Channel<'a> {
+    node_id: &'a str,
+    sid: &'a str,
+    /// Value index targeted inside the element (`.X`, `.ANGLE`, `(i)(j)`), or the whole element
+    member: Option<usize>,
+    times: Vec<f64>,
+    values: Vec<f64>,
+    step: bool,
+}
+
+impl Channel<'_> {
+    /// Values at `time`, linearly interpolated between keys (held outside them)
+    fn sample(&self, time: f64) -> Vec<f64> {
+        let width = self.values.len() / self.times.len().max(1);
+        let key = |i: usize| &self.values[i * width..(i + 1) * width];
+        let i = self.times.partition_point(|&t| t <= time);
+        if i == 0 {
+            return key(0).to_vec();
+        }
+        if i >= self.times.len() || self.step {
+            return key(i - 1).to_vec();
+        }
+        let (t0, t1) = (self.times[i - 1], self.times[i]);
+        let f = if t1 > t0 {
+            (time - t0) / (t1 - t0)
+        } else {
+            0.0
+        };
+        key(i - 1)
+            .iter()
+            .zip(key(i))
+            .map(|(a, b)| a + (b - a) * f)
+            .collect()
+    }
+}
+
+/// Parse `node/sid`, `node/sid.X` or `node/sid(i)(j)` channel targets
+fn parse_target(target: &str) -> Option<(&str, &str, Option<usize>)> {
+    let (node_id, rest) = target.split_once('/')?;
+    let end = rest.find(['.', '(']).unwrap_or(rest.len());
+    let (sid, member) = rest.split_at(end);
+    let member = if let Some(field) = member.strip_prefix('.') {
+        Some(match field {
+            "X" | "R" | "S" | "U" => 0,
+            "Y" | "G" | "T" | "V" => 1,
+            "Z" | "B" | "P" => 2,
+            "ANGLE" | "W" | "A" | "Q" => 3,
+            _ => return None,
+        })
+    } else if !member.is_empty() {
+        let indices: Vec<usize> = member
+            .split(['(', ')'])
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().ok())
+            .collect::<Option<_>>()?;
+        match indices[..] {
+            [i] => Some(i),
+            // Matrix members are (row)(column)
+            [row, column] => Some(row * 4 + column),
+            _ => return None,
+        }
+    } else {
+        None
+    };
+    Some((node_id, sid, member))
+}
+
+fn read_channels<'a>(doc: &Document<'a>, animation: &'a Element, out: &mut Vec<Channel<'a>>) {
+    for channel in animation.children_named("channel") {
+        let Some((node_id, sid, member)) = channel.attr("target").and_then(parse_target) else {
+            continue;
+        };
+        let Some(sampler) = doc.get(channel.url("source")) else {
+            continue;
+        };
+        let sampler_inputs = inputs(sampler);
+        let source = |semantic: &str| {
+            sampler_inputs
+                .iter()
+                .find(|i| i.semantic == semantic)
+                .and_then(|i| doc.source(i.source))
+                .map(|(d, _)| d)
+        };
+        let (Some(times), Some(values)) = (source("INPUT"), source("OUTPUT")) else {
+            continue;
+        };
+        if times.is_empty() || values.len() < times.len() {
+            continue;
+        }
+        let step = sampler_inputs
+            .iter()
+            .find(|i| i.semantic == "INTERPOLATION")
+            .map(|i| doc.names(i.source))
+            .is_some_and(|names| names.iter().all(|n| n == "STEP"));
+        out.push(Channel {
+            node_id,
+            sid,
+            member,
+            times,
+            values,
+            step,
+        });
+    }
+    for nested in animation.children_named("animation") {
+        read_channels(doc, nested, out);
+    }
+}
+
+/// Convert animations targeting skeleton nodes into clips: one per
+/// `animation_clip`, or a single clip named `default_name`
+fn read_animations(
+    root: &Element,
+    doc: &Document,
+    skeleton: &Skeleton,
+    node_to_joint: &HashMap<usize, usize>,
+    default_name: &str,
+) -> Vec<AnimationClip> {
+    let Some(library) = root.child("library_animations") else {
+        return Vec::new();
+    };
+
+    // Channels and the time window of each clip
+    struct ClipSource<'a> {
+        name: String,
+        start: f64,
+        end: Option<f64>,
+        channels: Vec<Channel<'a>>,
+    }
+    let mut clips: Vec<ClipSource> = Vec::new();
+    let clip_library = root.child("library_animation_clips");
+    for clip in clip_library
+        .iter()
+        .flat_map(|l| l.children_named("animation_clip"))
+    {
+        let mut channels = Vec::new();
+        for instance in clip.children_named("instance_animation") {
+            if let Some(animation) = doc.get(instance.url("url")) {
+                read_channels(doc, animation, &mut channels);
+            }
+        }
+        let time = |name: &str| clip.attr(name).and_then(|t| t.parse::<f64>().ok());
+        let name = clip
+            .attr("name")
+            .or(clip.attr("id"))
+            .unwrap_or(default_name);
+        clips.push(ClipSource {
+            name: name.to_string(),
+            start: time("start").unwrap_or(0.0),
+            end: time("end"),
+            channels,
+        });
+    }
+    if clips.is_empty() {
+        let mut channels = Vec::new();
+        for animation in library.children_named("animation") {
+            read_channels(doc, animation, &mut channels);
+        }
+        clips.push(ClipSource {
+            name: default_name.to_string(),
+            start: 0.0,
+            end: None,
+            channels,
+        });
+    }
+
+    let mut result = Vec::new();
+    for ClipSource {
+        name,
+        start,
+        end,
+        channels,
+    } in clips
+    {
+        // joint -> channels animating its node
+        let mut by_joint: HashMap<usize, Vec<&Channel>> = HashMap::new();
+        for channel in &channels {
+            let joint = doc
+                .nodes
+                .iter()
+                .position(|n| n.element.attr("id") == Some(channel.node_id))
+                .and_then(|n| node_to_joint.get(&n));
+            if let Some(&joint) = joint {
+                by_joint.entry(joint).or_default().push(channel);
+            }
+        }
+        if by_joint.is_empty() {
+            continue;
+        }
+
+        let in_range = |t: f64| t >= start - 1e-9 && end.is_none_or(|e| t <= e + 1e-9);
+        let mut animated: Vec<_> = by_joint.into_iter().collect();
+        animated.sort_by_key(|(j, _)| *j);
+        let mut out_channels = Vec::new();
+        let mut duration = 0.0f32;
+        for (joint, channels) in animated {
+            let node = doc.nodes[skeleton.joints[joint].node_index].element;
+            let mut times: Vec<f64> = channels
+                .iter()
+                .flat_map(|c| c.times.iter().copied())
+                .filter(|&t| in_range(t))
+                .collect();
+            times.sort_by(f64::total_cmp);
+            times.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+
+            let mut keys: [Vec<Keyframe>; 3] = Default::default();
+            let mut previous_rotation: Option<[f32; 4]> = None;
+            for &t in &times {
+                let mut overrides: HashMap<String, Vec<f64>> = HashMap::new();
+                for channel in &channels {
+                    let sampled = channel.sample(t);
+                    match channel.member {
+                        None => {
+                            overrides.insert(channel.sid.to_string(), sampled);
+                        }
+                        Some(member) => {
+                            let values =
+                                overrides.entry(channel.sid.to_string()).or_insert_with(|| {
+                                    node.children
+                                        .iter()
+                                        .find(|c| c.attr("sid") == Some(channel.sid))
+                                        .map(Element::floats)
+                                        .unwrap_or_default()
+                                });
+                            if let (Some(slot), Some(&v)) =
+                                (values.get_mut(member), sampled.first())
+                            {
+                                *slot = v;
+                            }
+                        }
+                    }
+                }
+                let local = doc.conjugate(&node_local(node, &overrides));
+                let (translation, mut rotation, scale) =
+                    JointTransform::Matrix(to_f32(&local)).decompose();
+                // Keep consecutive quaternions in the same hemisphere for slerp
+                if let Some(prev) = previous_rotation
+                    && prev.iter().zip(&rotation).map(|(a, b)| a * b).sum::<f32>() < 0.0
+                {
+                    rotation = rotation.map(|c| -c);
+                }
+                previous_rotation = Some(rotation);
+
+                let time = (t - start).max(0.0) as f32;
+                duration = duration.max(time);
+                for (k, value) in [translation.to_vec(), rotation.to_vec(), scale.to_vec()]
+                    .into_iter()
+                    .enumerate()
+                {
+                    keys[k].push(Keyframe {
+                        time,
+                        value,
+                        in_tangent: None,
+                        out_tangent: None,
+                    });
+                }
+            }
+            let properties = [
+                AnimationProperty::Translation,
+                AnimationProperty::Rotation,
+                AnimationProperty::Scale,
+            ];
+            for (property, keyframes) in properties.into_iter().zip(keys) {
+                if !keyframes.is_empty() {
+                    out_channels.push(AnimationChannel {
+                        joint_index: joint,
+                        property,
+                        interpolation: Interpolation::Linear,
+                        keyframes,
+                    });
+                }
+            }
+        }
+        result.push(AnimationClip {
+            name: Some(name),
+            channels: out_channels,
+            duration,
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::animation;
+
+    const SKINNED: &str = r##"<?xml version="1.0" encoding="utf-8"?>
+<COLLADA xmlns="http://www.collada.org/2005/11/COLLADASchema" version="1.4.1">
+  <asset>
+    <unit name="centimeter" meter="0.01"/>
+    <up_axis>Z_UP</up_axis>
+  </asset>
+  <library_effects>
+    <effect id="red-fx">
+      <profile_COMMON>
+        <technique sid="common">
+          <lambert><diffuse><color>1 0 0 1</color></diffuse></lambert>
+        </technique>
+      </profile_COMMON>
+    </effect>
+  </library_effects>
+  <library_materials>
+    <material id="red" name="red"><instance_effect url="#red-fx"/></material>
+  </library_materials>
+  <library_geometries>
+    <geometry id="body-mesh" name="body">
+      <mesh>
+        <source id="body-pos">
+          <float_array id="body-pos-array" count="15">0 0 0 100 0 0 100 0 100 0 0 100 0 0 200</float_array>
+          <technique_common>
+            <accessor source="#body-pos-array" count="5" stride="3"/>
+          </technique_common>
+        </source>
+        <source id="body-uv">
+          <float_array id="body-uv-array" count="4">0 0 1 1</float_array>
+          <technique_common>
+            <accessor source="#body-uv-array" count="2" stride="2"/>
+          </technique_common>
+        </source>
+        <vertices id="body-vtx">
+          <input semantic="POSITION" source="#body-pos"/>
+        </vertices>
+        <polylist material="mat0" count="2">
+          <input semantic="VERTEX" source="#body-vtx" offset="0"/>
+          <input semantic="TEXCOORD" source="#body-uv" offset="1" set="0"/>
+          <vcount>4 3</vcount>
+          <p>0 0 1 1 2 1 3 0 3 0 2 1 4 1</p>
+        </polylist>
+      </mesh>
+    </geometry>
+  </library_geometries>
+  <library_controllers>
+    <controller id="body-skin">
+      <skin source="#body-mesh">
+        <bind_shape_matrix>1 0 0 0 0 1 0 0 0 0 1 0 0 0 0 1</bind_shape_matrix>
+        <source id="skin-joints">
+          <Name_array id="skin-joints-array" count="2">hip knee</Name_array>
+        </source>
+        <source id="skin-ibm">
+          <float_array id="skin-ibm-array" count="32">1 0 0 0 0 1 0 0 0 0 1 0 0 0 0 1 1 0 0 0 0 1 0 0 0 0 1 -100 0 0 0 1</float_array>
+          <technique_common>
+            <accessor source="#skin-ibm-array" count="2" stride="16"/>
+          </technique_common>
+        </source>
+        <source id="skin-weights">
+          <float_array id="skin-weights-array" count="3">1 0.75 0.25</float_array>
+          <technique_common>
+            <accessor source="#skin-weights-array" count="3" stride="1"/>
+          </technique_common>
+        </source>
+        <joints>
+          <input semantic="JOINT" source="#skin-joints"/>
+          <input semantic="INV_BIND_MATRIX" source="#skin-ibm"/>
+        </joints>
+        <vertex_weights count="5">
+          <input semantic="JOINT" source="#skin-joints" offset="0"/>
+          <input semantic="WEIGHT" source="#skin-weights" offset="1"/>
+          <vcount>1 1 2 2 1</vcount>
+          <v>0 0 0 0 0 1 1 2 0 1 1 2 1 0</v>
+        </vertex_weights>
+      </skin>
+    </controller>
+  </library_controllers>
+  <library_animations>
+    <animation id="knee-anim">
+      <source id="knee-time">
+        <float_array id="knee-time-array" count="2">0 2</float_array>
+        <technique_common><accessor source="#knee-time-array" count="2" stride="1"/></technique_common>
+      </source>
+      <source id="knee-angle">
+        <float_array id="knee-angle-array" count="2">0 90</float_array>
+        <technique_common><accessor source="#knee-angle-array" count="2" stride="1"/></technique_common>
+      </source>
+      <sampler id="knee-sampler">
+        <input semantic="INPUT" source="#knee-time"/>
+        <input semantic="OUTPUT" source="#knee-angle"/>
+      </sampler>
+      <channel source="#knee-sampler" target="knee/rotateX.ANGLE"/>
+    </animation>
+  </library_animations>
+  <library_visual_scenes>
+    <visual_scene id="scene">
+      <node id="Armature" name="Armature" type="NODE">
+        <node id="hip" sid="hip" name="hip" type="JOINT">
+          <node id="knee" sid="knee" name="knee" type="JOINT">
+            <translate sid="location">0 0 100</translate>
+            <rotate sid="rotateX">1 0 0 0</rotate>
+          </node>
+        </node>
+      </node>
+      <node id="Body" name="Body" type="NODE">
+        <instance_controller url="#body-skin">
+          <skeleton>#hip</skeleton>
+          <bind_material>
+            <technique_common>
+              <instance_material symbol="mat0" target="#red"/>
+            </technique_common>
+          </bind_material>
+        </instance_controller>
+      </node>
+    </visual_scene>
+  </library_visual_scenes>
+  <scene><instance_visual_scene url="#scene"/></scene>
+</COLLADA>
+"##;
+
+    #[test]
+    fn parses_channel_targets() {
+        assert_eq!(
+            parse_target("knee/rotateX.ANGLE"),
+            Some(("knee", "rotateX", Some(3)))
+        );
+        assert_eq!(
+            parse_target("hip/transform"),
+            Some(("hip", "transform", None))
+        );
+        assert_eq!(
+            parse_target("hip/transform(0)(3)"),
+            Some(("hip", "transform", Some(3)))
+        );
+        assert_eq!(
+            parse_target("hip/location.Z"),
+            Some(("hip", "location", Some(2)))
+        );
+    }
+
+    #[test]
+    fn loads_geometry_materials_skin_and_animation() {
+        let dir = std::env::temp_dir().join(format!("msh_collada_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("walk.dae");
+        std::fs::write(&path, SKINNED).unwrap();
+
+        let mesh = load_collada(&path, None).unwrap();
+        // Centimeters become meters, Z up becomes Y up
+        assert_eq!(mesh.positions[2], [1.0, 1.0, 0.0]);
+        assert_eq!(mesh.positions[4], [0.0, 2.0, 0.0]);
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2], [0, 2, 3], [3, 2, 4]]);
+        assert_eq!(
+            mesh.corner_texcoords[0],
+            [[0.0, 1.0], [1.0, 0.0], [1.0, 0.0]]
+        );
+        assert!(mesh.face_colors.iter().all(|c| *c == [1.0, 0.0, 0.0, 1.0]));
+
+        let skeleton = mesh.skeleton.as_ref().unwrap();
+        let names: Vec<_> = skeleton
+            .joints
+            .iter()
+            .map(|j| j.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, ["Armature", "hip", "knee"]);
+        assert_eq!(skeleton.joints[2].parent, Some(1));
+        assert_eq!(mesh.joint_indices[2], [1, 2, 0, 0]);
+        assert_eq!(mesh.joint_weights[2], [0.75, 0.25, 0.0, 0.0]);
+        let (t, _, _) = skeleton.joints[2].local_transform.decompose();
+        assert!((t[1] - 1.0).abs() < 1e-5 && t[2].abs() < 1e-5);
+        assert!((skeleton.joints[2].inverse_bind_matrix[3][1] + 1.0).abs() < 1e-5);
+
+        // Bind pose leaves skinned vertices in place
+        let joint_matrices = skeleton.compute_joint_matrices();
+        let knee = Matrix4::from(joint_matrices[2]);
+        let p = knee.transform_point(&Point3::new(0.0, 2.0, 0.0));
+        assert!((p - Point3::new(0.0, 2.0, 0.0)).norm() < 1e-5);
+
+        let clip = &mesh.animations[0];
+        assert_eq!(clip.name.as_deref(), Some("walk"));
+        assert!((clip.duration - 2.0).abs() < 1e-6);
+        let end = animation::evaluate_animation(clip, skeleton, 2.0);
+        // The X axis is unchanged by the Z-up conversion
+        let (_, r, _) = JointTransform::Matrix(end[2]).decompose();
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((r[0].abs() - half).abs() < 1e-4 && (r[3].abs() - half).abs() < 1e-4);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
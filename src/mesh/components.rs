@@ -0,0 +1,235 @@
+//! Connected components ("islands") of a mesh.
+//!
+//! Faces are connected through shared vertices, welded by position so UV and
+//! normal seams don't split a component apart.
+
+use super::loader::{MeshWithColors, MorphTarget};
+use std::collections::HashMap;
+
+/// One connected component
+#[derive(Clone, Debug)]
+pub struct Component {
+    /// Face indices into the source mesh, ascending
+    pub faces: Vec<usize>,
+    pub area: f32,
+    /// Enclosed volume, measured from the component's centroid so open pieces
+    /// still get a size
+    pub volume: f32,
+    /// Whether every edge is shared by exactly two of its faces
+    pub closed: bool,
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+/// Which components to keep
+#[derive(Clone, Debug, Default)]
+pub struct ComponentFilter {
+    /// Keep only this many components, largest (by face count) first
+    pub keep_largest: Option<usize>,
+    /// Drop components with fewer faces
+    pub min_faces: Option<usize>,
+    /// Drop components enclosing less volume
+    pub min_volume: Option<f32>,
+}
+
+impl ComponentFilter {
+    /// True if the filter would drop anything at all
+    pub fn is_active(&self) -> bool {
+        self.keep_largest.is_some() || self.min_faces.is_some() || self.min_volume.is_some()
+    }
+
+    /// Whether each of `components` (largest first, as from [`find_components`]) is kept
+    pub fn keeps(&self, components: &[Component]) -> Vec<bool> {
+        let mut kept = 0;
+        components
+            .iter()
+            .map(|component| {
+                let keep = self.min_faces.is_none_or(|min| component.faces.len() >= min)
+                    && self.min_volume.is_none_or(|min| component.volume >= min)
+                    && self.keep_largest.is_none_or(|n| kept < n);
+                kept += keep as usize;
+                keep
+            })
+            .collect()
+    }
+}
+
+/// Connected components of `mesh`, largest (by face count) first
+pub fn find_components(mesh: &MeshWithColors) -> Vec<Component> {
+    let mut weld_index: HashMap<[u32; 3], usize> = HashMap::new();
+    let weld: Vec<usize> = mesh
+        .positions
+        .iter()
+        .map(|p| {
+            let next = weld_index.len();
+            *weld_index.entry(p.map(f32::to_bits)).or_insert(next)
+        })
+        .collect();
+    let valid = |face: &[u32; 3]| face.iter().all(|&v| (v as usize) < weld.len());
+
+    let mut parent: Vec<usize> = (0..weld_index.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for face in mesh.face_indices.iter().filter(|face| valid(face)) {
+        let a = root(&mut parent, weld[face[0] as usize]);
+        for &v in &face[1..] {
+            let b = root(&mut parent, weld[v as usize]);
+            parent[b] = a;
+        }
+    }
+
+    let mut by_root: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (f, face) in mesh.face_indices.iter().enumerate().filter(|(_, face)| valid(face)) {
+        by_root.entry(root(&mut parent, weld[face[0] as usize])).or_default().push(f);
+    }
+
+    let mut components: Vec<Component> = by_root.into_values().map(|faces| measure(mesh, &weld, faces)).collect();
+    components.sort_by(|a, b| b.faces.len().cmp(&a.faces.len()).then(a.faces[0].cmp(&b.faces[0])));
+    components
+}
+
+fn measure(mesh: &MeshWithColors, weld: &[usize], faces: Vec<usize>) -> Component {
+    let corners = || faces.iter().flat_map(|&f| mesh.face_indices[f]).map(|v| mesh.positions[v as usize]);
+    let (mut min, mut max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+    let mut centroid = [0.0f64; 3];
+    for p in corners() {
+        for k in 0..3 {
+            min[k] = min[k].min(p[k]);
+            max[k] = max[k].max(p[k]);
+            centroid[k] += p[k] as f64;
+        }
+    }
+    let centroid = centroid.map(|c| c / (3 * faces.len()) as f64);
+
+    let (mut area, mut volume) = (0.0f64, 0.0f64);
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for &f in &faces {
+        let face = mesh.face_indices[f];
+        let [a, b, c] = face.map(|v| {
+            let p = mesh.positions[v as usize];
+            [p[0] as f64 - centroid[0], p[1] as f64 - centroid[1], p[2] as f64 - centroid[2]]
+        });
+        let cross = |u: [f64; 3], v: [f64; 3]| [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        let dot = |u: [f64; 3], v: [f64; 3]| u[0] * v[0] + u[1] * v[1] + u[2] * v[2];
+        let n = cross([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+        area += dot(n, n).sqrt() / 2.0;
+        volume += dot(a, cross(b, c)) / 6.0;
+        for k in 0..3 {
+            let (u, v) = (weld[face[k] as usize], weld[face[(k + 1) % 3] as usize]);
+            *edges.entry((u.min(v), u.max(v))).or_insert(0) += 1;
+        }
+    }
+
+    Component {
+        faces,
+        area: area as f32,
+        volume: volume.abs() as f32,
+        closed: edges.values().all(|&count| count == 2),
+        min,
+        max,
+    }
+}
+
+/// A copy of `mesh` holding only `faces`, with unused vertices dropped and
+/// every attribute carried along
+pub fn extract_faces(mesh: &MeshWithColors, faces: &[usize]) -> MeshWithColors {
+    let mut faces = faces.to_vec();
+    faces.sort_unstable();
+
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    let mut used: Vec<usize> = Vec::new();
+    let face_indices: Vec<[u32; 3]> = faces
+        .iter()
+        .map(|&f| {
+            mesh.face_indices[f].map(|v| {
+                *remap.entry(v).or_insert_with(|| {
+                    used.push(v as usize);
+                    (used.len() - 1) as u32
+                })
+            })
+        })
+        .collect();
+
+    let vertex_count = mesh.positions.len();
+    fn per_vertex<T: Clone>(values: &[T], used: &[usize], vertex_count: usize) -> Vec<T> {
+        if values.len() == vertex_count { used.iter().map(|&v| values[v].clone()).collect() } else { Vec::new() }
+    }
+    fn per_face<T: Clone>(values: &[T], faces: &[usize], face_count: usize) -> Vec<T> {
+        if values.len() == face_count { faces.iter().map(|&f| values[f].clone()).collect() } else { Vec::new() }
+    }
+    let face_count = mesh.face_indices.len();
+
+    MeshWithColors {
+        positions: per_vertex(&mesh.positions, &used, vertex_count),
+        face_indices,
+        face_colors: per_face(&mesh.face_colors, &faces, face_count),
+        vertex_colors: per_vertex(&mesh.vertex_colors, &used, vertex_count),
+        normals: per_vertex(&mesh.normals, &used, vertex_count),
+        texcoords: per_vertex(&mesh.texcoords, &used, vertex_count),
+        corner_texcoords: per_face(&mesh.corner_texcoords, &faces, face_count),
+        corner_normals: per_face(&mesh.corner_normals, &faces, face_count),
+        texture: mesh.texture.clone(),
+        skeleton: mesh.skeleton.clone(),
+        joint_indices: per_vertex(&mesh.joint_indices, &used, vertex_count),
+        joint_weights: per_vertex(&mesh.joint_weights, &used, vertex_count),
+        animations: mesh.animations.clone(),
+        morph_targets: mesh
+            .morph_targets
+            .iter()
+            .map(|target| MorphTarget {
+                name: target.name.clone(),
+                position_deltas: per_vertex(&target.position_deltas, &used, vertex_count),
+            })
+            .collect(),
+        morph_weights: mesh.morph_weights.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A closed tetrahedron scaled by `size` at `x`, followed by a lone triangle
+    fn islands() -> MeshWithColors {
+        let mut mesh = MeshWithColors::default();
+        for (x, size) in [(0.0, 2.0), (5.0, 1.0)] {
+            let base = mesh.positions.len() as u32;
+            mesh.positions.extend([[x, 0.0, 0.0], [x + size, 0.0, 0.0], [x, size, 0.0], [x, 0.0, size]]);
+            mesh.face_indices.extend([[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]].map(|f: [u32; 3]| f.map(|v| v + base)));
+        }
+        mesh.positions.extend([[9.0, 0.0, 0.0], [10.0, 0.0, 0.0], [9.0, 1.0, 0.0]]);
+        mesh.face_indices.push([8, 9, 10]);
+        mesh.face_colors = (0..9).map(|f| [f as f32, 0.0, 0.0, 1.0]).collect();
+        mesh.texcoords = (0..11).map(|v| [v as f32, 0.0]).collect();
+        mesh
+    }
+
+    #[test]
+    fn measures_and_filters_islands() {
+        let components = find_components(&islands());
+        assert_eq!(components.iter().map(|c| c.faces.len()).collect::<Vec<_>>(), vec![4, 4, 1]);
+        assert!(components[0].closed && !components[2].closed);
+        assert!((components[0].volume - 8.0 / 6.0).abs() < 1e-5);
+        assert_eq!(components[2].max, [10.0, 1.0, 0.0]);
+
+        let filter = ComponentFilter { min_faces: Some(2), ..Default::default() };
+        assert_eq!(filter.keeps(&components), vec![true, true, false]);
+        let filter = ComponentFilter { keep_largest: Some(1), min_volume: Some(0.5), ..Default::default() };
+        assert_eq!(filter.keeps(&components), vec![true, false, false]);
+    }
+
+    #[test]
+    fn extracted_component_keeps_attributes() {
+        let mesh = islands();
+        let component = extract_faces(&mesh, &[4, 5, 6, 7]);
+        assert_eq!(component.positions.len(), 4);
+        assert_eq!(component.face_indices[0], [0, 1, 2]);
+        assert_eq!(component.face_colors[0], [4.0, 0.0, 0.0, 1.0]);
+        assert_eq!(component.texcoords[0], [4.0, 0.0]);
+    }
+}
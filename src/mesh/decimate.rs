@@ -0,0 +1,728 @@
+//! Quadric error metric decimation (Garland & Heckbert).
+//!
+//! Edges of a `CornerTableF` are collapsed cheapest-first, each collapse
+//! placing the merged vertex where the summed plane quadrics of both
+//! endpoints are smallest. Boundary edges get extra perpendicular planes so
+//! open borders don't shrink. Locked vertices (boundaries, UV seams, face
+//! color borders, depending on the options) never move: their neighbours
+//! collapse onto them instead.
+//!
+//! Every surviving vertex keeps the attributes (UVs, colors, skin, morph
+//! deltas) of one input vertex, and every surviving face its input face
+//! color, so seams and borders come out exactly as they went in.
+
+use baby_shark::algo::edge_collapse;
+use baby_shark::exports::nalgebra::{Matrix3, Vector3};
+use baby_shark::geometry::primitives::triangle3::Triangle3;
+use baby_shark::geometry::traits::ClosestPoint3;
+use baby_shark::io::{Builder, IndexedBuilder};
+use baby_shark::mesh::corner_table::{CornerTableF, EdgeId, FaceId, VertexAttribute, VertexId};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use super::loader::{MeshWithColors, MorphTarget};
+
+/// Collapses that would drop a neighbouring face below this fraction of its
+/// quality are rejected (matches baby_shark's decimator)
+const MIN_FACE_QUALITY: f32 = 0.1;
+/// Weight of the planes that hold boundary edges in place
+const BOUNDARY_WEIGHT: f64 = 100.0;
+
+/// How many faces decimation should leave
+#[derive(Clone, Copy, Debug)]
+pub enum DecimateTarget {
+    Faces(usize),
+    /// Fraction of the input face count
+    Ratio(f32),
+}
+
+impl DecimateTarget {
+    pub fn face_count(self, input_faces: usize) -> usize {
+        match self {
+            DecimateTarget::Faces(n) => n,
+            DecimateTarget::Ratio(r) => (input_faces as f64 * r as f64).round() as usize,
+        }
+    }
+}
+
+/// Options controlling decimation
+#[derive(Clone, Debug)]
+pub struct DecimateOptions {
+    pub target: DecimateTarget,
+    /// Keep boundary vertices in place
+    pub lock_boundary: bool,
+    /// Keep vertices on UV (and vertex color) seams in place
+    pub preserve_seams: bool,
+    /// Keep vertices between faces of different colors in place
+    pub preserve_color_borders: bool,
+}
+
+/// Outcome of a decimation
+#[derive(Clone, Debug)]
+pub struct DecimateReport {
+    pub faces_before: usize,
+    pub faces_after: usize,
+    pub target_faces: usize,
+    /// Input faces left out: degenerate once welded, or repeating a directed edge
+    pub skipped_faces: usize,
+    /// Largest distance from an input vertex to the decimated surface
+    pub max_error: f32,
+    /// Diagonal of the input's bounding box, for putting `max_error` in scale
+    pub diagonal: f32,
+}
+
+/// Error quadric: the symmetric 4x4 matrix sum of squared plane distances
+#[derive(Clone, Copy, Default)]
+struct Quadric {
+    /// Upper triangle, row by row: xx xy xz xw yy yz yw zz zw ww
+    m: [f64; 10],
+}
+
+impl Quadric {
+    /// Quadric of the plane through `point` with unit `normal`, scaled by `weight`
+    fn plane(normal: Vector3<f64>, point: Vector3<f64>, weight: f64) -> Self {
+        let [a, b, c] = [normal.x, normal.y, normal.z];
+        let d = -normal.dot(&point);
+        let m = [a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d];
+        Quadric { m: m.map(|v| v * weight) }
+    }
+
+    fn add(&self, other: &Quadric) -> Quadric {
+        let mut m = self.m;
+        for (v, o) in m.iter_mut().zip(other.m) {
+            *v += o;
+        }
+        Quadric { m }
+    }
+
+    fn error(&self, p: &Vector3<f64>) -> f64 {
+        let [xx, xy, xz, xw, yy, yz, yw, zz, zw, ww] = self.m;
+        let (x, y, z) = (p.x, p.y, p.z);
+        let e = xx * x * x + 2.0 * xy * x * y + 2.0 * xz * x * z + 2.0 * xw * x
+            + yy * y * y
+            + 2.0 * yz * y * z
+            + 2.0 * yw * y
+            + zz * z * z
+            + 2.0 * zw * z
+            + ww;
+        e.max(0.0)
+    }
+
+    /// Point minimising the error, if the quadric is well conditioned
+    fn minimum(&self) -> Option<Vector3<f64>> {
+        let [xx, xy, xz, xw, yy, yz, yw, zz, zw, _] = self.m;
+        let a = Matrix3::new(xx, xy, xz, xy, yy, yz, xz, yz, zz);
+        if a.determinant().abs() < 1e-12 {
+            return None;
+        }
+        a.try_inverse().map(|inv| -(inv * Vector3::new(xw, yw, zw)))
+    }
+}
+
+/// A queued collapse; stale once either endpoint has changed
+struct Candidate {
+    cost: f64,
+    edge: EdgeId,
+    vertices: (VertexId, VertexId),
+    versions: (u32, u32),
+    at: Vector3<f32>,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // Reversed so the max-heap pops the cheapest collapse first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// Per-vertex state carried through collapses
+struct VertexState {
+    quadric: VertexAttribute<Quadric>,
+    locked: VertexAttribute<bool>,
+    version: VertexAttribute<u32>,
+    /// Input vertex whose attributes the vertex carries
+    source: VertexAttribute<usize>,
+    /// Input vertices merged into the vertex, for measuring the error
+    absorbed: VertexAttribute<Vec<usize>>,
+}
+
+fn to_f64(v: &Vector3<f32>) -> Vector3<f64> {
+    v.cast::<f64>()
+}
+
+/// Bit pattern key of a float slice, for exact welding
+fn key_of(values: &[f32]) -> Vec<u32> {
+    values.iter().map(|v| v.to_bits()).collect()
+}
+
+/// Decimate `mesh` towards `options.target` faces
+pub fn decimate(
+    mesh: &MeshWithColors,
+    options: &DecimateOptions,
+) -> Result<(MeshWithColors, DecimateReport), Box<dyn std::error::Error>> {
+    if let DecimateTarget::Ratio(ratio) = options.target
+        && !(ratio > 0.0 && ratio <= 1.0)
+    {
+        return Err(format!("Decimation ratio {} is outside (0, 1]", ratio).into());
+    }
+    let source = mesh.with_vertex_attributes();
+    let n = source.positions.len();
+    let has_uvs = source.texcoords.len() == n;
+    let has_vertex_colors = source.vertex_colors.len() == n;
+    let has_face_colors = source.face_colors.len() == source.face_indices.len() && !source.face_colors.is_empty();
+
+    // Weld coincident vertices; with seams preserved, only where the
+    // attributes agree too, so seams stay open and keep both sides' values
+    let mut welded_of: HashMap<Vec<u32>, usize> = HashMap::new();
+    let mut weld = Vec::with_capacity(n);
+    let mut representative: Vec<usize> = Vec::new();
+    for i in 0..n {
+        let mut key = key_of(&source.positions[i]);
+        if options.preserve_seams {
+            if has_uvs {
+                key.extend(key_of(&source.texcoords[i]));
+            }
+            if has_vertex_colors {
+                key.extend(key_of(&source.vertex_colors[i]));
+            }
+        }
+        let next = representative.len();
+        let w = *welded_of.entry(key).or_insert(next);
+        if w == next {
+            representative.push(i);
+        }
+        weld.push(w);
+    }
+
+    // Keep the faces the corner table will accept, so table faces map back
+    // to input faces in order: no degenerate faces and no repeated directed edge
+    let mut directed: HashSet<(usize, usize)> = HashSet::new();
+    let mut faces: Vec<([usize; 3], usize)> = Vec::new();
+    for (f, tri) in source.face_indices.iter().enumerate() {
+        let [a, b, c] = tri.map(|i| weld[i as usize]);
+        if a == b || b == c || c == a {
+            continue;
+        }
+        let edges = [(b, c), (c, a), (a, b)];
+        if edges.iter().any(|e| directed.contains(e)) {
+            continue;
+        }
+        directed.extend(edges);
+        faces.push(([a, b, c], f));
+    }
+    let faces_before = source.face_indices.len();
+    let target_faces = options.target.face_count(faces_before);
+
+    let mut builder = CornerTableF::builder_indexed();
+    builder.set_num_vertices(representative.len());
+    for &i in &representative {
+        builder
+            .add_vertex(source.positions[i])
+            .map_err(|e| format!("Failed to add vertex: {:?}", e))?;
+    }
+    builder.set_num_faces(faces.len());
+    for ([a, b, c], _) in &faces {
+        builder
+            .add_face(*a, *b, *c)
+            .map_err(|e| format!("Failed to add face: {:?}", e))?;
+    }
+    let mut table = builder
+        .finish()
+        .map_err(|e| format!("Failed to build mesh: {:?}", e))?;
+
+    // Table faces come out in the order they were added; recover which
+    // welded vertex each table vertex is (non-manifold vertices get copies)
+    let mut welded_vertex: HashMap<VertexId, usize> = HashMap::new();
+    let mut input_face: HashMap<FaceId, usize> = HashMap::new();
+    for (face, (tri, f)) in table.faces().zip(&faces) {
+        let (v0, v1, v2) = table.face_vertices(face);
+        for (v, w) in [v0, v1, v2].into_iter().zip(tri) {
+            welded_vertex.insert(v, *w);
+        }
+        input_face.insert(face, *f);
+    }
+
+    // Positions shared by several welded vertices lie on a seam
+    let mut position_uses: HashMap<Vec<u32>, usize> = HashMap::new();
+    for &i in &representative {
+        *position_uses.entry(key_of(&source.positions[i])).or_default() += 1;
+    }
+    let mut border_colors: HashMap<VertexId, [u32; 4]> = HashMap::new();
+    let mut color_border: HashSet<VertexId> = HashSet::new();
+    if options.preserve_color_borders && has_face_colors {
+        for face in table.faces() {
+            let color = source.face_colors[input_face[&face]].map(f32::to_bits);
+            let (v0, v1, v2) = table.face_vertices(face);
+            for v in [v0, v1, v2] {
+                if *border_colors.entry(v).or_insert(color) != color {
+                    color_border.insert(v);
+                }
+            }
+        }
+    }
+
+    let mut state = VertexState {
+        quadric: table.create_vertex_attribute(),
+        locked: table.create_vertex_attribute(),
+        version: table.create_vertex_attribute(),
+        source: table.create_vertex_attribute(),
+        absorbed: table.create_vertex_attribute(),
+    };
+    let vertices: Vec<VertexId> = table.vertices().collect();
+    for &v in &vertices {
+        let Some(&w) = welded_vertex.get(&v) else { continue };
+        let input = representative[w];
+        let on_boundary = table.is_vertex_on_boundary(v);
+        state.source[v] = input;
+        state.absorbed[v] = vec![input];
+        state.locked[v] = (options.lock_boundary && on_boundary)
+            || (options.preserve_seams && position_uses[&key_of(&source.positions[input])] > 1)
+            || color_border.contains(&v);
+    }
+
+    // Area-weighted face planes, plus perpendicular planes along boundaries
+    for face in table.faces().collect::<Vec<_>>() {
+        let (v0, v1, v2) = table.face_vertices(face);
+        let [p0, p1, p2] = [v0, v1, v2].map(|v| to_f64(table.vertex_position(v)));
+        let cross = (p1 - p0).cross(&(p2 - p0));
+        let area = cross.norm() * 0.5;
+        if area <= 0.0 {
+            continue;
+        }
+        let normal = cross.normalize();
+        let q = Quadric::plane(normal, p0, area);
+        for v in [v0, v1, v2] {
+            state.quadric[v] = state.quadric[v].add(&q);
+        }
+        let (e0, e1, e2) = table.face_edges(face);
+        for edge in [e0, e1, e2] {
+            if !table.is_edge_on_boundary(edge) {
+                continue;
+            }
+            let (a, b) = table.edge_vertices(edge);
+            let (pa, pb) = (to_f64(table.vertex_position(a)), to_f64(table.vertex_position(b)));
+            let along = pb - pa;
+            let side = along.cross(&normal);
+            if side.norm() <= 0.0 {
+                continue;
+            }
+            let q = Quadric::plane(side.normalize(), pa, along.norm_squared() * BOUNDARY_WEIGHT);
+            state.quadric[a] = state.quadric[a].add(&q);
+            state.quadric[b] = state.quadric[b].add(&q);
+        }
+    }
+
+    let mut heap = BinaryHeap::new();
+    for edge in table.unique_edges() {
+        heap.extend(candidate(&table, &state, edge));
+    }
+
+    let mut face_count = table.count_faces();
+    while face_count > target_faces {
+        let Some(best) = heap.pop() else { break };
+        if !table.edge_exists(best.edge) {
+            continue;
+        }
+        let (v1, v2) = table.edge_vertices(best.edge);
+        if (v1, v2) != best.vertices || (state.version[v1], state.version[v2]) != best.versions {
+            continue;
+        }
+        if !keeps_manifold(&table, best.edge) || !edge_collapse::is_safe(&table, best.edge, &best.at, MIN_FACE_QUALITY) {
+            continue;
+        }
+
+        // v1 survives; it takes v2's attributes when it moves onto v2 (or nearer to it)
+        let (p1, p2) = (*table.vertex_position(v1), *table.vertex_position(v2));
+        if state.locked[v2] || (!state.locked[v1] && (best.at - p2).norm() < (best.at - p1).norm()) {
+            state.source[v1] = state.source[v2];
+        }
+        state.quadric[v1] = state.quadric[v1].add(&state.quadric[v2]);
+        state.locked[v1] |= state.locked[v2];
+        let absorbed = std::mem::take(&mut state.absorbed[v2]);
+        state.absorbed[v1].extend(absorbed);
+        state.version[v1] += 1;
+        state.version[v2] += 1;
+
+        face_count -= if table.is_edge_on_boundary(best.edge) { 1 } else { 2 };
+        table.collapse_edge(best.edge, &best.at);
+
+        let mut edges = Vec::new();
+        table.edges_around_vertex(v1, |edge| edges.push(edge.id()));
+        for edge in edges {
+            heap.extend(candidate(&table, &state, edge));
+        }
+    }
+
+    // Largest distance from an input vertex to the decimated surface. The faces
+    // around the vertex it merged into bound the search radius.
+    let grid = TriangleGrid::new(table.faces().map(|face| table.face_positions(face)).collect());
+    let mut max_error = 0.0f32;
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for v in table.vertices() {
+        let mut triangles = Vec::new();
+        table.faces_around_vertex(v, |face| triangles.push(table.face_positions(face)));
+        for &input in &state.absorbed[v] {
+            let p = Vector3::from(source.positions[input]);
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+            let bound = triangles
+                .iter()
+                .map(|t| (t.closest_point(&p) - p).norm())
+                .fold(f32::INFINITY, f32::min);
+            if !bound.is_finite() || bound <= max_error {
+                continue;
+            }
+            max_error = max_error.max(grid.distance(&p, bound));
+        }
+    }
+    let diagonal = (0..3).map(|k| (max[k] - min[k]).max(0.0).powi(2)).sum::<f32>().sqrt();
+
+    let result = rebuild(&table, &source, &state, &input_face);
+    let report = DecimateReport {
+        faces_before,
+        faces_after: result.face_indices.len(),
+        target_faces,
+        skipped_faces: faces_before - faces.len(),
+        max_error,
+        diagonal,
+    };
+    Ok((result, report))
+}
+
+/// Uniform grid over triangles for nearest-surface queries within a radius
+struct TriangleGrid {
+    triangles: Vec<Triangle3<f32>>,
+    min: Vector3<f32>,
+    cell_size: f32,
+    cells: HashMap<[i32; 3], Vec<usize>>,
+}
+
+impl TriangleGrid {
+    fn new(triangles: Vec<Triangle3<f32>>) -> Self {
+        let corners = || triangles.iter().flat_map(|t| [*t.p1(), *t.p2(), *t.p3()]);
+        let min = corners().fold(Vector3::repeat(f32::INFINITY), |a, p| a.inf(&p));
+        let max = corners().fold(Vector3::repeat(f32::NEG_INFINITY), |a, p| a.sup(&p));
+        let extent = (max - min).max();
+        let cells_per_axis = (triangles.len() as f32).cbrt().max(1.0);
+        let cell_size = if extent > 0.0 { extent / cells_per_axis } else { 1.0 };
+
+        let mut grid = TriangleGrid { triangles: Vec::new(), min, cell_size, cells: HashMap::new() };
+        for (i, t) in triangles.iter().enumerate() {
+            let lo = grid.cell_of(&t.p1().inf(t.p2()).inf(t.p3()));
+            let hi = grid.cell_of(&t.p1().sup(t.p2()).sup(t.p3()));
+            for x in lo[0]..=hi[0] {
+                for y in lo[1]..=hi[1] {
+                    for z in lo[2]..=hi[2] {
+                        grid.cells.entry([x, y, z]).or_default().push(i);
+                    }
+                }
+            }
+        }
+        grid.triangles = triangles;
+        grid
+    }
+
+    fn cell_of(&self, p: &Vector3<f32>) -> [i32; 3] {
+        [0, 1, 2].map(|k| ((p[k] - self.min[k]) / self.cell_size).floor() as i32)
+    }
+
+    /// Distance from `p` to the nearest triangle, or `bound` if none is closer
+    fn distance(&self, p: &Vector3<f32>, bound: f32) -> f32 {
+        let lo = self.cell_of(&p.add_scalar(-bound));
+        let hi = self.cell_of(&p.add_scalar(bound));
+        let mut best = bound;
+        for x in lo[0]..=hi[0] {
+            for y in lo[1]..=hi[1] {
+                for z in lo[2]..=hi[2] {
+                    for &i in self.cells.get(&[x, y, z]).into_iter().flatten() {
+                        best = best.min((self.triangles[i].closest_point(p) - p).norm());
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Queue entry for collapsing `edge`, or None if both ends are locked
+fn candidate(table: &CornerTableF, state: &VertexState, edge: EdgeId) -> Option<Candidate> {
+    if !table.edge_exists(edge) {
+        return None;
+    }
+    let (v1, v2) = table.edge_vertices(edge);
+    let (p1, p2) = (*table.vertex_position(v1), *table.vertex_position(v2));
+    let q = state.quadric[v1].add(&state.quadric[v2]);
+    let at = match (state.locked[v1], state.locked[v2]) {
+        (true, true) => return None,
+        (true, false) => p1,
+        (false, true) => p2,
+        (false, false) => {
+            let options = [(p1 + p2) * 0.5, p1, p2];
+            let fallback = options
+                .into_iter()
+                .min_by(|a, b| q.error(&to_f64(a)).total_cmp(&q.error(&to_f64(b))))
+                .unwrap_or(p1);
+            q.minimum()
+                .map(|m| m.cast::<f32>())
+                .filter(|m| q.error(&to_f64(m)) < q.error(&to_f64(&fallback)))
+                .unwrap_or(fallback)
+        }
+    };
+    Some(Candidate {
+        cost: q.error(&to_f64(&at)),
+        edge,
+        vertices: (v1, v2),
+        versions: (state.version[v1], state.version[v2]),
+        at,
+    })
+}
+
+/// Whether collapsing `edge` leaves every vertex around it on a manifold fan.
+///
+/// `is_safe` only counts shared neighbours, which still allows folding a
+/// valence-3 wing vertex flat, stranding a boundary vertex without faces, or
+/// pinching two boundaries together through an interior edge.
+fn keeps_manifold(table: &CornerTableF, edge: EdgeId) -> bool {
+    let (v1, v2) = table.edge_vertices(edge);
+    if !table.is_edge_on_boundary(edge) && table.is_vertex_on_boundary(v1) && table.is_vertex_on_boundary(v2) {
+        return false;
+    }
+    let (f1, f2) = table.edge_faces(edge);
+    for face in std::iter::once(f1).chain(f2) {
+        let (a, b, c) = table.face_vertices(face);
+        let Some(wing) = [a, b, c].into_iter().find(|&v| v != v1 && v != v2) else { return false };
+        let mut neighbours = 0;
+        table.vertices_around_vertex(wing, |_| neighbours += 1);
+        let min_neighbours = if table.is_vertex_on_boundary(wing) { 3 } else { 4 };
+        if neighbours < min_neighbours {
+            return false;
+        }
+    }
+    true
+}
+
+/// Mesh of the surviving table, with attributes looked up from `source`
+fn rebuild(
+    table: &CornerTableF,
+    source: &MeshWithColors,
+    state: &VertexState,
+    input_face: &HashMap<FaceId, usize>,
+) -> MeshWithColors {
+    let n = source.positions.len();
+    let mut index: HashMap<VertexId, u32> = HashMap::new();
+    let mut positions = Vec::new();
+    let mut inputs = Vec::new();
+    for v in table.vertices() {
+        let p = table.vertex_position(v);
+        index.insert(v, positions.len() as u32);
+        positions.push([p.x, p.y, p.z]);
+        inputs.push(state.source[v]);
+    }
+
+    let mut face_indices = Vec::new();
+    let mut face_colors = Vec::new();
+    let has_face_colors = source.face_colors.len() == source.face_indices.len();
+    for face in table.faces() {
+        let (v0, v1, v2) = table.face_vertices(face);
+        face_indices.push([index[&v0], index[&v1], index[&v2]]);
+        if has_face_colors {
+            face_colors.push(source.face_colors[input_face[&face]]);
+        }
+    }
+
+    let pick = |values: &[[f32; 4]]| -> Vec<[f32; 4]> {
+        if values.len() == n { inputs.iter().map(|&i| values[i]).collect() } else { Vec::new() }
+    };
+    let has_skin = source.joint_indices.len() == n && source.joint_weights.len() == n;
+    let morph_targets = source
+        .morph_targets
+        .iter()
+        .filter(|t| t.position_deltas.len() == n)
+        .map(|t| MorphTarget {
+            name: t.name.clone(),
+            position_deltas: inputs.iter().map(|&i| t.position_deltas[i]).collect(),
+        })
+        .collect::<Vec<_>>();
+
+    MeshWithColors {
+        positions,
+        face_indices,
+        face_colors,
+        vertex_colors: pick(&source.vertex_colors),
+        texcoords: if source.texcoords.len() == n {
+            inputs.iter().map(|&i| source.texcoords[i]).collect()
+        } else {
+            Vec::new()
+        },
+        texture: source.texture.clone(),
+        skeleton: if has_skin { source.skeleton.clone() } else { None },
+        joint_indices: if has_skin { inputs.iter().map(|&i| source.joint_indices[i]).collect() } else { Vec::new() },
+        joint_weights: if has_skin { pick(&source.joint_weights) } else { Vec::new() },
+        animations: source.animations.clone(),
+        morph_weights: if morph_targets.is_empty() { Vec::new() } else { source.morph_weights.clone() },
+        morph_targets,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat `size` x `size` grid of quads in the XY plane, UV-mapped as two
+    /// charts split down the middle column, with the left half red
+    fn split_grid(size: usize) -> MeshWithColors {
+        let mut mesh = MeshWithColors::default();
+        let half = size / 2;
+        // Column `half` is duplicated: one copy per chart
+        let mut index = HashMap::new();
+        for chart in 0..2 {
+            let columns = if chart == 0 { 0..=half } else { half..=size };
+            for x in columns {
+                for y in 0..=size {
+                    index.insert((chart, x, y), mesh.positions.len() as u32);
+                    mesh.positions.push([x as f32, y as f32, 0.0]);
+                    mesh.texcoords.push([x as f32 / size as f32 + chart as f32, y as f32 / size as f32]);
+                }
+            }
+        }
+        for x in 0..size {
+            for y in 0..size {
+                let chart = usize::from(x >= half);
+                let v = |dx, dy| index[&(chart, x + dx, y + dy)];
+                let color = if chart == 0 { [1.0, 0.0, 0.0, 1.0] } else { [0.0, 0.0, 1.0, 1.0] };
+                mesh.face_indices.push([v(0, 0), v(1, 0), v(1, 1)]);
+                mesh.face_indices.push([v(0, 0), v(1, 1), v(0, 1)]);
+                mesh.face_colors.extend([color, color]);
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn quadric_minimum_lies_on_intersecting_planes() {
+        let q = Quadric::plane(Vector3::x(), Vector3::new(1.0, 0.0, 0.0), 1.0)
+            .add(&Quadric::plane(Vector3::y(), Vector3::new(0.0, 2.0, 0.0), 1.0))
+            .add(&Quadric::plane(Vector3::z(), Vector3::new(0.0, 0.0, 3.0), 1.0));
+        let m = q.minimum().unwrap();
+        assert!((m - Vector3::new(1.0, 2.0, 3.0)).norm() < 1e-9);
+        assert!(q.error(&m) < 1e-12);
+        assert!((q.error(&Vector3::new(1.0, 2.0, 5.0)) - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flat_grid_decimates_without_error_and_keeps_seams() {
+        let mesh = split_grid(8);
+        let options = DecimateOptions {
+            target: DecimateTarget::Ratio(0.25),
+            lock_boundary: true,
+            preserve_seams: true,
+            preserve_color_borders: true,
+        };
+        let (result, report) = decimate(&mesh, &options).unwrap();
+
+        assert_eq!(report.faces_before, 128);
+        assert!(result.face_indices.len() < 128);
+        assert_eq!(result.face_colors.len(), result.face_indices.len());
+        assert_eq!(result.texcoords.len(), result.positions.len());
+        assert!(report.max_error < 1e-4, "flat grid error {}", report.max_error);
+
+        // Every seam vertex survives on both sides with its own UVs
+        for y in 0..=8 {
+            let uvs: Vec<[f32; 2]> = result
+                .positions
+                .iter()
+                .zip(&result.texcoords)
+                .filter(|(p, _)| p[0] == 4.0 && p[1] == y as f32)
+                .map(|(_, uv)| *uv)
+                .collect();
+            assert_eq!(uvs.len(), 2, "seam vertex at y={} lost a side", y);
+            assert!(uvs.iter().any(|uv| uv[0] < 1.0) && uvs.iter().any(|uv| uv[0] >= 1.0));
+        }
+
+        // Faces keep the color of their side of the border
+        for (tri, color) in result.face_indices.iter().zip(&result.face_colors) {
+            let cx = tri.iter().map(|&i| result.positions[i as usize][0]).sum::<f32>() / 3.0;
+            assert_eq!(color[0] == 1.0, cx < 4.0);
+        }
+
+        // Locked boundary: the outline is unchanged
+        for corner in [[0.0, 0.0], [8.0, 0.0], [0.0, 8.0], [8.0, 8.0]] {
+            assert!(result.positions.iter().any(|p| p[0] == corner[0] && p[1] == corner[1]));
+        }
+    }
+
+    #[test]
+    fn target_face_count_is_reached_on_curved_surface() {
+        // Open cylinder: plenty of freedom, non-zero error
+        let (around, rings) = (24, 12);
+        let mut mesh = MeshWithColors::default();
+        for r in 0..rings {
+            for a in 0..around {
+                let t = a as f32 / around as f32 * std::f32::consts::TAU;
+                mesh.positions.push([t.cos(), r as f32 * 0.2, t.sin()]);
+            }
+        }
+        for r in 0..rings - 1 {
+            for a in 0..around {
+                let v = |dr: usize, da: usize| ((r + dr) * around + (a + da) % around) as u32;
+                mesh.face_indices.push([v(0, 0), v(1, 0), v(1, 1)]);
+                mesh.face_indices.push([v(0, 0), v(1, 1), v(0, 1)]);
+            }
+        }
+        let options = DecimateOptions {
+            target: DecimateTarget::Faces(100),
+            lock_boundary: false,
+            preserve_seams: false,
+            preserve_color_borders: false,
+        };
+        let (result, report) = decimate(&mesh, &options).unwrap();
+        assert!(result.face_indices.len() <= 100);
+        assert!(report.max_error > 0.0 && report.max_error < 0.5);
+        assert!(result.to_corner_table().unwrap().boundary_rings().len() == 2);
+    }
+
+    #[test]
+    fn rejects_bad_ratios_and_counts_skipped_faces() {
+        let mut mesh = split_grid(4);
+        for ratio in [0.0, -0.5, 3.0, f32::NAN] {
+            let options = DecimateOptions {
+                target: DecimateTarget::Ratio(ratio),
+                lock_boundary: false,
+                preserve_seams: false,
+                preserve_color_borders: false,
+            };
+            assert!(decimate(&mesh, &options).is_err(), "ratio {} accepted", ratio);
+        }
+
+        // A duplicated face and a collapsed one can't enter the corner table
+        mesh.face_indices.push(mesh.face_indices[0]);
+        mesh.face_indices.push([0, 0, 1]);
+        mesh.face_colors.extend([[1.0; 4]; 2]);
+        let options = DecimateOptions {
+            target: DecimateTarget::Ratio(0.5),
+            lock_boundary: false,
+            preserve_seams: false,
+            preserve_color_borders: false,
+        };
+        let (_, report) = decimate(&mesh, &options).unwrap();
+        assert_eq!(report.skipped_faces, 2);
+    }
+}
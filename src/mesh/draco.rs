@@ -0,0 +1,2144 @@
+//! Draco mesh decoder.
+//!
+//! Decodes the Draco 2.2 bitstream written by `draco_encoder`, gltf-pipeline
+//! and gltfpack into faces and per-point attribute values. Sequential and
+//! edgebreaker (standard and valence) connectivity are supported, as are the
+//! generic, integer, quantized and octahedral normal attribute decoders with
+//! the difference, parallelogram, multi-parallelogram, texture coordinate and
+//! geometric normal prediction schemes. Point clouds and the pre-2.2
+//! bitstreams are rejected.
+
+use std::error::Error;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+const INVALID: u32 = u32::MAX;
+
+fn corrupt() -> Box<dyn Error> {
+    "Draco data is truncated or corrupt".into()
+}
+
+// --- Byte and bit reader ---
+
+#[derive(Clone)]
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_start: usize,
+    bit_offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0, bit_start: 0, bit_offset: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(n).ok_or_else(corrupt)?).ok_or_else(corrupt)?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn advance(&mut self, n: usize) -> Result<()> {
+        self.bytes(n).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    /// LEB128 varint
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(corrupt())
+    }
+
+    fn varint_u32(&mut self) -> Result<u32> {
+        u32::try_from(self.varint()?).map_err(|_| corrupt())
+    }
+
+    /// Count read as a varint, rejected when larger than `limit`
+    fn count(&mut self, limit: usize) -> Result<usize> {
+        let n = self.varint()?;
+        if n > limit as u64 {
+            return Err(corrupt());
+        }
+        Ok(n as usize)
+    }
+
+    /// Start reading bits at the current position, optionally after a
+    /// varint byte size, which is returned
+    fn start_bits(&mut self, with_size: bool) -> Result<u64> {
+        let size = if with_size { self.varint()? } else { 0 };
+        self.bit_start = self.pos;
+        self.bit_offset = 0;
+        Ok(size)
+    }
+
+    /// Read `n` bits, least significant first
+    fn bits(&mut self, n: u32) -> Result<u32> {
+        let mut value = 0;
+        for bit in 0..n {
+            let offset = self.bit_start * 8 + self.bit_offset;
+            let byte = *self.data.get(offset / 8).ok_or_else(corrupt)?;
+            value |= (((byte >> (offset % 8)) & 1) as u32) << bit;
+            self.bit_offset += 1;
+        }
+        Ok(value)
+    }
+
+    /// Skip past the bytes the bit reads touched
+    fn end_bits(&mut self) {
+        self.pos = self.bit_start + self.bit_offset.div_ceil(8);
+    }
+}
+
+// --- Entropy coding ---
+
+/// Initial rANS state and read offset from the last bytes of `data`
+fn ans_state(data: &[u8], l_base: u32, six_bit_only: bool) -> Result<(u32, usize)> {
+    let last = *data.last().ok_or_else(corrupt)?;
+    let len = data.len();
+    let (offset, state) = match last >> 6 {
+        0 => (len - 1, (last & 0x3F) as u32),
+        1 if len >= 2 => (len - 2, u16::from_le_bytes([data[len - 2], last]) as u32 & 0x3FFF),
+        2 if len >= 3 => (len - 3, u32::from_le_bytes([data[len - 3], data[len - 2], last, 0]) & 0x3F_FFFF),
+        3 if len >= 4 && !six_bit_only => {
+            (len - 4, u32::from_le_bytes(data[len - 4..].try_into().unwrap()) & 0x3FFF_FFFF)
+        }
+        _ => return Err(corrupt()),
+    };
+    let state = state + l_base;
+    if state as u64 >= l_base as u64 * 256 {
+        return Err(corrupt());
+    }
+    Ok((state, offset))
+}
+
+/// Binary rANS decoder with a fixed probability of zero
+struct BitDecoder<'a> {
+    data: &'a [u8],
+    offset: usize,
+    state: u32,
+    prob_zero: u8,
+}
+
+impl<'a> BitDecoder<'a> {
+    const L_BASE: u32 = 4096;
+
+    fn start(r: &mut Reader<'a>) -> Result<Self> {
+        let prob_zero = r.u8()?;
+        let size = r.count(r.remaining())?;
+        let data = r.bytes(size)?;
+        let (state, offset) = if data.is_empty() { (Self::L_BASE, 0) } else { ans_state(data, Self::L_BASE, true)? };
+        Ok(BitDecoder { data, offset, state, prob_zero })
+    }
+
+    fn next(&mut self) -> bool {
+        let p = 256 - self.prob_zero as u32;
+        if self.state < Self::L_BASE && self.offset > 0 {
+            self.offset -= 1;
+            self.state = self.state * 256 + self.data[self.offset] as u32;
+        }
+        let (quot, rem) = (self.state / 256, self.state % 256);
+        let xn = quot * p;
+        if rem < p {
+            self.state = xn + rem;
+            true
+        } else {
+            self.state -= xn + p;
+            false
+        }
+    }
+}
+
+/// Multi-symbol rANS decoder over a probability table read from the stream
+struct SymbolDecoder {
+    precision_bits: u32,
+    probs: Vec<(u32, u32)>,
+    lookup: Vec<u32>,
+}
+
+impl SymbolDecoder {
+    fn read(r: &mut Reader, max_bit_length: u32) -> Result<Self> {
+        let precision_bits = (3 * max_bit_length / 2).clamp(12, 20);
+        let precision = 1u32 << precision_bits;
+        let num_symbols = r.count(r.remaining() * 64)?;
+        let mut table = Vec::with_capacity(num_symbols.min(r.remaining() * 4 + 1));
+        while table.len() < num_symbols {
+            let data = r.u8()?;
+            if data & 3 == 3 {
+                let zeros = (data >> 2) as usize + 1;
+                if table.len() + zeros > num_symbols {
+                    return Err(corrupt());
+                }
+                table.extend(std::iter::repeat_n(0, zeros));
+            } else {
+                let mut prob = (data >> 2) as u32;
+                for b in 0..(data & 3) as u32 {
+                    prob |= (r.u8()? as u32) << (8 * (b + 1) - 2);
+                }
+                table.push(prob);
+            }
+        }
+
+        let mut probs = Vec::with_capacity(num_symbols);
+        let mut lookup = Vec::new();
+        let mut cumulative = 0u32;
+        for (symbol, &prob) in table.iter().enumerate() {
+            probs.push((prob, cumulative));
+            cumulative = cumulative.checked_add(prob).filter(|&c| c <= precision).ok_or_else(corrupt)?;
+            lookup.resize(cumulative as usize, symbol as u32);
+        }
+        if num_symbols > 0 && cumulative != precision {
+            return Err(corrupt());
+        }
+        Ok(SymbolDecoder { precision_bits, probs, lookup })
+    }
+
+    /// Decode `count` symbols from the encoded block that follows
+    fn decode(&self, r: &mut Reader, count: usize, out: &mut Vec<u32>) -> Result<()> {
+        let size = r.count(r.remaining())?;
+        let data = r.bytes(size)?;
+        if count == 0 {
+            return Ok(());
+        }
+        if self.probs.is_empty() {
+            return Err(corrupt());
+        }
+        let l_base = 4 << self.precision_bits;
+        let (mut state, mut offset) = ans_state(data, l_base, false)?;
+        let mask = (1 << self.precision_bits) - 1;
+        for _ in 0..count {
+            while state < l_base && offset > 0 {
+                offset -= 1;
+                state = state * 256 + data[offset] as u32;
+            }
+            let (quot, rem) = (state >> self.precision_bits, state & mask);
+            let symbol = self.lookup[rem as usize];
+            let (prob, cumulative) = self.probs[symbol as usize];
+            state = quot * prob + rem - cumulative;
+            out.push(symbol);
+        }
+        Ok(())
+    }
+}
+
+/// Decode `count` entropy-coded symbols (`components` values per tag when
+/// tagged)
+fn decode_symbols(r: &mut Reader, count: usize, components: usize, out: &mut Vec<u32>) -> Result<()> {
+    if count == 0 {
+        return Ok(());
+    }
+    out.reserve(count.min(r.remaining() * 8));
+    match r.u8()? {
+        0 => {
+            let tags = SymbolDecoder::read(r, 5)?;
+            let mut bit_lengths = Vec::new();
+            tags.decode(r, count.div_ceil(components.max(1)), &mut bit_lengths)?;
+            r.start_bits(false)?;
+            for bit_length in bit_lengths {
+                if bit_length > 32 {
+                    return Err(corrupt());
+                }
+                for _ in 0..components {
+                    if out.len() < count {
+                        out.push(r.bits(bit_length)?);
+                    }
+                }
+            }
+            r.end_bits();
+        }
+        1 => {
+            let max_bit_length = r.u8()? as u32;
+            if !(1..=18).contains(&max_bit_length) {
+                return Err(corrupt());
+            }
+            SymbolDecoder::read(r, max_bit_length)?.decode(r, count, out)?;
+        }
+        scheme => return Err(format!("Unknown Draco symbol coding {}", scheme).into()),
+    }
+    if out.len() < count {
+        return Err(corrupt());
+    }
+    Ok(())
+}
+
+fn unzigzag(v: u32) -> i32 {
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+// --- Connectivity ---
+
+/// Corner table: three corners per face, each mapped to a vertex and to the
+/// corner opposite it across the face's edge
+#[derive(Clone, Default)]
+struct CornerTable {
+    vertices: Vec<u32>,
+    opposites: Vec<u32>,
+    left_most: Vec<u32>,
+}
+
+fn next(c: u32) -> u32 {
+    if c == INVALID {
+        INVALID
+    } else if c % 3 == 2 {
+        c - 2
+    } else {
+        c + 1
+    }
+}
+
+fn previous(c: u32) -> u32 {
+    if c == INVALID {
+        INVALID
+    } else if c.is_multiple_of(3) {
+        c + 2
+    } else {
+        c - 1
+    }
+}
+
+impl CornerTable {
+    fn num_faces(&self) -> usize {
+        self.vertices.len() / 3
+    }
+
+    fn num_vertices(&self) -> usize {
+        self.left_most.len()
+    }
+
+    fn vertex(&self, c: u32) -> u32 {
+        if c == INVALID { INVALID } else { self.vertices[c as usize] }
+    }
+
+    fn opposite(&self, c: u32) -> u32 {
+        if c == INVALID { INVALID } else { self.opposites[c as usize] }
+    }
+
+    fn left_most_corner(&self, v: u32) -> u32 {
+        if v == INVALID { INVALID } else { self.left_most[v as usize] }
+    }
+
+    fn swing_left(&self, c: u32) -> u32 {
+        next(self.opposite(next(c)))
+    }
+
+    fn swing_right(&self, c: u32) -> u32 {
+        previous(self.opposite(previous(c)))
+    }
+
+    /// Corner on the face across the edge after `c`
+    fn left_corner(&self, c: u32) -> u32 {
+        self.opposite(previous(c))
+    }
+
+    /// Corner on the face across the edge before `c`
+    fn right_corner(&self, c: u32) -> u32 {
+        self.opposite(next(c))
+    }
+
+    fn is_on_boundary(&self, v: u32) -> bool {
+        self.swing_left(self.left_most_corner(v)) == INVALID
+    }
+
+    fn set_opposite(&mut self, a: u32, b: u32) {
+        self.opposites[a as usize] = b;
+        self.opposites[b as usize] = a;
+    }
+
+    fn map(&mut self, c: u32, v: u32) {
+        self.vertices[c as usize] = v;
+    }
+
+    fn add_vertex(&mut self) -> u32 {
+        self.left_most.push(INVALID);
+        self.left_most.len() as u32 - 1
+    }
+
+    /// Every corner around `v`, swinging left from its left-most corner and
+    /// then right at an open boundary
+    fn corners_around(&self, start: u32) -> Vec<u32> {
+        let mut corners = Vec::new();
+        let mut c = start;
+        while c != INVALID {
+            corners.push(c);
+            c = self.swing_left(c);
+            if c == start {
+                return corners;
+            }
+        }
+        c = self.swing_right(start);
+        while c != INVALID && c != start {
+            corners.push(c);
+            c = self.swing_right(c);
+        }
+        corners
+    }
+}
+
+/// Connectivity of one attribute with seams: the position corner table with
+/// opposites cut along seam edges and vertices split where values differ
+struct AttributeConnectivity {
+    seam_corners: Vec<u32>,
+    on_seam: Vec<bool>,
+    table: CornerTable,
+}
+
+impl AttributeConnectivity {
+    /// Build the attribute table from the recorded seam edges
+    fn recompute(&mut self, base: &CornerTable) -> Result<()> {
+        let mut seam_edge = vec![false; base.vertices.len()];
+        self.on_seam = vec![false; base.num_vertices()];
+        for &c in &self.seam_corners {
+            for corner in [c, base.opposite(c)] {
+                if corner != INVALID {
+                    seam_edge[corner as usize] = true;
+                    self.on_seam[base.vertex(next(corner)) as usize] = true;
+                    self.on_seam[base.vertex(previous(corner)) as usize] = true;
+                }
+            }
+        }
+
+        let mut table = CornerTable {
+            vertices: vec![INVALID; base.vertices.len()],
+            opposites: base.opposites.iter().zip(&seam_edge).map(|(&o, &seam)| if seam { INVALID } else { o }).collect(),
+            left_most: Vec::new(),
+        };
+        for v in 0..base.num_vertices() {
+            let c = base.left_most[v];
+            if c == INVALID {
+                continue;
+            }
+            let mut first = c;
+            if self.on_seam[v] {
+                let mut act = table.swing_left(first);
+                while act != INVALID {
+                    first = act;
+                    act = table.swing_left(act);
+                    if act == c {
+                        return Err(corrupt());
+                    }
+                }
+            }
+            let mut vertex = table.left_most.len() as u32;
+            table.left_most.push(first);
+            table.vertices[first as usize] = vertex;
+            let mut act = base.swing_right(first);
+            while act != INVALID && act != first {
+                if seam_edge[next(act) as usize] {
+                    vertex = table.left_most.len() as u32;
+                    table.left_most.push(act);
+                }
+                table.vertices[act as usize] = vertex;
+                act = base.swing_right(act);
+            }
+        }
+        self.table = table;
+        Ok(())
+    }
+}
+
+/// Order in which attribute values were encoded: the corner each value was
+/// first reached from and the value index of every table vertex
+#[derive(Default)]
+struct EncodingData {
+    value_corners: Vec<u32>,
+    vertex_values: Vec<i32>,
+}
+
+/// Walk the faces of `table` the way the encoder did, recording the order
+/// vertices were reached in, which is the order their values are stored in
+fn traverse(table: &CornerTable, prediction_degree: bool) -> Result<EncodingData> {
+    let mut data = EncodingData { value_corners: Vec::with_capacity(table.num_vertices()), vertex_values: vec![-1; table.num_vertices()] };
+    let mut face_visited = vec![false; table.num_faces()];
+    let visit = |data: &mut EncodingData, c: u32| -> Result<()> {
+        let v = table.vertex(c);
+        let slot = data.vertex_values.get_mut(v as usize).ok_or_else(corrupt)?;
+        if *slot < 0 {
+            *slot = data.value_corners.len() as i32;
+            data.value_corners.push(c);
+        }
+        Ok(())
+    };
+    let is_face_visited = |visited: &[bool], c: u32| c == INVALID || visited[c as usize / 3];
+
+    let mut degree = vec![0u32; if prediction_degree { table.num_vertices() } else { 0 }];
+    let mut stack: Vec<u32> = Vec::new();
+    let mut stacks: [Vec<u32>; 3] = Default::default();
+    for face in 0..table.num_faces() as u32 {
+        let start = 3 * face;
+        if !prediction_degree {
+            // Depth first
+            if face_visited[face as usize] {
+                continue;
+            }
+            visit(&mut data, next(start))?;
+            visit(&mut data, previous(start))?;
+            stack.clear();
+            stack.push(start);
+            while let Some(&top) = stack.last() {
+                let mut c = top;
+                if is_face_visited(&face_visited, c) {
+                    stack.pop();
+                    continue;
+                }
+                loop {
+                    face_visited[c as usize / 3] = true;
+                    let v = table.vertex(c);
+                    if v == INVALID {
+                        return Err(corrupt());
+                    }
+                    if data.vertex_values[v as usize] < 0 {
+                        let on_boundary = table.is_on_boundary(v);
+                        visit(&mut data, c)?;
+                        if !on_boundary {
+                            c = table.right_corner(c);
+                            if c == INVALID {
+                                return Err(corrupt());
+                            }
+                            continue;
+                        }
+                    }
+                    let right = table.right_corner(c);
+                    let left = table.left_corner(c);
+                    match (is_face_visited(&face_visited, right), is_face_visited(&face_visited, left)) {
+                        (true, true) => {
+                            stack.pop();
+                            break;
+                        }
+                        (true, false) => c = left,
+                        (false, true) => c = right,
+                        (false, false) => {
+                            *stack.last_mut().unwrap() = left;
+                            stack.push(right);
+                            break;
+                        }
+                    }
+                }
+            }
+        } else {
+            // Highest prediction degree first
+            let mut best = 0;
+            stacks[0].push(start);
+            visit(&mut data, next(start))?;
+            visit(&mut data, previous(start))?;
+            visit(&mut data, start)?;
+            let mut priority = |data: &EncodingData, c: u32| {
+                let v = table.vertex(c) as usize;
+                if data.vertex_values[v] >= 0 {
+                    return 0;
+                }
+                degree[v] += 1;
+                if degree[v] > 1 { 1 } else { 2 }
+            };
+            while let Some(level) = (best..3).find(|&i| !stacks[i].is_empty()) {
+                best = level;
+                let mut c = stacks[level].pop().unwrap();
+                if is_face_visited(&face_visited, c) {
+                    continue;
+                }
+                loop {
+                    face_visited[c as usize / 3] = true;
+                    visit(&mut data, c)?;
+                    let right = table.right_corner(c);
+                    let left = table.left_corner(c);
+                    let right_visited = is_face_visited(&face_visited, right);
+                    if !is_face_visited(&face_visited, left) {
+                        let p = priority(&data, left);
+                        if right_visited && p <= best {
+                            c = left;
+                            continue;
+                        }
+                        stacks[p].push(left);
+                        best = best.min(p);
+                    }
+                    if !right_visited {
+                        let p = priority(&data, right);
+                        if p <= best {
+                            c = right;
+                            continue;
+                        }
+                        stacks[p].push(right);
+                        best = best.min(p);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    Ok(data)
+}
+
+/// Faces over point ids, plus the tables edgebreaker attributes are
+/// traversed on
+struct Connectivity {
+    faces: Vec<[u32; 3]>,
+    num_points: usize,
+    table: CornerTable,
+    attribute_data: Vec<AttributeConnectivity>,
+}
+
+fn decode_sequential(r: &mut Reader) -> Result<Connectivity> {
+    let num_faces = r.count(u32::MAX as usize / 3)?;
+    let num_points = r.varint_u32()? as usize;
+    if num_points > r.remaining().saturating_mul(1024) + 1024 {
+        return Err(corrupt());
+    }
+    let mut faces = Vec::with_capacity(num_faces.min(r.remaining()));
+    match r.u8()? {
+        0 => {
+            let mut symbols = Vec::new();
+            decode_symbols(r, num_faces * 3, 1, &mut symbols)?;
+            let mut last = 0i64;
+            for corners in symbols.chunks_exact(3) {
+                let mut face = [0; 3];
+                for (index, &symbol) in face.iter_mut().zip(corners) {
+                    let diff = (symbol >> 1) as i64;
+                    last += if symbol & 1 == 1 { -diff } else { diff };
+                    *index = u32::try_from(last).map_err(|_| corrupt())?;
+                }
+                faces.push(face);
+            }
+        }
+        1 => {
+            for _ in 0..num_faces {
+                let mut face = [0; 3];
+                for index in &mut face {
+                    *index = match num_points {
+                        0..256 => r.u8()? as u32,
+                        256..65536 => r.u16()? as u32,
+                        65536..0x20_0000 => r.varint_u32()?,
+                        _ => r.i32()? as u32,
+                    };
+                }
+                faces.push(face);
+            }
+        }
+        method => return Err(format!("Unknown Draco sequential connectivity {}", method).into()),
+    }
+    if faces.iter().flatten().any(|&i| i as usize >= num_points) {
+        return Err(corrupt());
+    }
+    Ok(Connectivity { faces, num_points, table: CornerTable::default(), attribute_data: Vec::new() })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Symbol {
+    C,
+    S,
+    L,
+    R,
+    E,
+}
+
+const SYMBOLS: [Symbol; 5] = [Symbol::C, Symbol::S, Symbol::L, Symbol::R, Symbol::E];
+
+/// Source of edgebreaker symbols: a bit stream, or per-valence context
+/// streams for the valence coder
+enum Traversal<'a> {
+    Standard(Reader<'a>),
+    Valence { valences: Vec<u32>, contexts: Vec<Vec<u32>>, active: Option<usize>, last: Symbol },
+}
+
+impl Traversal<'_> {
+    fn symbol(&mut self) -> Result<Symbol> {
+        match self {
+            Traversal::Standard(bits) => Ok(match bits.bits(1)? {
+                0 => Symbol::C,
+                _ => match bits.bits(2)? {
+                    0 => Symbol::S,
+                    1 => Symbol::L,
+                    2 => Symbol::R,
+                    _ => Symbol::E,
+                },
+            }),
+            Traversal::Valence { contexts, active, last, .. } => {
+                *last = match active {
+                    // Context symbols are consumed from the back
+                    Some(context) => {
+                        let symbol = contexts[*context].pop().ok_or_else(corrupt)?;
+                        *SYMBOLS.get(symbol as usize).ok_or_else(corrupt)?
+                    }
+                    None => Symbol::E,
+                };
+                Ok(*last)
+            }
+        }
+    }
+
+    fn merge_vertices(&mut self, dest: u32, source: u32) {
+        if let Traversal::Valence { valences, .. } = self {
+            valences[dest as usize] += valences[source as usize];
+        }
+    }
+
+    /// Update valences around the new active corner and pick the context
+    /// of the next symbol
+    fn corner_reached(&mut self, table: &CornerTable, c: u32) {
+        if let Traversal::Valence { valences, active, last, .. } = self {
+            let [v, n, p] = [c, next(c), previous(c)].map(|c| table.vertex(c) as usize);
+            let add = match last {
+                Symbol::C | Symbol::S => [0, 1, 1],
+                Symbol::R => [1, 1, 2],
+                Symbol::L => [1, 2, 1],
+                Symbol::E => [2, 2, 2],
+            };
+            for (v, add) in [v, n, p].into_iter().zip(add) {
+                valences[v] += add;
+            }
+            *active = Some(valences[n].clamp(2, 7) as usize - 2);
+        }
+    }
+}
+
+fn decode_edgebreaker(r: &mut Reader) -> Result<Connectivity> {
+    let traversal_type = r.u8()?;
+    let num_encoded_vertices = r.count(u32::MAX as usize / 2)?;
+    let num_faces = r.count(u32::MAX as usize / 3)?;
+    let num_attribute_data = r.u8()? as usize;
+    let num_symbols = r.count(num_faces)?;
+    if num_faces > num_symbols + num_symbols / 3 {
+        return Err(corrupt());
+    }
+    let num_split_symbols = r.count(num_symbols)?;
+    let max_vertices = num_encoded_vertices + num_split_symbols;
+    if num_faces > r.remaining().saturating_mul(1024) + 1024 || max_vertices > 3 * num_faces + 3 {
+        return Err(corrupt());
+    }
+
+    // Topology splits: (source symbol, split symbol, split on the right edge)
+    let num_splits = r.count(num_faces)?;
+    let mut splits = Vec::with_capacity(num_splits);
+    let mut last_source = 0u64;
+    for _ in 0..num_splits {
+        let source = r.varint()? + last_source;
+        let split = source.checked_sub(r.varint()?).ok_or_else(corrupt)?;
+        splits.push((source, split, false));
+        last_source = source;
+    }
+    r.start_bits(false)?;
+    for split in &mut splits {
+        split.2 = r.bits(1)? == 1;
+    }
+    r.end_bits();
+
+    let mut traversal = match traversal_type {
+        0 => {
+            let mut bits = r.clone();
+            let size = bits.start_bits(true)?;
+            *r = bits.clone();
+            r.advance(usize::try_from(size).map_err(|_| corrupt())?)?;
+            Traversal::Standard(bits)
+        }
+        2 => Traversal::Valence { valences: vec![0; max_vertices], contexts: Vec::new(), active: None, last: Symbol::E },
+        other => return Err(format!("Unsupported Draco edgebreaker coder {}", other).into()),
+    };
+    let mut start_faces = BitDecoder::start(r)?;
+    let mut seam_decoders = (0..num_attribute_data).map(|_| BitDecoder::start(r)).collect::<Result<Vec<_>>>()?;
+    if let Traversal::Valence { contexts, .. } = &mut traversal {
+        if r.u8()? != 0 {
+            return Err("Unsupported Draco valence mode".into());
+        }
+        for _ in 2..=7 {
+            let count = r.count(num_faces)?;
+            let mut symbols = Vec::new();
+            decode_symbols(r, count, 1, &mut symbols)?;
+            contexts.push(symbols);
+        }
+    }
+
+    let mut table = CornerTable {
+        vertices: vec![INVALID; 3 * num_faces],
+        opposites: vec![INVALID; 3 * num_faces],
+        left_most: Vec::with_capacity(max_vertices),
+    };
+    let mut is_hole = vec![true; max_vertices];
+    let mut active: Vec<u32> = Vec::new();
+    let mut split_corners = std::collections::HashMap::new();
+    let mut invalid_vertices = Vec::new();
+    let remove_invalid = num_attribute_data == 0;
+    let mut face = 0u32;
+
+    for symbol_id in 0..num_symbols {
+        let corner = 3 * face;
+        face += 1;
+        let symbol = traversal.symbol()?;
+        match symbol {
+            Symbol::C => {
+                let a = *active.last().ok_or_else(corrupt)?;
+                let x = table.vertex(next(a));
+                let b = next(table.left_most_corner(x));
+                if a == b || b == INVALID || table.opposite(a) != INVALID || table.opposite(b) != INVALID {
+                    return Err(corrupt());
+                }
+                table.set_opposite(a, corner + 1);
+                table.set_opposite(b, corner + 2);
+                let a_prev = table.vertex(previous(a));
+                let b_next = table.vertex(next(b));
+                if x == a_prev || x == b_next {
+                    return Err(corrupt());
+                }
+                table.map(corner, x);
+                table.map(corner + 1, b_next);
+                table.map(corner + 2, a_prev);
+                table.left_most[a_prev as usize] = corner + 2;
+                is_hole[x as usize] = false;
+                *active.last_mut().unwrap() = corner;
+            }
+            Symbol::R | Symbol::L => {
+                let a = *active.last().ok_or_else(corrupt)?;
+                if table.opposite(a) != INVALID {
+                    return Err(corrupt());
+                }
+                let (opp, l, r) = if symbol == Symbol::R {
+                    (corner + 2, corner + 1, corner)
+                } else {
+                    (corner + 1, corner, corner + 2)
+                };
+                table.set_opposite(opp, a);
+                let new_vertex = table.add_vertex();
+                if table.num_vertices() > max_vertices {
+                    return Err(corrupt());
+                }
+                table.map(opp, new_vertex);
+                table.left_most[new_vertex as usize] = opp;
+                let vertex_r = table.vertex(previous(a));
+                table.map(r, vertex_r);
+                table.left_most[vertex_r as usize] = r;
+                table.map(l, table.vertex(next(a)));
+                *active.last_mut().unwrap() = corner;
+            }
+            Symbol::S => {
+                let b = active.pop().ok_or_else(corrupt)?;
+                if let Some(&split) = split_corners.get(&symbol_id) {
+                    active.push(split);
+                }
+                let a = *active.last().ok_or_else(corrupt)?;
+                if a == b || table.opposite(a) != INVALID || table.opposite(b) != INVALID {
+                    return Err(corrupt());
+                }
+                table.set_opposite(a, corner + 2);
+                table.set_opposite(b, corner + 1);
+                let vertex_p = table.vertex(previous(a));
+                table.map(corner, vertex_p);
+                table.map(corner + 1, table.vertex(next(a)));
+                let b_prev = table.vertex(previous(b));
+                table.map(corner + 2, b_prev);
+                table.left_most[b_prev as usize] = corner + 2;
+                let mut n = next(b);
+                let vertex_n = table.vertex(n);
+                traversal.merge_vertices(vertex_p, vertex_n);
+                table.left_most[vertex_p as usize] = table.left_most_corner(vertex_n);
+                let first = n;
+                while n != INVALID {
+                    table.map(n, vertex_p);
+                    n = table.swing_left(n);
+                    if n == first {
+                        return Err(corrupt());
+                    }
+                }
+                table.left_most[vertex_n as usize] = INVALID;
+                if remove_invalid {
+                    invalid_vertices.push(vertex_n);
+                }
+                *active.last_mut().unwrap() = corner;
+            }
+            Symbol::E => {
+                let first = table.add_vertex();
+                table.add_vertex();
+                table.add_vertex();
+                if table.num_vertices() > max_vertices {
+                    return Err(corrupt());
+                }
+                for i in 0..3 {
+                    table.map(corner + i, first + i);
+                    table.left_most[(first + i) as usize] = corner + i;
+                }
+                active.push(corner);
+            }
+        }
+        traversal.corner_reached(&table, *active.last().unwrap());
+
+        if matches!(symbol, Symbol::R | Symbol::L | Symbol::E) {
+            // Faces the encoder split the traversal at leave an extra active
+            // edge for the S symbol that closes it
+            let encoder_symbol = (num_symbols - symbol_id - 1) as u64;
+            while let Some(&(source, split, right)) = splits.last() {
+                if source > encoder_symbol {
+                    return Err(corrupt());
+                }
+                if source != encoder_symbol {
+                    break;
+                }
+                splits.pop();
+                let top = *active.last().unwrap();
+                let corner = if right { next(top) } else { previous(top) };
+                let decoder_split = (num_symbols as u64).checked_sub(split + 1).ok_or_else(corrupt)?;
+                split_corners.insert(decoder_split as usize, corner);
+            }
+        }
+    }
+
+    // Start faces close the remaining active edges
+    while let Some(corner) = active.pop() {
+        if start_faces.next() {
+            if face as usize >= num_faces {
+                return Err(corrupt());
+            }
+            let vert_n = table.vertex(next(corner));
+            let b = next(table.left_most_corner(vert_n));
+            let vert_x = table.vertex(next(b));
+            let c = next(table.left_most_corner(vert_x));
+            let vert_p = table.vertex(next(c));
+            if b == INVALID || c == INVALID || [vert_n, vert_x, vert_p].contains(&INVALID) {
+                return Err(corrupt());
+            }
+            let new_corner = 3 * face;
+            face += 1;
+            table.set_opposite(new_corner, corner);
+            table.set_opposite(new_corner + 1, b);
+            table.set_opposite(new_corner + 2, c);
+            table.map(new_corner, vert_x);
+            table.map(new_corner + 1, vert_p);
+            table.map(new_corner + 2, vert_n);
+            for v in [vert_x, vert_p, vert_n] {
+                is_hole[v as usize] = false;
+            }
+        }
+    }
+    if face as usize != num_faces || table.vertices.contains(&INVALID) {
+        return Err(corrupt());
+    }
+
+    // Move the last valid vertices into the slots merged vertices left
+    let mut num_vertices = table.num_vertices();
+    for invalid in invalid_vertices {
+        let mut source = num_vertices - 1;
+        while table.left_most[source] == INVALID {
+            num_vertices -= 1;
+            source = num_vertices.checked_sub(1).ok_or_else(corrupt)?;
+        }
+        if source < invalid as usize {
+            continue;
+        }
+        for c in table.corners_around(table.left_most[source]) {
+            if table.vertex(c) as usize != source {
+                return Err(corrupt());
+            }
+            table.map(c, invalid);
+        }
+        table.left_most[invalid as usize] = table.left_most[source];
+        table.left_most[source] = INVALID;
+        is_hole[invalid as usize] = is_hole[source];
+        is_hole[source] = false;
+        num_vertices -= 1;
+    }
+    table.left_most.truncate(num_vertices);
+
+    // Attribute seams, one bit per interior edge and attribute
+    let mut attribute_data: Vec<AttributeConnectivity> = (0..num_attribute_data)
+        .map(|_| AttributeConnectivity {
+            seam_corners: Vec::new(),
+            on_seam: Vec::new(),
+            table: CornerTable::default(),
+        })
+        .collect();
+    if num_attribute_data > 0 {
+        for c in 0..table.vertices.len() as u32 {
+            let opp = table.opposite(c);
+            if opp == INVALID {
+                attribute_data.iter_mut().for_each(|data| data.seam_corners.push(c));
+            } else if opp / 3 >= c / 3 {
+                for (data, decoder) in attribute_data.iter_mut().zip(&mut seam_decoders) {
+                    if decoder.next() {
+                        data.seam_corners.push(c);
+                    }
+                }
+            }
+        }
+        for data in &mut attribute_data {
+            data.recompute(&table)?;
+        }
+    }
+
+    let (faces, num_points) = assign_points(&table, &is_hole, &attribute_data)?;
+    Ok(Connectivity { faces, num_points, table, attribute_data })
+}
+
+/// Split table vertices into points wherever any attribute has a seam
+fn assign_points(table: &CornerTable, is_hole: &[bool], attribute_data: &[AttributeConnectivity]) -> Result<(Vec<[u32; 3]>, usize)> {
+    let corner_faces = |points: &[u32]| points.chunks_exact(3).map(|f| [f[0], f[1], f[2]]).collect();
+    if attribute_data.is_empty() {
+        return Ok((corner_faces(&table.vertices), table.num_vertices()));
+    }
+
+    let mut corner_points = vec![INVALID; table.vertices.len()];
+    let mut num_points = 0;
+    for (&c, &hole) in table.left_most.iter().zip(is_hole) {
+        if c == INVALID {
+            continue;
+        }
+        let mut first = c;
+        if !hole {
+            // Start at a seam so each run of equal values gets one point
+            for data in attribute_data {
+                if !data.on_seam[table.vertex(c) as usize] {
+                    continue;
+                }
+                let vertex = data.table.vertex(c);
+                let mut act = table.swing_right(c);
+                let mut found = false;
+                while act != c {
+                    if act == INVALID {
+                        return Err(corrupt());
+                    }
+                    if data.table.vertex(act) != vertex {
+                        first = act;
+                        found = true;
+                        break;
+                    }
+                    act = table.swing_right(act);
+                }
+                if found {
+                    break;
+                }
+            }
+        }
+
+        corner_points[first as usize] = num_points;
+        num_points += 1;
+        let mut prev = first;
+        let mut act = table.swing_right(first);
+        while act != INVALID && act != first {
+            if attribute_data.iter().any(|data| data.table.vertex(act) != data.table.vertex(prev)) {
+                corner_points[act as usize] = num_points;
+                num_points += 1;
+            } else {
+                corner_points[act as usize] = corner_points[prev as usize];
+            }
+            prev = act;
+            act = table.swing_right(act);
+        }
+    }
+    if corner_points.contains(&INVALID) {
+        return Err(corrupt());
+    }
+    Ok((corner_faces(&corner_points), num_points as usize))
+}
+
+// --- Attribute transforms ---
+
+/// Octahedral normal quantization
+#[derive(Clone, Copy)]
+struct Octahedron {
+    max_quantized: i32,
+    max_value: i32,
+    center: i32,
+}
+
+impl Octahedron {
+    fn new(bits: u32) -> Result<Self> {
+        if !(2..=30).contains(&bits) {
+            return Err(corrupt());
+        }
+        let max_quantized = (1 << bits) - 1;
+        Ok(Octahedron { max_quantized, max_value: max_quantized - 1, center: (max_quantized - 1) / 2 })
+    }
+
+    /// Scale an integer vector onto the octahedron's L1 sphere
+    fn canonicalize_vector(&self, v: [i64; 3]) -> [i32; 3] {
+        let abs_sum = v[0].abs() + v[1].abs() + v[2].abs();
+        if abs_sum == 0 {
+            return [self.center, 0, 0];
+        }
+        let center = self.center as i64;
+        let x = (v[0] * center / abs_sum) as i32;
+        let y = (v[1] * center / abs_sum) as i32;
+        let z = self.center - x.abs() - y.abs();
+        [x, y, if v[2] >= 0 { z } else { -z }]
+    }
+
+    fn vector_to_coords(&self, v: [i32; 3]) -> [i32; 2] {
+        let (s, t) = if v[0] >= 0 {
+            (v[1] + self.center, v[2] + self.center)
+        } else {
+            (
+                if v[1] < 0 { v[2].abs() } else { self.max_value - v[2].abs() },
+                if v[2] < 0 { v[1].abs() } else { self.max_value - v[1].abs() },
+            )
+        };
+        self.canonicalize_coords(s, t)
+    }
+
+    fn canonicalize_coords(&self, mut s: i32, mut t: i32) -> [i32; 2] {
+        let (max, center) = (self.max_value, self.center);
+        if (s == 0 && (t == 0 || t == max)) || (s == max && t == 0) {
+            s = max;
+            t = max;
+        } else if s == 0 && t > center {
+            t = center - (t - center);
+        } else if s == max && t < center {
+            t = center + (center - t);
+        } else if t == max && s < center {
+            s = center + (center - s);
+        } else if t == 0 && s > center {
+            s = center - (s - center);
+        }
+        [s, t]
+    }
+
+    fn unit_vector(&self, s: i32, t: i32) -> [f32; 3] {
+        let scale = 2.0 / self.max_value as f32;
+        let mut y = s as f32 * scale - 1.0;
+        let mut z = t as f32 * scale - 1.0;
+        let x = 1.0 - y.abs() - z.abs();
+        let offset = (-x).max(0.0);
+        y += if y < 0.0 { offset } else { -offset };
+        z += if z < 0.0 { offset } else { -offset };
+        let norm_squared = x * x + y * y + z * z;
+        if norm_squared < 1e-6 {
+            return [0.0; 3];
+        }
+        let d = 1.0 / norm_squared.sqrt();
+        [x * d, y * d, z * d]
+    }
+
+    fn is_in_diamond(&self, s: i32, t: i32) -> bool {
+        s.abs() + t.abs() <= self.center
+    }
+
+    fn invert_diamond(&self, s: i32, t: i32) -> [i32; 2] {
+        let (sign_s, sign_t) = if s >= 0 && t >= 0 {
+            (1, 1)
+        } else if s <= 0 && t <= 0 {
+            (-1, -1)
+        } else {
+            (if s > 0 { 1 } else { -1 }, if t > 0 { 1 } else { -1 })
+        };
+        let corner_s = (sign_s * self.center) as u32;
+        let corner_t = (sign_t * self.center) as u32;
+        let mut us = (s as u32).wrapping_mul(2).wrapping_sub(corner_s);
+        let mut ut = (t as u32).wrapping_mul(2).wrapping_sub(corner_t);
+        if sign_s * sign_t >= 0 {
+            (us, ut) = (ut.wrapping_neg(), us.wrapping_neg());
+        } else {
+            (us, ut) = (ut, us);
+        }
+        [us.wrapping_add(corner_s) as i32 / 2, ut.wrapping_add(corner_t) as i32 / 2]
+    }
+
+    fn mod_max(&self, x: i32) -> i32 {
+        if x > self.center {
+            x - self.max_quantized
+        } else if x < -self.center {
+            x + self.max_quantized
+        } else {
+            x
+        }
+    }
+}
+
+/// Maps a prediction and a correction back to the encoded value
+enum Transform {
+    Wrap { min: i32, max: i32 },
+    Octahedron(Octahedron),
+}
+
+impl Transform {
+    fn read(r: &mut Reader, transform_type: i8, normals: bool) -> Result<Self> {
+        match (transform_type, normals) {
+            (1, false) => {
+                let (min, max) = (r.i32()?, r.i32()?);
+                if min > max || max as i64 - min as i64 >= i32::MAX as i64 {
+                    return Err(corrupt());
+                }
+                Ok(Transform::Wrap { min, max })
+            }
+            (3, true) => {
+                let max_quantized = r.i32()?;
+                let _center = r.i32()?;
+                if max_quantized <= 0 || max_quantized % 2 == 0 {
+                    return Err(corrupt());
+                }
+                Ok(Transform::Octahedron(Octahedron::new(32 - max_quantized.leading_zeros())?))
+            }
+            _ => Err(format!("Unsupported Draco prediction transform {}", transform_type).into()),
+        }
+    }
+
+    fn original(&self, pred: &[i32], corr: &[i32], out: &mut [i32]) {
+        match *self {
+            Transform::Wrap { min, max } => {
+                let dif = max.wrapping_sub(min).wrapping_add(1);
+                for ((o, &p), &c) in out.iter_mut().zip(pred).zip(corr) {
+                    let v = p.clamp(min, max).wrapping_add(c);
+                    *o = if v > max {
+                        v.wrapping_sub(dif)
+                    } else if v < min {
+                        v.wrapping_add(dif)
+                    } else {
+                        v
+                    };
+                }
+            }
+            Transform::Octahedron(oct) => {
+                let mut p = [pred[0] - oct.center, pred[1] - oct.center];
+                let in_diamond = oct.is_in_diamond(p[0], p[1]);
+                if !in_diamond {
+                    p = oct.invert_diamond(p[0], p[1]);
+                }
+                let bottom_left = (p[0] == 0 && p[1] == 0) || (p[0] < 0 && p[1] <= 0);
+                let rotation = match (p[0].signum(), p[1].signum()) {
+                    (0, 0) => 0,
+                    (0, 1) => 3,
+                    (0, _) => 1,
+                    (1, y) if y >= 0 => 2,
+                    (1, _) => 1,
+                    (_, y) if y <= 0 => 0,
+                    _ => 3,
+                };
+                let rotate = |[x, y]: [i32; 2], count: i32| match count {
+                    1 => [y, -x],
+                    2 => [-x, -y],
+                    3 => [-y, x],
+                    _ => [x, y],
+                };
+                if !bottom_left {
+                    p = rotate(p, rotation);
+                }
+                let mut o = [oct.mod_max(p[0].wrapping_add(corr[0])), oct.mod_max(p[1].wrapping_add(corr[1]))];
+                if !bottom_left {
+                    o = rotate(o, (4 - rotation) % 4);
+                }
+                if !in_diamond {
+                    o = oct.invert_diamond(o[0], o[1]);
+                }
+                out[0] = o[0] + oct.center;
+                out[1] = o[1] + oct.center;
+            }
+        }
+    }
+}
+
+// --- Prediction schemes ---
+
+/// Corner table and traversal an edgebreaker attribute was encoded over
+struct MeshData<'a> {
+    table: &'a CornerTable,
+    encoding: &'a EncodingData,
+}
+
+impl MeshData<'_> {
+    fn corner(&self, value: usize) -> Result<u32> {
+        self.encoding.value_corners.get(value).copied().ok_or_else(corrupt)
+    }
+
+    /// Value stored at the vertex of corner `c`
+    fn value_of(&self, c: u32) -> Result<usize> {
+        let v = self.table.vertex(c);
+        let value = *self.encoding.vertex_values.get(v as usize).ok_or_else(corrupt)?;
+        usize::try_from(value).map_err(|_| corrupt())
+    }
+
+    /// Parallelogram prediction of `value` across the edge opposite `c`,
+    /// when the three other corners of the parallelogram are decoded
+    fn parallelogram(&self, value: usize, c: u32, decoded: &[i32], components: usize, out: &mut [i32]) -> Result<bool> {
+        let opp = self.table.opposite(c);
+        if opp == INVALID {
+            return Ok(false);
+        }
+        let [o, n, p] = [self.value_of(opp)?, self.value_of(next(opp))?, self.value_of(previous(opp))?];
+        if o >= value || n >= value || p >= value {
+            return Ok(false);
+        }
+        for (i, out) in out.iter_mut().enumerate() {
+            *out = decoded[n * components + i].wrapping_add(decoded[p * components + i]).wrapping_sub(decoded[o * components + i]);
+        }
+        Ok(true)
+    }
+}
+
+/// Prediction scheme with the data it reads from the stream
+enum Prediction<'a> {
+    Difference,
+    Parallelogram,
+    MultiParallelogram,
+    /// Crease flags per number of available parallelograms
+    ConstrainedMultiParallelogram(Vec<Vec<bool>>),
+    /// Which side of the opposite edge each predicted coordinate is on
+    TexCoords(Vec<bool>),
+    /// Whether each predicted normal points inwards
+    GeometricNormal(BitDecoder<'a>),
+}
+
+impl<'a> Prediction<'a> {
+    /// Read the scheme data and transform of `method`. Mesh schemes fall
+    /// back to differences without edgebreaker connectivity, whose corner
+    /// count is `num_corners`.
+    fn read(r: &mut Reader<'a>, method: i8, transform_type: i8, normals: bool, num_corners: Option<usize>) -> Result<(Self, Transform)> {
+        let Some(num_corners) = num_corners.filter(|_| method != 0) else {
+            if !(0..=6).contains(&method) || method == 3 {
+                return Err(format!("Unsupported Draco prediction scheme {}", method).into());
+            }
+            return Ok((Prediction::Difference, Transform::read(r, transform_type, normals)?));
+        };
+        let prediction = match method {
+            1 => Prediction::Parallelogram,
+            2 => Prediction::MultiParallelogram,
+            4 => {
+                let mut crease_edges = Vec::with_capacity(4);
+                for _ in 0..4 {
+                    let count = r.count(num_corners)?;
+                    let mut flags = Vec::with_capacity(count);
+                    if count > 0 {
+                        let mut bits = BitDecoder::start(r)?;
+                        flags.extend((0..count).map(|_| bits.next()));
+                    }
+                    crease_edges.push(flags);
+                }
+                Prediction::ConstrainedMultiParallelogram(crease_edges)
+            }
+            5 => {
+                let count = usize::try_from(r.i32()?).ok().filter(|&n| n <= num_corners).ok_or_else(corrupt)?;
+                let mut bits = BitDecoder::start(r)?;
+                let mut orientation = true;
+                let orientations = (0..count)
+                    .map(|_| {
+                        if !bits.next() {
+                            orientation = !orientation;
+                        }
+                        orientation
+                    })
+                    .collect();
+                Prediction::TexCoords(orientations)
+            }
+            6 => {
+                // The flips follow the transform data
+                let transform = Transform::read(r, transform_type, normals)?;
+                return Ok((Prediction::GeometricNormal(BitDecoder::start(r)?), transform));
+            }
+            _ => return Err(format!("Unsupported Draco prediction scheme {}", method).into()),
+        };
+        Ok((prediction, Transform::read(r, transform_type, normals)?))
+    }
+}
+
+/// What predictions are made from besides earlier values of the attribute
+struct PredictionInput<'a> {
+    mesh: Option<MeshData<'a>>,
+    /// Point each value belongs to
+    value_points: &'a [u32],
+    /// Quantized position of each point
+    positions: Option<&'a [[i64; 3]]>,
+}
+
+impl PredictionInput<'_> {
+    fn position(&self, value: usize) -> Result<[i64; 3]> {
+        let positions = self.positions.ok_or("Draco attribute is predicted from positions it does not have")?;
+        let point = *self.value_points.get(value).ok_or_else(corrupt)?;
+        positions.get(point as usize).copied().ok_or_else(corrupt)
+    }
+}
+
+/// Floor of the square root of `number`
+fn int_sqrt(number: u64) -> u64 {
+    if number == 0 {
+        return 0;
+    }
+    let mut act = number;
+    let mut root = 1u64;
+    while act >= 2 {
+        root *= 2;
+        act /= 4;
+    }
+    loop {
+        root = (root + number / root) / 2;
+        if root.checked_mul(root).is_some_and(|square| square <= number) {
+            return root;
+        }
+    }
+}
+
+/// Predict each value from the ones decoded before it and apply its
+/// correction
+fn predict(prediction: &mut Prediction, transform: &Transform, corr: &[i32], components: usize, input: &PredictionInput) -> Result<Vec<i32>> {
+    let num_values = corr.len() / components;
+    let mut out = vec![0; corr.len()];
+    // Fallback of every scheme: the previous value, or zero for the first
+    let delta = |out: &mut [i32], value: usize| {
+        let (decoded, rest) = out.split_at_mut(value * components);
+        let zero = [0; 3];
+        let previous = if value == 0 { &zero[..components] } else { &decoded[(value - 1) * components..] };
+        transform.original(previous, &corr[value * components..(value + 1) * components], &mut rest[..components]);
+    };
+    let apply = |out: &mut [i32], value: usize, pred: &[i32]| {
+        let range = value * components..(value + 1) * components;
+        transform.original(pred, &corr[range.clone()], &mut out[range]);
+    };
+    let mesh = || input.mesh.as_ref().ok_or_else(corrupt);
+
+    match prediction {
+        Prediction::Difference => {
+            for value in 0..num_values {
+                delta(&mut out, value);
+            }
+        }
+        Prediction::Parallelogram => {
+            let mesh = mesh()?;
+            let mut pred = vec![0; components];
+            for value in 0..num_values {
+                if value > 0 && mesh.parallelogram(value, mesh.corner(value)?, &out, components, &mut pred)? {
+                    apply(&mut out, value, &pred);
+                } else {
+                    delta(&mut out, value);
+                }
+            }
+        }
+        Prediction::MultiParallelogram => {
+            let mesh = mesh()?;
+            let mut pred = vec![0; components];
+            let mut sum = vec![0i32; components];
+            for value in 0..num_values {
+                let start = mesh.corner(value)?;
+                let mut count = 0;
+                sum.fill(0);
+                let mut c = start;
+                while value > 0 && c != INVALID {
+                    if mesh.parallelogram(value, c, &out, components, &mut pred)? {
+                        count += 1;
+                        sum.iter_mut().zip(&pred).for_each(|(s, &p)| *s = s.wrapping_add(p));
+                    }
+                    c = mesh.table.swing_right(c);
+                    if c == start {
+                        break;
+                    }
+                }
+                if count == 0 {
+                    delta(&mut out, value);
+                } else {
+                    sum.iter_mut().for_each(|s| *s /= count);
+                    apply(&mut out, value, &sum);
+                }
+            }
+        }
+        Prediction::ConstrainedMultiParallelogram(crease_edges) => {
+            let mesh = mesh()?;
+            let mut preds = vec![vec![0; components]; 4];
+            let mut sum = vec![0i32; components];
+            let mut crease_positions = [0; 4];
+            for value in 0..num_values {
+                let start = mesh.corner(value)?;
+                // Up to four parallelograms, swinging left and then right
+                // from an open boundary
+                let mut found = 0;
+                let mut c = start;
+                let mut first_pass = true;
+                while value > 0 && c != INVALID {
+                    if mesh.parallelogram(value, c, &out, components, &mut preds[found])? {
+                        found += 1;
+                        if found == 4 {
+                            break;
+                        }
+                    }
+                    c = if first_pass { mesh.table.swing_left(c) } else { mesh.table.swing_right(c) };
+                    if c == start {
+                        break;
+                    }
+                    if c == INVALID && first_pass {
+                        first_pass = false;
+                        c = mesh.table.swing_right(start);
+                    }
+                }
+                let mut used = 0;
+                sum.fill(0);
+                for pred in &preds[..found] {
+                    let context = found - 1;
+                    let crease = *crease_edges[context].get(crease_positions[context]).ok_or_else(corrupt)?;
+                    crease_positions[context] += 1;
+                    if !crease {
+                        used += 1;
+                        sum.iter_mut().zip(pred).for_each(|(s, &p)| *s = s.wrapping_add(p));
+                    }
+                }
+                if used == 0 {
+                    delta(&mut out, value);
+                } else {
+                    sum.iter_mut().for_each(|s| *s /= used);
+                    apply(&mut out, value, &sum);
+                }
+            }
+        }
+        Prediction::TexCoords(orientations) => {
+            let mesh = mesh()?;
+            if components != 2 {
+                return Err(corrupt());
+            }
+            for value in 0..num_values {
+                let c = mesh.corner(value)?;
+                let (next_value, prev_value) = (mesh.value_of(next(c))?, mesh.value_of(previous(c))?);
+                let pred = if next_value < value && prev_value < value {
+                    tex_coord_prediction(&out, value, next_value, prev_value, orientations, input)?
+                } else {
+                    None
+                };
+                let pred = pred.unwrap_or_else(|| {
+                    let source = if next_value < value { Some(next_value) } else { value.checked_sub(1) };
+                    source.map_or([0, 0], |v| [out[2 * v], out[2 * v + 1]])
+                });
+                apply(&mut out, value, &pred);
+            }
+        }
+        Prediction::GeometricNormal(flips) => {
+            let mesh = mesh()?;
+            let Transform::Octahedron(octahedron) = transform else {
+                return Err(corrupt());
+            };
+            if components != 2 {
+                return Err(corrupt());
+            }
+            let position = |c: u32| input.position(mesh.value_of(c)?);
+            for value in 0..num_values {
+                let start = mesh.corner(value)?;
+                let center = position(start)?;
+                // Sum of the area-weighted normals of the faces around the
+                // vertex, swinging left and then right from an open boundary
+                let mut normal = [0i64; 3];
+                let mut c = start;
+                let mut left = true;
+                while c != INVALID {
+                    let [n, p] = [position(next(c))?, position(previous(c))?].map(|v| [0, 1, 2].map(|i| v[i].wrapping_sub(center[i])));
+                    let cross = [
+                        n[1].wrapping_mul(p[2]).wrapping_sub(n[2].wrapping_mul(p[1])),
+                        n[2].wrapping_mul(p[0]).wrapping_sub(n[0].wrapping_mul(p[2])),
+                        n[0].wrapping_mul(p[1]).wrapping_sub(n[1].wrapping_mul(p[0])),
+                    ];
+                    normal = [0, 1, 2].map(|i| normal[i].wrapping_add(cross[i]));
+                    if left {
+                        c = mesh.table.swing_left(c);
+                        if c == INVALID {
+                            c = mesh.table.swing_right(start);
+                            left = false;
+                        } else if c == start {
+                            c = INVALID;
+                        }
+                    } else {
+                        c = mesh.table.swing_right(c);
+                    }
+                }
+                const UPPER_BOUND: i64 = 1 << 29;
+                let abs_sum = normal.iter().fold(0i64, |sum, v| sum.wrapping_add(v.wrapping_abs()));
+                if abs_sum > UPPER_BOUND {
+                    let quotient = abs_sum / UPPER_BOUND;
+                    normal = normal.map(|v| v / quotient);
+                }
+                let mut vector = octahedron.canonicalize_vector(normal.map(|v| v as i32 as i64));
+                if flips.next() {
+                    vector = vector.map(|v| -v);
+                }
+                apply(&mut out, value, &octahedron.vector_to_coords(vector));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Predict a texture coordinate from the triangle's positions and the
+/// coordinates of its other two corners, or `None` when they are degenerate
+fn tex_coord_prediction(out: &[i32], value: usize, next_value: usize, prev_value: usize, orientations: &mut Vec<bool>, input: &PredictionInput) -> Result<Option<[i32; 2]>> {
+    let uv = |v: usize| [out[2 * v] as i64, out[2 * v + 1] as i64];
+    let (n_uv, p_uv) = (uv(next_value), uv(prev_value));
+    if n_uv == p_uv {
+        return Ok(Some(p_uv.map(|v| v as i32)));
+    }
+    let tip = input.position(value)?;
+    let next_pos = input.position(next_value)?;
+    let prev_pos = input.position(prev_value)?;
+    let pn = [0, 1, 2].map(|i| prev_pos[i].wrapping_sub(next_pos[i]));
+    let pn_norm2 = pn.iter().fold(0i64, |sum, v| sum.wrapping_add(v.wrapping_mul(*v)));
+    if pn_norm2 == 0 {
+        return Ok(None);
+    }
+    let cn = [0, 1, 2].map(|i| tip[i].wrapping_sub(next_pos[i]));
+    let cn_dot_pn = (0..3).fold(0i64, |sum, i| sum.wrapping_add(pn[i].wrapping_mul(cn[i])));
+    let pn_uv = [p_uv[0] - n_uv[0], p_uv[1] - n_uv[1]];
+    let abs_max = |v: &[i64]| v.iter().map(|x| x.wrapping_abs()).max().unwrap_or(0).max(1);
+    if abs_max(&n_uv) > i64::MAX / pn_norm2 || cn_dot_pn > i64::MAX / abs_max(&pn_uv) || cn_dot_pn > i64::MAX / abs_max(&pn) {
+        return Err(corrupt());
+    }
+    let x_uv = [0, 1].map(|i| n_uv[i].wrapping_mul(pn_norm2).wrapping_add(cn_dot_pn.wrapping_mul(pn_uv[i])));
+    let x_pos = [0, 1, 2].map(|i| next_pos[i].wrapping_add(cn_dot_pn.wrapping_mul(pn[i]) / pn_norm2));
+    let cx_norm2 = (0..3).fold(0i64, |sum, i| {
+        let d = tip[i].wrapping_sub(x_pos[i]);
+        sum.wrapping_add(d.wrapping_mul(d))
+    });
+    let root = int_sqrt((cx_norm2 as u64).wrapping_mul(pn_norm2 as u64)) as i64;
+    let cx_uv = [pn_uv[1].wrapping_mul(root), pn_uv[0].wrapping_neg().wrapping_mul(root)];
+    let orientation = orientations.pop().ok_or_else(corrupt)?;
+    Ok(Some([0, 1].map(|i| {
+        let uv = if orientation { x_uv[i].wrapping_add(cx_uv[i]) } else { x_uv[i].wrapping_sub(cx_uv[i]) };
+        (uv / pn_norm2) as i32
+    })))
+}
+
+// --- Attributes ---
+
+/// Attribute decoded to one value per point
+#[derive(Debug)]
+pub struct DracoAttribute {
+    /// Id the glTF extension refers to the attribute by
+    pub unique_id: u32,
+    pub components: usize,
+    /// Whether the values are floats rather than integers
+    pub float: bool,
+    /// `components` values per point
+    pub values: Vec<f64>,
+}
+
+/// Decoded Draco mesh: triangles over point indices and the attributes of
+/// each point
+#[derive(Debug)]
+pub struct DracoMesh {
+    pub faces: Vec<[u32; 3]>,
+    pub attributes: Vec<DracoAttribute>,
+}
+
+impl DracoMesh {
+    pub fn attribute(&self, unique_id: u32) -> Option<&DracoAttribute> {
+        self.attributes.iter().find(|a| a.unique_id == unique_id)
+    }
+}
+
+const POSITION: u8 = 0;
+
+/// Attribute descriptor and the sequential decoder chosen for it
+struct AttributeHeader {
+    kind: u8,
+    data_type: u8,
+    components: usize,
+    unique_id: u32,
+    decoder: u8,
+}
+
+/// Byte size of a Draco data type
+fn data_type_size(data_type: u8) -> usize {
+    match data_type {
+        1 | 2 | 11 => 1,
+        3 | 4 => 2,
+        5 | 6 | 9 => 4,
+        _ => 8,
+    }
+}
+
+fn read_value(bytes: &[u8], data_type: u8) -> f64 {
+    match data_type {
+        1 => bytes[0] as i8 as f64,
+        2 | 11 => bytes[0] as f64,
+        3 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+        4 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+        5 => i32::from_le_bytes(bytes[..4].try_into().unwrap()) as f64,
+        6 => u32::from_le_bytes(bytes[..4].try_into().unwrap()) as f64,
+        7 => i64::from_le_bytes(bytes[..8].try_into().unwrap()) as f64,
+        8 => u64::from_le_bytes(bytes[..8].try_into().unwrap()) as f64,
+        9 => f32::from_le_bytes(bytes[..4].try_into().unwrap()) as f64,
+        _ => f64::from_le_bytes(bytes[..8].try_into().unwrap()),
+    }
+}
+
+/// Order a decoder's values were encoded in, and the value of each point
+struct Sequence<'a> {
+    value_points: Vec<u32>,
+    point_values: Vec<u32>,
+    mesh: Option<(&'a CornerTable, EncodingData)>,
+}
+
+impl<'a> Sequence<'a> {
+    /// Identity order of sequential connectivity
+    fn linear(num_points: usize) -> Self {
+        let ids = (0..num_points as u32).collect::<Vec<_>>();
+        Sequence { value_points: ids.clone(), point_values: ids, mesh: None }
+    }
+
+    /// Order of an edgebreaker traversal over `table`
+    fn traversal(table: &'a CornerTable, prediction_degree: bool, connectivity: &Connectivity) -> Result<Self> {
+        let encoding = traverse(table, prediction_degree)?;
+        let point = |c: u32| connectivity.faces[c as usize / 3][c as usize % 3];
+        let value_points = encoding.value_corners.iter().map(|&c| point(c)).collect();
+        let mut point_values = vec![INVALID; connectivity.num_points];
+        for c in 0..table.vertices.len() as u32 {
+            let value = *encoding.vertex_values.get(table.vertex(c) as usize).ok_or_else(corrupt)?;
+            let value = u32::try_from(value).ok().filter(|&v| (v as usize) < connectivity.num_points).ok_or_else(corrupt)?;
+            point_values[point(c) as usize] = value;
+        }
+        if point_values.contains(&INVALID) {
+            return Err(corrupt());
+        }
+        Ok(Sequence { value_points, point_values, mesh: Some((table, encoding)) })
+    }
+
+    fn num_values(&self) -> usize {
+        self.value_points.len()
+    }
+}
+
+/// Values of one attribute as the decoder stores them before the final
+/// transform
+enum Portable {
+    /// Generic attributes are stored as-is
+    Raw(Vec<f64>),
+    Integer(Vec<i32>),
+}
+
+/// Read the integer values of an integer, quantized or normal attribute
+/// and undo their prediction
+fn decode_integers(r: &mut Reader, header: &AttributeHeader, sequence: &Sequence, positions: Option<&[[i64; 3]]>) -> Result<Vec<i32>> {
+    let components = if header.decoder == 3 { 2 } else { header.components };
+    let num_values = sequence.num_values() * components;
+    let method = r.u8()? as i8;
+    let transform_type = if method == -2 { None } else { Some(r.u8()? as i8) };
+
+    let mut symbols = Vec::new();
+    if r.u8()? > 0 {
+        decode_symbols(r, num_values, components, &mut symbols)?;
+    } else {
+        let num_bytes = r.u8()? as usize;
+        if !(1..=4).contains(&num_bytes) || num_bytes.saturating_mul(num_values) > r.remaining() {
+            return Err(corrupt());
+        }
+        symbols.extend(r.bytes(num_bytes * num_values)?.chunks_exact(num_bytes).map(|bytes| {
+            let mut value = [0; 4];
+            value[..num_bytes].copy_from_slice(bytes);
+            u32::from_le_bytes(value)
+        }));
+    }
+    // Octahedral corrections are stored unsigned
+    let positive = matches!(transform_type, Some(2 | 3));
+    let corr: Vec<i32> = symbols.into_iter().map(|v| if positive { v as i32 } else { unzigzag(v) }).collect();
+
+    let Some(transform_type) = transform_type else {
+        return Ok(corr);
+    };
+    let num_corners = sequence.mesh.as_ref().map(|(table, _)| table.vertices.len());
+    let (mut prediction, transform) = Prediction::read(r, method, transform_type, header.decoder == 3, num_corners)?;
+    if num_values == 0 {
+        return Ok(corr);
+    }
+    let input = PredictionInput {
+        mesh: sequence.mesh.as_ref().map(|(table, encoding)| MeshData { table, encoding }),
+        value_points: &sequence.value_points,
+        positions,
+    };
+    predict(&mut prediction, &transform, &corr, components, &input)
+}
+
+/// Decode the attributes of one attribute decoder in the order they were
+/// encoded: every portable value, then every transform, then the results
+fn decode_attributes(r: &mut Reader, headers: &[AttributeHeader], sequence: &Sequence, positions: &mut Option<Vec<[i64; 3]>>, position_seen: &mut bool) -> Result<Vec<DracoAttribute>> {
+    let mut portables = Vec::with_capacity(headers.len());
+    for header in headers {
+        let portable = if header.decoder == 0 {
+            let size = data_type_size(header.data_type) * header.components;
+            let bytes = r.bytes(size.checked_mul(sequence.num_values()).ok_or_else(corrupt)?)?;
+            Portable::Raw(bytes.chunks_exact(data_type_size(header.data_type)).map(|b| read_value(b, header.data_type)).collect())
+        } else {
+            Portable::Integer(decode_integers(r, header, sequence, positions.as_deref())?)
+        };
+        // Later predictions use the first position attribute's integers
+        if header.kind == POSITION && !*position_seen {
+            *position_seen = true;
+            if let Portable::Integer(values) = &portable
+                && header.components == 3
+            {
+                let value = |v: u32| {
+                    let v = v as usize * 3;
+                    [values[v] as i64, values[v + 1] as i64, values[v + 2] as i64]
+                };
+                *positions = Some(sequence.point_values.iter().map(|&v| value(v)).collect());
+            }
+        }
+        portables.push(portable);
+    }
+
+    let mut dequantize = Vec::with_capacity(headers.len());
+    for header in headers {
+        dequantize.push(match header.decoder {
+            2 => {
+                let min = (0..header.components).map(|_| r.f32()).collect::<Result<Vec<_>>>()?;
+                let range = r.f32()?;
+                let bits = r.u8()?;
+                if !(1..=30).contains(&bits) {
+                    return Err(corrupt());
+                }
+                let delta = range / ((1u32 << bits) - 1) as f32;
+                Some((min, delta, None))
+            }
+            3 => Some((Vec::new(), 0.0, Some(Octahedron::new(r.u8()? as u32)?))),
+            _ => None,
+        });
+    }
+
+    let mut attributes = Vec::with_capacity(headers.len());
+    for ((header, portable), dequantize) in headers.iter().zip(portables).zip(dequantize) {
+        let components = header.components;
+        let values: Vec<f64> = match (portable, dequantize) {
+            (Portable::Raw(values), _) => values,
+            (Portable::Integer(values), None) => values.into_iter().map(|v| v as f64).collect(),
+            (Portable::Integer(values), Some((_, _, Some(octahedron)))) => {
+                values.chunks_exact(2).flat_map(|st| octahedron.unit_vector(st[0], st[1])).map(|v| v as f64).collect()
+            }
+            (Portable::Integer(values), Some((min, delta, None))) => {
+                values.iter().enumerate().map(|(i, &q)| (q as f32 * delta + min[i % components]) as f64).collect()
+            }
+        };
+        if values.len() != sequence.num_values() * components {
+            return Err(corrupt());
+        }
+        let mut per_point = Vec::with_capacity(sequence.point_values.len() * components);
+        for &value in &sequence.point_values {
+            let value = value as usize;
+            per_point.extend_from_slice(values.get(value * components..(value + 1) * components).ok_or_else(corrupt)?);
+        }
+        attributes.push(DracoAttribute { unique_id: header.unique_id, components, float: matches!(header.data_type, 9 | 10), values: per_point });
+    }
+    Ok(attributes)
+}
+
+/// Skip a metadata element: its entries, then its named children
+fn skip_metadata(r: &mut Reader, depth: usize) -> Result<()> {
+    if depth > 64 {
+        return Err(corrupt());
+    }
+    for _ in 0..r.count(r.remaining())? {
+        let name_len = r.u8()? as usize;
+        r.advance(name_len)?;
+        let size = r.count(r.remaining())?;
+        r.advance(size)?;
+    }
+    for _ in 0..r.count(r.remaining())? {
+        let name_len = r.u8()? as usize;
+        r.advance(name_len)?;
+        skip_metadata(r, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Decode a Draco 2.2 mesh
+pub fn decode(data: &[u8]) -> Result<DracoMesh> {
+    let mut r = Reader::new(data);
+    if r.bytes(5).ok() != Some(b"DRACO".as_slice()) {
+        return Err("Not Draco data".into());
+    }
+    let (major, minor) = (r.u8()?, r.u8()?);
+    if (major, minor) != (2, 2) {
+        return Err(format!("Unsupported Draco bitstream version {}.{}", major, minor).into());
+    }
+    if r.u8()? != 1 {
+        return Err("Draco point clouds are not supported".into());
+    }
+    let method = r.u8()?;
+    if r.u16()? & 0x8000 != 0 {
+        for _ in 0..r.count(r.remaining())? {
+            r.varint()?;
+            skip_metadata(&mut r, 0)?;
+        }
+        skip_metadata(&mut r, 0)?;
+    }
+
+    let connectivity = match method {
+        0 => decode_sequential(&mut r)?,
+        1 => decode_edgebreaker(&mut r)?,
+        _ => return Err(format!("Unknown Draco connectivity {}", method).into()),
+    };
+
+    // Each attribute decoder: the connectivity it traverses, then its
+    // attribute descriptors and sequential decoder types
+    let num_decoders = r.u8()? as usize;
+    let mut traversals = Vec::with_capacity(num_decoders);
+    if method == 1 {
+        for _ in 0..num_decoders {
+            let data_id = r.u8()? as i8;
+            let (corner, prediction_degree) = (r.u8()?, r.u8()?);
+            // Vertex decoders traverse the position table, corner decoders
+            // the table of their attribute's seams
+            let attribute = usize::try_from(data_id).ok().map(|id| connectivity.attribute_data.get(id).ok_or_else(corrupt)).transpose()?;
+            let table = match (corner, attribute) {
+                (0, _) => &connectivity.table,
+                (1, Some(attribute)) if prediction_degree == 0 => &attribute.table,
+                _ => return Err(corrupt()),
+            };
+            if data_id < -1 || prediction_degree > 1 {
+                return Err(corrupt());
+            }
+            traversals.push((table, prediction_degree == 1));
+        }
+    }
+    let mut headers = Vec::with_capacity(num_decoders);
+    for _ in 0..num_decoders {
+        let count = r.count(r.remaining() / 5)?;
+        if count == 0 {
+            return Err(corrupt());
+        }
+        let mut decoder_headers = Vec::with_capacity(count);
+        for _ in 0..count {
+            let (kind, data_type, components, _normalized) = (r.u8()?, r.u8()?, r.u8()? as usize, r.u8()?);
+            if kind > 4 || !(1..=11).contains(&data_type) || components == 0 {
+                return Err(corrupt());
+            }
+            let unique_id = r.varint_u32()?;
+            decoder_headers.push(AttributeHeader { kind, data_type, components, unique_id, decoder: 0 });
+        }
+        for header in &mut decoder_headers {
+            header.decoder = r.u8()?;
+            let valid = match header.decoder {
+                0 => true,
+                1 => header.data_type <= 6,
+                2 => header.data_type == 9,
+                3 => header.data_type == 9 && header.components == 3,
+                _ => false,
+            };
+            if !valid {
+                return Err(format!("Unsupported Draco attribute decoder {}", header.decoder).into());
+            }
+        }
+        headers.push(decoder_headers);
+    }
+
+    let mut attributes = Vec::new();
+    let mut positions = None;
+    let mut position_seen = false;
+    for (i, headers) in headers.iter().enumerate() {
+        let sequence = match traversals.get(i) {
+            Some(&(table, prediction_degree)) => Sequence::traversal(table, prediction_degree, &connectivity)?,
+            None => Sequence::linear(connectivity.num_points),
+        };
+        attributes.extend(decode_attributes(&mut r, headers, &sequence, &mut positions, &mut position_seen)?);
+    }
+    Ok(DracoMesh { faces: connectivity.faces, attributes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(out: &mut Vec<u8>, mut v: usize) {
+        while v >= 0x80 {
+            out.push((v & 0x7F) as u8 | 0x80);
+            v >>= 7;
+        }
+        out.push(v as u8);
+    }
+
+    fn zigzag(v: i32) -> u8 {
+        ((v << 1) ^ (v >> 31)) as u8
+    }
+
+    /// Final rANS state, tagged with its byte count in the top two bits
+    fn ans_end(data: &mut Vec<u8>, s: u32) {
+        match s {
+            0..0x40 => data.push(s as u8),
+            0x40..0x4000 => data.extend_from_slice(&((1 << 14) + s as u16).to_le_bytes()),
+            _ => data.extend_from_slice(&((2 << 22) + s).to_le_bytes()[..3]),
+        }
+    }
+
+    /// Bits as `BitDecoder::start` reads them, coded at even odds
+    fn rabs_bits(out: &mut Vec<u8>, bits: &[bool]) {
+        let (l_base, prob_zero) = (4096u32, 128u32);
+        let mut state = l_base;
+        let mut data = Vec::new();
+        for &bit in bits.iter().rev() {
+            let l_s = if bit { 256 - prob_zero } else { prob_zero };
+            if state >= l_base / 256 * 256 * l_s {
+                data.push(state as u8);
+                state /= 256;
+            }
+            state = (state / l_s) * 256 + state % l_s + if bit { 0 } else { 256 - prob_zero };
+        }
+        ans_end(&mut data, state - l_base);
+        out.push(prob_zero as u8);
+        varint(out, data.len());
+        out.extend(data);
+    }
+
+    /// Symbols as `decode_symbols` reads them with the raw rANS scheme
+    fn rans_symbols(out: &mut Vec<u8>, symbols: &[u32]) {
+        let max_symbol = *symbols.iter().max().unwrap();
+        let bit_length = (32 - max_symbol.leading_zeros()).max(1);
+        let precision = 1u32 << (3 * bit_length / 2).clamp(12, 20);
+        let mut probs = vec![0u32; max_symbol as usize + 1];
+        for &s in symbols {
+            probs[s as usize] += precision / symbols.len() as u32;
+        }
+        let top = (0..probs.len()).max_by_key(|&i| probs[i]).unwrap();
+        probs[top] += precision - probs.iter().sum::<u32>();
+
+        out.extend_from_slice(&[1, bit_length as u8]);
+        varint(out, probs.len());
+        for &p in &probs {
+            match p {
+                0..64 => out.push((p << 2) as u8),
+                _ => out.extend_from_slice(&[((p & 63) << 2) as u8 | 1, (p >> 6) as u8]),
+            }
+        }
+        let cumulative: Vec<u32> = probs.iter().scan(0, |c, &p| Some(std::mem::replace(c, *c + p))).collect();
+        let l_base = 4 * precision;
+        let mut state = l_base;
+        let mut data = Vec::new();
+        for &s in symbols.iter().rev() {
+            let p = probs[s as usize];
+            while state >= l_base / precision * 256 * p {
+                data.push(state as u8);
+                state /= 256;
+            }
+            state = (state / p) * precision + state % p + cumulative[s as usize];
+        }
+        ans_end(&mut data, state - l_base);
+        varint(out, data.len());
+        out.extend(data);
+    }
+
+    fn floats(out: &mut Vec<u8>, values: &[f32]) {
+        out.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn bit_and_symbol_coders_round_trip() {
+        let bits = [true, false, false, true, true, true, false, true, false, false];
+        let mut data = Vec::new();
+        rabs_bits(&mut data, &bits);
+        let mut r = Reader::new(&data);
+        let mut decoder = BitDecoder::start(&mut r).unwrap();
+        assert_eq!(bits.map(|_| decoder.next()), bits);
+        assert_eq!(r.remaining(), 0);
+
+        let symbols: Vec<u32> = (0..200).map(|i| (i * 7 % 13) as u32).collect();
+        let mut data = Vec::new();
+        rans_symbols(&mut data, &symbols);
+        let mut decoded = Vec::new();
+        decode_symbols(&mut Reader::new(&data), symbols.len(), 1, &mut decoded).unwrap();
+        assert_eq!(decoded, symbols);
+    }
+
+    /// Quad with rANS-coded, delta-predicted quantized positions, raw
+    /// integer texture coordinates and octahedral normals, behind some
+    /// metadata
+    fn sequential_quad() -> Vec<u8> {
+        let mut d = b"DRACO".to_vec();
+        d.extend_from_slice(&[2, 2, 1, 0, 0x00, 0x80]);
+        // One attribute's metadata, then the file's with a named child
+        d.extend_from_slice(&[1, 0, 1, 4]);
+        d.extend_from_slice(b"name");
+        d.extend_from_slice(&[3]);
+        d.extend_from_slice(b"pos");
+        d.extend_from_slice(&[0, 0, 1, 1, b'a', 0, 0]);
+
+        // Two faces over four points, raw indices
+        d.extend_from_slice(&[2, 4, 1, 0, 1, 2, 2, 1, 3]);
+
+        // One decoder: quantized POSITION (id 7), integer TEX_COORD (id 9)
+        // and NORMAL (id 11)
+        d.extend_from_slice(&[1, 3, 0, 9, 3, 0, 7, 3, 4, 2, 0, 9, 1, 9, 3, 0, 11, 2, 1, 3]);
+        // Differences wrapped into [0, 15], zigzagged
+        d.extend_from_slice(&[0, 1, 1]);
+        let corrections = [0, 0, 0, -1, 0, 0, 1, -1, 0, -1, 0, -1];
+        rans_symbols(&mut d, &corrections.map(|c| zigzag(c) as u32));
+        d.extend(0i32.to_le_bytes().into_iter().chain(15i32.to_le_bytes()));
+        // No prediction, two raw bytes per zigzagged value
+        d.extend_from_slice(&[0xFE, 0, 2]);
+        for v in [0, 0, 100, 0, 0, 100, 100, 100] {
+            d.extend_from_slice(&((v as u16) << 1).to_le_bytes());
+        }
+        // Octahedral differences for +X, +Z, -Y, +X at 4 bits (center 7)
+        d.extend_from_slice(&[0, 3, 0, 1, 8, 8, 0, 7, 7, 8, 7, 0]);
+        d.extend(15i32.to_le_bytes().into_iter().chain(7i32.to_le_bytes()));
+        // Quantization min, range and bits, then normal bits
+        floats(&mut d, &[-1.0, -1.0, 0.0, 2.0]);
+        d.extend_from_slice(&[4, 4]);
+        d
+    }
+
+    #[test]
+    fn decodes_sequential_mesh() {
+        let data = sequential_quad();
+        let mesh = decode(&data).unwrap();
+        assert_eq!(mesh.faces, vec![[0, 1, 2], [2, 1, 3]]);
+
+        let positions = mesh.attribute(7).unwrap();
+        assert!(positions.float);
+        assert_close(&positions.values, &[-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, -1.0, 1.0, 0.0, 1.0, 1.0, 2.0]);
+        let uvs = mesh.attribute(9).unwrap();
+        assert_eq!((uvs.components, uvs.float), (2, false));
+        assert_eq!(uvs.values, vec![0.0, 0.0, 100.0, 0.0, 0.0, 100.0, 100.0, 100.0]);
+        assert_close(&mesh.attribute(11).unwrap().values, &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.0, 0.0]);
+
+        assert!(decode(&data[..data.len() - 3]).is_err());
+    }
+
+    #[test]
+    fn decodes_edgebreaker_mesh_with_parallelogram_prediction() {
+        let mut d = b"DRACO".to_vec();
+        d.extend_from_slice(&[2, 2, 1, 1, 0, 0]);
+        // Standard traversal: 4 vertices, 2 faces, 2 symbols, no splits
+        d.extend_from_slice(&[0, 4, 2, 0, 2, 0, 0]);
+        // E then R, as 1+11 and 1+01 least significant bit first
+        d.extend_from_slice(&[1, 0b10_1111]);
+        rabs_bits(&mut d, &[false]);
+
+        // Position decoder over the vertices, depth first
+        d.extend_from_slice(&[1, 0xFF, 0, 0, 1, 0, 9, 3, 0, 0, 2]);
+        // Values reach the decoder as v1, v2, v0, v3: the first three are
+        // deltas wrapped into [0, 10], v3 completes the parallelogram exactly
+        d.extend_from_slice(&[1, 1, 0, 1]);
+        for c in [-1, 0, 0, 1, -1, 0, 0, 1, 0, 0, 0, 0] {
+            d.push(zigzag(c));
+        }
+        d.extend(0i32.to_le_bytes().into_iter().chain(10i32.to_le_bytes()));
+        floats(&mut d, &[0.0, 0.0, 0.0, 15.0]);
+        d.push(4);
+
+        let mesh = decode(&d).unwrap();
+        assert_eq!(mesh.faces, vec![[0, 1, 2], [2, 1, 3]]);
+        assert_close(&mesh.attribute(0).unwrap().values, &[0.0, 0.0, 0.0, 10.0, 0.0, 0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0]);
+    }
+
+    #[test]
+    fn rejects_unsupported_streams() {
+        let mut data = sequential_quad();
+        data[5] = 1;
+        assert!(decode(&data).unwrap_err().to_string().contains("version 1.2"));
+        let mut data = sequential_quad();
+        data[7] = 0;
+        assert!(decode(&data).unwrap_err().to_string().contains("point clouds"));
+        assert!(decode(b"glTF").is_err());
+    }
+}
@@ -0,0 +1,1437 @@
+//! Binary FBX (7.x) reader.
+//!
+//! Reads one mesh model: control points, fan-triangulated polygons, the first
+//! UV and normal layer elements (kept per corner), per-polygon materials as
+//! face colors and the first diffuse texture (embedded or on disk). Skin
+//! clusters become a `Skeleton` whose joints are the cluster bones and their
+//! ancestors, and every AnimationStack is sampled into an `AnimationClip`.
+//! Coordinates are converted from the file's unit to meters; axes are kept
+//! as stored.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use nalgebra::{Matrix3, Matrix4, Rotation3, Vector3};
+
+use super::animation::{
+    AnimationChannel, AnimationClip, AnimationProperty, Interpolation, Keyframe,
+};
+use super::loader::{MeshWithColors, TextureData};
+use super::skeleton::{Joint, JointTransform, Skeleton};
+use super::stdio::{self, progress};
+
+/// Color used for faces without a material
+const DEFAULT_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+
+const MAGIC: &[u8] = b"Kaydara FBX Binary  \0";
+
+/// FBX time units per second
+const TICKS_PER_SECOND: f64 = 46_186_158_000.0;
+
+// --- Binary node tree ---
+
+#[derive(Debug, Clone)]
+enum Property {
+    Int(i64),
+    Float(f64),
+    Ints(Vec<i64>),
+    Floats(Vec<f64>),
+    String(String),
+    Raw(Vec<u8>),
+}
+
+impl Property {
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Property::Int(v) => Some(*v),
+            Property::Float(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Property::Int(v) => Some(*v as f64),
+            Property::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Property::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    props: Vec<Property>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn id(&self) -> Option<i64> {
+        self.props.first().and_then(Property::as_i64)
+    }
+
+    /// Object name with the binary `\0\x01Class` suffix removed
+    fn object_name(&self) -> &str {
+        let full = self.props.get(1).and_then(Property::as_str).unwrap_or("");
+        full.split("\0\u{1}").next().unwrap_or(full)
+    }
+
+    /// Object subtype, e.g. "Mesh", "LimbNode", "Skin" or "Cluster"
+    fn object_kind(&self) -> &str {
+        self.props.get(2).and_then(Property::as_str).unwrap_or("")
+    }
+
+    fn string(&self, child: &str) -> Option<&str> {
+        self.child(child)?.props.first()?.as_str()
+    }
+
+    fn floats(&self, child: &str) -> Vec<f64> {
+        match self.child(child).and_then(|c| c.props.first()) {
+            Some(Property::Floats(v)) => v.clone(),
+            Some(Property::Ints(v)) => v.iter().map(|&i| i as f64).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn ints(&self, child: &str) -> Vec<i64> {
+        match self.child(child).and_then(|c| c.props.first()) {
+            Some(Property::Ints(v)) => v.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Values of a `Properties70` entry (after its name/type/label/flags)
+    fn p70(&self, name: &str) -> Option<&[Property]> {
+        self.child("Properties70")?
+            .children_named("P")
+            .find(|p| p.props.first().and_then(Property::as_str) == Some(name))
+            .map(|p| p.props.get(4..).unwrap_or(&[]))
+    }
+
+    fn p70_f64(&self, name: &str) -> Option<f64> {
+        self.p70(name)?.first()?.as_f64()
+    }
+
+    fn p70_vec3(&self, name: &str) -> Option<[f64; 3]> {
+        let v = self.p70(name)?;
+        Some([
+            v.first()?.as_f64()?,
+            v.get(1)?.as_f64()?,
+            v.get(2)?.as_f64()?,
+        ])
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.data.len());
+        let slice = end
+            .map(|e| &self.data[self.pos..e])
+            .ok_or("FBX file is truncated")?;
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Box<dyn std::error::Error>> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn std::error::Error>> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    /// Node record; None for the null record that ends a node list
+    fn node(&mut self, version: u32) -> Result<Option<Node>, Box<dyn std::error::Error>> {
+        let (end, prop_count) = if version >= 7500 {
+            let end = self.u64()?;
+            let count = self.u64()?;
+            self.u64()?;
+            (end as usize, count as usize)
+        } else {
+            let end = self.u32()?;
+            let count = self.u32()?;
+            self.u32()?;
+            (end as usize, count as usize)
+        };
+        let name_len = self.bytes(1)?[0] as usize;
+        if end == 0 {
+            return Ok(None);
+        }
+        if end > self.data.len() || end < self.pos {
+            return Err("FBX node record points outside the file".into());
+        }
+        let name = String::from_utf8_lossy(self.bytes(name_len)?).into_owned();
+        let props = (0..prop_count)
+            .map(|_| self.property())
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut children = Vec::new();
+        while self.pos < end {
+            match self.node(version)? {
+                Some(child) => children.push(child),
+                None => break,
+            }
+        }
+        self.pos = end;
+        Ok(Some(Node {
+            name,
+            props,
+            children,
+        }))
+    }
+
+    fn property(&mut self) -> Result<Property, Box<dyn std::error::Error>> {
+        let code = self.bytes(1)?[0];
+        Ok(match code {
+            b'C' => Property::Int(self.bytes(1)?[0] as i64),
+            b'Y' => Property::Int(i16::from_le_bytes(self.array()?) as i64),
+            b'I' => Property::Int(i32::from_le_bytes(self.array()?) as i64),
+            b'L' => Property::Int(i64::from_le_bytes(self.array()?)),
+            b'F' => Property::Float(f32::from_le_bytes(self.array()?) as f64),
+            b'D' => Property::Float(f64::from_le_bytes(self.array()?)),
+            b'S' | b'R' => {
+                let len = self.u32()? as usize;
+                let bytes = self.bytes(len)?;
+                if code == b'S' {
+                    Property::String(String::from_utf8_lossy(bytes).into_owned())
+                } else {
+                    Property::Raw(bytes.to_vec())
+                }
+            }
+            b'f' | b'd' | b'l' | b'i' | b'b' => {
+                let count = self.u32()? as usize;
+                let encoding = self.u32()?;
+                let len = self.u32()? as usize;
+                let raw = self.bytes(len)?;
+                let size = match code {
+                    b'd' | b'l' => 8,
+                    b'b' => 1,
+                    _ => 4,
+                };
+                let expected = count.checked_mul(size).ok_or("FBX array element count overflows")?;
+                let data = match encoding {
+                    0 => raw.to_vec(),
+                    1 => {
+                        // Stop inflating at the declared size, so a crafted stream can't grow without bound
+                        let mut out = Vec::new();
+                        flate2::read::ZlibDecoder::new(raw).take(expected as u64).read_to_end(&mut out)?;
+                        out
+                    }
+                    other => return Err(format!("Unknown FBX array encoding {}", other).into()),
+                };
+                if data.len() < expected {
+                    return Err("FBX array is shorter than its element count".into());
+                }
+                let elems = data.chunks_exact(size).take(count);
+                match code {
+                    b'f' => Property::Floats(
+                        elems
+                            .map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64)
+                            .collect(),
+                    ),
+                    b'd' => Property::Floats(
+                        elems
+                            .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+                            .collect(),
+                    ),
+                    b'l' => Property::Ints(
+                        elems
+                            .map(|c| i64::from_le_bytes(c.try_into().unwrap()))
+                            .collect(),
+                    ),
+                    b'i' => Property::Ints(
+                        elems
+                            .map(|c| i32::from_le_bytes(c.try_into().unwrap()) as i64)
+                            .collect(),
+                    ),
+                    _ => Property::Ints(elems.map(|c| c[0] as i64).collect()),
+                }
+            }
+            other => return Err(format!("Unknown FBX property type '{}'", other as char).into()),
+        })
+    }
+}
+
+/// Parse a binary FBX file into its top-level nodes
+fn parse_document(data: &[u8]) -> Result<Vec<Node>, Box<dyn std::error::Error>> {
+    if !data.starts_with(MAGIC) {
+        if data.starts_with(b"; FBX") || data.windows(12).take(1024).any(|w| w == b"FBXHeaderExt") {
+            return Err("ASCII FBX files are not supported; export as binary FBX".into());
+        }
+        return Err("Not a binary FBX file".into());
+    }
+    let mut reader = Reader {
+        data,
+        pos: MAGIC.len() + 2,
+    };
+    let version = reader.u32()?;
+    if version < 7000 {
+        return Err(format!("FBX version {} is not supported (7.x required)", version).into());
+    }
+
+    let mut nodes = Vec::new();
+    let header_size = if version >= 7500 { 25 } else { 13 };
+    while data.len() - reader.pos >= header_size {
+        match reader.node(version)? {
+            Some(node) => nodes.push(node),
+            None => break,
+        }
+    }
+    Ok(nodes)
+}
+
+// --- Scene objects ---
+
+/// A Model object's transform properties, in file units and degrees
+struct Transform {
+    translation: [f64; 3],
+    rotation: [f64; 3],
+    scaling: [f64; 3],
+    pre_rotation: [f64; 3],
+    post_rotation: [f64; 3],
+    rotation_offset: [f64; 3],
+    rotation_pivot: [f64; 3],
+    scaling_offset: [f64; 3],
+    scaling_pivot: [f64; 3],
+    rotation_order: i64,
+    geometric: Matrix4<f64>,
+}
+
+impl Transform {
+    fn from_model(model: &Node) -> Self {
+        let zero = |name| model.p70_vec3(name).unwrap_or([0.0; 3]);
+        let one = |name| model.p70_vec3(name).unwrap_or([1.0; 3]);
+        let geometric = translation(zero("GeometricTranslation"))
+            * euler(zero("GeometricRotation"), 0)
+            * scaling(one("GeometricScaling"));
+        Transform {
+            translation: zero("Lcl Translation"),
+            rotation: zero("Lcl Rotation"),
+            scaling: one("Lcl Scaling"),
+            pre_rotation: zero("PreRotation"),
+            post_rotation: zero("PostRotation"),
+            rotation_offset: zero("RotationOffset"),
+            rotation_pivot: zero("RotationPivot"),
+            scaling_offset: zero("ScalingOffset"),
+            scaling_pivot: zero("ScalingPivot"),
+            rotation_order: model
+                .p70("RotationOrder")
+                .and_then(|v| v.first())
+                .and_then(Property::as_i64)
+                .unwrap_or(0),
+            geometric,
+        }
+    }
+
+    /// Local matrix for the given (possibly animated) T/R/S values:
+    /// T * Roff * Rp * Rpre * R * Rpost^-1 * Rp^-1 * Soff * Sp * S * Sp^-1
+    fn local(&self, t: [f64; 3], r: [f64; 3], s: [f64; 3]) -> Matrix4<f64> {
+        let neg = |v: [f64; 3]| v.map(|c| -c);
+        translation(t)
+            * translation(self.rotation_offset)
+            * translation(self.rotation_pivot)
+            * euler(self.pre_rotation, 0)
+            * euler(r, self.rotation_order)
+            * euler(self.post_rotation, 0).transpose()
+            * translation(neg(self.rotation_pivot))
+            * translation(self.scaling_offset)
+            * translation(self.scaling_pivot)
+            * scaling(s)
+            * translation(neg(self.scaling_pivot))
+    }
+
+    fn rest(&self) -> Matrix4<f64> {
+        self.local(self.translation, self.rotation, self.scaling)
+    }
+}
+
+fn translation(t: [f64; 3]) -> Matrix4<f64> {
+    Matrix4::new_translation(&Vector3::from(t))
+}
+
+fn scaling(s: [f64; 3]) -> Matrix4<f64> {
+    Matrix4::new_nonuniform_scaling(&Vector3::from(s))
+}
+
+/// Rotation matrix from Euler angles in degrees and an FBX rotation order
+/// (0 = XYZ, meaning X is applied first)
+fn euler(degrees: [f64; 3], order: i64) -> Matrix4<f64> {
+    let [x, y, z] = [0, 1, 2].map(|i| {
+        let mut axis = Vector3::zeros();
+        axis[i] = 1.0;
+        Rotation3::from_axis_angle(
+            &nalgebra::Unit::new_unchecked(axis),
+            degrees[i].to_radians(),
+        )
+    });
+    let r = match order {
+        1 => y * z * x,
+        2 => x * z * y,
+        3 => z * x * y,
+        4 => y * x * z,
+        5 => x * y * z,
+        _ => z * y * x,
+    };
+    r.to_homogeneous()
+}
+
+/// Scale the translation part of a matrix, converting it to meters
+fn scale_translation(m: &Matrix4<f64>, unit: f64) -> Matrix4<f64> {
+    let mut m = *m;
+    for row in 0..3 {
+        m[(row, 3)] *= unit;
+    }
+    m
+}
+
+fn to_f32(m: &Matrix4<f64>) -> [[f32; 4]; 4] {
+    m.cast::<f32>().into()
+}
+
+/// Objects and connections of a parsed FBX file
+struct Scene<'a> {
+    objects: Vec<&'a Node>,
+    by_id: HashMap<i64, &'a Node>,
+    /// (child, parent, property) in file order
+    connections: Vec<(i64, i64, Option<&'a str>)>,
+    unit: f64,
+}
+
+impl<'a> Scene<'a> {
+    fn new(nodes: &'a [Node]) -> Self {
+        let objects: Vec<&Node> = nodes
+            .iter()
+            .filter(|n| n.name == "Objects")
+            .flat_map(|n| n.children.iter())
+            .collect();
+        let by_id = objects.iter().filter_map(|o| Some((o.id()?, *o))).collect();
+        let connections = nodes
+            .iter()
+            .filter(|n| n.name == "Connections")
+            .flat_map(|n| n.children_named("C"))
+            .filter_map(|c| {
+                let child = c.props.get(1)?.as_i64()?;
+                let parent = c.props.get(2)?.as_i64()?;
+                Some((child, parent, c.props.get(3).and_then(Property::as_str)))
+            })
+            .collect();
+        // UnitScaleFactor is in centimeters per file unit
+        let unit = nodes
+            .iter()
+            .find(|n| n.name == "GlobalSettings")
+            .and_then(|n| n.p70_f64("UnitScaleFactor"))
+            .unwrap_or(1.0)
+            / 100.0;
+        Scene {
+            objects,
+            by_id,
+            connections,
+            unit,
+        }
+    }
+
+    /// Objects of `class` connected as children of `parent`, in connection order
+    fn children_of(&self, parent: i64, class: &str) -> Vec<&'a Node> {
+        self.connections
+            .iter()
+            .filter(|(_, p, _)| *p == parent)
+            .filter_map(|(c, _, _)| self.by_id.get(c).copied())
+            .filter(|n| n.name == class)
+            .collect()
+    }
+
+    /// Objects of `class` that `child` is connected to
+    fn parents_of(&self, child: i64, class: &str) -> Vec<(&'a Node, Option<&'a str>)> {
+        self.connections
+            .iter()
+            .filter(|(c, _, _)| *c == child)
+            .filter_map(|(_, p, prop)| Some((self.by_id.get(p).copied()?, *prop)))
+            .filter(|(n, _)| n.name == class)
+            .collect()
+    }
+
+    fn parent_model(&self, model: i64) -> Option<&'a Node> {
+        self.parents_of(model, "Model").first().map(|(n, _)| *n)
+    }
+
+    /// Global (file units) transform of a model
+    fn global(&self, model: &Node) -> Matrix4<f64> {
+        let mut m = Transform::from_model(model).rest();
+        let mut current = model.id();
+        // Bounded walk in case of cyclic connections
+        for _ in 0..256 {
+            let Some(parent) = current.and_then(|id| self.parent_model(id)) else {
+                break;
+            };
+            m = Transform::from_model(parent).rest() * m;
+            current = parent.id();
+        }
+        m
+    }
+}
+
+// --- Loading ---
+
+/// Load one mesh (selected by model name when the file has several) from a binary FBX file
+pub fn load_fbx(
+    path: &Path,
+    mesh_name: Option<&str>,
+) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let data = stdio::read(path)?;
+    let nodes = parse_document(&data)?;
+    let scene = Scene::new(&nodes);
+
+    let meshes: Vec<(&Node, &Node)> = scene
+        .objects
+        .iter()
+        .filter(|o| o.name == "Model" && o.object_kind() == "Mesh")
+        .filter_map(|m| {
+            let geometry = scene
+                .children_of(m.id()?, "Geometry")
+                .into_iter()
+                .find(|g| g.object_kind() == "Mesh")?;
+            Some((*m, geometry))
+        })
+        .collect();
+    if meshes.is_empty() {
+        return Err("FBX file contains no meshes".into());
+    }
+    let mesh_list = || {
+        meshes
+            .iter()
+            .map(|(m, _)| m.object_name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let (model, geometry) = match (meshes.len(), mesh_name) {
+        (1, None) => meshes[0],
+        (_, None) => {
+            return Err(format!(
+                "FBX file contains {} meshes. Please specify one with --mesh <name>.\nAvailable meshes: {}",
+                meshes.len(),
+                mesh_list()
+            )
+            .into());
+        }
+        (_, Some(name)) => *meshes
+            .iter()
+            .find(|(m, _)| m.object_name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Mesh '{}' not found in FBX file.\nAvailable meshes: {}",
+                    name,
+                    mesh_list()
+                )
+            })?,
+    };
+    progress!("Loading mesh: {}", model.object_name());
+
+    let skin = geometry.id().and_then(|id| {
+        scene
+            .children_of(id, "Deformer")
+            .into_iter()
+            .find(|d| d.object_kind() == "Skin")
+    });
+
+    // Skinned vertices stay in mesh space (the clusters' bind matrices place them);
+    // static meshes get their node's global transform baked in
+    let model_transform = Transform::from_model(model);
+    let vertex_transform = match skin {
+        Some(_) => model_transform.geometric,
+        None => scene.global(model) * model_transform.geometric,
+    };
+    let (mut mesh, polygon_of_face) =
+        read_geometry(geometry, &scene_transform(&vertex_transform, scene.unit))?;
+
+    let materials = model
+        .id()
+        .map(|id| scene.children_of(id, "Material"))
+        .unwrap_or_default();
+    apply_materials(
+        &mut mesh,
+        &polygon_of_face,
+        geometry,
+        &materials,
+        &scene,
+        path,
+    );
+
+    if let Some(skin) = skin {
+        let (skeleton, model_to_joint) = read_skin(&mut mesh, skin, &scene)?;
+        mesh.animations = read_animations(&scene, &skeleton, &model_to_joint);
+        progress!(
+            "Loaded skeleton with {} joints, {} skinned vertices",
+            skeleton.joints.len(),
+            mesh.joint_indices.len()
+        );
+        if !mesh.animations.is_empty() {
+            progress!(
+                "Loaded {} animation clip(s): {}",
+                mesh.animations.len(),
+                mesh.animations
+                    .iter()
+                    .map(|a| a.name.as_deref().unwrap_or("<unnamed>"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        mesh.skeleton = Some(skeleton);
+    }
+
+    Ok(mesh)
+}
+
+/// Matrix taking file-unit geometry to meters
+fn scene_transform(m: &Matrix4<f64>, unit: f64) -> Matrix4<f64> {
+    Matrix4::new_scaling(unit) * m
+}
+
+/// Index into a layer element's data for a polygon corner
+fn layer_index(
+    element: &Node,
+    index_array: &[i64],
+    corner: usize,
+    control_point: usize,
+    polygon: usize,
+) -> Option<usize> {
+    let i = match element.string("MappingInformationType").unwrap_or("") {
+        "ByPolygonVertex" => corner,
+        "ByVertex" | "ByVertice" | "ByControlPoint" => control_point,
+        "ByPolygon" => polygon,
+        "AllSame" => 0,
+        _ => return None,
+    };
+    match element
+        .string("ReferenceInformationType")
+        .unwrap_or("Direct")
+    {
+        "Direct" => Some(i),
+        _ => index_array.get(i).and_then(|&j| usize::try_from(j).ok()),
+    }
+}
+
+/// Build positions, triangles and per-corner UVs/normals from a Geometry object.
+/// Also returns the source polygon of each triangle.
+fn read_geometry(
+    geometry: &Node,
+    transform: &Matrix4<f64>,
+) -> Result<(MeshWithColors, Vec<usize>), Box<dyn std::error::Error>> {
+    let vertices = geometry.floats("Vertices");
+    let polygon_vertices = geometry.ints("PolygonVertexIndex");
+    if vertices.is_empty() || polygon_vertices.is_empty() {
+        return Err("FBX mesh has no vertices or polygons".into());
+    }
+    let positions: Vec<[f32; 3]> = vertices
+        .chunks_exact(3)
+        .map(|v| {
+            let p = transform.transform_point(&nalgebra::Point3::new(v[0], v[1], v[2]));
+            [p.x as f32, p.y as f32, p.z as f32]
+        })
+        .collect();
+    let normal_matrix: Matrix3<f64> = transform
+        .fixed_view::<3, 3>(0, 0)
+        .try_inverse()
+        .unwrap_or_else(Matrix3::identity)
+        .transpose();
+    let flip = transform.fixed_view::<3, 3>(0, 0).determinant() < 0.0;
+
+    let uv_layer = geometry.children_named("LayerElementUV").next();
+    let uvs = uv_layer.map(|l| l.floats("UV")).unwrap_or_default();
+    let uv_index = uv_layer.map(|l| l.ints("UVIndex")).unwrap_or_default();
+    let normal_layer = geometry.children_named("LayerElementNormal").next();
+    let normals = normal_layer
+        .map(|l| l.floats("Normals"))
+        .unwrap_or_default();
+    let normal_index = normal_layer
+        .map(|l| l.ints("NormalsIndex"))
+        .unwrap_or_default();
+
+    let mut mesh = MeshWithColors {
+        positions,
+        ..Default::default()
+    };
+    let mut polygon = 0;
+    let mut start = 0;
+    let mut polygon_of_face = Vec::new();
+    for (end, &raw) in polygon_vertices.iter().enumerate() {
+        if raw >= 0 {
+            continue;
+        }
+        // The last corner of each polygon is stored as a bitwise-negated index
+        let corners: Vec<(usize, usize)> = (start..=end)
+            .map(|c| {
+                let v = polygon_vertices[c];
+                (c, if v < 0 { !v } else { v } as usize)
+            })
+            .collect();
+        if corners.iter().any(|&(_, v)| v >= mesh.positions.len()) {
+            return Err(format!("FBX polygon {} references a missing vertex", polygon).into());
+        }
+        for k in 1..corners.len().saturating_sub(1) {
+            let mut tri = [corners[0], corners[k], corners[k + 1]];
+            if flip {
+                tri.swap(1, 2);
+            }
+            mesh.face_indices.push(tri.map(|(_, v)| v as u32));
+            polygon_of_face.push(polygon);
+            if let Some(layer) = uv_layer {
+                mesh.corner_texcoords.push(tri.map(|(c, v)| {
+                    layer_index(layer, &uv_index, c, v, polygon)
+                        .and_then(|i| uvs.get(2 * i..2 * i + 2))
+                        .map(|uv| [uv[0] as f32, 1.0 - uv[1] as f32])
+                        .unwrap_or([0.0; 2])
+                }));
+            }
+            if let Some(layer) = normal_layer {
+                mesh.corner_normals.push(tri.map(|(c, v)| {
+                    layer_index(layer, &normal_index, c, v, polygon)
+                        .and_then(|i| normals.get(3 * i..3 * i + 3))
+                        .map(|n| {
+                            let n = (normal_matrix * Vector3::new(n[0], n[1], n[2])).normalize();
+                            [n.x as f32, n.y as f32, n.z as f32]
+                        })
+                        .unwrap_or([0.0; 3])
+                }));
+            }
+        }
+        polygon += 1;
+        start = end + 1;
+    }
+
+    Ok((mesh, polygon_of_face))
+}
+
+/// Set face colors from the per-polygon material slots and load the first
+/// diffuse texture
+fn apply_materials(
+    mesh: &mut MeshWithColors,
+    polygon_of_face: &[usize],
+    geometry: &Node,
+    materials: &[&Node],
+    scene: &Scene,
+    path: &Path,
+) {
+    let colors: Vec<[f32; 4]> = materials
+        .iter()
+        .map(|m| {
+            let [r, g, b] = m.p70_vec3("DiffuseColor").unwrap_or([0.85; 3]);
+            let a = m.p70_f64("Opacity").unwrap_or(1.0);
+            [r as f32, g as f32, b as f32, a as f32]
+        })
+        .collect();
+    if let Some(layer) = geometry.children_named("LayerElementMaterial").next()
+        && !colors.is_empty()
+    {
+        let slots = layer.ints("Materials");
+        let all_same = layer.string("MappingInformationType") == Some("AllSame");
+        mesh.face_colors = polygon_of_face
+            .iter()
+            .map(|&p| {
+                let slot = slots
+                    .get(if all_same { 0 } else { p })
+                    .copied()
+                    .unwrap_or(0);
+                usize::try_from(slot)
+                    .ok()
+                    .and_then(|s| colors.get(s))
+                    .copied()
+                    .unwrap_or(DEFAULT_COLOR)
+            })
+            .collect();
+    }
+
+    let texture = materials.iter().find_map(|m| {
+        let textures = m.id().map(|id| {
+            scene
+                .connections
+                .iter()
+                .filter(|(_, p, _)| *p == id)
+                .filter_map(|(c, _, prop)| Some((scene.by_id.get(c).copied()?, *prop)))
+                .filter(|(n, _)| n.name == "Texture")
+                .collect::<Vec<_>>()
+        })?;
+        textures
+            .iter()
+            .find(|(_, prop)| *prop == Some("DiffuseColor"))
+            .or(textures.first())
+            .map(|(t, _)| *t)
+    });
+    let Some(texture) = texture else {
+        return;
+    };
+
+    let embedded = texture
+        .id()
+        .map(|id| scene.children_of(id, "Video"))
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|video| match video.child("Content")?.props.first()? {
+            Property::Raw(bytes) if !bytes.is_empty() => Some(bytes.clone()),
+            _ => None,
+        });
+    let image = match embedded {
+        Some(bytes) => image::load_from_memory(&bytes).map_err(|e| e.to_string()),
+        None => {
+            let dir = path.parent().unwrap_or(Path::new("."));
+            let relative = texture.string("RelativeFilename").unwrap_or("");
+            let absolute = texture.string("FileName").unwrap_or("");
+            let file_name = Path::new(&absolute.replace('\\', "/"))
+                .file_name()
+                .map(|f| dir.join(f));
+            let candidates = [
+                (!relative.is_empty()).then(|| dir.join(relative.replace('\\', "/"))),
+                (!absolute.is_empty()).then(|| absolute.into()),
+                file_name,
+            ];
+            let found = candidates.into_iter().flatten().find(|p| p.is_file());
+            match found {
+                Some(p) => image::open(&p).map_err(|e| format!("{:?}: {}", p, e)),
+                None => Err(format!("texture file '{}' not found", absolute)),
+            }
+        }
+    };
+    match image {
+        Ok(img) => {
+            let rgba = img.to_rgba8();
+            mesh.texture = Some(TextureData {
+                width: rgba.width(),
+                height: rgba.height(),
+                pixels: rgba.into_raw(),
+            });
+        }
+        Err(e) => eprintln!("Warning: failed to load FBX texture: {}", e),
+    }
+}
+
+/// Build the skeleton and per-vertex joint weights from a Skin deformer.
+/// Returns the skeleton and the map from Model id to joint index.
+fn read_skin(
+    mesh: &mut MeshWithColors,
+    skin: &Node,
+    scene: &Scene,
+) -> Result<(Skeleton, HashMap<i64, usize>), Box<dyn std::error::Error>> {
+    let clusters: Vec<&Node> = skin
+        .id()
+        .map(|id| scene.children_of(id, "Deformer"))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|d| d.object_kind() == "Cluster")
+        .collect();
+
+    // Bones linked by clusters, plus their ancestors so world transforms are complete
+    let mut links: Vec<(&Node, i64)> = Vec::new();
+    for cluster in &clusters {
+        if let Some(bone) = cluster
+            .id()
+            .and_then(|id| scene.children_of(id, "Model").first().copied())
+            && let Some(bone_id) = bone.id()
+        {
+            links.push((cluster, bone_id));
+        }
+    }
+    if links.is_empty() {
+        return Err("FBX skin has no clusters linked to bones".into());
+    }
+    let mut in_skeleton: HashSet<i64> = HashSet::new();
+    for &(_, bone) in &links {
+        let mut current = Some(bone);
+        while let Some(id) = current
+            && in_skeleton.insert(id)
+        {
+            current = scene.parent_model(id).and_then(Node::id);
+        }
+    }
+
+    // Depth-first order so that parents precede children
+    let models: Vec<&Node> = scene
+        .objects
+        .iter()
+        .filter(|o| o.name == "Model" && o.id().is_some_and(|id| in_skeleton.contains(&id)))
+        .copied()
+        .collect();
+    let parent_of = |m: &Node| {
+        scene
+            .parent_model(m.id()?)
+            .and_then(Node::id)
+            .filter(|p| in_skeleton.contains(p))
+    };
+    let mut ordered: Vec<(&Node, Option<i64>)> = Vec::new();
+    let mut stack: Vec<&Node> = models
+        .iter()
+        .rev()
+        .filter(|m| parent_of(m).is_none())
+        .copied()
+        .collect();
+    while let Some(model) = stack.pop() {
+        ordered.push((model, parent_of(model)));
+        stack.extend(models.iter().rev().filter(|c| parent_of(c) == model.id()));
+    }
+    let model_to_joint: HashMap<i64, usize> = ordered
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (m, _))| Some((m.id()?, i)))
+        .collect();
+
+    let unit = scene.unit;
+    let mut joints: Vec<Joint> = ordered
+        .iter()
+        .enumerate()
+        .map(|(i, (model, parent))| {
+            let local = scale_translation(&Transform::from_model(model).rest(), unit);
+            let (translation, rotation, scale) = JointTransform::Matrix(to_f32(&local)).decompose();
+            let inverse_bind = scale_translation(&scene.global(model), unit)
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity);
+            Joint {
+                index: i,
+                node_index: scene
+                    .objects
+                    .iter()
+                    .position(|o| std::ptr::eq(*o, *model))
+                    .unwrap_or(i),
+                name: Some(model.object_name().to_string()),
+                parent: parent.and_then(|p| model_to_joint.get(&p).copied()),
+                inverse_bind_matrix: to_f32(&inverse_bind),
+                local_transform: JointTransform::Decomposed {
+                    translation,
+                    rotation,
+                    scale,
+                },
+            }
+        })
+        .collect();
+
+    let mut influences: Vec<Vec<(u16, f32)>> = vec![Vec::new(); mesh.positions.len()];
+    for (cluster, bone) in links {
+        let joint = model_to_joint[&bone];
+        let matrix = |name| {
+            let v = cluster.floats(name);
+            (v.len() == 16).then(|| scale_translation(&Matrix4::from_column_slice(&v), unit))
+        };
+        // Bind pose: the mesh's global transform (Transform) relative to the bone's (TransformLink)
+        if let (Some(transform), Some(link)) = (matrix("Transform"), matrix("TransformLink"))
+            && let Some(link_inverse) = link.try_inverse()
+        {
+            joints[joint].inverse_bind_matrix = to_f32(&(link_inverse * transform));
+        }
+        let weights = cluster.floats("Weights");
+        for (&vertex, &weight) in cluster.ints("Indexes").iter().zip(&weights) {
+            if let Some(list) = usize::try_from(vertex)
+                .ok()
+                .and_then(|v| influences.get_mut(v))
+            {
+                list.push((joint as u16, weight as f32));
+            }
+        }
+    }
+
+    for list in &mut influences {
+        list.sort_by(|a, b| b.1.total_cmp(&a.1));
+        list.truncate(4);
+        let total: f32 = list.iter().map(|(_, w)| w).sum();
+        let mut indices = [0u16; 4];
+        let mut weights = [0.0f32; 4];
+        for (k, &(j, w)) in list.iter().enumerate() {
+            indices[k] = j;
+            weights[k] = if total > 0.0 { w / total } else { 0.0 };
+        }
+        mesh.joint_indices.push(indices);
+        mesh.joint_weights.push(weights);
+    }
+
+    Ok((Skeleton { joints }, model_to_joint))
+}
+
+/// One animated X/Y/Z property of a bone
+#[derive(Default)]
+struct CurveSet {
+    curves: [Option<(Vec<i64>, Vec<f64>)>; 3],
+}
+
+impl CurveSet {
+    fn sample(&self, time: i64, rest: [f64; 3]) -> [f64; 3] {
+        let mut out = rest;
+        for (axis, curve) in self.curves.iter().enumerate() {
+            let Some((times, values)) = curve else {
+                continue;
+            };
+            let i = times.partition_point(|&t| t <= time);
+            out[axis] = match (i.checked_sub(1), times.get(i)) {
+                (None, _) => values[0],
+                (Some(prev), None) => values[prev],
+                (Some(prev), Some(&next_time)) => {
+                    let t = (time - times[prev]) as f64 / (next_time - times[prev]) as f64;
+                    values[prev] + (values[i] - values[prev]) * t
+                }
+            };
+        }
+        out
+    }
+}
+
+/// Sample every AnimationStack's bone curves into T/R/S keyframes
+fn read_animations(
+    scene: &Scene,
+    skeleton: &Skeleton,
+    model_to_joint: &HashMap<i64, usize>,
+) -> Vec<AnimationClip> {
+    let mut clips = Vec::new();
+    for stack in scene.objects.iter().filter(|o| o.name == "AnimationStack") {
+        let Some(stack_id) = stack.id() else { continue };
+        let start = stack
+            .p70("LocalStart")
+            .and_then(|v| v.first())
+            .and_then(Property::as_i64)
+            .unwrap_or(0);
+
+        // joint -> [translation, rotation, scaling] curves
+        let mut animated: HashMap<usize, [CurveSet; 3]> = HashMap::new();
+        for layer in scene.children_of(stack_id, "AnimationLayer") {
+            let Some(layer_id) = layer.id() else { continue };
+            for curve_node in scene.children_of(layer_id, "AnimationCurveNode") {
+                let Some(node_id) = curve_node.id() else {
+                    continue;
+                };
+                for (model, prop) in scene.parents_of(node_id, "Model") {
+                    let slot = match prop {
+                        Some("Lcl Translation") => 0,
+                        Some("Lcl Rotation") => 1,
+                        Some("Lcl Scaling") => 2,
+                        _ => continue,
+                    };
+                    let Some(&joint) = model.id().and_then(|id| model_to_joint.get(&id)) else {
+                        continue;
+                    };
+                    let sets = animated.entry(joint).or_default();
+                    for (c, _, axis) in scene.connections.iter().filter(|(_, p, _)| *p == node_id) {
+                        let axis = match axis {
+                            Some("d|X") => 0,
+                            Some("d|Y") => 1,
+                            Some("d|Z") => 2,
+                            _ => continue,
+                        };
+                        let Some(curve) = scene.by_id.get(c).filter(|n| n.name == "AnimationCurve")
+                        else {
+                            continue;
+                        };
+                        let times = curve.ints("KeyTime");
+                        let values = curve.floats("KeyValueFloat");
+                        if !times.is_empty() && times.len() == values.len() {
+                            sets[slot].curves[axis] = Some((times, values));
+                        }
+                    }
+                }
+            }
+        }
+        if animated.is_empty() {
+            continue;
+        }
+
+        let mut channels = Vec::new();
+        let mut duration = 0.0f32;
+        let mut joints: Vec<_> = animated.into_iter().collect();
+        joints.sort_by_key(|(j, _)| *j);
+        for (joint, sets) in joints {
+            let model = scene.objects[skeleton.joints[joint].node_index];
+            let transform = Transform::from_model(model);
+            let mut times: Vec<i64> = sets
+                .iter()
+                .flat_map(|s| s.curves.iter().flatten())
+                .flat_map(|(t, _)| t.iter().copied())
+                .collect();
+            times.sort_unstable();
+            times.dedup();
+
+            let mut keys: [Vec<Keyframe>; 3] = Default::default();
+            let mut previous_rotation: Option<[f32; 4]> = None;
+            for &tick in &times {
+                let t = sets[0].sample(tick, transform.translation);
+                let r = sets[1].sample(tick, transform.rotation);
+                let s = sets[2].sample(tick, transform.scaling);
+                let local = scale_translation(&transform.local(t, r, s), scene.unit);
+                let (translation, mut rotation, scale) =
+                    JointTransform::Matrix(to_f32(&local)).decompose();
+                // Keep consecutive quaternions in the same hemisphere for slerp
+                if let Some(prev) = previous_rotation
+                    && prev.iter().zip(&rotation).map(|(a, b)| a * b).sum::<f32>() < 0.0
+                {
+                    rotation = rotation.map(|c| -c);
+                }
+                previous_rotation = Some(rotation);
+
+                let time = ((tick - start) as f64 / TICKS_PER_SECOND).max(0.0) as f32;
+                duration = duration.max(time);
+                for (k, value) in [translation.to_vec(), rotation.to_vec(), scale.to_vec()]
+                    .into_iter()
+                    .enumerate()
+                {
+                    keys[k].push(Keyframe {
+                        time,
+                        value,
+                        in_tangent: None,
+                        out_tangent: None,
+                    });
+                }
+            }
+            let properties = [
+                AnimationProperty::Translation,
+                AnimationProperty::Rotation,
+                AnimationProperty::Scale,
+            ];
+            for (property, keyframes) in properties.into_iter().zip(keys) {
+                channels.push(AnimationChannel {
+                    joint_index: joint,
+                    property,
+                    interpolation: Interpolation::Linear,
+                    keyframes,
+                });
+            }
+        }
+        clips.push(AnimationClip {
+            name: Some(stack.object_name().to_string()),
+            channels,
+            duration,
+        });
+    }
+    clips
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    enum P {
+        I(i64),
+        D(f64),
+        S(&'static str),
+        Ints(Vec<i32>),
+        Longs(Vec<i64>),
+        Floats(Vec<f64>),
+    }
+
+    struct T(&'static str, Vec<P>, Vec<T>);
+
+    /// Serialize a 7.4 node record (u32 offsets) at the end of `out`,
+    /// zlib-compressing f64 arrays
+    fn write_node(out: &mut Vec<u8>, T(name, props, children): &T) {
+        let start = out.len();
+        out.extend_from_slice(&[0; 12]);
+        out.push(name.len() as u8);
+        out.extend_from_slice(name.as_bytes());
+        let props_start = out.len();
+        for p in props {
+            let mut array = |code: u8, count: usize, raw: Vec<u8>, compress: bool| {
+                out.push(code);
+                out.extend_from_slice(&(count as u32).to_le_bytes());
+                let data = if compress {
+                    let mut z =
+                        flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                    z.write_all(&raw).unwrap();
+                    z.finish().unwrap()
+                } else {
+                    raw
+                };
+                out.extend_from_slice(&(compress as u32).to_le_bytes());
+                out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                out.extend_from_slice(&data);
+            };
+            match p {
+                P::Ints(v) => array(
+                    b'i',
+                    v.len(),
+                    v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+                    false,
+                ),
+                P::Longs(v) => array(
+                    b'l',
+                    v.len(),
+                    v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+                    false,
+                ),
+                P::Floats(v) => array(
+                    b'd',
+                    v.len(),
+                    v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+                    true,
+                ),
+                P::I(v) => {
+                    out.push(b'L');
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+                P::D(v) => {
+                    out.push(b'D');
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+                P::S(s) => {
+                    out.push(b'S');
+                    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                    out.extend_from_slice(s.as_bytes());
+                }
+            }
+        }
+        let props_len = out.len() - props_start;
+        for child in children {
+            write_node(out, child);
+        }
+        if !children.is_empty() {
+            out.extend_from_slice(&[0; 13]);
+        }
+        let end = out.len() as u32;
+        out[start..start + 4].copy_from_slice(&end.to_le_bytes());
+        out[start + 4..start + 8].copy_from_slice(&(props.len() as u32).to_le_bytes());
+        out[start + 8..start + 12].copy_from_slice(&(props_len as u32).to_le_bytes());
+    }
+
+    fn p70(entries: Vec<(&'static str, Vec<f64>)>) -> T {
+        let ps = entries
+            .into_iter()
+            .map(|(name, values)| {
+                let mut props = vec![P::S(name), P::S(""), P::S(""), P::S("A")];
+                props.extend(values.into_iter().map(P::D));
+                T("P", props, vec![])
+            })
+            .collect();
+        T("Properties70", vec![], ps)
+    }
+
+    fn object(
+        class: &'static str,
+        id: i64,
+        name: &'static str,
+        kind: &'static str,
+        children: Vec<T>,
+    ) -> T {
+        T(class, vec![P::I(id), P::S(name), P::S(kind)], children)
+    }
+
+    fn connection(child: i64, parent: i64, prop: Option<&'static str>) -> T {
+        let mut props = vec![
+            P::S(if prop.is_some() { "OP" } else { "OO" }),
+            P::I(child),
+            P::I(parent),
+        ];
+        props.extend(prop.map(P::S));
+        T("C", props, vec![])
+    }
+
+    /// A quad with UVs and two materials, skinned to a two-bone chain whose
+    /// child bone rotates 90 degrees about Z over one second; units are meters
+    fn write_fbx(path: &Path) {
+        let leaf = |name, prop| T(name, vec![prop], vec![]);
+        let identity: Vec<f64> = Matrix4::<f64>::identity().as_slice().to_vec();
+        let mut tip_link = Matrix4::<f64>::identity();
+        tip_link[(0, 3)] = 1.0;
+        let cluster = |id, indexes: Vec<i32>, link: &[f64]| {
+            object(
+                "Deformer",
+                id,
+                "Cluster\0\u{1}SubDeformer",
+                "Cluster",
+                vec![
+                    leaf("Indexes", P::Ints(indexes)),
+                    leaf("Weights", P::Floats(vec![1.0, 1.0])),
+                    leaf("Transform", P::Floats(identity.clone())),
+                    leaf("TransformLink", P::Floats(link.to_vec())),
+                ],
+            )
+        };
+        let material = |id, name, rgb: [f64; 3]| {
+            object(
+                "Material",
+                id,
+                name,
+                "",
+                vec![p70(vec![("DiffuseColor", rgb.to_vec())])],
+            )
+        };
+
+        let objects = T(
+            "Objects",
+            vec![],
+            vec![
+                object(
+                    "Geometry",
+                    10,
+                    "Quad\0\u{1}Geometry",
+                    "Mesh",
+                    vec![
+                        leaf(
+                            "Vertices",
+                            P::Floats(vec![0., 0., 0., 1., 0., 0., 1., 1., 0., 0., 1., 0.]),
+                        ),
+                        leaf("PolygonVertexIndex", P::Ints(vec![0, 1, 2, !3])),
+                        T(
+                            "LayerElementUV",
+                            vec![P::I(0)],
+                            vec![
+                                leaf("MappingInformationType", P::S("ByPolygonVertex")),
+                                leaf("ReferenceInformationType", P::S("IndexToDirect")),
+                                leaf("UV", P::Floats(vec![0., 0., 1., 0., 1., 1., 0., 1.])),
+                                leaf("UVIndex", P::Ints(vec![0, 1, 2, 3])),
+                            ],
+                        ),
+                        T(
+                            "LayerElementMaterial",
+                            vec![P::I(0)],
+                            vec![
+                                leaf("MappingInformationType", P::S("AllSame")),
+                                leaf("ReferenceInformationType", P::S("IndexToDirect")),
+                                leaf("Materials", P::Ints(vec![1])),
+                            ],
+                        ),
+                    ],
+                ),
+                object("Model", 1, "Body\0\u{1}Model", "Mesh", vec![]),
+                material(20, "Red\0\u{1}Material", [1.0, 0.0, 0.0]),
+                material(21, "Blue\0\u{1}Material", [0.0, 0.0, 1.0]),
+                object("Model", 30, "Root\0\u{1}Model", "LimbNode", vec![]),
+                object(
+                    "Model",
+                    31,
+                    "Tip\0\u{1}Model",
+                    "LimbNode",
+                    vec![p70(vec![("Lcl Translation", vec![1.0, 0.0, 0.0])])],
+                ),
+                object("Deformer", 40, "Skin\0\u{1}Deformer", "Skin", vec![]),
+                cluster(41, vec![0, 3], &identity),
+                cluster(42, vec![1, 2], tip_link.as_slice()),
+                object("AnimationStack", 50, "Wave\0\u{1}AnimStack", "", vec![]),
+                object("AnimationLayer", 51, "Base\0\u{1}AnimLayer", "", vec![]),
+                object(
+                    "AnimationCurveNode",
+                    52,
+                    "R\0\u{1}AnimCurveNode",
+                    "",
+                    vec![],
+                ),
+                object(
+                    "AnimationCurve",
+                    53,
+                    "\0\u{1}AnimCurve",
+                    "",
+                    vec![
+                        leaf("KeyTime", P::Longs(vec![0, TICKS_PER_SECOND as i64])),
+                        leaf("KeyValueFloat", P::Floats(vec![0.0, 90.0])),
+                    ],
+                ),
+            ],
+        );
+        let connections = T(
+            "Connections",
+            vec![],
+            [
+                (1, 0, None),
+                (10, 1, None),
+                (20, 1, None),
+                (21, 1, None),
+                (30, 0, None),
+                (31, 30, None),
+                (40, 10, None),
+                (41, 40, None),
+                (42, 40, None),
+                (30, 41, None),
+                (31, 42, None),
+                (51, 50, None),
+                (52, 51, None),
+                (52, 31, Some("Lcl Rotation")),
+                (53, 52, Some("d|Z")),
+            ]
+            .into_iter()
+            .map(|(c, p, prop)| connection(c, p, prop))
+            .collect(),
+        );
+        let settings = T(
+            "GlobalSettings",
+            vec![],
+            vec![p70(vec![("UnitScaleFactor", vec![100.0])])],
+        );
+
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&[0x1A, 0x00]);
+        data.extend_from_slice(&7400u32.to_le_bytes());
+        for node in [settings, objects, connections] {
+            write_node(&mut data, &node);
+        }
+        data.extend_from_slice(&[0; 13]);
+        std::fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn loads_geometry_materials_skin_and_animation() {
+        let path = std::env::temp_dir().join(format!("msh_fbx_{}.fbx", std::process::id()));
+        write_fbx(&path);
+        let mesh = load_fbx(&path, None).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2], [0, 2, 3]]);
+        // V flipped to the glTF convention
+        assert_eq!(
+            mesh.corner_texcoords[0],
+            [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0]]
+        );
+        // AllSame material slot 1
+        assert_eq!(mesh.face_colors, vec![[0.0, 0.0, 1.0, 1.0]; 2]);
+
+        let skeleton = mesh.skeleton.as_ref().unwrap();
+        let names: Vec<_> = skeleton
+            .joints
+            .iter()
+            .map(|j| j.name.clone().unwrap())
+            .collect();
+        assert_eq!(names, vec!["Root", "Tip"]);
+        assert_eq!(skeleton.joints[1].parent, Some(0));
+        assert_eq!(mesh.joint_indices[1], [1, 0, 0, 0]);
+        assert_eq!(mesh.joint_weights[1], [1.0, 0.0, 0.0, 0.0]);
+        // Inverse bind of the tip bone undoes its +1 X offset
+        assert_eq!(skeleton.joints[1].inverse_bind_matrix[3][0], -1.0);
+
+        assert_eq!(mesh.animations.len(), 1);
+        let clip = &mesh.animations[0];
+        assert_eq!(clip.name.as_deref(), Some("Wave"));
+        assert!((clip.duration - 1.0).abs() < 1e-6);
+        let rotation = clip
+            .channels
+            .iter()
+            .find(|c| c.joint_index == 1 && c.property == AnimationProperty::Rotation)
+            .unwrap();
+        let end = &rotation.keyframes[1].value;
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((end[2] - half).abs() < 1e-5 && (end[3] - half).abs() < 1e-5);
+    }
+
+    #[test]
+    fn euler_orders_apply_first_axis_first() {
+        // XYZ: X by 90 takes +Y to +Z, which Z by 90 leaves alone
+        let v = euler([90.0, 0.0, 90.0], 0).transform_vector(&Vector3::new(0.0, 1.0, 0.0));
+        assert!((v - Vector3::new(0.0, 0.0, 1.0)).norm() < 1e-9);
+        // ZYX: Z by 90 takes +Y to -X, which X by 90 leaves alone
+        let v = euler([90.0, 0.0, 90.0], 5).transform_vector(&Vector3::new(0.0, 1.0, 0.0));
+        assert!((v - Vector3::new(-1.0, 0.0, 0.0)).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,287 @@
+//! Input format registry.
+//!
+//! Formats are detected from the file contents (magic bytes, headers and, for
+//! OBJ, the leading keywords), falling back to the extension when the
+//! contents are inconclusive. `--format` bypasses detection entirely, so
+//! extension-less and misnamed files load either way. Stdin (`-`) has no
+//! extension, so it is detected from its contents alone.
+
+use std::io::Read;
+use std::path::Path;
+
+use super::{stdio, stl};
+
+/// Number of leading bytes inspected when sniffing
+const SNIFF_LEN: usize = 4096;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Keywords that may start a line of an OBJ file
+const OBJ_KEYWORDS: &[&str] = &[
+    "v", "vt", "vn", "vp", "f", "l", "p", "o", "g", "s", "mtllib", "usemtl", "usemap", "maplib",
+    "cstype", "deg", "curv", "curv2", "surf", "parm", "trim", "hole", "scrv", "sp", "end", "con",
+    "mg", "bevel", "c_interp", "d_interp", "lod", "shadow_obj", "trace_obj",
+];
+
+/// A mesh (or motion) file format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeshFormat {
+    Obj,
+    /// glTF JSON or binary GLB
+    Gltf,
+    ThreeMf,
+    Fbx,
+    Collada,
+    Usda,
+    Stl,
+    Ply,
+    Bvh,
+}
+
+impl MeshFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            MeshFormat::Obj => "OBJ",
+            MeshFormat::Gltf => "glTF",
+            MeshFormat::ThreeMf => "3MF",
+            MeshFormat::Fbx => "FBX",
+            MeshFormat::Collada => "Collada",
+            MeshFormat::Usda => "USDA",
+            MeshFormat::Stl => "STL",
+            MeshFormat::Ply => "PLY",
+            MeshFormat::Bvh => "BVH",
+        }
+    }
+
+    /// Format conventionally stored under a file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Some(match extension.to_lowercase().as_str() {
+            "obj" => MeshFormat::Obj,
+            "glb" | "gltf" => MeshFormat::Gltf,
+            "3mf" => MeshFormat::ThreeMf,
+            "fbx" => MeshFormat::Fbx,
+            "dae" => MeshFormat::Collada,
+            "usda" => MeshFormat::Usda,
+            "stl" => MeshFormat::Stl,
+            "ply" => MeshFormat::Ply,
+            "bvh" => MeshFormat::Bvh,
+            _ => return None,
+        })
+    }
+
+    /// Extension of the single-file output written for this format, if msh can write it
+    pub fn output_extension(self) -> Option<&'static str> {
+        match self {
+            MeshFormat::Obj => Some("obj"),
+            MeshFormat::Gltf => Some("glb"),
+            MeshFormat::ThreeMf => Some("3mf"),
+            MeshFormat::Usda => Some("usda"),
+            MeshFormat::Stl => Some("stl"),
+            MeshFormat::Ply => Some("ply"),
+            MeshFormat::Fbx | MeshFormat::Collada | MeshFormat::Bvh => None,
+        }
+    }
+
+    /// Identify a format from the leading bytes of a file of `len` bytes
+    pub fn sniff(head: &[u8], len: u64) -> Option<Self> {
+        if head.starts_with(b"glTF") {
+            return Some(MeshFormat::Gltf);
+        }
+        if head.starts_with(ZIP_MAGIC) {
+            return is_3mf_package(head).then_some(MeshFormat::ThreeMf);
+        }
+        if head.starts_with(b"Kaydara FBX Binary") {
+            return Some(MeshFormat::Fbx);
+        }
+        // Binary STL headers are free-form, so only the size can identify them
+        if stl::matches_binary_size(head, len) {
+            return Some(MeshFormat::Stl);
+        }
+
+        let text = String::from_utf8_lossy(head);
+        let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with("#usda") {
+            return Some(MeshFormat::Usda);
+        }
+        if trimmed.starts_with("ply\n") || trimmed.starts_with("ply\r\n") {
+            return Some(MeshFormat::Ply);
+        }
+        if trimmed.starts_with("HIERARCHY") {
+            return Some(MeshFormat::Bvh);
+        }
+        if trimmed.starts_with('<') && text.contains("<COLLADA") {
+            return Some(MeshFormat::Collada);
+        }
+        if trimmed.starts_with('{') {
+            return Some(MeshFormat::Gltf);
+        }
+        if trimmed.starts_with("solid") && (text.contains("facet") || text.contains("endsolid")) {
+            return Some(MeshFormat::Stl);
+        }
+        if looks_like_obj(&text, head.len() as u64 == len) {
+            return Some(MeshFormat::Obj);
+        }
+        None
+    }
+}
+
+/// Whether a zip's leading entries name the OPC content types or a 3MF model part
+fn is_3mf_package(head: &[u8]) -> bool {
+    let names: [&[u8]; 2] = [b"[Content_Types].xml", b"3D/3dmodel.model"];
+    names.iter().any(|name| head.windows(name.len()).any(|w| w == *name))
+}
+
+/// Why recognisable contents can't be loaded whatever the extension says
+fn unsupported(head: &[u8], named: Option<MeshFormat>) -> Option<&'static str> {
+    let text = String::from_utf8_lossy(&head[..head.len().min(64)]);
+    if text.trim_start_matches('\u{feff}').trim_start().starts_with("; FBX") {
+        return Some("ASCII FBX files are not supported; export as binary FBX");
+    }
+    // A .3mf may keep its model part past the sniff window
+    if head.starts_with(ZIP_MAGIC) && !is_3mf_package(head) && named != Some(MeshFormat::ThreeMf) {
+        return Some("it is a zip archive but not a 3MF package; unzip it first");
+    }
+    None
+}
+
+/// OBJ heuristic: every complete non-comment line starts with an OBJ keyword,
+/// and at least one declares geometry
+fn looks_like_obj(text: &str, complete: bool) -> bool {
+    let mut lines: Vec<&str> = text.lines().collect();
+    // The last line may be cut off by the sniff window
+    if !complete {
+        lines.pop();
+    }
+    let mut geometry = false;
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let keyword = line.split_whitespace().next().unwrap_or("");
+        if !OBJ_KEYWORDS.contains(&keyword) {
+            return false;
+        }
+        geometry |= matches!(keyword, "v" | "f" | "o" | "g");
+    }
+    geometry
+}
+
+/// Detect the format of a file (or stdin for `-`): `forced` wins, then the
+/// contents, then the extension
+pub fn detect(path: &Path, forced: Option<MeshFormat>) -> Result<MeshFormat, Box<dyn std::error::Error>> {
+    if let Some(format) = forced {
+        return Ok(format);
+    }
+
+    if stdio::is_std(path) {
+        let bytes = stdio::stdin_bytes()?;
+        let head = &bytes[..bytes.len().min(SNIFF_LEN)];
+        if let Some(reason) = unsupported(head, None) {
+            return Err(format!("Can't load stdin: {}", reason).into());
+        }
+        return MeshFormat::sniff(head, bytes.len() as u64)
+            .ok_or_else(|| "Could not detect the format of stdin; pass --format".into());
+    }
+
+    let file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64).read_to_end(&mut head)?;
+
+    let extension = path.extension().and_then(|s| s.to_str());
+    let by_extension = extension.and_then(MeshFormat::from_extension);
+    if let Some(reason) = unsupported(&head, by_extension) {
+        return Err(format!("Can't load {:?}: {}", path, reason).into());
+    }
+    match (MeshFormat::sniff(&head, len), by_extension) {
+        (Some(sniffed), Some(named)) if sniffed != named => {
+            eprintln!(
+                "Warning: {:?} has a .{} extension but contains {} data; loading as {}",
+                path,
+                extension.unwrap_or_default(),
+                sniffed.name(),
+                sniffed.name()
+            );
+            Ok(sniffed)
+        }
+        (Some(format), _) | (None, Some(format)) => Ok(format),
+        (None, None) => Err(match extension {
+            Some(ext) => format!("Unsupported file format: {} (use --format to choose a parser)", ext),
+            None => format!("Could not detect the format of {:?}; pass --format", path),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sniff(bytes: &[u8]) -> Option<MeshFormat> {
+        MeshFormat::sniff(bytes, bytes.len() as u64)
+    }
+
+    #[test]
+    fn sniffs_magic_bytes_and_headers() {
+        assert_eq!(sniff(b"glTF\x02\x00\x00\x00"), Some(MeshFormat::Gltf));
+        assert_eq!(sniff(b"{\"asset\":{\"version\":\"2.0\"}}"), Some(MeshFormat::Gltf));
+        assert_eq!(sniff(b"PK\x03\x04\x14\x00[Content_Types].xml"), Some(MeshFormat::ThreeMf));
+        assert_eq!(sniff(b"PK\x03\x04\x14\x00model.obj"), None);
+        assert_eq!(sniff(b"Kaydara FBX Binary  \x00"), Some(MeshFormat::Fbx));
+        assert_eq!(sniff(b"#usda 1.0\n"), Some(MeshFormat::Usda));
+        assert_eq!(sniff(b"ply\nformat ascii 1.0\n"), Some(MeshFormat::Ply));
+        assert_eq!(sniff(b"HIERARCHY\nROOT Hips\n"), Some(MeshFormat::Bvh));
+        assert_eq!(
+            sniff(b"<?xml version=\"1.0\"?>\n<COLLADA version=\"1.4.1\">"),
+            Some(MeshFormat::Collada)
+        );
+        assert_eq!(sniff(b"solid cube\n  facet normal 0 0 1\n"), Some(MeshFormat::Stl));
+
+        // Binary STL whose header starts with "solid"
+        let mut binary = b"solid exported".to_vec();
+        binary.resize(80, 0);
+        binary.extend_from_slice(&1u32.to_le_bytes());
+        binary.resize(84 + 50, 0);
+        assert_eq!(sniff(&binary), Some(MeshFormat::Stl));
+    }
+
+    #[test]
+    fn recognizes_obj_by_keywords() {
+        assert_eq!(
+            sniff(b"# exported\nmtllib a.mtl\no cube\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n"),
+            Some(MeshFormat::Obj)
+        );
+        assert_eq!(sniff(b"hello world\nv 0 0 0\n"), None);
+        assert_eq!(sniff(b"# only a comment\n"), None);
+        // A partial trailing line past the sniff window is ignored
+        assert_eq!(MeshFormat::sniff(b"v 0 0 0\nf 1 2 3\nvert", 10_000), Some(MeshFormat::Obj));
+    }
+
+    #[test]
+    fn detection_prefers_contents_and_falls_back_to_extension() {
+        let dir = std::env::temp_dir().join(format!("msh_format_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let hashed = dir.join("3f2a9c");
+        std::fs::write(&hashed, "ply\nformat ascii 1.0\nend_header\n").unwrap();
+        assert_eq!(detect(&hashed, None).unwrap(), MeshFormat::Ply);
+
+        let misnamed = dir.join("model.stl");
+        std::fs::write(&misnamed, "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+        assert_eq!(detect(&misnamed, None).unwrap(), MeshFormat::Obj);
+        assert_eq!(detect(&misnamed, Some(MeshFormat::Stl)).unwrap(), MeshFormat::Stl);
+
+        let zipped = dir.join("model.obj");
+        std::fs::write(&zipped, b"PK\x03\x04\x14\x00model.obj").unwrap();
+        assert!(detect(&zipped, None).unwrap_err().to_string().contains("zip archive"));
+        let ascii_fbx = dir.join("rig.fbx");
+        std::fs::write(&ascii_fbx, "; FBX 7.4.0 project file\n").unwrap();
+        assert!(detect(&ascii_fbx, None).unwrap_err().to_string().contains("ASCII FBX"));
+
+        let unknown = dir.join("blob");
+        std::fs::write(&unknown, [0u8, 1, 2, 3]).unwrap();
+        assert!(detect(&unknown, None).unwrap_err().to_string().contains("--format"));
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,816 @@
+//! Compressed glTF geometry.
+//!
+//! `EXT_meshopt_compression` buffer views are decoded up front into their
+//! (usually uri-less) fallback buffers, so the regular accessor readers work
+//! on the result. `KHR_draco_mesh_compression` primitives are decoded into an
+//! extra in-memory buffer that their accessors are pointed at.
+
+use std::path::Path;
+
+use super::{draco, stdio};
+
+pub const DRACO: &str = "KHR_draco_mesh_compression";
+pub const MESHOPT: &str = "EXT_meshopt_compression";
+
+/// Document, buffer data and image data, as returned by `gltf::import`
+pub type Import = (gltf::Document, Vec<gltf::buffer::Data>, Vec<gltf::image::Data>);
+
+/// Import a glTF/GLB file like `gltf::import`, decoding meshopt-compressed
+/// buffer views and accepting the compression extensions as required
+pub fn import<P: AsRef<Path>>(path: P) -> Result<Import, Box<dyn std::error::Error>> {
+    let path = path.as_ref();
+    let bytes = stdio::read(path)?;
+    let gltf::Gltf { document, mut blob } = gltf::Gltf::from_slice_without_validation(&bytes)?;
+    let mut json = document.into_json();
+    json.extensions_required.retain(|e| e != DRACO && e != MESHOPT);
+    validate(&json)?;
+    let mut document = gltf::Document::from_json_without_validation(json);
+
+    let base = path.parent().unwrap_or_else(|| Path::new("./"));
+    let mut buffers = Vec::new();
+    for buffer in document.buffers() {
+        let is_fallback = buffer
+            .extension_value(MESHOPT)
+            .and_then(|ext| ext.get("fallback"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let data = if is_fallback {
+            // Only ever filled in by decoding, so no data needs to be read
+            gltf::buffer::Data(vec![0; buffer.length().next_multiple_of(4)])
+        } else {
+            gltf::buffer::Data::from_source_and_blob(buffer.source(), Some(base), &mut blob)?
+        };
+        if data.len() < buffer.length() {
+            return Err(format!(
+                "Buffer {} is {} bytes, expected {}",
+                buffer.index(),
+                data.len(),
+                buffer.length()
+            )
+            .into());
+        }
+        buffers.push(data);
+    }
+
+    decode_meshopt_views(&document, &mut buffers)?;
+    if document.meshes().flat_map(|m| m.primitives()).any(|p| p.extension_value(DRACO).is_some()) {
+        let mut json = document.into_json();
+        decode_draco_primitives(&mut json, &mut buffers)?;
+        document = gltf::Document::from_json_without_validation(json);
+    }
+    let images = gltf::import_images(&document, Some(base), &buffers)?;
+    Ok((document, buffers, images))
+}
+
+/// Validate like `gltf::import`, except that Draco-compressed accessors
+/// legitimately have no buffer view
+fn validate(json: &gltf::json::Root) -> Result<(), gltf::Error> {
+    use gltf::json::validation::{Error, Validate};
+    let mut errors = Vec::new();
+    json.validate(json, gltf::json::Path::new, &mut |path, error| {
+        let path = path();
+        let draco_accessor = matches!(error, Error::Missing)
+            && path.as_str().starts_with("accessors[")
+            && path.as_str().ends_with(".bufferView");
+        if !draco_accessor {
+            errors.push((path, error));
+        }
+    });
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(gltf::Error::Validation(errors))
+    }
+}
+
+/// Name of the compression extension a primitive's geometry uses, if any
+pub fn primitive_compression(primitive: &gltf::Primitive) -> Option<&'static str> {
+    if primitive.extension_value(DRACO).is_some() {
+        return Some(DRACO);
+    }
+    let meshopt = primitive
+        .attributes()
+        .map(|(_, accessor)| accessor)
+        .chain(primitive.indices())
+        .any(|accessor| {
+            accessor
+                .view()
+                .is_some_and(|view| view.extension_value(MESHOPT).is_some())
+        });
+    meshopt.then_some(MESHOPT)
+}
+
+/// Error for a primitive whose positions could not be read
+pub fn missing_positions_error(primitive: &gltf::Primitive) -> String {
+    if primitive.extension_value(DRACO).is_some() {
+        format!("Primitive uses {} but its Draco data has no POSITION attribute", DRACO)
+    } else {
+        "Primitive has no position data".to_string()
+    }
+}
+
+/// Decode every Draco-compressed primitive into a new buffer and point the
+/// primitive's index and attribute accessors at the decoded data
+fn decode_draco_primitives(
+    json: &mut gltf::json::Root,
+    buffers: &mut Vec<gltf::buffer::Data>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use gltf::json::validation::Checked;
+
+    let buffer = gltf::json::Index::new(json.buffers.len() as u32);
+    let mut data = Vec::new();
+    let mut views = Vec::new();
+    // (accessor, view, element count) to apply once every primitive is decoded
+    let mut updates = Vec::new();
+    for (m, mesh) in json.meshes.iter().enumerate() {
+        for (p, primitive) in mesh.primitives.iter().enumerate() {
+            let Some(ext) = primitive.extensions.as_ref().and_then(|e| e.others.get(DRACO)) else {
+                continue;
+            };
+            let context = |e: &dyn std::fmt::Display| format!("Mesh {} primitive {}: {}", m, p, e);
+            let view = ext
+                .get("bufferView")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| json.buffer_views.get(v as usize))
+                .ok_or_else(|| context(&format!("{} has no valid bufferView", DRACO)))?;
+            let offset = view.byte_offset.map_or(0, |o| o.0 as usize);
+            let compressed = buffers
+                .get(view.buffer.value())
+                .and_then(|b| b.get(offset..offset + view.byte_length.0 as usize))
+                .ok_or_else(|| context(&"Draco buffer view is out of range"))?;
+            let mesh = draco::decode(compressed).map_err(|e| context(&e))?;
+
+            let mut targets = Vec::new();
+            if let Some(indices) = primitive.indices {
+                let faces: Vec<f64> = mesh.faces.iter().flatten().map(|&i| i as f64).collect();
+                targets.push((indices, faces, 1, false));
+            }
+            for (semantic, accessor) in &primitive.attributes {
+                let Checked::Valid(semantic) = semantic else {
+                    continue;
+                };
+                let semantic = semantic.to_string();
+                let Some(id) = ext.get("attributes").and_then(|a| a.get(&semantic)).and_then(|v| v.as_u64()) else {
+                    // Not compressed; the accessor keeps its own data
+                    continue;
+                };
+                let attribute = mesh
+                    .attribute(id as u32)
+                    .ok_or_else(|| context(&format!("Draco data has no attribute {} for {}", id, semantic)))?;
+                targets.push((*accessor, attribute.values.clone(), attribute.components, attribute.float));
+            }
+
+            for (accessor, values, components, float) in targets {
+                let json_accessor = json
+                    .accessors
+                    .get(accessor.value())
+                    .ok_or_else(|| context(&format!("Accessor {} does not exist", accessor.value())))?;
+                let (Checked::Valid(component_type), Checked::Valid(type_)) = (&json_accessor.component_type, &json_accessor.type_) else {
+                    continue;
+                };
+                if components != type_.multiplicity() {
+                    return Err(context(&format!("Draco data does not match the type of accessor {}", accessor.value())).into());
+                }
+                let count = values.len() / components;
+                while data.len() % 4 != 0 {
+                    data.push(0);
+                }
+                let start = data.len();
+                push_components(&mut data, &values, component_type.0, json_accessor.normalized && float);
+                views.push(gltf::json::buffer::View {
+                    buffer,
+                    byte_length: (data.len() - start).into(),
+                    byte_offset: Some(start.into()),
+                    byte_stride: None,
+                    name: None,
+                    target: None,
+                    extensions: None,
+                    extras: Default::default(),
+                });
+                updates.push((accessor, json.buffer_views.len() + views.len() - 1, count));
+            }
+        }
+    }
+
+    for (accessor, view, count) in updates {
+        let accessor = &mut json.accessors[accessor.value()];
+        accessor.buffer_view = Some(gltf::json::Index::new(view as u32));
+        accessor.byte_offset = None;
+        accessor.count = count.into();
+    }
+    json.buffer_views.extend(views);
+    json.buffers.push(gltf::json::Buffer {
+        byte_length: data.len().into(),
+        name: None,
+        uri: None,
+        extensions: None,
+        extras: Default::default(),
+    });
+    buffers.push(gltf::buffer::Data(data));
+    Ok(())
+}
+
+/// Append `values` as little-endian components of `component_type`, scaling
+/// unit floats to the integer range when `normalize` is set
+fn push_components(out: &mut Vec<u8>, values: &[f64], component_type: gltf::json::accessor::ComponentType, normalize: bool) {
+    use gltf::json::accessor::ComponentType::*;
+    let max = match component_type {
+        I8 => i8::MAX as f64,
+        U8 => u8::MAX as f64,
+        I16 => i16::MAX as f64,
+        U16 => u16::MAX as f64,
+        U32 => u32::MAX as f64,
+        F32 => 1.0,
+    };
+    for &v in values {
+        let v = if normalize { (v * max).round() } else { v };
+        match component_type {
+            I8 => out.push(v as i8 as u8),
+            U8 => out.push(v as u8),
+            I16 => out.extend_from_slice(&(v as i16).to_le_bytes()),
+            U16 => out.extend_from_slice(&(v as u16).to_le_bytes()),
+            U32 => out.extend_from_slice(&(v as u32).to_le_bytes()),
+            F32 => out.extend_from_slice(&(v as f32).to_le_bytes()),
+        }
+    }
+}
+
+/// Decode every meshopt-compressed buffer view into its target buffer
+fn decode_meshopt_views(
+    document: &gltf::Document,
+    buffers: &mut [gltf::buffer::Data],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for view in document.views() {
+        let Some(ext) = view.extension_value(MESHOPT) else {
+            continue;
+        };
+        let field = |name: &str| ext.get(name).and_then(|v| v.as_u64()).map(|v| v as usize);
+        let source_buffer = field("buffer").ok_or("meshopt buffer view has no buffer")?;
+        let offset = field("byteOffset").unwrap_or(0);
+        let length = field("byteLength").ok_or("meshopt buffer view has no byteLength")?;
+        let stride = field("byteStride").ok_or("meshopt buffer view has no byteStride")?;
+        let count = field("count").ok_or("meshopt buffer view has no count")?;
+        let mode = ext.get("mode").and_then(|v| v.as_str()).unwrap_or("");
+        let filter = ext.get("filter").and_then(|v| v.as_str()).unwrap_or("NONE");
+
+        let source = buffers
+            .get(source_buffer)
+            .and_then(|b| b.get(offset..offset + length))
+            .ok_or_else(|| format!("meshopt buffer view {} is out of range", view.index()))?;
+        let decoded = match mode {
+            "ATTRIBUTES" => {
+                let mut data = decode_vertex_buffer(source, count, stride)?;
+                apply_filter(&mut data, stride, filter)?;
+                data
+            }
+            "TRIANGLES" => decode_index_buffer(source, count, stride)?,
+            "INDICES" => decode_index_sequence(source, count, stride)?,
+            other => return Err(format!("Unknown meshopt mode '{}'", other).into()),
+        };
+
+        let target = buffers[view.buffer().index()]
+            .0
+            .get_mut(view.offset()..view.offset() + decoded.len())
+            .ok_or_else(|| format!("meshopt buffer view {} does not fit its buffer", view.index()))?;
+        target.copy_from_slice(&decoded);
+    }
+    Ok(())
+}
+
+// --- Vertex codec ---
+
+const BYTE_GROUP_SIZE: usize = 16;
+const VERTEX_BLOCK_MAX_SIZE: usize = 256;
+const VERTEX_BLOCK_SIZE_BYTES: usize = 8192;
+const TAIL_MIN_SIZE: usize = 32;
+
+fn truncated() -> Box<dyn std::error::Error> {
+    "meshopt data is truncated".into()
+}
+
+/// Decode an `ATTRIBUTES` stream of `count` elements of `stride` bytes
+fn decode_vertex_buffer(
+    data: &[u8],
+    count: usize,
+    stride: usize,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if stride == 0 || stride > 256 || !stride.is_multiple_of(4) {
+        return Err(format!("Invalid meshopt vertex stride {}", stride).into());
+    }
+    match data.first() {
+        Some(0xA0) => {}
+        Some(h) if h & 0xF0 == 0xA0 => {
+            return Err(format!("Unsupported meshopt vertex codec version {}", h & 0x0F).into());
+        }
+        _ => return Err("Invalid meshopt vertex data header".into()),
+    }
+
+    let tail_size = stride.max(TAIL_MIN_SIZE);
+    if data.len() < 1 + tail_size {
+        return Err(truncated());
+    }
+    let mut last_vertex = data[data.len() - stride..].to_vec();
+    let block_size = ((VERTEX_BLOCK_SIZE_BYTES / stride) & !(BYTE_GROUP_SIZE - 1)).min(VERTEX_BLOCK_MAX_SIZE);
+
+    let mut output = vec![0u8; count * stride];
+    let mut pos = 1;
+    let mut bytes = [0u8; VERTEX_BLOCK_MAX_SIZE];
+    let mut vertex = 0;
+    while vertex < count {
+        let n = block_size.min(count - vertex);
+        let aligned = n.next_multiple_of(BYTE_GROUP_SIZE);
+        for k in 0..stride {
+            pos = decode_bytes(data, pos, &mut bytes[..aligned])?;
+            let mut p = last_vertex[k];
+            for (i, &b) in bytes[..n].iter().enumerate() {
+                let v = unzigzag8(b).wrapping_add(p);
+                output[(vertex + i) * stride + k] = v;
+                p = v;
+            }
+        }
+        last_vertex.copy_from_slice(&output[(vertex + n - 1) * stride..(vertex + n) * stride]);
+        vertex += n;
+    }
+
+    if data.len() - pos != tail_size {
+        return Err("meshopt vertex data has trailing bytes".into());
+    }
+    Ok(output)
+}
+
+fn unzigzag8(v: u8) -> u8 {
+    (v >> 1) ^ (v & 1).wrapping_neg()
+}
+
+/// Decode one byte channel of a vertex block, returning the new read position
+fn decode_bytes(data: &[u8], mut pos: usize, buffer: &mut [u8]) -> Result<usize, Box<dyn std::error::Error>> {
+    let groups = buffer.len() / BYTE_GROUP_SIZE;
+    let header_size = groups.div_ceil(4);
+    let header = data.get(pos..pos + header_size).ok_or_else(truncated)?;
+    pos += header_size;
+    for (g, group) in buffer.chunks_exact_mut(BYTE_GROUP_SIZE).enumerate() {
+        let bits_log2 = (header[g / 4] >> ((g % 4) * 2)) & 3;
+        pos = decode_bytes_group(data, pos, group, bits_log2)?;
+    }
+    Ok(pos)
+}
+
+/// Decode 16 values packed at 0, 2, 4 or 8 bits each; packed values with all
+/// bits set are escapes whose real value follows the packed bytes
+fn decode_bytes_group(
+    data: &[u8],
+    pos: usize,
+    group: &mut [u8],
+    bits_log2: u8,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    match bits_log2 {
+        0 => {
+            group.fill(0);
+            Ok(pos)
+        }
+        3 => {
+            group.copy_from_slice(data.get(pos..pos + BYTE_GROUP_SIZE).ok_or_else(truncated)?);
+            Ok(pos + BYTE_GROUP_SIZE)
+        }
+        _ => {
+            let bits = 1usize << bits_log2;
+            let packed_len = BYTE_GROUP_SIZE * bits / 8;
+            let packed = data.get(pos..pos + packed_len).ok_or_else(truncated)?;
+            let escape = ((1u16 << bits) - 1) as u8;
+            let mut extra = pos + packed_len;
+            for (i, out) in group.iter_mut().enumerate() {
+                let bit = i * bits;
+                let v = (packed[bit / 8] >> (8 - bits - bit % 8)) & escape;
+                *out = if v == escape {
+                    let e = *data.get(extra).ok_or_else(truncated)?;
+                    extra += 1;
+                    e
+                } else {
+                    v
+                };
+            }
+            Ok(extra)
+        }
+    }
+}
+
+// --- Vertex filters ---
+
+fn apply_filter(data: &mut [u8], stride: usize, filter: &str) -> Result<(), Box<dyn std::error::Error>> {
+    match (filter, stride) {
+        ("NONE", _) => {}
+        ("OCTAHEDRAL", 4) => {
+            for v in data.chunks_exact_mut(4) {
+                let c = [0, 1, 2].map(|i| v[i] as i8 as f32);
+                let n = decode_octahedral(c, 127.0);
+                for i in 0..3 {
+                    v[i] = n[i] as i8 as u8;
+                }
+            }
+        }
+        ("OCTAHEDRAL", 8) => {
+            for v in data.chunks_exact_mut(8) {
+                let c = [0, 1, 2].map(|i| i16::from_le_bytes([v[2 * i], v[2 * i + 1]]) as f32);
+                let n = decode_octahedral(c, 32767.0);
+                for i in 0..3 {
+                    v[2 * i..2 * i + 2].copy_from_slice(&(n[i] as i16).to_le_bytes());
+                }
+            }
+        }
+        ("QUATERNION", 8) => {
+            for v in data.chunks_exact_mut(8) {
+                let c = [0, 1, 2, 3].map(|i| i16::from_le_bytes([v[2 * i], v[2 * i + 1]]));
+                for (i, q) in decode_quaternion(c).into_iter().enumerate() {
+                    v[2 * i..2 * i + 2].copy_from_slice(&q.to_le_bytes());
+                }
+            }
+        }
+        ("EXPONENTIAL", s) if s.is_multiple_of(4) => {
+            for v in data.chunks_exact_mut(4) {
+                let bits = u32::from_le_bytes([v[0], v[1], v[2], v[3]]);
+                v.copy_from_slice(&decode_exponential(bits).to_le_bytes());
+            }
+        }
+        _ => {
+            return Err(format!("Unsupported meshopt filter {} for stride {}", filter, stride).into());
+        }
+    }
+    Ok(())
+}
+
+fn round_to_int(x: f32) -> i32 {
+    (x + if x >= 0.0 { 0.5 } else { -0.5 }) as i32
+}
+
+/// Unit vector from octahedral x/y and the encoded one in z, scaled to `max`
+fn decode_octahedral([x, y, one]: [f32; 3], max: f32) -> [i32; 3] {
+    let z = one - x.abs() - y.abs();
+    let t = z.min(0.0);
+    let x = x + if x >= 0.0 { t } else { -t };
+    let y = y + if y >= 0.0 { t } else { -t };
+    let s = max / (x * x + y * y + z * z).sqrt();
+    [x, y, z].map(|c| round_to_int(c * s))
+}
+
+/// Quaternion from three components plus the index of the dropped (largest) one
+fn decode_quaternion(c: [i16; 4]) -> [i16; 4] {
+    let ss = std::f32::consts::FRAC_1_SQRT_2 / (c[3] | 3) as f32;
+    let [x, y, z] = [0, 1, 2].map(|i| c[i] as f32 * ss);
+    let w = (1.0 - x * x - y * y - z * z).max(0.0).sqrt();
+    let qc = (c[3] & 3) as usize;
+    let mut out = [0i16; 4];
+    for (k, v) in [w, x, y, z].into_iter().enumerate() {
+        out[(qc + k) & 3] = round_to_int(v * 32767.0) as i16;
+    }
+    out
+}
+
+/// Float from a signed 24-bit mantissa and signed 8-bit exponent
+fn decode_exponential(bits: u32) -> f32 {
+    let mantissa = ((bits << 8) as i32) >> 8;
+    let exponent = (bits as i32) >> 24;
+    mantissa as f32 * 2f32.powi(exponent)
+}
+
+// --- Index codecs ---
+
+fn write_index(output: &mut Vec<u8>, index: u32, size: usize) {
+    match size {
+        2 => output.extend_from_slice(&(index as u16).to_le_bytes()),
+        _ => output.extend_from_slice(&index.to_le_bytes()),
+    }
+}
+
+fn read_vbyte(data: &[u8], pos: &mut usize) -> Result<u32, Box<dyn std::error::Error>> {
+    let mut result = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *data.get(*pos).ok_or_else(truncated)?;
+        *pos += 1;
+        result |= ((byte & 127) as u32) << shift;
+        if byte < 128 {
+            break;
+        }
+    }
+    Ok(result)
+}
+
+fn unzigzag32(v: u32) -> u32 {
+    (v >> 1) ^ (v & 1).wrapping_neg()
+}
+
+/// Recently seen edges and vertices shared by the triangle encoder and decoder
+struct IndexFifos {
+    edges: [[u32; 2]; 16],
+    edge_offset: usize,
+    vertices: [u32; 16],
+    vertex_offset: usize,
+}
+
+impl Default for IndexFifos {
+    fn default() -> Self {
+        IndexFifos {
+            edges: [[u32::MAX; 2]; 16],
+            edge_offset: 0,
+            vertices: [u32::MAX; 16],
+            vertex_offset: 0,
+        }
+    }
+}
+
+impl IndexFifos {
+    fn edge(&self, back: usize) -> [u32; 2] {
+        self.edges[self.edge_offset.wrapping_sub(back) & 15]
+    }
+
+    fn vertex(&self, back: usize) -> u32 {
+        self.vertices[self.vertex_offset.wrapping_sub(back) & 15]
+    }
+
+    fn push_edge(&mut self, a: u32, b: u32) {
+        self.edges[self.edge_offset] = [a, b];
+        self.edge_offset = (self.edge_offset + 1) & 15;
+    }
+
+    fn push_vertex(&mut self, v: u32, advance: bool) {
+        self.vertices[self.vertex_offset] = v;
+        self.vertex_offset = (self.vertex_offset + advance as usize) & 15;
+    }
+}
+
+/// Decode a `TRIANGLES` stream of `count` indices of `size` bytes
+fn decode_index_buffer(
+    data: &[u8],
+    count: usize,
+    size: usize,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if !count.is_multiple_of(3) || (size != 2 && size != 4) {
+        return Err(format!("Invalid meshopt triangle stream ({} indices of {} bytes)", count, size).into());
+    }
+    let version = match data.first() {
+        Some(h) if h & 0xF0 == 0xE0 && h & 0x0F <= 1 => h & 0x0F,
+        _ => return Err("Invalid meshopt index data header".into()),
+    };
+    if data.len() < 1 + count / 3 + 16 {
+        return Err(truncated());
+    }
+
+    let codes = &data[1..1 + count / 3];
+    let data_end = data.len() - 16;
+    let codeaux_table = &data[data_end..];
+    let mut pos = 1 + count / 3;
+
+    let mut fifo = IndexFifos::default();
+    let mut next = 0u32;
+    let mut last = 0u32;
+    let fec_max = if version >= 1 { 13 } else { 15 };
+    let mut output = Vec::with_capacity(count * size);
+
+    for &code in codes {
+        if pos > data_end {
+            return Err(truncated());
+        }
+        let [a, b, c];
+        if code < 0xF0 {
+            // Triangle sharing a recent edge; the third vertex is new, recent or explicit
+            [a, b] = fifo.edge(1 + (code >> 4) as usize);
+            let fec = code & 15;
+            if fec < fec_max {
+                c = if fec == 0 { next } else { fifo.vertex(1 + fec as usize) };
+                next += (fec == 0) as u32;
+                fifo.push_vertex(c, fec == 0);
+            } else {
+                last = if fec != 15 {
+                    // 13 and 14 encode -1 and +1
+                    last.wrapping_add((fec as i32 - (fec ^ 3) as i32) as u32)
+                } else {
+                    last.wrapping_add(unzigzag32(read_vbyte(data, &mut pos)?))
+                };
+                c = last;
+                fifo.push_vertex(c, true);
+            }
+            fifo.push_edge(c, b);
+            fifo.push_edge(a, c);
+        } else {
+            let explicit = code >= 0xFE;
+            let codeaux = if explicit {
+                let aux = *data.get(pos).ok_or_else(truncated)?;
+                pos += 1;
+                if aux == 0 {
+                    next = 0;
+                }
+                aux
+            } else {
+                codeaux_table[(code & 15) as usize]
+            };
+            let fea = if code == 0xFF { 15 } else { 0 };
+            let feb = (codeaux >> 4) as usize;
+            let fec = (codeaux & 15) as usize;
+            let mut take = |fe: usize| {
+                if fe == 0 {
+                    next += 1;
+                    next - 1
+                } else {
+                    fifo.vertex(fe)
+                }
+            };
+            let mut v = [if fea == 0 { take(0) } else { 0 }, take(feb), take(fec)];
+            if explicit {
+                for (k, fe) in [fea, feb, fec].into_iter().enumerate() {
+                    if fe == 15 {
+                        last = last.wrapping_add(unzigzag32(read_vbyte(data, &mut pos)?));
+                        v[k] = last;
+                    }
+                }
+            }
+            [a, b, c] = v;
+            fifo.push_vertex(a, true);
+            fifo.push_vertex(b, feb == 0 || (explicit && feb == 15));
+            fifo.push_vertex(c, fec == 0 || (explicit && fec == 15));
+            fifo.push_edge(b, a);
+            fifo.push_edge(c, b);
+            fifo.push_edge(a, c);
+        }
+        for index in [a, b, c] {
+            write_index(&mut output, index, size);
+        }
+    }
+
+    if pos != data_end {
+        return Err("meshopt index data has trailing bytes".into());
+    }
+    Ok(output)
+}
+
+/// Decode an `INDICES` stream of `count` indices of `size` bytes
+fn decode_index_sequence(
+    data: &[u8],
+    count: usize,
+    size: usize,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if size != 2 && size != 4 {
+        return Err(format!("Invalid meshopt index size {}", size).into());
+    }
+    if data.first() != Some(&0xD0) {
+        return Err("Invalid meshopt index sequence header".into());
+    }
+    if data.len() < 1 + count + 4 {
+        return Err(truncated());
+    }
+    let data_end = data.len() - 4;
+    let mut pos = 1;
+    let mut last = [0u32; 2];
+    let mut output = Vec::with_capacity(count * size);
+    for _ in 0..count {
+        if pos >= data_end {
+            return Err(truncated());
+        }
+        let v = read_vbyte(data, &mut pos)?;
+        // The low bit selects which of two running baselines the delta applies to
+        let baseline = (v & 1) as usize;
+        let index = last[baseline].wrapping_add(unzigzag32(v >> 1));
+        last[baseline] = index;
+        write_index(&mut output, index, size);
+    }
+    if pos != data_end {
+        return Err("meshopt index data has trailing bytes".into());
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zigzag(v: i32) -> u32 {
+        ((v << 1) ^ (v >> 31)) as u32
+    }
+
+    fn vbyte(out: &mut Vec<u8>, mut v: u32) {
+        while v >= 128 {
+            out.push((v & 127) as u8 | 128);
+            v >>= 7;
+        }
+        out.push(v as u8);
+    }
+
+    fn indices_u16(bytes: &[u8]) -> Vec<u16> {
+        bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect()
+    }
+
+    #[test]
+    fn decodes_raw_vertex_block() {
+        let vertices: Vec<u8> = (0..3u8).flat_map(|i| [i, 10 + i, 200 - i, 7]).collect();
+        let stride = 4;
+        let mut data = vec![0xA0];
+        for k in 0..stride {
+            // One 16-byte group stored at 8 bits per delta
+            data.push(0b11);
+            let mut prev = 0u8;
+            for i in 0..16 {
+                let v = vertices.get(i * stride + k).copied().unwrap_or(prev);
+                let delta = v.wrapping_sub(prev) as i8;
+                data.push(((delta << 1) ^ (delta >> 7)) as u8);
+                prev = v;
+            }
+        }
+        data.extend_from_slice(&[0; TAIL_MIN_SIZE]);
+
+        assert_eq!(decode_vertex_buffer(&data, 3, stride).unwrap(), vertices);
+    }
+
+    #[test]
+    fn decodes_packed_byte_groups() {
+        // 2-bit values 0,1,2 then escapes (3) resolved from the following bytes
+        let data = [0b0001_1011, 0b1111_0000, 0, 0, 42, 7, 9];
+        let mut group = [0u8; 16];
+        let end = decode_bytes_group(&data, 0, &mut group, 1).unwrap();
+        assert_eq!(&group[..8], &[0, 1, 2, 42, 7, 9, 0, 0]);
+        assert_eq!(end, 7);
+
+        let data = [0x1F, 0, 0, 0, 0, 0, 0, 0x0F, 99, 100];
+        let end = decode_bytes_group(&data, 0, &mut group, 2).unwrap();
+        assert_eq!(&group[..3], &[1, 99, 0]);
+        assert_eq!(&group[14..], &[0, 100]);
+        assert_eq!(end, 10);
+    }
+
+    #[test]
+    fn decodes_triangle_codes() {
+        // New triangle (0, 1, 2), then (2, 1, 3) off the FIFO's second edge,
+        // then a triangle of explicit delta-coded indices (5, 9, 4)
+        let mut data = vec![0xE1, 0xFE, 0x10, 0xFF, 0x00, 0xFF];
+        for d in [5, 4, -5] {
+            vbyte(&mut data, zigzag(d));
+        }
+        data.extend_from_slice(&[0; 16]);
+
+        let decoded = decode_index_buffer(&data, 9, 2).unwrap();
+        assert_eq!(indices_u16(&decoded), vec![0, 1, 2, 2, 1, 3, 5, 9, 4]);
+    }
+
+    #[test]
+    fn decodes_index_sequence() {
+        let mut data = vec![0xD0];
+        for (delta, baseline) in [(4, 0), (1, 0), (-2, 1), (100, 0)] {
+            vbyte(&mut data, (zigzag(delta) << 1) | baseline);
+        }
+        data.extend_from_slice(&[0; 4]);
+
+        let decoded = decode_index_sequence(&data, 4, 2).unwrap();
+        assert_eq!(indices_u16(&decoded), vec![4, 5, u16::MAX - 1, 105]);
+    }
+
+    #[test]
+    fn loads_draco_primitives() {
+        // Sequential Draco triangle with raw indices and a generic f32 POSITION
+        let mut draco = b"DRACO".to_vec();
+        draco.extend_from_slice(&[2, 2, 1, 0, 0, 0, 1, 3, 1, 0, 1, 2, 1, 1, 0, 9, 3, 0, 0, 0]);
+        let positions: [f32; 9] = [0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 3.0, 0.0];
+        draco.extend(positions.iter().flat_map(|v| v.to_le_bytes()));
+
+        let dir = std::env::temp_dir().join(format!("msh_draco_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("draco.bin"), &draco).unwrap();
+        let json = format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "extensionsUsed": ["{0}"],
+                "extensionsRequired": ["{0}"],
+                "buffers": [{{"uri": "draco.bin", "byteLength": {1}}}],
+                "bufferViews": [{{"buffer": 0, "byteLength": {1}}}],
+                "accessors": [
+                    {{"componentType": 5123, "count": 3, "type": "SCALAR"}},
+                    {{"componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [2, 3, 0]}}
+                ],
+                "meshes": [{{"primitives": [{{
+                    "attributes": {{"POSITION": 1}},
+                    "indices": 0,
+                    "extensions": {{"{0}": {{"bufferView": 0, "attributes": {{"POSITION": 0}}}}}}
+                }}]}}]
+            }}"#,
+            DRACO,
+            draco.len()
+        );
+        let path = dir.join("draco.gltf");
+        std::fs::write(&path, json).unwrap();
+
+        let mesh = crate::mesh::loader::load_mesh_with_colors(&path, &Default::default());
+        let (document, ..) = import(&path).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let mesh = mesh.unwrap();
+        assert_eq!(mesh.positions, vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 3.0, 0.0]]);
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2]]);
+        let primitive = document.meshes().next().unwrap().primitives().next().unwrap();
+        assert_eq!(primitive_compression(&primitive), Some(DRACO));
+    }
+
+    #[test]
+    fn filters_reconstruct_values() {
+        assert_eq!(decode_octahedral([127.0, 0.0, 127.0], 127.0), [127, 0, 0]);
+        assert_eq!(decode_octahedral([0.0, 0.0, 127.0], 127.0), [0, 0, 127]);
+        // Identity rotation with w (dropped component index 3) reconstructed
+        assert_eq!(decode_quaternion([0, 0, 0, 0x7FFF]), [0, 0, 0, 32767]);
+        assert_eq!(decode_exponential((((-2i32) as u32) << 24) | 6), 1.5);
+    }
+}
@@ -0,0 +1,720 @@
+//! glTF 2.0 writer for `MeshWithColors`.
+//!
+//! Produces a single mesh node with one primitive per distinct face color
+//! (all primitives share the vertex attributes), the embedded texture as PNG,
+//! the skin (joint nodes, inverse bind matrices, JOINTS_0/WEIGHTS_0), morph
+//! targets with their default weights, and all animation clips. Output is either a binary `.glb` or a `.gltf` JSON file
+//! with a sibling `.bin` buffer.
+//!
+//! LOD chains are written as one mesh node per level, linked from the first
+//! with the `MSFT_lod` extension and sharing the texture, skin and animations.
+
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+
+use super::animation::{AnimationProperty, Interpolation};
+use super::loader::MeshWithColors;
+use super::skeleton::JointTransform;
+
+const GLB_MAGIC: u32 = 0x4654_6C67; // "glTF"
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Accumulates the binary buffer along with its buffer views and accessors
+#[derive(Default)]
+struct BufferBuilder {
+    data: Vec<u8>,
+    views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+impl BufferBuilder {
+    /// Append bytes as a new buffer view (4-byte aligned) and return its index
+    fn push_view(&mut self, bytes: &[u8], target: Option<u32>) -> usize {
+        while !self.data.len().is_multiple_of(4) {
+            self.data.push(0);
+        }
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.data.len(),
+            "byteLength": bytes.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.data.extend_from_slice(bytes);
+        self.views.push(view);
+        self.views.len() - 1
+    }
+
+    /// Append a float accessor with `N` components per element
+    fn push_floats<const N: usize>(
+        &mut self,
+        values: &[[f32; N]],
+        target: Option<u32>,
+        with_bounds: bool,
+    ) -> usize {
+        let bytes: Vec<u8> = values.iter().flatten().flat_map(|f| f.to_le_bytes()).collect();
+        let view = self.push_view(&bytes, target);
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": accessor_type(N),
+        });
+        if with_bounds && !values.is_empty() {
+            let mut min = [f32::INFINITY; N];
+            let mut max = [f32::NEG_INFINITY; N];
+            for v in values {
+                for i in 0..N {
+                    min[i] = min[i].min(v[i]);
+                    max[i] = max[i].max(v[i]);
+                }
+            }
+            accessor["min"] = json!(min.to_vec());
+            accessor["max"] = json!(max.to_vec());
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    /// Append a triangle index accessor, using 16-bit indices when they fit
+    fn push_indices(&mut self, indices: &[u32], vertex_count: usize) -> usize {
+        let (bytes, component_type): (Vec<u8>, u32) = if vertex_count <= u16::MAX as usize {
+            let bytes = indices.iter().flat_map(|&i| (i as u16).to_le_bytes()).collect();
+            (bytes, UNSIGNED_SHORT)
+        } else {
+            (indices.iter().flat_map(|i| i.to_le_bytes()).collect(), UNSIGNED_INT)
+        };
+        let view = self.push_view(&bytes, Some(ELEMENT_ARRAY_BUFFER));
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": component_type,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn push_joints(&mut self, joints: &[[u16; 4]]) -> usize {
+        let bytes: Vec<u8> = joints.iter().flatten().flat_map(|j| j.to_le_bytes()).collect();
+        let view = self.push_view(&bytes, Some(ARRAY_BUFFER));
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_SHORT,
+            "count": joints.len(),
+            "type": "VEC4",
+        }));
+        self.accessors.len() - 1
+    }
+}
+
+fn accessor_type(components: usize) -> &'static str {
+    match components {
+        1 => "SCALAR",
+        2 => "VEC2",
+        3 => "VEC3",
+        4 => "VEC4",
+        _ => "MAT4",
+    }
+}
+
+/// Vertex attributes, morph targets and primitives of one mesh, written into `buffer`
+struct MeshEntry {
+    json: Value,
+    skinned: bool,
+    morph_count: usize,
+}
+
+/// Write one mesh's vertex data and primitives, sharing `materials` by face color
+fn push_mesh(
+    mesh: &MeshWithColors,
+    name: &str,
+    buffer: &mut BufferBuilder,
+    materials: &mut Vec<[f32; 4]>,
+) -> MeshEntry {
+    let split = mesh.with_vertex_attributes();
+    let mesh: &MeshWithColors = &split;
+    let n = mesh.positions.len();
+
+    // --- Vertex attributes (shared by every primitive) ---
+    let mut attributes = serde_json::Map::new();
+    attributes.insert(
+        "POSITION".into(),
+        json!(buffer.push_floats(&mesh.positions, Some(ARRAY_BUFFER), true)),
+    );
+    if mesh.normals.len() == n && n > 0 {
+        let normals: Vec<[f32; 3]> = mesh
+            .normals
+            .iter()
+            .map(|nrm| {
+                let len = (nrm[0] * nrm[0] + nrm[1] * nrm[1] + nrm[2] * nrm[2]).sqrt();
+                if len > 0.0 {
+                    [nrm[0] / len, nrm[1] / len, nrm[2] / len]
+                } else {
+                    [0.0, 0.0, 1.0]
+                }
+            })
+            .collect();
+        attributes.insert("NORMAL".into(), json!(buffer.push_floats(&normals, Some(ARRAY_BUFFER), false)));
+    }
+    if mesh.texcoords.len() == n && n > 0 {
+        attributes.insert(
+            "TEXCOORD_0".into(),
+            json!(buffer.push_floats(&mesh.texcoords, Some(ARRAY_BUFFER), false)),
+        );
+    }
+    if mesh.vertex_colors.len() == n && n > 0 {
+        attributes.insert(
+            "COLOR_0".into(),
+            json!(buffer.push_floats(&mesh.vertex_colors, Some(ARRAY_BUFFER), false)),
+        );
+    }
+    let skinned = mesh.skeleton.as_ref().is_some_and(|s| !s.joints.is_empty())
+        && mesh.joint_indices.len() == n
+        && mesh.joint_weights.len() == n;
+    if skinned {
+        attributes.insert("JOINTS_0".into(), json!(buffer.push_joints(&mesh.joint_indices)));
+        attributes.insert(
+            "WEIGHTS_0".into(),
+            json!(buffer.push_floats(&mesh.joint_weights, Some(ARRAY_BUFFER), false)),
+        );
+    }
+
+    // --- Materials: one primitive per distinct face color ---
+    let mut groups: Vec<([f32; 4], Vec<u32>)> = Vec::new();
+    if mesh.face_colors.len() == mesh.face_indices.len() && !mesh.face_colors.is_empty() {
+        for (tri, color) in mesh.face_indices.iter().zip(&mesh.face_colors) {
+            match groups.iter_mut().find(|(c, _)| c == color) {
+                Some((_, indices)) => indices.extend_from_slice(tri),
+                None => groups.push((*color, tri.to_vec())),
+            }
+        }
+    } else {
+        groups.push(([1.0; 4], mesh.face_indices.iter().flatten().copied().collect()));
+    }
+
+    // --- Morph targets (shared by every primitive) ---
+    let morph_count = mesh.morph_targets.len();
+    let has_morphs = morph_count > 0 && mesh.morph_targets.iter().all(|t| t.position_deltas.len() == n);
+    let targets: Vec<Value> = if has_morphs {
+        mesh.morph_targets
+            .iter()
+            .map(|t| json!({ "POSITION": buffer.push_floats(&t.position_deltas, Some(ARRAY_BUFFER), true) }))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut primitives = Vec::with_capacity(groups.len());
+    for (color, indices) in &groups {
+        let material = match materials.iter().position(|c| c == color) {
+            Some(i) => i,
+            None => {
+                materials.push(*color);
+                materials.len() - 1
+            }
+        };
+        let mut primitive = json!({
+            "attributes": attributes.clone(),
+            "indices": buffer.push_indices(indices, n),
+            "material": material,
+        });
+        if has_morphs {
+            primitive["targets"] = json!(targets);
+        }
+        primitives.push(primitive);
+    }
+
+    let mut json = json!({ "name": name, "primitives": primitives });
+    if has_morphs {
+        let mut weights = mesh.morph_weights.clone();
+        weights.resize(morph_count, 0.0);
+        json["weights"] = json!(weights);
+        if mesh.morph_targets.iter().any(|t| t.name.is_some()) {
+            let names: Vec<&str> = mesh.morph_targets.iter().map(|t| t.name.as_deref().unwrap_or("")).collect();
+            json["extras"] = json!({ "targetNames": names });
+        }
+    }
+    MeshEntry { json, skinned, morph_count: if has_morphs { morph_count } else { 0 } }
+}
+
+/// Build the glTF JSON document and its binary buffer.
+///
+/// Each of `levels` becomes its own mesh node. With more than one level the
+/// first node links the rest through `MSFT_lod`, with `coverage` stored in its
+/// extras. The texture, skin and animations come from the first level and are
+/// shared by all of them.
+fn build_document(
+    levels: &[MeshWithColors],
+    coverage: &[f32],
+) -> Result<(Value, Vec<u8>), Box<dyn std::error::Error>> {
+    let first = levels.first().ok_or("No meshes to write")?;
+    let mut buffer = BufferBuilder::default();
+    let mut root = serde_json::Map::new();
+
+    // --- Texture ---
+    let texture_index = match &first.texture {
+        Some(tex) if !tex.pixels.is_empty() => {
+            let image = image::RgbaImage::from_raw(tex.width, tex.height, tex.pixels.clone())
+                .ok_or("Texture pixel data does not match its dimensions")?;
+            let mut png = std::io::Cursor::new(Vec::new());
+            image.write_to(&mut png, image::ImageFormat::Png)?;
+            let view = buffer.push_view(png.get_ref(), None);
+            root.insert("images".into(), json!([{ "bufferView": view, "mimeType": "image/png" }]));
+            root.insert("samplers".into(), json!([{}]));
+            root.insert("textures".into(), json!([{ "sampler": 0, "source": 0 }]));
+            Some(0)
+        }
+        _ => None,
+    };
+
+    let mut colors = Vec::new();
+    let entries: Vec<MeshEntry> = if levels.len() == 1 {
+        vec![push_mesh(first, "mesh", &mut buffer, &mut colors)]
+    } else {
+        levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| push_mesh(level, &format!("lod{}", i), &mut buffer, &mut colors))
+            .collect()
+    };
+
+    let materials: Vec<Value> = colors
+        .iter()
+        .enumerate()
+        .map(|(i, color)| {
+            let mut pbr = json!({
+                "baseColorFactor": color.to_vec(),
+                "metallicFactor": 0.0,
+            });
+            if let Some(texture) = texture_index {
+                pbr["baseColorTexture"] = json!({ "index": texture });
+            }
+            let mut material = json!({ "name": format!("material_{}", i), "pbrMetallicRoughness": pbr });
+            if color[3] < 1.0 {
+                material["alphaMode"] = json!("BLEND");
+            }
+            material
+        })
+        .collect();
+
+    // --- Nodes: mesh nodes first, then one node per joint ---
+    let skeleton = first.skeleton.as_ref().filter(|_| entries[0].skinned);
+    let mut nodes: Vec<Value> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let mut node = json!({ "name": entry.json["name"], "mesh": i });
+            if skeleton.is_some() && entry.skinned {
+                node["skin"] = json!(0);
+            }
+            node
+        })
+        .collect();
+    if entries.len() > 1 {
+        // Lower levels are reached through the extension rather than the scene
+        let ids: Vec<usize> = (1..entries.len()).collect();
+        nodes[0]["extensions"] = json!({ "MSFT_lod": { "ids": ids } });
+        nodes[0]["extras"] = json!({ "MSFT_screencoverage": coverage });
+        root.insert("extensionsUsed".into(), json!(["MSFT_lod"]));
+    }
+    let mut scene_roots = vec![0];
+    let joint_node = |joint_index: usize| joint_index + entries.len();
+
+    if let Some(skeleton) = skeleton {
+        for joint in &skeleton.joints {
+            let mut node = json!({});
+            if let Some(name) = &joint.name {
+                node["name"] = json!(name);
+            }
+            match &joint.local_transform {
+                JointTransform::Decomposed { translation, rotation, scale } => {
+                    node["translation"] = json!(translation.to_vec());
+                    node["rotation"] = json!(rotation.to_vec());
+                    node["scale"] = json!(scale.to_vec());
+                }
+                JointTransform::Matrix(m) => {
+                    node["matrix"] = json!(m.iter().flatten().copied().collect::<Vec<f32>>());
+                }
+            }
+            let children: Vec<usize> = skeleton
+                .joints
+                .iter()
+                .filter(|child| child.parent == Some(joint.index))
+                .map(|child| joint_node(child.index))
+                .collect();
+            if !children.is_empty() {
+                node["children"] = json!(children);
+            }
+            if joint.parent.is_none() {
+                scene_roots.push(joint_node(joint.index));
+            }
+            nodes.push(node);
+        }
+
+        let ibms: Vec<[f32; 16]> = skeleton
+            .joints
+            .iter()
+            .map(|j| {
+                let mut flat = [0.0f32; 16];
+                for (dst, src) in flat.iter_mut().zip(j.inverse_bind_matrix.iter().flatten()) {
+                    *dst = *src;
+                }
+                flat
+            })
+            .collect();
+        let ibm_accessor = buffer.push_floats(&ibms, None, false);
+        root.insert(
+            "skins".into(),
+            json!([{
+                "inverseBindMatrices": ibm_accessor,
+                "joints": (0..skeleton.joints.len()).map(joint_node).collect::<Vec<_>>(),
+            }]),
+        );
+    }
+
+    // --- Animations: joint channels need the skin, weight channels the morph targets ---
+    let skin_joints = skeleton.map_or(0, |s| s.joints.len());
+    let morph_count = entries[0].morph_count;
+    let morph_nodes: Vec<usize> = (0..entries.len())
+        .filter(|&i| morph_count > 0 && entries[i].morph_count == morph_count)
+        .collect();
+    let mut animations = Vec::new();
+    for clip in &first.animations {
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        for channel in &clip.channels {
+            let (targets, path, width) = match channel.property {
+                AnimationProperty::MorphWeights if morph_count > 0 => (morph_nodes.clone(), "weights", morph_count),
+                AnimationProperty::MorphWeights => continue,
+                _ if channel.joint_index >= skin_joints => continue,
+                AnimationProperty::Translation => (vec![joint_node(channel.joint_index)], "translation", 3),
+                AnimationProperty::Rotation => (vec![joint_node(channel.joint_index)], "rotation", 4),
+                AnimationProperty::Scale => (vec![joint_node(channel.joint_index)], "scale", 3),
+            };
+            if channel.keyframes.is_empty() {
+                continue;
+            }
+            let times: Vec<[f32; 1]> = channel.keyframes.iter().map(|k| [k.time]).collect();
+            let input = buffer.push_floats(&times, None, true);
+
+            let mut values: Vec<f32> = Vec::new();
+            for key in &channel.keyframes {
+                let zeros = vec![0.0; width];
+                if channel.interpolation == Interpolation::CubicSpline {
+                    values.extend(key.in_tangent.as_deref().unwrap_or(&zeros));
+                    values.extend(&key.value);
+                    values.extend(key.out_tangent.as_deref().unwrap_or(&zeros));
+                } else {
+                    values.extend(&key.value);
+                }
+            }
+            let output = match channel.property {
+                AnimationProperty::MorphWeights => buffer.push_floats(&to_arrays::<1>(&values), None, false),
+                AnimationProperty::Rotation => buffer.push_floats(&to_arrays::<4>(&values), None, false),
+                _ => buffer.push_floats(&to_arrays::<3>(&values), None, false),
+            };
+
+            samplers.push(json!({
+                "input": input,
+                "output": output,
+                "interpolation": match channel.interpolation {
+                    Interpolation::Step => "STEP",
+                    Interpolation::Linear => "LINEAR",
+                    Interpolation::CubicSpline => "CUBICSPLINE",
+                },
+            }));
+            // Every LOD level with the morph targets follows the same weights
+            for node in targets {
+                channels.push(json!({
+                    "sampler": samplers.len() - 1,
+                    "target": { "node": node, "path": path },
+                }));
+            }
+        }
+        if channels.is_empty() {
+            continue;
+        }
+        let mut animation = json!({ "samplers": samplers, "channels": channels });
+        if let Some(name) = &clip.name {
+            animation["name"] = json!(name);
+        }
+        animations.push(animation);
+    }
+    if !animations.is_empty() {
+        root.insert("animations".into(), json!(animations));
+    }
+
+    root.insert("asset".into(), json!({ "version": "2.0", "generator": "msh" }));
+    root.insert("scene".into(), json!(0));
+    root.insert("scenes".into(), json!([{ "nodes": scene_roots }]));
+    root.insert("nodes".into(), json!(nodes));
+    root.insert("meshes".into(), json!(entries.into_iter().map(|e| e.json).collect::<Vec<_>>()));
+    root.insert("materials".into(), json!(materials));
+    root.insert("accessors".into(), json!(buffer.accessors));
+    root.insert("bufferViews".into(), json!(buffer.views));
+
+    while !buffer.data.len().is_multiple_of(4) {
+        buffer.data.push(0);
+    }
+    root.insert("buffers".into(), json!([{ "byteLength": buffer.data.len() }]));
+
+    Ok((Value::Object(root), buffer.data))
+}
+
+fn to_arrays<const N: usize>(values: &[f32]) -> Vec<[f32; N]> {
+    values
+        .chunks_exact(N)
+        .map(|c| {
+            let mut a = [0.0; N];
+            a.copy_from_slice(c);
+            a
+        })
+        .collect()
+}
+
+/// Write a mesh as binary glTF (.glb)
+pub fn write_glb<W: Write>(mesh: &MeshWithColors, writer: &mut W) -> Result<(), Box<dyn std::error::Error>> {
+    write_lod_glb(std::slice::from_ref(mesh), &[], writer)
+}
+
+/// Write a mesh as a `.gltf` JSON file with its buffer in a sibling `.bin` file
+pub fn write_gltf(mesh: &MeshWithColors, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    write_lod_gltf(std::slice::from_ref(mesh), &[], path)
+}
+
+/// Write LOD levels (finest first) as binary glTF linked with `MSFT_lod`,
+/// with the minimum screen coverage of each level in `coverage`
+pub fn write_lod_glb<W: Write>(
+    levels: &[MeshWithColors],
+    coverage: &[f32],
+    writer: &mut W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (document, bin) = build_document(levels, coverage)?;
+    let mut json_bytes = serde_json::to_vec(&document)?;
+    while !json_bytes.len().is_multiple_of(4) {
+        json_bytes.push(b' ');
+    }
+
+    let total_len = 12 + 8 + json_bytes.len() + 8 + bin.len();
+    writer.write_all(&GLB_MAGIC.to_le_bytes())?;
+    writer.write_all(&2u32.to_le_bytes())?;
+    writer.write_all(&(total_len as u32).to_le_bytes())?;
+    writer.write_all(&(json_bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&CHUNK_JSON.to_le_bytes())?;
+    writer.write_all(&json_bytes)?;
+    writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+    writer.write_all(&CHUNK_BIN.to_le_bytes())?;
+    writer.write_all(&bin)?;
+    Ok(())
+}
+
+/// Write LOD levels as a `.gltf` JSON file with a sibling `.bin` buffer
+pub fn write_lod_gltf(
+    levels: &[MeshWithColors],
+    coverage: &[f32],
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut document, bin) = build_document(levels, coverage)?;
+    let bin_path = path.with_extension("bin");
+    let bin_name = bin_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid output file name")?
+        .to_string();
+    document["buffers"][0]["uri"] = json!(bin_name);
+
+    std::fs::write(&bin_path, bin)?;
+    std::fs::write(path, serde_json::to_vec_pretty(&document)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::animation::{AnimationChannel, AnimationClip, Keyframe};
+    use crate::mesh::loader::{load_mesh_with_colors, LoadOptions, MorphTarget, TextureData};
+    use crate::mesh::skeleton::{Joint, Skeleton};
+
+    fn identity() -> [[f32; 4]; 4] {
+        [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]
+    }
+
+    fn skinned_quad() -> MeshWithColors {
+        let joint = |index: usize, parent: Option<usize>| Joint {
+            index,
+            node_index: index + 10,
+            name: Some(format!("bone{}", index)),
+            parent,
+            inverse_bind_matrix: identity(),
+            local_transform: JointTransform::Decomposed {
+                translation: [0.0, index as f32, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                scale: [1.0; 3],
+            },
+        };
+        MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            face_indices: vec![[0, 1, 2], [0, 2, 3]],
+            face_colors: vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]],
+            texcoords: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+            texture: Some(TextureData { pixels: vec![255; 2 * 2 * 4], width: 2, height: 2 }),
+            skeleton: Some(Skeleton { joints: vec![joint(0, None), joint(1, Some(0))] }),
+            joint_indices: vec![[0, 0, 0, 0], [0, 1, 0, 0], [1, 0, 0, 0], [1, 0, 0, 0]],
+            joint_weights: vec![[1.0, 0.0, 0.0, 0.0], [0.5, 0.5, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]],
+            animations: vec![AnimationClip {
+                name: Some("wave".into()),
+                channels: vec![AnimationChannel {
+                    joint_index: 1,
+                    property: AnimationProperty::Rotation,
+                    interpolation: Interpolation::Linear,
+                    keyframes: vec![
+                        Keyframe { time: 0.0, value: vec![0.0, 0.0, 0.0, 1.0], in_tangent: None, out_tangent: None },
+                        Keyframe { time: 1.0, value: vec![0.0, 0.0, 1.0, 0.0], in_tangent: None, out_tangent: None },
+                    ],
+                }],
+                duration: 1.0,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn glb_round_trip() {
+        let mesh = skinned_quad();
+        let path = std::env::temp_dir().join(format!("msh_gltf_writer_{}.glb", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        write_glb(&mesh, &mut file).unwrap();
+        drop(file);
+
+        let loaded = load_mesh_with_colors(&path, &LoadOptions::default()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // Primitives are split per material, so compare per-face positions
+        assert_eq!(loaded.face_indices.len(), 2);
+        for (a, b) in loaded.face_indices.iter().zip(&mesh.face_indices) {
+            for k in 0..3 {
+                assert_eq!(loaded.positions[a[k] as usize], mesh.positions[b[k] as usize]);
+            }
+        }
+        assert_eq!(loaded.face_colors, mesh.face_colors);
+        assert!(loaded.texture.is_some());
+        assert_eq!(loaded.skeleton.as_ref().unwrap().joints.len(), 2);
+        assert_eq!(loaded.skeleton.as_ref().unwrap().joints[1].parent, Some(0));
+        assert_eq!(loaded.joint_weights.len(), loaded.positions.len());
+        assert_eq!(loaded.animations.len(), 1);
+        assert_eq!(loaded.animations[0].channels[0].joint_index, 1);
+        assert_eq!(loaded.animations[0].channels[0].keyframes.len(), 2);
+    }
+
+    #[test]
+    fn lod_levels_share_skin_and_animations() {
+        let full = skinned_quad();
+        let mut coarse = skinned_quad();
+        coarse.face_indices.truncate(1);
+        coarse.face_colors.truncate(1);
+
+        let (document, _) = build_document(&[full.clone(), coarse], &[0.25, 0.0]).unwrap();
+        assert_eq!(document["extensionsUsed"], json!(["MSFT_lod"]));
+        assert_eq!(document["nodes"][0]["extensions"]["MSFT_lod"]["ids"], json!([1]));
+        assert_eq!(document["nodes"][0]["extras"]["MSFT_screencoverage"], json!([0.25, 0.0]));
+        assert_eq!(document["scenes"][0]["nodes"][0], json!(0));
+        assert_eq!(document["materials"].as_array().unwrap().len(), 2);
+
+        let path = std::env::temp_dir().join(format!("msh_gltf_lod_{}.glb", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        write_lod_glb(&[full, skinned_quad()], &[0.25, 0.0], &mut file).unwrap();
+        drop(file);
+
+        let options = LoadOptions { mesh_name: Some("lod1".into()), ..Default::default() };
+        let loaded = load_mesh_with_colors(&path, &options).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.skeleton.as_ref().unwrap().joints.len(), 2);
+        assert_eq!(loaded.joint_weights.len(), loaded.positions.len());
+        assert_eq!(loaded.animations.len(), 1);
+        assert_eq!(loaded.animations[0].channels[0].keyframes.len(), 2);
+    }
+
+    #[test]
+    fn gltf_writes_sibling_buffer() {
+        let mesh = MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            face_indices: vec![[0, 1, 2]],
+            ..Default::default()
+        };
+        let path = std::env::temp_dir().join(format!("msh_gltf_writer_{}.gltf", std::process::id()));
+        write_gltf(&mesh, &path).unwrap();
+
+        let loaded = load_mesh_with_colors(&path, &LoadOptions::default()).unwrap();
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(path.with_extension("bin")).ok();
+
+        assert_eq!(loaded.positions, mesh.positions);
+        assert_eq!(loaded.face_indices, mesh.face_indices);
+        assert!(loaded.skeleton.is_none());
+    }
+
+    #[test]
+    fn morph_targets_round_trip() {
+        let mesh = MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            face_indices: vec![[0, 1, 2]],
+            morph_targets: vec![
+                MorphTarget {
+                    name: Some("smile".into()),
+                    position_deltas: vec![[0.0, 0.0, 0.0], [0.0, 0.5, 0.0], [0.0, 0.0, 0.0]],
+                },
+                MorphTarget {
+                    name: Some("blink".into()),
+                    position_deltas: vec![[0.0, 0.0, 1.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]],
+                },
+            ],
+            morph_weights: vec![0.25, 0.0],
+            animations: vec![AnimationClip {
+                name: Some("talk".into()),
+                channels: vec![AnimationChannel {
+                    joint_index: 0,
+                    property: AnimationProperty::MorphWeights,
+                    interpolation: Interpolation::Linear,
+                    keyframes: vec![
+                        Keyframe { time: 0.0, value: vec![0.0, 0.0], in_tangent: None, out_tangent: None },
+                        Keyframe { time: 1.0, value: vec![1.0, 0.5], in_tangent: None, out_tangent: None },
+                    ],
+                }],
+                duration: 1.0,
+            }],
+            ..Default::default()
+        };
+        let path = std::env::temp_dir().join(format!("msh_gltf_morph_{}.glb", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        write_glb(&mesh, &mut file).unwrap();
+        drop(file);
+
+        let loaded = load_mesh_with_colors(&path, &LoadOptions::default()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.morph_targets.len(), 2);
+        assert_eq!(loaded.morph_targets[0].name.as_deref(), Some("smile"));
+        assert_eq!(loaded.morph_targets[1].position_deltas, mesh.morph_targets[1].position_deltas);
+        assert_eq!(loaded.morph_weights, vec![0.25, 0.0]);
+
+        let clip = &loaded.animations[0];
+        assert_eq!(clip.channels[0].property, AnimationProperty::MorphWeights);
+        let weights = crate::mesh::animation::evaluate_morph_weights(clip, &loaded.morph_weights, 0.5);
+        assert_eq!(weights, vec![0.5, 0.25]);
+    }
+}
@@ -0,0 +1,731 @@
+//! Local hole filling, after Liepa's "Filling Holes in Meshes" (2003).
+//!
+//! Each boundary ring is triangulated on its own by dynamic programming,
+//! minimising either the total patch area or, first, the largest dihedral
+//! angle inside the patch and against the faces around the hole. The patch
+//! can then be refined to the density of the surrounding mesh (centroid
+//! splits plus Delaunay edge flips) and faired with a least-squares
+//! umbrella-operator bi-Laplacian so it blends into the curvature around it.
+//! Faces outside the holes are left exactly as they were.
+
+use super::loader::MeshWithColors;
+use baby_shark::exports::nalgebra::Vector3;
+use std::collections::{HashMap, HashSet};
+
+/// Centroids are inserted while they are this many local edge lengths from
+/// every corner of their triangle (Liepa's alpha)
+const REFINE_DENSITY: f32 = std::f32::consts::SQRT_2;
+/// Upper bound on centroid-insertion rounds per hole
+const MAX_REFINE_ROUNDS: usize = 16;
+/// Upper bound on edge-flip passes after each round
+const MAX_RELAX_PASSES: usize = 32;
+/// Upper bound on conjugate-gradient iterations when fairing
+const FAIRING_ITERATIONS: usize = 2000;
+
+/// What the hole triangulation minimises
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HoleTriangulation {
+    /// Total patch area
+    MinArea,
+    /// Largest dihedral angle, then area
+    MinDihedral,
+}
+
+/// Options controlling local hole filling
+#[derive(Clone, Debug)]
+pub struct HoleFillOptions {
+    pub triangulation: HoleTriangulation,
+    /// Split patch triangles down to the density of the surrounding mesh
+    pub refine: bool,
+    /// Move refined patch vertices to blend with the surrounding curvature (implies `refine`)
+    pub fair: bool,
+    /// Leave holes with more boundary edges than this open; triangulation
+    /// takes cubic time in the edge count
+    pub max_edges: usize,
+    /// Also cap each component's outer border, its longest boundary ring
+    pub fill_border: bool,
+}
+
+/// What local hole filling did
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HoleFillReport {
+    pub filled: usize,
+    /// Holes over `max_edges`, or whose boundary touches itself
+    pub skipped: usize,
+    /// Outer borders left open
+    pub borders: usize,
+    pub faces_added: usize,
+    pub vertices_added: usize,
+}
+
+/// A face corner of the source mesh
+#[derive(Clone, Copy, Debug)]
+struct Corner {
+    face: usize,
+    corner: usize,
+}
+
+/// Fill the holes of `mesh` with local patches.
+///
+/// Vertices are welded by position to find the boundary, so UV and normal
+/// seams aren't taken for holes. The original vertices and faces are kept
+/// verbatim with all their attributes; only patch vertices and faces get new,
+/// interpolated ones.
+pub fn fill_holes(
+    mesh: &MeshWithColors,
+    options: &HoleFillOptions,
+) -> Result<(MeshWithColors, HoleFillReport), Box<dyn std::error::Error>> {
+    let mut weld_index: HashMap<[u32; 3], usize> = HashMap::new();
+    let mut positions: Vec<Vector3<f32>> = Vec::new();
+    let weld: Vec<usize> = mesh
+        .positions
+        .iter()
+        .map(|p| {
+            // Adding zero folds -0.0 into 0.0
+            *weld_index.entry(p.map(|x| (x + 0.0).to_bits())).or_insert_with(|| {
+                positions.push(Vector3::from(*p));
+                positions.len() - 1
+            })
+        })
+        .collect();
+    // Faces collapsed by the weld (such as those at UV sphere poles) take no part
+    let source_faces: Vec<usize> = (0..mesh.face_indices.len())
+        .filter(|&f| {
+            let face = mesh.face_indices[f];
+            face.iter().all(|&v| (v as usize) < weld.len()) && {
+                let [a, b, c] = face.map(|v| weld[v as usize]);
+                a != b && b != c && c != a
+            }
+        })
+        .collect();
+    let mut faces: Vec<[usize; 3]> = source_faces.iter().map(|&f| mesh.face_indices[f].map(|v| weld[v as usize])).collect();
+    let welded_vertices = positions.len();
+
+    // Local edge length around each vertex, and how often each directed edge is used
+    let mut length_sum = vec![0.0f32; positions.len()];
+    let mut length_count = vec![0u32; positions.len()];
+    let mut directed: HashMap<(usize, usize), usize> = HashMap::new();
+    for f in &faces {
+        for k in 0..3 {
+            let (a, b) = (f[k], f[(k + 1) % 3]);
+            let length = (positions[a] - positions[b]).norm();
+            for v in [a, b] {
+                length_sum[v] += length;
+                length_count[v] += 1;
+            }
+            *directed.entry((a, b)).or_insert(0) += 1;
+        }
+    }
+    let mut sigma: Vec<f32> =
+        length_sum.iter().zip(&length_count).map(|(&s, &c)| if c > 0 { s / c as f32 } else { 0.0 }).collect();
+    let mut mesh_edges: HashSet<(usize, usize)> = directed.keys().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+
+    // Boundary edges in mesh winding, each with the vertex opposite it and
+    // the corner it leaves from, grouped into rings by shared vertices
+    let mut boundary = Vec::new();
+    let mut corner_of: HashMap<usize, Corner> = HashMap::new();
+    for (i, f) in faces.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (f[k], f[(k + 1) % 3]);
+            if directed[&(a, b)] != 1 || directed.contains_key(&(b, a)) {
+                continue;
+            }
+            boundary.push((a, b, f[(k + 2) % 3]));
+            corner_of.insert(a, Corner { face: source_faces[i], corner: k });
+        }
+    }
+    let mut parent: HashMap<usize, usize> = HashMap::new();
+    fn root(parent: &HashMap<usize, usize>, mut v: usize) -> usize {
+        while let Some(&p) = parent.get(&v).filter(|&&p| p != v) {
+            v = p;
+        }
+        v
+    }
+    for &(a, b, _) in &boundary {
+        let (ra, rb) = (root(&parent, a), root(&parent, b));
+        parent.insert(ra, rb);
+    }
+    let mut rings: Vec<Vec<(usize, usize, usize)>> = Vec::new();
+    let mut ring_of: HashMap<usize, usize> = HashMap::new();
+    for &edge in &boundary {
+        let next = rings.len();
+        let ring = *ring_of.entry(root(&parent, edge.0)).or_insert(next);
+        if ring == rings.len() {
+            rings.push(Vec::new());
+        }
+        rings[ring].push(edge);
+    }
+
+    // Components with more than one ring keep their longest as the outer border
+    let mut border = vec![false; rings.len()];
+    if !options.fill_border {
+        let mut component: Vec<usize> = (0..welded_vertices).collect();
+        fn find(component: &mut [usize], mut v: usize) -> usize {
+            while component[v] != v {
+                component[v] = component[component[v]];
+                v = component[v];
+            }
+            v
+        }
+        for f in &faces {
+            let a = find(&mut component, f[0]);
+            for &v in &f[1..] {
+                let b = find(&mut component, v);
+                component[b] = a;
+            }
+        }
+        let mut by_component: HashMap<usize, Vec<(f32, usize)>> = HashMap::new();
+        for (ring, edges) in rings.iter().enumerate() {
+            let perimeter = edges.iter().map(|&(a, b, _)| (positions[a] - positions[b]).norm()).sum();
+            by_component.entry(find(&mut component, edges[0].0)).or_default().push((perimeter, ring));
+        }
+        for component_rings in by_component.values().filter(|r| r.len() > 1) {
+            let &(_, longest) = component_rings.iter().max_by(|a, b| a.0.total_cmp(&b.0)).unwrap();
+            border[longest] = true;
+        }
+    }
+
+    let mut report = HoleFillReport::default();
+    let mut fair_vertices = Vec::new();
+    let mut origins: Vec<[usize; 3]> = Vec::new();
+    // Patch faces, each with the ring it closes
+    let mut patches: Vec<([usize; 3], usize)> = Vec::new();
+    for (ring, edges) in rings.iter().enumerate() {
+        if border[ring] {
+            report.borders += 1;
+            continue;
+        }
+        let Some(hole) = chain(edges) else {
+            report.skipped += 1;
+            continue;
+        };
+        if hole.len() > options.max_edges {
+            report.skipped += 1;
+            continue;
+        }
+
+        let mut patch = triangulate(&positions, &hole, options.triangulation);
+        if options.refine || options.fair {
+            let first_new = positions.len();
+            refine(&mut patch, &mut positions, &mut sigma, &mut origins, &mesh_edges);
+            fair_vertices.extend(first_new..positions.len());
+        }
+        for f in &patch {
+            for k in 0..3 {
+                let (a, b) = (f[k], f[(k + 1) % 3]);
+                mesh_edges.insert((a.min(b), a.max(b)));
+            }
+        }
+        faces.extend(&patch);
+        patches.extend(patch.into_iter().map(|f| (f, ring)));
+        report.filled += 1;
+    }
+
+    if options.fair && !fair_vertices.is_empty() {
+        fair(&mut positions, &faces, &fair_vertices);
+    }
+    report.faces_added = patches.len();
+    report.vertices_added = positions.len() - welded_vertices;
+
+    let mut filled = mesh.clone();
+    // Ring vertices reuse the source vertex their boundary edge leaves from
+    let vertex = |v: usize| match v.checked_sub(welded_vertices) {
+        Some(new) => (mesh.positions.len() + new) as u32,
+        None => mesh.face_indices[corner_of[&v].face][corner_of[&v].corner],
+    };
+    filled.positions.extend(positions[welded_vertices..].iter().map(|p| [p.x, p.y, p.z]));
+    filled.face_indices.extend(patches.iter().map(|(f, _)| f.map(vertex)));
+
+    let vertex_count = mesh.positions.len();
+    let per_vertex = |values: &[[f32; 3]]| interpolate(&origins, welded_vertices, |v| values[vertex(v) as usize]);
+    if mesh.normals.len() == vertex_count {
+        filled.normals.extend(per_vertex(&mesh.normals).into_iter().map(unit));
+    }
+    if mesh.texcoords.len() == vertex_count {
+        filled.texcoords.extend(interpolate(&origins, welded_vertices, |v| mesh.texcoords[vertex(v) as usize]));
+    }
+    if mesh.vertex_colors.len() == vertex_count {
+        filled.vertex_colors.extend(interpolate(&origins, welded_vertices, |v| mesh.vertex_colors[vertex(v) as usize]));
+    }
+    if mesh.joint_indices.len() == vertex_count && mesh.joint_weights.len() == vertex_count {
+        // Joint indices can't be averaged, so refined vertices take their first corner's skin
+        let mut skin_of: Vec<usize> = Vec::with_capacity(origins.len());
+        for o in &origins {
+            let source = match o[0].checked_sub(welded_vertices) {
+                Some(new) => skin_of[new],
+                None => vertex(o[0]) as usize,
+            };
+            skin_of.push(source);
+        }
+        filled.joint_indices.extend(skin_of.iter().map(|&v| mesh.joint_indices[v]));
+        filled.joint_weights.extend(skin_of.iter().map(|&v| mesh.joint_weights[v]));
+    }
+    for (target, source) in filled.morph_targets.iter_mut().zip(&mesh.morph_targets) {
+        if source.position_deltas.len() == vertex_count {
+            target.position_deltas.extend(per_vertex(&source.position_deltas));
+        }
+    }
+
+    // Patch corners on the ring copy the corner their boundary edge leaves from
+    let face_count = mesh.face_indices.len();
+    fn per_corner<const N: usize>(
+        values: &[[[f32; N]; 3]],
+        origins: &[[usize; 3]],
+        welded_vertices: usize,
+        corner_of: &HashMap<usize, Corner>,
+        patches: &[([usize; 3], usize)],
+    ) -> Vec<[[f32; N]; 3]> {
+        let at = |v: usize| values[corner_of[&v].face][corner_of[&v].corner];
+        let refined = interpolate(origins, welded_vertices, at);
+        let value = |v: usize| v.checked_sub(welded_vertices).map_or_else(|| at(v), |new| refined[new]);
+        patches.iter().map(|(f, _)| f.map(value)).collect()
+    }
+    if mesh.corner_texcoords.len() == face_count {
+        let uvs = per_corner(&mesh.corner_texcoords, &origins, welded_vertices, &corner_of, &patches);
+        filled.corner_texcoords.extend(uvs);
+    }
+    if mesh.corner_normals.len() == face_count {
+        let normals = per_corner(&mesh.corner_normals, &origins, welded_vertices, &corner_of, &patches);
+        filled.corner_normals.extend(normals.into_iter().map(|n| n.map(unit)));
+    }
+    if mesh.face_colors.len() == face_count {
+        // A patch takes the average color of the faces around its hole
+        let colors: Vec<[f32; 4]> = rings
+            .iter()
+            .map(|edges| {
+                let mut sum = [0.0f32; 4];
+                for &(a, ..) in edges {
+                    let color = mesh.face_colors[corner_of[&a].face];
+                    for k in 0..4 {
+                        sum[k] += color[k] / edges.len() as f32;
+                    }
+                }
+                sum
+            })
+            .collect();
+        filled.face_colors.extend(patches.iter().map(|&(_, ring)| colors[ring]));
+    }
+    Ok((filled, report))
+}
+
+/// Order boundary edges into a loop of (vertex, vertex opposite the edge
+/// leaving it); `None` when the boundary passes through a vertex twice
+fn chain(edges: &[(usize, usize, usize)]) -> Option<Vec<(usize, usize)>> {
+    let mut next: HashMap<usize, (usize, usize)> = HashMap::new();
+    for &(a, b, opposite) in edges {
+        if next.insert(a, (b, opposite)).is_some() {
+            return None;
+        }
+    }
+    let start = edges.first()?.0;
+    let mut hole = Vec::with_capacity(edges.len());
+    let mut v = start;
+    loop {
+        let &(b, opposite) = next.get(&v)?;
+        hole.push((v, opposite));
+        v = b;
+        if v == start {
+            break;
+        }
+        if hole.len() > edges.len() {
+            return None;
+        }
+    }
+    (hole.len() == edges.len() && hole.len() >= 3).then_some(hole)
+}
+
+/// Attribute values of the refined vertices (`first_new` onwards), each the
+/// average of the triangle it split; ring vertices are read with `at`
+fn interpolate<const N: usize>(origins: &[[usize; 3]], first_new: usize, at: impl Fn(usize) -> [f32; N]) -> Vec<[f32; N]> {
+    let mut values: Vec<[f32; N]> = Vec::with_capacity(origins.len());
+    for origin in origins {
+        let mut sum = [0.0f32; N];
+        for &v in origin {
+            let value = v.checked_sub(first_new).map_or_else(|| at(v), |new| values[new]);
+            for k in 0..N {
+                sum[k] += value[k] / 3.0;
+            }
+        }
+        values.push(sum);
+    }
+    values
+}
+
+/// `v` scaled to unit length, or unchanged if it has none
+fn unit(v: [f32; 3]) -> [f32; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if length > f32::EPSILON { v.map(|x| x / length) } else { v }
+}
+
+fn normal(a: &Vector3<f32>, b: &Vector3<f32>, c: &Vector3<f32>) -> Option<Vector3<f32>> {
+    (b - a).cross(&(c - a)).try_normalize(f32::EPSILON)
+}
+
+fn dihedral(n1: Option<Vector3<f32>>, n2: Option<Vector3<f32>>) -> f32 {
+    match (n1, n2) {
+        (Some(n1), Some(n2)) => n1.dot(&n2).clamp(-1.0, 1.0).acos(),
+        _ => std::f32::consts::PI,
+    }
+}
+
+/// Triangulate a hole by dynamic programming over its boundary polygon.
+///
+/// Triangle (i, m, k) of the polygon becomes face [k, m, i], reversing the
+/// mesh winding along the boundary so the patch is oriented like the mesh.
+fn triangulate(positions: &[Vector3<f32>], hole: &[(usize, usize)], method: HoleTriangulation) -> Vec<[usize; 3]> {
+    let n = hole.len();
+    let p = |i: usize| &positions[hole[i].0];
+    // Normal of the mesh face on boundary edge (i, i + 1)
+    let mesh_normal = |i: usize| normal(p(i), p((i + 1) % n), &positions[hole[i].1]);
+
+    // Weights are (largest dihedral angle, area); min-area ignores the angle
+    let mut weight = vec![(0.0f32, 0.0f32); n * n];
+    let mut choice = vec![0usize; n * n];
+    let at = |i: usize, k: usize| i * n + k;
+    for len in 2..n {
+        for i in 0..n - len {
+            let k = i + len;
+            let mut best = (f32::INFINITY, f32::INFINITY);
+            for m in i + 1..k {
+                let face_normal = normal(p(k), p(m), p(i));
+                let area = (p(m) - p(k)).cross(&(p(i) - p(k))).norm() * 0.5;
+                let mut candidate = (0.0, weight[at(i, m)].1 + weight[at(m, k)].1 + area);
+                if method == HoleTriangulation::MinDihedral {
+                    // Neighbours across each edge: a mesh face on the boundary, a patch face inside
+                    let neighbour = |a: usize, b: usize| {
+                        if b == a + 1 {
+                            mesh_normal(a)
+                        } else {
+                            let t = choice[at(a, b)];
+                            normal(p(b), p(t), p(a))
+                        }
+                    };
+                    let mut angle = weight[at(i, m)].0.max(weight[at(m, k)].0);
+                    angle = angle.max(dihedral(face_normal, neighbour(i, m)));
+                    angle = angle.max(dihedral(face_normal, neighbour(m, k)));
+                    if i == 0 && k == n - 1 {
+                        angle = angle.max(dihedral(face_normal, mesh_normal(n - 1)));
+                    }
+                    candidate.0 = angle;
+                }
+                if candidate.partial_cmp(&best) == Some(std::cmp::Ordering::Less) {
+                    best = candidate;
+                    choice[at(i, k)] = m;
+                }
+            }
+            weight[at(i, k)] = best;
+        }
+    }
+
+    let mut faces = Vec::with_capacity(n - 2);
+    let mut stack = vec![(0, n - 1)];
+    while let Some((i, k)) = stack.pop() {
+        if k < i + 2 {
+            continue;
+        }
+        let m = choice[at(i, k)];
+        faces.push([hole[k].0, hole[m].0, hole[i].0]);
+        stack.push((i, m));
+        stack.push((m, k));
+    }
+    faces
+}
+
+/// Insert centroids until the patch matches the local edge length, keeping
+/// it Delaunay with edge flips; `origins` gets the triangle each one split
+fn refine(
+    patch: &mut Vec<[usize; 3]>,
+    positions: &mut Vec<Vector3<f32>>,
+    sigma: &mut Vec<f32>,
+    origins: &mut Vec<[usize; 3]>,
+    mesh_edges: &HashSet<(usize, usize)>,
+) {
+    for _ in 0..MAX_REFINE_ROUNDS {
+        let mut split = false;
+        let mut refined = Vec::with_capacity(patch.len() * 3);
+        for &[a, b, c] in patch.iter() {
+            let centroid = (positions[a] + positions[b] + positions[c]) / 3.0;
+            let sigma_c = (sigma[a] + sigma[b] + sigma[c]) / 3.0;
+            let dense = [a, b, c].iter().all(|&v| {
+                let d = REFINE_DENSITY * (centroid - positions[v]).norm();
+                d > sigma_c && d > sigma[v]
+            });
+            if !dense {
+                refined.push([a, b, c]);
+                continue;
+            }
+            let x = positions.len();
+            positions.push(centroid);
+            sigma.push(sigma_c);
+            origins.push([a, b, c]);
+            refined.extend([[a, b, x], [b, c, x], [c, a, x]]);
+            split = true;
+        }
+        *patch = refined;
+        relax(patch, positions, mesh_edges);
+        if !split {
+            break;
+        }
+    }
+}
+
+/// Flip interior patch edges whose opposite vertex lies inside the
+/// circumsphere of the triangle across the edge
+fn relax(patch: &mut [[usize; 3]], positions: &[Vector3<f32>], mesh_edges: &HashSet<(usize, usize)>) {
+    for _ in 0..MAX_RELAX_PASSES {
+        let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (fi, f) in patch.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (f[k], f[(k + 1) % 3]);
+                by_edge.entry((a.min(b), a.max(b))).or_default().push(fi);
+            }
+        }
+        let mut touched = vec![false; patch.len()];
+        let mut flipped = false;
+        for (&(u, v), incident) in &by_edge {
+            let &[f1, f2] = incident.as_slice() else { continue };
+            if touched[f1] || touched[f2] {
+                continue;
+            }
+            // Rotate f1 to [a, b, c] with a -> b, so f2 holds b -> a and its apex d
+            let Some(r) = (0..3).find(|&k| {
+                let (a, b) = (patch[f1][k], patch[f1][(k + 1) % 3]);
+                (a, b) == (u, v) || (a, b) == (v, u)
+            }) else {
+                continue;
+            };
+            let (a, b, c) = (patch[f1][r], patch[f1][(r + 1) % 3], patch[f1][(r + 2) % 3]);
+            let Some(&d) = patch[f2].iter().find(|&&x| x != a && x != b) else { continue };
+            if c == d || mesh_edges.contains(&(c.min(d), c.max(d))) || by_edge.contains_key(&(c.min(d), c.max(d))) {
+                continue;
+            }
+            let (pa, pb, pc, pd) = (positions[a], positions[b], positions[c], positions[d]);
+            let (ab, ac) = (pb - pa, pc - pa);
+            let n = ab.cross(&ac);
+            let denom = 2.0 * n.norm_squared();
+            if denom <= f32::EPSILON {
+                continue;
+            }
+            let center = pa + (n.cross(&ab) * ac.norm_squared() + ac.cross(&n) * ab.norm_squared()) / denom;
+            let radius = (center - pa).norm();
+            if (pd - center).norm() >= radius * (1.0 - 1e-4) {
+                continue;
+            }
+            patch[f1] = [c, a, d];
+            patch[f2] = [d, b, c];
+            touched[f1] = true;
+            touched[f2] = true;
+            flipped = true;
+        }
+        if !flipped {
+            break;
+        }
+    }
+}
+
+/// Fair `free` vertices by minimising the squared umbrella Laplacian over
+/// them and their neighbours, holding everything else in place. This is the
+/// least-squares form of the bi-Laplacian, solved with conjugate gradients on
+/// the normal equations.
+fn fair(positions: &mut [Vector3<f32>], faces: &[[usize; 3]], free: &[usize]) {
+    let mut neighbours: HashMap<usize, HashSet<usize>> = HashMap::new();
+    for f in faces {
+        for k in 0..3 {
+            let (a, b) = (f[k], f[(k + 1) % 3]);
+            neighbours.entry(a).or_default().insert(b);
+            neighbours.entry(b).or_default().insert(a);
+        }
+    }
+    let neighbours: HashMap<usize, Vec<usize>> =
+        neighbours.into_iter().map(|(v, set)| (v, set.into_iter().collect())).collect();
+    let ring = |v: usize| neighbours.get(&v).map_or(&[][..], Vec::as_slice);
+
+    let column: HashMap<usize, usize> = free.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let mut rows: Vec<usize> = free.iter().flat_map(|&v| ring(v).iter().copied().chain([v])).collect();
+    rows.sort_unstable();
+    rows.dedup();
+    rows.retain(|&v| !ring(v).is_empty());
+
+    // Umbrella Laplacian on `rows`, with free vertices from `x` and the rest
+    // from `fixed` (or zero)
+    let laplacian = |x: &[Vector3<f32>], fixed: Option<&[Vector3<f32>]>| -> Vec<Vector3<f32>> {
+        let value = |u: usize| match column.get(&u) {
+            Some(&i) => x[i],
+            None => fixed.map_or(Vector3::zeros(), |p| p[u]),
+        };
+        rows.iter()
+            .map(|&v| {
+                let ring = ring(v);
+                ring.iter().map(|&u| value(u)).sum::<Vector3<f32>>() / ring.len() as f32 - value(v)
+            })
+            .collect()
+    };
+    let transpose = |r: &[Vector3<f32>]| -> Vec<Vector3<f32>> {
+        let mut out = vec![Vector3::zeros(); free.len()];
+        for (&v, rv) in rows.iter().zip(r) {
+            if let Some(&i) = column.get(&v) {
+                out[i] -= rv;
+            }
+            let ring = ring(v);
+            for u in ring {
+                if let Some(&i) = column.get(u) {
+                    out[i] += rv / ring.len() as f32;
+                }
+            }
+        }
+        out
+    };
+    let dot = |a: &[Vector3<f32>], b: &[Vector3<f32>]| a.iter().zip(b).map(|(x, y)| x.dot(y)).sum::<f32>();
+
+    let mut x: Vec<Vector3<f32>> = free.iter().map(|&v| positions[v]).collect();
+    let mut r: Vec<Vector3<f32>> = transpose(&laplacian(&x, Some(positions))).iter().map(|g| -g).collect();
+    let mut p = r.clone();
+    let mut rs = dot(&r, &r);
+    let tolerance = rs * 1e-10;
+    for _ in 0..FAIRING_ITERATIONS {
+        if rs <= tolerance || rs <= f32::MIN_POSITIVE {
+            break;
+        }
+        let ap = transpose(&laplacian(&p, None));
+        let curvature = dot(&p, &ap);
+        if curvature <= 0.0 {
+            break;
+        }
+        let alpha = rs / curvature;
+        for i in 0..x.len() {
+            x[i] += p[i] * alpha;
+            r[i] -= ap[i] * alpha;
+        }
+        let rs_next = dot(&r, &r);
+        let beta = rs_next / rs;
+        for i in 0..p.len() {
+            p[i] = r[i] + p[i] * beta;
+        }
+        rs = rs_next;
+    }
+    for (&v, p) in free.iter().zip(x) {
+        positions[v] = p;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Grid of `size` x `size` quads in the XY plane with the middle `hole`
+    /// x `hole` quads missing
+    fn holed_grid(size: usize, hole: usize, bump: f32) -> MeshWithColors {
+        let mut mesh = MeshWithColors::default();
+        for y in 0..=size {
+            for x in 0..=size {
+                let (dx, dy) = (x as f32 - size as f32 / 2.0, y as f32 - size as f32 / 2.0);
+                mesh.positions.push([x as f32, y as f32, bump * -(dx * dx + dy * dy)]);
+            }
+        }
+        let lo = (size - hole) / 2;
+        for y in 0..size {
+            for x in 0..size {
+                if (lo..lo + hole).contains(&x) && (lo..lo + hole).contains(&y) {
+                    continue;
+                }
+                let v = (y * (size + 1) + x) as u32;
+                let row = size as u32 + 1;
+                mesh.face_indices.extend([[v, v + 1, v + row + 1], [v, v + row + 1, v + row]]);
+            }
+        }
+        mesh
+    }
+
+    fn boundary_rings(mesh: &MeshWithColors) -> usize {
+        mesh.to_corner_table().unwrap().boundary_rings().len()
+    }
+
+    fn options(triangulation: HoleTriangulation, fair: bool) -> HoleFillOptions {
+        HoleFillOptions { triangulation, refine: fair, fair, max_edges: usize::MAX, fill_border: true }
+    }
+
+    #[test]
+    fn fills_only_the_hole() {
+        let mesh = holed_grid(6, 2, 0.0);
+        assert_eq!(boundary_rings(&mesh), 2);
+        for triangulation in [HoleTriangulation::MinArea, HoleTriangulation::MinDihedral] {
+            let (filled, report) = fill_holes(&mesh, &options(triangulation, false)).unwrap();
+            // The outer border is a hole too, as far as the topology goes
+            assert_eq!(report.filled, 2);
+            assert_eq!(boundary_rings(&filled), 0);
+            assert_eq!(report.vertices_added, 0);
+        }
+
+        let capped = HoleFillOptions { max_edges: 8, ..options(HoleTriangulation::MinArea, false) };
+        let (filled, report) = fill_holes(&mesh, &capped).unwrap();
+        assert_eq!((report.filled, report.skipped), (1, 1));
+        assert_eq!(boundary_rings(&filled), 1);
+        assert_eq!(report.faces_added, 6);
+    }
+
+    #[test]
+    fn leaves_the_outer_border_open() {
+        let mesh = holed_grid(6, 2, 0.0);
+        let uncapped = HoleFillOptions { fill_border: false, ..options(HoleTriangulation::MinArea, false) };
+        let (filled, report) = fill_holes(&mesh, &uncapped).unwrap();
+        assert_eq!((report.filled, report.borders, report.skipped), (1, 1, 0));
+        assert_eq!(report.faces_added, 6);
+        assert_eq!(boundary_rings(&filled), 1);
+
+        // A lone ring is a hole, not a border
+        let (_, report) = fill_holes(&holed_grid(2, 0, 0.0), &uncapped).unwrap();
+        assert_eq!((report.filled, report.borders), (1, 0));
+    }
+
+    #[test]
+    fn fairing_follows_the_curvature() {
+        let mesh = holed_grid(12, 4, 0.05);
+        let capped = HoleFillOptions { max_edges: 16, ..options(HoleTriangulation::MinDihedral, true) };
+        let (filled, report) = fill_holes(&mesh, &capped).unwrap();
+        assert_eq!(report.filled, 1);
+        assert!(report.vertices_added > 0);
+        // The bump peaks at the centre of the hole; the flat boundary sits at z = -0.2
+        let top = filled.positions.iter().map(|p| p[2]).fold(f32::NEG_INFINITY, f32::max);
+        assert!(top > -0.15, "patch stayed flat at {}", top);
+    }
+
+    #[test]
+    fn untouched_faces_keep_their_attributes() {
+        let mut mesh = holed_grid(8, 4, 0.0);
+        // A UV seam down the middle, through the hole: faces right of x = 4
+        // use their own copies of the seam vertices
+        let row = 9;
+        let seam: Vec<u32> = (0..row).map(|y| y * row + 4).collect();
+        let copies: HashMap<u32, u32> =
+            seam.iter().enumerate().map(|(i, &v)| (v, (mesh.positions.len() + i) as u32)).collect();
+        let seam_positions: Vec<[f32; 3]> = seam.iter().map(|&v| mesh.positions[v as usize]).collect();
+        mesh.positions.extend(seam_positions);
+        for face in &mut mesh.face_indices {
+            if face.iter().any(|&v| mesh.positions[v as usize][0] > 4.0) {
+                *face = face.map(|v| copies.get(&v).copied().unwrap_or(v));
+            }
+        }
+        mesh.corner_texcoords = (0..mesh.face_indices.len()).map(|f| [[f as f32, 0.0], [f as f32, 0.5], [f as f32, 1.0]]).collect();
+        mesh.corner_normals = (0..mesh.face_indices.len()).map(|f| [[0.0, f as f32, 1.0]; 3]).collect();
+        mesh.normals = vec![[0.0, 0.0, 1.0]; mesh.positions.len()];
+
+        let capped = HoleFillOptions { max_edges: 16, ..options(HoleTriangulation::MinDihedral, true) };
+        let (filled, report) = fill_holes(&mesh, &capped).unwrap();
+        // The seam isn't taken for a hole
+        assert_eq!((report.filled, report.skipped), (1, 1));
+        assert!(report.vertices_added > 0);
+
+        let faces = mesh.face_indices.len();
+        assert_eq!(filled.positions[..mesh.positions.len()], mesh.positions[..]);
+        assert_eq!(filled.face_indices[..faces], mesh.face_indices[..]);
+        assert_eq!(filled.corner_texcoords[..faces], mesh.corner_texcoords[..]);
+        assert_eq!(filled.corner_normals[..faces], mesh.corner_normals[..]);
+        assert_eq!(filled.normals.len(), filled.positions.len());
+        assert_eq!(filled.corner_normals.len(), filled.face_indices.len());
+        for corners in &filled.corner_normals[faces..] {
+            for n in corners {
+                assert!(((n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+}
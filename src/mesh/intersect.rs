@@ -0,0 +1,310 @@
+//! Self-intersection detection.
+//!
+//! Face bounding boxes go into an AABB tree, and only faces whose boxes
+//! overlap are tested exactly. Vertices are matched by position rather than
+//! index, so faces meeting across a UV seam count as neighbours, not as
+//! intersecting. Neighbours only intersect where they fold through each other:
+//! a face sharing one corner counts if the edge opposite that corner crosses
+//! the other face, and a face sharing an edge counts if it lies in the same
+//! plane, folded back over its neighbour.
+
+use super::loader::MeshWithColors;
+use baby_shark::exports::nalgebra::Vector3;
+
+/// Color painted on intersecting faces by [`highlight_faces`]
+pub const HIGHLIGHT_COLOR: [f32; 4] = [0.9, 0.1, 0.1, 1.0];
+
+/// Color of the remaining faces when the mesh has no face colors of its own
+const NEUTRAL_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+
+/// Faces per leaf of the AABB tree
+const LEAF_SIZE: usize = 4;
+
+type Vec3 = Vector3<f64>;
+
+#[derive(Clone, Copy)]
+struct Aabb {
+    min: Vec3,
+    max: Vec3,
+}
+
+impl Aabb {
+    fn of(points: &[Vec3]) -> Self {
+        let mut aabb = Aabb { min: points[0], max: points[0] };
+        for p in &points[1..] {
+            aabb.min = aabb.min.inf(p);
+            aabb.max = aabb.max.sup(p);
+        }
+        aabb
+    }
+
+    fn union(&self, other: &Aabb) -> Aabb {
+        Aabb { min: self.min.inf(&other.min), max: self.max.sup(&other.max) }
+    }
+
+    fn overlaps(&self, other: &Aabb) -> bool {
+        (0..3).all(|k| self.min[k] <= other.max[k] && other.min[k] <= self.max[k])
+    }
+}
+
+enum Node {
+    Leaf { aabb: Aabb, start: usize, end: usize },
+    Inner { aabb: Aabb, left: usize, right: usize },
+}
+
+impl Node {
+    fn aabb(&self) -> &Aabb {
+        match self {
+            Node::Leaf { aabb, .. } | Node::Inner { aabb, .. } => aabb,
+        }
+    }
+}
+
+/// Bounding-volume hierarchy over face boxes, split at the median of the longest axis
+struct AabbTree {
+    nodes: Vec<Node>,
+    /// Face indices, grouped so every leaf owns a contiguous range
+    faces: Vec<usize>,
+}
+
+impl AabbTree {
+    fn build(boxes: &[Aabb], faces: Vec<usize>) -> Self {
+        let mut tree = AabbTree { nodes: Vec::new(), faces };
+        if !tree.faces.is_empty() {
+            tree.split(boxes, 0, tree.faces.len());
+        }
+        tree
+    }
+
+    fn split(&mut self, boxes: &[Aabb], start: usize, end: usize) -> usize {
+        let aabb = self.faces[start + 1..end]
+            .iter()
+            .fold(boxes[self.faces[start]], |acc, &f| acc.union(&boxes[f]));
+        let index = self.nodes.len();
+        if end - start <= LEAF_SIZE {
+            self.nodes.push(Node::Leaf { aabb, start, end });
+            return index;
+        }
+
+        let extent = aabb.max - aabb.min;
+        let axis = extent.imax();
+        let center = |f: usize| boxes[f].min[axis] + boxes[f].max[axis];
+        let mid = (start + end) / 2;
+        self.faces[start..end].select_nth_unstable_by(mid - start, |&a, &b| center(a).total_cmp(&center(b)));
+
+        // Reserve this node's slot before building the children
+        self.nodes.push(Node::Leaf { aabb, start, end });
+        let left = self.split(boxes, start, mid);
+        let right = self.split(boxes, mid, end);
+        self.nodes[index] = Node::Inner { aabb, left, right };
+        index
+    }
+
+    /// Call `visit` with every face whose box overlaps `query`
+    fn query(&self, boxes: &[Aabb], query: &Aabb, mut visit: impl FnMut(usize)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.aabb().overlaps(query) {
+                continue;
+            }
+            match *node {
+                Node::Leaf { start, end, .. } => {
+                    for &f in &self.faces[start..end] {
+                        if boxes[f].overlaps(query) {
+                            visit(f);
+                        }
+                    }
+                }
+                Node::Inner { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+    }
+}
+
+/// Every pair of faces that intersect each other, as (lower, higher) face
+/// indices in ascending order. Degenerate faces and faces with out-of-range
+/// indices are skipped.
+pub fn self_intersections(mesh: &MeshWithColors) -> Vec<(usize, usize)> {
+    let triangles: Vec<Option<[Vec3; 3]>> = mesh
+        .face_indices
+        .iter()
+        .map(|face| {
+            let corner = |v: u32| mesh.positions.get(v as usize).map(|p| Vec3::new(p[0] as f64, p[1] as f64, p[2] as f64));
+            let tri = [corner(face[0])?, corner(face[1])?, corner(face[2])?];
+            let normal = (tri[1] - tri[0]).cross(&(tri[2] - tri[0]));
+            (normal.norm_squared() > 0.0).then_some(tri)
+        })
+        .collect();
+
+    let boxes: Vec<Aabb> = triangles
+        .iter()
+        .map(|tri| tri.as_ref().map(|t| Aabb::of(t)).unwrap_or(Aabb { min: Vec3::zeros(), max: Vec3::zeros() }))
+        .collect();
+    let valid: Vec<usize> = (0..triangles.len()).filter(|&f| triangles[f].is_some()).collect();
+    let tree = AabbTree::build(&boxes, valid.clone());
+
+    let mut pairs = Vec::new();
+    for &f in &valid {
+        let tri = triangles[f].as_ref().unwrap();
+        let mut candidates = Vec::new();
+        tree.query(&boxes, &boxes[f], |g| {
+            if g > f {
+                candidates.push(g);
+            }
+        });
+        candidates.sort_unstable();
+        for g in candidates {
+            if triangles_intersect(tri, triangles[g].as_ref().unwrap()) {
+                pairs.push((f, g));
+            }
+        }
+    }
+    pairs
+}
+
+/// Paint the faces in `pairs` with [`HIGHLIGHT_COLOR`].
+///
+/// Vertex colors and the texture are dropped so the face colors show; faces
+/// without a color of their own get a neutral grey.
+pub fn highlight_faces(mesh: &mut MeshWithColors, pairs: &[(usize, usize)]) {
+    if mesh.face_colors.len() != mesh.face_indices.len() {
+        mesh.face_colors = vec![NEUTRAL_COLOR; mesh.face_indices.len()];
+    }
+    for &(f, g) in pairs {
+        mesh.face_colors[f] = HIGHLIGHT_COLOR;
+        mesh.face_colors[g] = HIGHLIGHT_COLOR;
+    }
+    mesh.vertex_colors.clear();
+    mesh.texture = None;
+}
+
+fn triangles_intersect(t1: &[Vec3; 3], t2: &[Vec3; 3]) -> bool {
+    // Corners shared by position, as (index in t1, index in t2)
+    let mut shared = Vec::new();
+    for (i, p) in t1.iter().enumerate() {
+        if let Some(j) = t2.iter().position(|q| q == p) {
+            shared.push((i, j));
+        }
+    }
+
+    match shared[..] {
+        [] => {
+            (0..3).any(|k| segment_hits_triangle(t1[k], t1[(k + 1) % 3], t2))
+                || (0..3).any(|k| segment_hits_triangle(t2[k], t2[(k + 1) % 3], t1))
+                || (coplanar(t1, t2) && coplanar_overlap(t1, t2))
+        }
+        [(i, j)] => {
+            segment_hits_triangle(t1[(i + 1) % 3], t1[(i + 2) % 3], t2)
+                || segment_hits_triangle(t2[(j + 1) % 3], t2[(j + 2) % 3], t1)
+        }
+        [(i1, j1), (i2, j2)] => {
+            // Folded back: both free corners on the same side of the shared edge
+            let (a, b) = (t1[i1], t1[i2]);
+            let normal = (t1[1] - t1[0]).cross(&(t1[2] - t1[0]));
+            let side = |p: Vec3| (b - a).cross(&(p - a)).dot(&normal);
+            coplanar(t1, t2) && side(t1[3 - i1 - i2]) * side(t2[3 - j1 - j2]) > 0.0
+        }
+        // Same three corners: a duplicate face, which `check` reports on its own
+        _ => false,
+    }
+}
+
+/// Whether segment `p`-`q` touches triangle `tri` (Möller–Trumbore)
+fn segment_hits_triangle(p: Vec3, q: Vec3, tri: &[Vec3; 3]) -> bool {
+    let d = q - p;
+    let (e1, e2) = (tri[1] - tri[0], tri[2] - tri[0]);
+    let h = d.cross(&e2);
+    let det = e1.dot(&h);
+    // Parallel to the plane; coplanar overlap is tested separately
+    if det.abs() <= 1e-12 * d.norm() * e1.norm() * e2.norm() {
+        return false;
+    }
+    let s = p - tri[0];
+    let u = s.dot(&h) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return false;
+    }
+    let qv = s.cross(&e1);
+    let v = d.dot(&qv) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return false;
+    }
+    let t = e2.dot(&qv) / det;
+    (0.0..=1.0).contains(&t)
+}
+
+fn coplanar(t1: &[Vec3; 3], t2: &[Vec3; 3]) -> bool {
+    let normal = (t1[1] - t1[0]).cross(&(t1[2] - t1[0]));
+    let scale = normal.norm() * (t1[1] - t1[0]).norm().max((t1[2] - t1[0]).norm());
+    t2.iter().all(|p| normal.dot(&(p - t1[0])).abs() <= 1e-9 * scale)
+}
+
+/// Overlap of two triangles in the same plane, tested in 2D along the
+/// normal's dominant axis. Touching along an edge or at a corner (as at a
+/// T-junction) doesn't count.
+fn coplanar_overlap(t1: &[Vec3; 3], t2: &[Vec3; 3]) -> bool {
+    let axis = (t1[1] - t1[0]).cross(&(t1[2] - t1[0])).abs().imax();
+    let (x, y) = ((axis + 1) % 3, (axis + 2) % 3);
+    let flat = |t: &[Vec3; 3]| t.map(|p| [p[x], p[y]]);
+    let (a, b) = (flat(t1), flat(t2));
+
+    let cross = |o: [f64; 2], p: [f64; 2], q: [f64; 2]| (p[0] - o[0]) * (q[1] - o[1]) - (p[1] - o[1]) * (q[0] - o[0]);
+    let inside = |p: [f64; 2], t: &[[f64; 2]; 3]| {
+        let s = [cross(t[0], t[1], p), cross(t[1], t[2], p), cross(t[2], t[0], p)];
+        s.iter().all(|&c| c > 0.0) || s.iter().all(|&c| c < 0.0)
+    };
+    let edges_cross = |p1: [f64; 2], p2: [f64; 2], q1: [f64; 2], q2: [f64; 2]| {
+        cross(p1, p2, q1) * cross(p1, p2, q2) < 0.0 && cross(q1, q2, p1) * cross(q1, q2, p2) < 0.0
+    };
+
+    (0..3).any(|i| (0..3).any(|j| edges_cross(a[i], a[(i + 1) % 3], b[j], b[(j + 1) % 3])))
+        || a.iter().any(|&p| inside(p, &b))
+        || b.iter().any(|&p| inside(p, &a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two tetrahedra, the second pushed into the first when `overlap` is set
+    fn tetrahedra(overlap: bool) -> MeshWithColors {
+        let offset = if overlap { 0.5 } else { 2.0 };
+        let mut positions = Vec::new();
+        let mut face_indices = Vec::new();
+        for dx in [0.0, offset] {
+            let base = positions.len() as u32;
+            positions.extend([[dx, 0.0, 0.0], [dx + 1.0, 0.0, 0.0], [dx, 1.0, 0.0], [dx, 0.0, 1.0]]);
+            face_indices.extend([[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]].map(|f: [u32; 3]| f.map(|v| v + base)));
+        }
+        MeshWithColors { positions, face_indices, ..Default::default() }
+    }
+
+    #[test]
+    fn finds_pairs_between_overlapping_parts_only() {
+        assert!(self_intersections(&tetrahedra(false)).is_empty());
+
+        let pairs = self_intersections(&tetrahedra(true));
+        assert!(!pairs.is_empty());
+        assert!(pairs.iter().all(|&(f, g)| f < 4 && g >= 4));
+    }
+
+    #[test]
+    fn neighbours_only_intersect_when_folded() {
+        let flat = [Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)];
+        let beside = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 0.0)];
+        let folded = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.2, 0.2, 0.0)];
+        let fan = [Vec3::new(0.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, -1.0, 0.5)];
+        let pierce = [Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.3, 0.3, -1.0), Vec3::new(0.3, 0.3, 1.0)];
+        assert!(!triangles_intersect(&flat, &beside));
+        assert!(triangles_intersect(&flat, &folded));
+        assert!(!triangles_intersect(&flat, &fan));
+        assert!(triangles_intersect(&flat, &pierce));
+    }
+}
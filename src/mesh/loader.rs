@@ -1,19 +1,35 @@
 use baby_shark::io::{Builder, IndexedBuilder};
 use baby_shark::mesh::corner_table::CornerTableF;
+use nalgebra::{Matrix4, Point3};
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-use super::animation::{self, AnimationClip};
+use super::animation::{self, AnimationClip, AnimationProperty};
 use super::skeleton::{Joint, JointTransform, Skeleton};
+use super::format::{self, MeshFormat};
+use super::stdio::progress;
+use super::{collada, fbx, gltf_compression, obj, ply, stl, threemf, usd};
 
 /// Embedded texture image data
+#[derive(Clone)]
 pub struct TextureData {
     pub pixels: Vec<u8>,  // RGBA8
     pub width: u32,
     pub height: u32,
 }
 
+/// Morph target (blend shape) with per-vertex position offsets
+#[derive(Clone, Debug)]
+pub struct MorphTarget {
+    /// Target name from the glTF mesh's `extras.targetNames` (if present)
+    pub name: Option<String>,
+    /// Position delta per vertex, parallel to `positions`
+    pub position_deltas: Vec<[f32; 3]>,
+}
+
 /// Mesh geometry with optional per-face material colors and textures
+#[derive(Clone, Default)]
 pub struct MeshWithColors {
     /// Vertex positions
     pub positions: Vec<[f32; 3]>,
@@ -21,8 +37,17 @@ pub struct MeshWithColors {
     pub face_indices: Vec<[u32; 3]>,
     /// Per-face RGBA colors (empty if no materials found)
     pub face_colors: Vec<[f32; 4]>,
+    /// Per-vertex RGBA colors (empty if none; takes precedence over face colors when rendering)
+    pub vertex_colors: Vec<[f32; 4]>,
+    /// Per-vertex normals (empty if none)
+    pub normals: Vec<[f32; 3]>,
     /// Per-vertex UV coordinates (empty if no UVs)
     pub texcoords: Vec<[f32; 2]>,
+    /// Per-corner UV coordinates, parallel to `face_indices` (empty if none).
+    /// Used by formats that index UVs separately from positions, such as OBJ.
+    pub corner_texcoords: Vec<[[f32; 2]; 3]>,
+    /// Per-corner normals, parallel to `face_indices` (empty if none)
+    pub corner_normals: Vec<[[f32; 3]; 3]>,
     /// Embedded texture (first baseColorTexture found, if any)
     pub texture: Option<TextureData>,
     /// Skeleton extracted from glTF skin data (None if no skin)
@@ -34,16 +59,178 @@ pub struct MeshWithColors {
     pub joint_weights: Vec<[f32; 4]>,
     /// Animation clips extracted from glTF (empty if no animations)
     pub animations: Vec<AnimationClip>,
+    /// Morph targets (empty if none)
+    pub morph_targets: Vec<MorphTarget>,
+    /// Default morph target weights, one per target
+    pub morph_weights: Vec<f32>,
 }
 
 impl MeshWithColors {
-    /// Convert Z-up coordinates to Y-up by swapping Y and Z
+    /// Convert Z-up coordinates to Y-up by swapping Y and Z. The skeleton and
+    /// its animations are rotated the same way so skinning stays consistent.
     pub fn convert_z_up_to_y_up(&mut self) {
-        for pos in &mut self.positions {
+        let corner_normals = self.corner_normals.iter_mut().flatten();
+        let morph_deltas = self.morph_targets.iter_mut().flat_map(|t| t.position_deltas.iter_mut());
+        for pos in self
+            .positions
+            .iter_mut()
+            .chain(self.normals.iter_mut())
+            .chain(corner_normals)
+            .chain(morph_deltas)
+        {
             let y = pos[1];
             pos[1] = pos[2];
             pos[2] = -y;
         }
+
+        // -90 degrees about X; joint matrices are conjugated (R * M * R^-1)
+        let rotate = |v: &mut [f32]| {
+            let y = v[1];
+            v[1] = v[2];
+            v[2] = -y;
+        };
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let r = nalgebra::UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(half, -half, 0.0, 0.0));
+        let rotate_quat = |q: &mut [f32]| {
+            let q2 = r.quaternion() * nalgebra::Quaternion::new(q[3], q[0], q[1], q[2]) * r.inverse().quaternion();
+            q.copy_from_slice(&[q2.i, q2.j, q2.k, q2.w]);
+        };
+        let m = r.to_homogeneous();
+        let conjugate = |a: &[[f32; 4]; 4]| -> [[f32; 4]; 4] { (m * Matrix4::from(*a) * m.transpose()).into() };
+        let (skeleton, animations) = (&mut self.skeleton, &mut self.animations);
+        let Some(skeleton) = skeleton else { return };
+        for joint in &mut skeleton.joints {
+            joint.inverse_bind_matrix = conjugate(&joint.inverse_bind_matrix);
+            match &mut joint.local_transform {
+                JointTransform::Decomposed { translation, rotation, scale } => {
+                    rotate(translation);
+                    rotate_quat(rotation);
+                    scale.swap(1, 2);
+                }
+                JointTransform::Matrix(matrix) => *matrix = conjugate(matrix),
+            }
+        }
+        for channel in animations.iter_mut().flat_map(|c| c.channels.iter_mut()) {
+            let keyframes = channel.keyframes.iter_mut();
+            let values = keyframes.flat_map(|k| {
+                std::iter::once(&mut k.value)
+                    .chain(k.in_tangent.as_mut())
+                    .chain(k.out_tangent.as_mut())
+            });
+            for value in values {
+                match channel.property {
+                    AnimationProperty::Translation if value.len() == 3 => rotate(value),
+                    AnimationProperty::Rotation if value.len() == 4 => rotate_quat(value),
+                    AnimationProperty::Scale if value.len() == 3 => value.swap(1, 2),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Scale the mesh uniformly, including morph targets, joint translations and
+    /// translation animation
+    pub fn scale_uniform(&mut self, factor: f32) {
+        let morph_deltas = self.morph_targets.iter_mut().flat_map(|t| t.position_deltas.iter_mut());
+        for pos in self.positions.iter_mut().chain(morph_deltas) {
+            *pos = pos.map(|c| c * factor);
+        }
+        let Some(skeleton) = &mut self.skeleton else { return };
+        for joint in &mut skeleton.joints {
+            // Column 3 holds the translation of these column-major matrices
+            (0..3).for_each(|row| joint.inverse_bind_matrix[3][row] *= factor);
+            match &mut joint.local_transform {
+                JointTransform::Decomposed { translation, .. } => *translation = translation.map(|c| c * factor),
+                JointTransform::Matrix(matrix) => (0..3).for_each(|row| matrix[3][row] *= factor),
+            }
+        }
+        for channel in self.animations.iter_mut().flat_map(|c| c.channels.iter_mut()) {
+            if channel.property != AnimationProperty::Translation {
+                continue;
+            }
+            for keyframe in &mut channel.keyframes {
+                for value in std::iter::once(&mut keyframe.value)
+                    .chain(keyframe.in_tangent.as_mut())
+                    .chain(keyframe.out_tangent.as_mut())
+                {
+                    value.iter_mut().for_each(|c| *c *= factor);
+                }
+            }
+        }
+    }
+
+    /// Whether UVs or normals are stored per corner rather than per vertex
+    pub fn has_corner_attributes(&self) -> bool {
+        !self.corner_texcoords.is_empty() || !self.corner_normals.is_empty()
+    }
+
+    /// Expand per-corner UVs and normals into per-vertex ones, splitting
+    /// vertices wherever the corners sharing them disagree (UV seams, hard edges)
+    pub fn with_vertex_attributes(&self) -> Cow<'_, MeshWithColors> {
+        if !self.has_corner_attributes() {
+            return Cow::Borrowed(self);
+        }
+
+        let n = self.positions.len();
+        let has_uvs = self.corner_texcoords.len() == self.face_indices.len();
+        let has_normals = self.corner_normals.len() == self.face_indices.len();
+        let mut split = MeshWithColors {
+            face_colors: self.face_colors.clone(),
+            texture: self.texture.clone(),
+            skeleton: self.skeleton.clone(),
+            animations: self.animations.clone(),
+            morph_weights: self.morph_weights.clone(),
+            ..Default::default()
+        };
+        let has_morphs = self.morph_targets.iter().all(|t| t.position_deltas.len() == n);
+        let mut source_vertices: Vec<usize> = Vec::new();
+
+        let mut corner_map: HashMap<(u32, [u32; 2], [u32; 3]), u32> = HashMap::new();
+        for (face_i, tri) in self.face_indices.iter().enumerate() {
+            let mut new_tri = [u32::MAX; 3];
+            for k in 0..3 {
+                let vi = tri[k];
+                if vi as usize >= n {
+                    continue; // Leave out-of-range indices out of range
+                }
+                let uv = if has_uvs { self.corner_texcoords[face_i][k] } else { [0.0; 2] };
+                let normal = if has_normals { self.corner_normals[face_i][k] } else { [0.0; 3] };
+                let key = (vi, uv.map(f32::to_bits), normal.map(f32::to_bits));
+                new_tri[k] = *corner_map.entry(key).or_insert_with(|| {
+                    let v = vi as usize;
+                    split.positions.push(self.positions[v]);
+                    if has_uvs {
+                        split.texcoords.push(uv);
+                    }
+                    if has_normals {
+                        split.normals.push(normal);
+                    }
+                    if self.vertex_colors.len() == n {
+                        split.vertex_colors.push(self.vertex_colors[v]);
+                    }
+                    if self.joint_indices.len() == n && self.joint_weights.len() == n {
+                        split.joint_indices.push(self.joint_indices[v]);
+                        split.joint_weights.push(self.joint_weights[v]);
+                    }
+                    source_vertices.push(v);
+                    split.positions.len() as u32 - 1
+                });
+            }
+            split.face_indices.push(new_tri);
+        }
+
+        if has_morphs {
+            split.morph_targets = self
+                .morph_targets
+                .iter()
+                .map(|t| MorphTarget {
+                    name: t.name.clone(),
+                    position_deltas: source_vertices.iter().map(|&v| t.position_deltas[v]).collect(),
+                })
+                .collect();
+        }
+
+        Cow::Owned(split)
     }
 
     /// Build a CornerTableF from the parsed geometry (for mesh stats)
@@ -65,175 +252,81 @@ impl MeshWithColors {
             .finish()
             .map_err(|e| format!("Failed to build mesh: {:?}", e).into())
     }
-}
 
-/// Load mesh with per-face material colors (supports .obj+.mtl and .glb/.gltf)
-pub fn load_mesh_with_colors(
-    input: &PathBuf,
-    mesh_name: Option<&str>,
-) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
-    let extension = input
-        .extension()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_lowercase())
-        .ok_or("File has no extension")?;
-
-    match extension.as_str() {
-        "obj" => parse_obj_with_colors(input),
-        "glb" | "gltf" => load_glb_with_colors(input, mesh_name),
-        "3mf" => load_3mf_with_colors(input),
-        _ => Err(format!("Unsupported file format: {}", extension).into()),
-    }
-}
+    /// Extract plain geometry from a CornerTableF (after processing, no attributes)
+    pub fn from_corner_table(mesh: &CornerTableF) -> Self {
+        let mut positions: Vec<[f32; 3]> = Vec::with_capacity(mesh.count_vertices());
+        let mut vertex_id_to_idx: HashMap<_, u32> = HashMap::new();
 
-/// Load mesh from file (supports .obj, .glb, .3mf) — returns CornerTableF for processing
-pub fn load_mesh(
-    input: &PathBuf,
-    mesh_name: Option<&str>,
-) -> Result<CornerTableF, Box<dyn std::error::Error>> {
-    let extension = input
-        .extension()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_lowercase())
-        .ok_or("File has no extension")?;
-
-    match extension.as_str() {
-        "obj" => {
-            use baby_shark::io::read_from_file;
-            read_from_file(input).map_err(|e| format!("Failed to read OBJ file: {:?}", e).into())
-        }
-        "glb" | "gltf" => load_mesh_from_glb(input, mesh_name),
-        "3mf" => {
-            let mesh_data = load_3mf_with_colors(input)?;
-            mesh_data.to_corner_table()
+        for vertex_id in mesh.vertices() {
+            let pos = mesh.vertex_position(vertex_id);
+            vertex_id_to_idx.insert(vertex_id, positions.len() as u32);
+            positions.push([pos.x, pos.y, pos.z]);
         }
-        _ => Err(format!("Unsupported file format: {}", extension).into()),
-    }
-}
 
-// --- OBJ + MTL parsing ---
-
-/// Parse an OBJ file with optional MTL material colors
-fn parse_obj_with_colors(path: &PathBuf) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
-    let content = std::fs::read_to_string(path)?;
-    let parent_dir = path.parent().unwrap_or(Path::new("."));
-
-    let mut positions: Vec<[f32; 3]> = Vec::new();
-    let mut face_indices: Vec<[u32; 3]> = Vec::new();
-    let mut face_colors: Vec<[f32; 4]> = Vec::new();
-    let mut materials: HashMap<String, [f32; 4]> = HashMap::new();
-    let mut current_color: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
-    let mut has_materials = false;
-
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
+        let face_indices = mesh
+            .faces()
+            .map(|face_id| {
+                let (v0, v1, v2) = mesh.face_vertices(face_id);
+                [vertex_id_to_idx[&v0], vertex_id_to_idx[&v1], vertex_id_to_idx[&v2]]
+            })
+            .collect();
+
+        MeshWithColors {
+            positions,
+            face_indices,
+            ..Default::default()
         }
-
-        if let Some(mtl_name) = line.strip_prefix("mtllib ") {
-            let mtl_name = mtl_name.trim();
-            let mtl_path = parent_dir.join(mtl_name);
-            if mtl_path.exists() {
-                match parse_mtl(&mtl_path) {
-                    Ok(mtls) => {
-                        if !mtls.is_empty() {
-                            has_materials = true;
-                            materials = mtls;
-                        }
-                    }
-                    Err(e) => eprintln!("Warning: failed to parse MTL file {:?}: {}", mtl_path, e),
-                }
-            }
-        } else if let Some(coords) = line.strip_prefix("v ") {
-            let parts: Vec<f32> = coords
-                .split_whitespace()
-                .filter_map(|s| s.parse().ok())
-                .collect();
-            if parts.len() >= 3 {
-                positions.push([parts[0], parts[1], parts[2]]);
-            }
-        } else if let Some(mat_name) = line.strip_prefix("usemtl ") {
-            let mat_name = mat_name.trim();
-            if let Some(color) = materials.get(mat_name) {
-                current_color = *color;
-            }
-        } else if let Some(face_str) = line.strip_prefix("f ") {
-            let verts: Vec<u32> = face_str
-                .split_whitespace()
-                .filter_map(|s| {
-                    // Parse v, v/vt, v//vn, or v/vt/vn — extract just the vertex index
-                    s.split('/').next()?.parse::<u32>().ok().map(|i| i - 1) // 1-based to 0-based
-                })
-                .collect();
-
-            // Fan triangulation for n-gons
-            if verts.len() >= 3 {
-                for i in 1..verts.len() - 1 {
-                    face_indices.push([verts[0], verts[i], verts[i + 1]]);
-                    face_colors.push(current_color);
-                }
-            }
-        }
-    }
-
-    if !has_materials {
-        face_colors.clear();
     }
+}
 
-    Ok(MeshWithColors {
-        positions,
-        face_indices,
-        face_colors,
-        texcoords: Vec::new(),
-        texture: None,
-        skeleton: None,
-        joint_indices: Vec::new(),
-        joint_weights: Vec::new(),
-        animations: Vec::new(),
-    })
+/// Options selecting what to load from a mesh file
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    /// Mesh name (GLB mesh or OBJ `o`/`g` group)
+    pub mesh_name: Option<String>,
+    /// Load a whole glTF scene with node transforms applied instead of a
+    /// single mesh. `Some(None)` selects the file's default scene.
+    pub scene: Option<Option<usize>>,
+    /// Parse as this format instead of detecting it from the contents and extension
+    pub format: Option<MeshFormat>,
 }
 
-/// Parse an MTL file, returning material name → RGBA color
-fn parse_mtl(path: &Path) -> Result<HashMap<String, [f32; 4]>, Box<dyn std::error::Error>> {
-    let content = std::fs::read_to_string(path)?;
-    let mut materials = HashMap::new();
-    let mut current_name: Option<String> = None;
-    let mut current_color: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
-
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
+/// Load mesh with per-face material colors (supports .obj+.mtl, .glb/.gltf, .3mf, .fbx, .dae, .usda, .stl and .ply).
+/// The format is detected from the file contents unless `options.format` is set.
+pub fn load_mesh_with_colors(
+    input: &PathBuf,
+    options: &LoadOptions,
+) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let format = format::detect(input, options.format)?;
 
-        if let Some(name) = line.strip_prefix("newmtl ") {
-            // Save previous material
-            if let Some(prev_name) = current_name.take() {
-                materials.insert(prev_name, current_color);
-            }
-            current_name = Some(name.trim().to_string());
-            current_color = [0.85, 0.85, 0.85, 1.0]; // reset
-        } else if let Some(kd) = line.strip_prefix("Kd ") {
-            let parts: Vec<f32> = kd
-                .split_whitespace()
-                .filter_map(|s| s.parse().ok())
-                .collect();
-            if parts.len() >= 3 {
-                current_color = [parts[0], parts[1], parts[2], 1.0];
-            }
-        } else if let Some(d_val) = line.strip_prefix("d ")
-            && let Ok(alpha) = d_val.trim().parse::<f32>() {
-                current_color[3] = alpha;
-            }
+    if options.scene.is_some() && format != MeshFormat::Gltf {
+        return Err("--scene is only supported for .glb/.gltf files".into());
     }
 
-    // Save last material
-    if let Some(name) = current_name {
-        materials.insert(name, current_color);
+    let mesh_name = options.mesh_name.as_deref();
+    match format {
+        MeshFormat::Obj => obj::load_obj(input, mesh_name),
+        MeshFormat::Gltf => match options.scene {
+            Some(scene_index) => load_gltf_scene(input, scene_index),
+            None => load_glb_with_colors(input, mesh_name),
+        },
+        MeshFormat::ThreeMf => threemf::load_3mf(input),
+        MeshFormat::Fbx => fbx::load_fbx(input, mesh_name),
+        MeshFormat::Collada => collada::load_collada(input, mesh_name),
+        MeshFormat::Usda => usd::load_usda(input, mesh_name),
+        MeshFormat::Stl => stl::load_stl(input),
+        MeshFormat::Ply => ply::load_ply(input),
+        MeshFormat::Bvh => Err("BVH files contain motion only; view them with `msh view` or apply them with --bvh".into()),
     }
+}
 
-    Ok(materials)
+/// Load mesh from file (supports .obj, .glb, .3mf, .fbx, .dae, .usda, .stl, .ply) — returns CornerTableF for processing
+pub fn load_mesh(
+    input: &PathBuf,
+    options: &LoadOptions,
+) -> Result<CornerTableF, Box<dyn std::error::Error>> {
+    load_mesh_with_colors(input, options)?.to_corner_table()
 }
 
 // --- GLB/glTF loading ---
@@ -243,7 +336,7 @@ fn load_glb_with_colors(
     path: &PathBuf,
     mesh_name: Option<&str>,
 ) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
-    let (document, buffers, images) = gltf::import(path)?;
+    let (document, buffers, images) = gltf_compression::import(path)?;
 
     let meshes: Vec<_> = document.meshes().collect();
     if meshes.is_empty() {
@@ -282,7 +375,7 @@ fn load_glb_with_colors(
         }
     };
 
-    println!(
+    progress!(
         "Loading mesh: {}",
         selected_mesh.name().unwrap_or("<unnamed>")
     );
@@ -295,17 +388,28 @@ fn load_glb_with_colors(
     let mut first_color: Option<[f32; 4]> = None;
     let mut has_materials = false;
     let mut texture_image_index: Option<usize> = None;
+    // Per-primitive morph target deltas, concatenated once all primitives are read
+    let mut prim_morph_deltas: Vec<(usize, Vec<Vec<[f32; 3]>>)> = Vec::new();
 
     for primitive in selected_mesh.primitives() {
         let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
 
         let prim_positions = reader
             .read_positions()
-            .ok_or("Primitive has no position data")?;
+            .ok_or_else(|| gltf_compression::missing_positions_error(&primitive))?;
         let pos_vec: Vec<[f32; 3]> = prim_positions.collect();
         let vert_count = pos_vec.len();
         positions.extend_from_slice(&pos_vec);
 
+        let targets: Vec<Vec<[f32; 3]>> = reader
+            .read_morph_targets()
+            .map(|(deltas, _, _)| match deltas {
+                Some(deltas) => deltas.collect(),
+                None => vec![[0.0; 3]; vert_count],
+            })
+            .collect();
+        prim_morph_deltas.push((vert_count, targets));
+
         // Read UV coordinates
         if let Some(tex_coords) = reader.read_tex_coords(0) {
             let uv_vec: Vec<[f32; 2]> = tex_coords.into_f32().collect();
@@ -366,31 +470,40 @@ fn load_glb_with_colors(
     }
 
     // Load texture image data
-    let texture = texture_image_index.and_then(|idx| {
-        images.get(idx).map(|img| {
-            let rgba_pixels = match img.format {
-                gltf::image::Format::R8G8B8A8 => img.pixels.clone(),
-                gltf::image::Format::R8G8B8 => {
-                    // Convert RGB to RGBA
-                    let mut rgba = Vec::with_capacity(img.pixels.len() / 3 * 4);
-                    for chunk in img.pixels.chunks(3) {
-                        rgba.extend_from_slice(chunk);
-                        rgba.push(255);
-                    }
-                    rgba
-                }
-                _ => {
-                    eprintln!("Warning: unsupported texture format {:?}, skipping", img.format);
-                    return TextureData { pixels: Vec::new(), width: 0, height: 0 };
-                }
-            };
-            TextureData {
-                pixels: rgba_pixels,
-                width: img.width,
-                height: img.height,
-            }
+    let texture = texture_image_index.and_then(|idx| decode_texture(&images, idx));
+
+    // --- Morph targets ---
+    // Primitives without a target contribute zero deltas for it
+    let target_count = prim_morph_deltas.iter().map(|(_, t)| t.len()).max().unwrap_or(0);
+    let target_names = morph_target_names(selected_mesh);
+    let morph_targets: Vec<MorphTarget> = (0..target_count)
+        .map(|t| MorphTarget {
+            name: target_names.get(t).cloned(),
+            position_deltas: prim_morph_deltas
+                .iter()
+                .flat_map(|(count, targets)| match targets.get(t) {
+                    Some(deltas) => deltas.clone(),
+                    None => vec![[0.0; 3]; *count],
+                })
+                .collect(),
         })
-    }).filter(|t| !t.pixels.is_empty());
+        .collect();
+
+    // Node weights override the mesh defaults
+    let mesh_node = document
+        .nodes()
+        .find(|node| node.mesh().is_some_and(|m| m.index() == selected_mesh.index()));
+    let mut morph_weights: Vec<f32> = mesh_node
+        .as_ref()
+        .and_then(|node| node.weights())
+        .or_else(|| selected_mesh.weights())
+        .map(|w| w.to_vec())
+        .unwrap_or_default();
+    morph_weights.resize(target_count, 0.0);
+
+    if target_count > 0 {
+        progress!("Loaded {} morph target(s)", target_count);
+    }
 
     // --- Skin data extraction ---
     // Find the node that binds the selected mesh to a skin
@@ -409,14 +522,16 @@ fn load_glb_with_colors(
     let joint_node_indices: Option<Vec<usize>> = skeleton.as_ref().map(|s| {
         s.joints.iter().map(|j| j.node_index).collect()
     });
+    let morph_node_index = mesh_node.filter(|_| target_count > 0).map(|node| node.index());
     let animations = animation::extract_animations(
         &document,
         &buffers,
         joint_node_indices.as_deref(),
+        morph_node_index,
     );
 
     if !animations.is_empty() {
-        println!(
+        progress!(
             "Loaded {} animation clip(s): {}",
             animations.len(),
             animations
@@ -428,7 +543,7 @@ fn load_glb_with_colors(
     }
 
     if let Some(ref skel) = skeleton {
-        println!(
+        progress!(
             "Loaded skeleton with {} joints, {} skinned vertices",
             skel.joints.len(),
             joint_indices.len(),
@@ -439,12 +554,179 @@ fn load_glb_with_colors(
         positions,
         face_indices,
         face_colors,
+        vertex_colors: Vec::new(),
+        normals: Vec::new(),
         texcoords,
+        corner_texcoords: Vec::new(),
+        corner_normals: Vec::new(),
         texture,
         skeleton,
         joint_indices,
         joint_weights,
         animations,
+        morph_targets,
+        morph_weights,
+    })
+}
+
+/// Morph target names from the conventional `extras.targetNames` array
+fn morph_target_names(mesh: &gltf::Mesh) -> Vec<String> {
+    mesh.extras()
+        .as_ref()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw.get()).ok())
+        .and_then(|extras| {
+            extras["targetNames"].as_array().map(|names| {
+                names
+                    .iter()
+                    .map(|n| n.as_str().unwrap_or_default().to_string())
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Convert a decoded glTF image to RGBA texture data
+fn decode_texture(images: &[gltf::image::Data], idx: usize) -> Option<TextureData> {
+    let img = images.get(idx)?;
+    let pixels = match img.format {
+        gltf::image::Format::R8G8B8A8 => img.pixels.clone(),
+        gltf::image::Format::R8G8B8 => {
+            // Convert RGB to RGBA
+            let mut rgba = Vec::with_capacity(img.pixels.len() / 3 * 4);
+            for chunk in img.pixels.chunks(3) {
+                rgba.extend_from_slice(chunk);
+                rgba.push(255);
+            }
+            rgba
+        }
+        _ => {
+            eprintln!("Warning: unsupported texture format {:?}, skipping", img.format);
+            return None;
+        }
+    };
+    Some(TextureData {
+        pixels,
+        width: img.width,
+        height: img.height,
+    })
+}
+
+/// Load every mesh instance in a glTF scene, baking node world transforms
+/// into the vertex positions and merging them into one mesh
+fn load_gltf_scene(
+    path: &PathBuf,
+    scene_index: Option<usize>,
+) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let (document, buffers, images) = gltf_compression::import(path)?;
+
+    let scene_count = document.scenes().len();
+    let scene = match scene_index {
+        Some(i) => document.scenes().nth(i).ok_or_else(|| {
+            format!("Scene {} not found (file has {} scene(s))", i, scene_count)
+        })?,
+        None => document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+            .ok_or("glTF file contains no scenes")?,
+    };
+
+    // Collect (mesh, world transform) for every node that references a mesh
+    let mut instances: Vec<(gltf::Mesh, Matrix4<f32>)> = Vec::new();
+    let mut stack: Vec<(gltf::Node, Matrix4<f32>)> =
+        scene.nodes().map(|n| (n, Matrix4::identity())).collect();
+    let mut has_skin = false;
+    while let Some((node, parent)) = stack.pop() {
+        let world = parent * Matrix4::from(node.transform().matrix());
+        if let Some(mesh) = node.mesh() {
+            // Skinned meshes ignore their node transform (glTF 2.0 §3.7.3.3)
+            if node.skin().is_some() {
+                has_skin = true;
+                instances.push((mesh, Matrix4::identity()));
+            } else {
+                instances.push((mesh, world));
+            }
+        }
+        stack.extend(node.children().map(|c| (c, world)));
+    }
+    if instances.is_empty() {
+        return Err(format!("Scene {} contains no meshes", scene.index()).into());
+    }
+
+    progress!(
+        "Loading scene {}: {} ({} mesh instance(s))",
+        scene.index(),
+        scene.name().unwrap_or("<unnamed>"),
+        instances.len()
+    );
+    if has_skin || document.animations().next().is_some() {
+        eprintln!("Warning: skins and animations are not applied when loading a whole scene");
+    }
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut texcoords: Vec<[f32; 2]> = Vec::new();
+    let mut face_indices: Vec<[u32; 3]> = Vec::new();
+    let mut face_colors: Vec<[f32; 4]> = Vec::new();
+    let mut texture_image_index: Option<usize> = None;
+
+    for (mesh, world) in &instances {
+        // Mirroring transforms flip the triangle winding
+        let flip = world.fixed_view::<3, 3>(0, 0).determinant() < 0.0;
+
+        for primitive in mesh.primitives() {
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let vertex_offset = positions.len() as u32;
+
+            let prim_positions = reader
+                .read_positions()
+                .ok_or_else(|| gltf_compression::missing_positions_error(&primitive))?;
+            positions.extend(prim_positions.map(|p| {
+                let v = world.transform_point(&Point3::from(p));
+                [v.x, v.y, v.z]
+            }));
+            let vert_count = positions.len() - vertex_offset as usize;
+
+            match reader.read_tex_coords(0) {
+                Some(tex_coords) => texcoords.extend(tex_coords.into_f32()),
+                None => texcoords.extend(std::iter::repeat_n([0.0f32; 2], vert_count)),
+            }
+
+            let pbr = primitive.material().pbr_metallic_roughness();
+            let base_color_factor = pbr.base_color_factor();
+            if texture_image_index.is_none()
+                && let Some(tex_info) = pbr.base_color_texture()
+            {
+                texture_image_index = Some(tex_info.texture().source().index());
+            }
+
+            let idx_list: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..vert_count as u32).collect(),
+            };
+            for chunk in idx_list.chunks_exact(3) {
+                let [a, b, c] = [chunk[0], chunk[1], chunk[2]].map(|i| i + vertex_offset);
+                face_indices.push(if flip { [a, c, b] } else { [a, b, c] });
+                face_colors.push(base_color_factor);
+            }
+        }
+    }
+
+    // Keep face colors only when the materials actually differ
+    if face_colors.iter().all(|c| *c == face_colors[0]) {
+        face_colors.clear();
+    }
+    if texcoords.iter().all(|uv| uv[0] == 0.0 && uv[1] == 0.0) {
+        texcoords.clear();
+    }
+
+    let texture = texture_image_index.and_then(|idx| decode_texture(&images, idx));
+
+    Ok(MeshWithColors {
+        positions,
+        face_indices,
+        face_colors,
+        texcoords,
+        texture,
+        ..Default::default()
     })
 }
 
@@ -555,203 +837,137 @@ fn extract_skin_data(
     Ok((Some(skeleton), all_joint_indices, all_joint_weights))
 }
 
-// --- 3MF loading ---
-
-/// Parse a #RRGGBB or #RRGGBBAA hex color string to [f32; 4]
-fn parse_hex_color(s: &str) -> Option<[f32; 4]> {
-    let s = s.strip_prefix('#')?;
-    if s.len() != 6 && s.len() != 8 {
-        return None;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One triangle mesh instanced by a translated parent/child pair and a mirrored node
+    fn write_scene_gltf(dir: &std::path::Path) -> PathBuf {
+        let positions: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let bytes: Vec<u8> = positions.iter().flatten().flat_map(|v| v.to_le_bytes()).collect();
+        std::fs::write(dir.join("scene.bin"), &bytes).unwrap();
+
+        let json = r#"{
+            "asset": {"version": "2.0"},
+            "buffers": [{"uri": "scene.bin", "byteLength": 36}],
+            "bufferViews": [{"buffer": 0, "byteLength": 36}],
+            "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                           "min": [0, 0, 0], "max": [1, 1, 0]}],
+            "meshes": [{"primitives": [{"attributes": {"POSITION": 0}}]}],
+            "nodes": [
+                {"translation": [10, 0, 0], "children": [1]},
+                {"translation": [0, 5, 0], "mesh": 0},
+                {"scale": [-1, 1, 1], "mesh": 0}
+            ],
+            "scenes": [{"nodes": [0]}, {"nodes": [0, 2]}],
+            "scene": 1
+        }"#;
+        let path = dir.join("scene.gltf");
+        std::fs::write(&path, json).unwrap();
+        path
     }
-    let r = u8::from_str_radix(&s[0..2], 16).ok()? as f32 / 255.0;
-    let g = u8::from_str_radix(&s[2..4], 16).ok()? as f32 / 255.0;
-    let b = u8::from_str_radix(&s[4..6], 16).ok()? as f32 / 255.0;
-    let a = if s.len() == 8 {
-        u8::from_str_radix(&s[6..8], 16).ok()? as f32 / 255.0
-    } else {
-        1.0
-    };
-    Some([r, g, b, a])
-}
 
-/// Load a .3mf file with per-triangle colors
-fn load_3mf_with_colors(path: &PathBuf) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
-    use quick_xml::events::Event;
-    use quick_xml::Reader;
-    use std::io::Read;
-
-    let file = std::fs::File::open(path)?;
-    let mut archive = zip::ZipArchive::new(file)?;
-
-    // Find the model file (typically 3D/3dmodel.model)
-    let model_name = (0..archive.len())
-        .filter_map(|i| {
-            let entry = archive.by_index(i).ok()?;
-            let name = entry.name().to_string();
-            if name.ends_with(".model") {
-                Some(name)
-            } else {
-                None
-            }
-        })
-        .next()
-        .ok_or("No .model file found in 3MF archive")?;
+    #[test]
+    fn scene_bakes_node_transforms() {
+        let dir = std::env::temp_dir().join(format!("msh_scene_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = write_scene_gltf(&dir);
+
+        let first = load_gltf_scene(&path, Some(0)).unwrap();
+        let default = load_gltf_scene(&path, None).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(first.positions, vec![[10.0, 5.0, 0.0], [11.0, 5.0, 0.0], [10.0, 6.0, 0.0]]);
+        assert_eq!(default.face_indices.len(), 2);
+        assert!(default.positions.contains(&[-1.0, 0.0, 0.0]));
+
+        // The mirrored instance has its winding flipped to keep facing outward
+        let mirrored = default
+            .face_indices
+            .iter()
+            .find(|f| f.iter().any(|&i| default.positions[i as usize] == [-1.0, 0.0, 0.0]))
+            .unwrap();
+        let [a, b, c] = mirrored.map(|i| default.positions[i as usize]);
+        let normal_z = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        assert!(normal_z > 0.0);
+    }
 
-    let mut model_file = archive.by_name(&model_name)?;
-    let mut xml_content = String::new();
-    model_file.read_to_string(&mut xml_content)?;
+    #[test]
+    fn z_up_and_scale_keep_skinned_poses_consistent() {
+        use crate::mesh::animation::{AnimationChannel, Interpolation, Keyframe};
 
-    // Parse XML
-    let mut reader = Reader::from_str(&xml_content);
+        let rest = |translation| JointTransform::Decomposed {
+            translation,
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0, 2.0, 3.0],
+        };
+        let ibm = Matrix4::<f32>::new_nonuniform_scaling(&nalgebra::Vector3::new(1.0, 0.5, 1.0 / 3.0))
+            * Matrix4::new_translation(&nalgebra::Vector3::new(0.0, 0.0, -1.0));
+        let quarter = std::f32::consts::FRAC_1_SQRT_2;
+        let mut mesh = MeshWithColors {
+            positions: vec![[0.5, 0.0, 1.5]],
+            joint_indices: vec![[1, 0, 0, 0]],
+            joint_weights: vec![[1.0, 0.0, 0.0, 0.0]],
+            skeleton: Some(Skeleton {
+                joints: vec![
+                    Joint {
+                        index: 0,
+                        node_index: 0,
+                        name: None,
+                        parent: None,
+                        inverse_bind_matrix: Matrix4::<f32>::identity().into(),
+                        local_transform: rest([0.0; 3]),
+                    },
+                    Joint {
+                        index: 1,
+                        node_index: 1,
+                        name: None,
+                        parent: Some(0),
+                        inverse_bind_matrix: ibm.into(),
+                        local_transform: rest([0.0, 0.0, 1.0]),
+                    },
+                ],
+            }),
+            animations: vec![AnimationClip {
+                name: None,
+                channels: vec![AnimationChannel {
+                    joint_index: 1,
+                    property: AnimationProperty::Rotation,
+                    interpolation: Interpolation::Linear,
+                    keyframes: vec![Keyframe {
+                        time: 0.0,
+                        value: vec![0.0, quarter, 0.0, quarter],
+                        in_tangent: None,
+                        out_tangent: None,
+                    }],
+                }],
+                duration: 0.0,
+            }],
+            ..Default::default()
+        };
+        let posed = |mesh: &MeshWithColors| {
+            let skeleton = mesh.skeleton.as_ref().unwrap();
+            let locals = animation::evaluate_animation(&mesh.animations[0], skeleton, 0.0);
+            let joint = Matrix4::from(skeleton.compute_joint_matrices_with_pose(&locals)[1]);
+            let p = mesh.positions[0];
+            joint.transform_point(&Point3::new(p[0], p[1], p[2]))
+        };
 
-    let mut positions: Vec<[f32; 3]> = Vec::new();
-    let mut face_indices: Vec<[u32; 3]> = Vec::new();
-    let mut face_colors: Vec<[f32; 4]> = Vec::new();
-    let mut has_colors = false;
-
-    // Color groups: group_id -> Vec<[f32; 4]> (indexed by position in group)
-    let mut color_groups: HashMap<String, Vec<[f32; 4]>> = HashMap::new();
-    let mut current_color_group_id: Option<String> = None;
-
-    // Object-level default: pid (color group id) + pindex (index within group)
-    let mut default_pid: Option<String> = None;
-    let mut default_pindex: Option<usize> = None;
-
-    loop {
-        match reader.read_event() {
-            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e)) => {
-                let ln = e.local_name();
-                let local_name = std::str::from_utf8(ln.as_ref()).unwrap_or("");
-                match local_name {
-                    "vertex" => {
-                        let mut x = 0.0f32;
-                        let mut y = 0.0f32;
-                        let mut z = 0.0f32;
-                        for attr in e.attributes().flatten() {
-                            let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                            let val = std::str::from_utf8(&attr.value).unwrap_or("");
-                            match key {
-                                "x" => x = val.parse().unwrap_or(0.0),
-                                "y" => y = val.parse().unwrap_or(0.0),
-                                "z" => z = val.parse().unwrap_or(0.0),
-                                _ => {}
-                            }
-                        }
-                        positions.push([x, y, z]);
-                    }
-                    "triangle" => {
-                        let mut v1 = 0u32;
-                        let mut v2 = 0u32;
-                        let mut v3 = 0u32;
-                        let mut pid: Option<String> = None;
-                        let mut pindex: Option<usize> = None;
-                        for attr in e.attributes().flatten() {
-                            let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                            let val = std::str::from_utf8(&attr.value).unwrap_or("");
-                            match key {
-                                "v1" => v1 = val.parse().unwrap_or(0),
-                                "v2" => v2 = val.parse().unwrap_or(0),
-                                "v3" => v3 = val.parse().unwrap_or(0),
-                                "pid" => pid = Some(val.to_string()),
-                                "p1" => pindex = val.parse().ok(),
-                                _ => {}
-                            }
-                        }
-                        face_indices.push([v1, v2, v3]);
-
-                        // Resolve color: triangle-level pid/p1, or object-level default
-                        let use_pid = pid.as_ref().or(default_pid.as_ref());
-                        let use_pindex = pindex.or(default_pindex);
-
-                        let color = use_pid
-                            .and_then(|pid| color_groups.get(pid))
-                            .and_then(|group| {
-                                use_pindex.and_then(|idx| group.get(idx).copied())
-                            });
-
-                        if let Some(c) = color {
-                            has_colors = true;
-                            face_colors.push(c);
-                        } else {
-                            face_colors.push([0.85, 0.85, 0.85, 1.0]);
-                        }
-                    }
-                    "colorgroup" | "m:colorgroup" => {
-                        for attr in e.attributes().flatten() {
-                            let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                            if key == "id" {
-                                let val = std::str::from_utf8(&attr.value).unwrap_or("");
-                                current_color_group_id = Some(val.to_string());
-                                color_groups.entry(val.to_string()).or_default();
-                            }
-                        }
-                    }
-                    "color" | "m:color" => {
-                        if let Some(ref group_id) = current_color_group_id {
-                            for attr in e.attributes().flatten() {
-                                let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                                if key == "color" {
-                                    let val = std::str::from_utf8(&attr.value).unwrap_or("");
-                                    if let Some(rgba) = parse_hex_color(val) {
-                                        color_groups
-                                            .get_mut(group_id)
-                                            .unwrap()
-                                            .push(rgba);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    "object" => {
-                        // Check for default pid/pindex on the object element
-                        for attr in e.attributes().flatten() {
-                            let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                            let val = std::str::from_utf8(&attr.value).unwrap_or("");
-                            match key {
-                                "pid" => default_pid = Some(val.to_string()),
-                                "pindex" => default_pindex = val.parse().ok(),
-                                _ => {}
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            Ok(Event::End(ref e)) => {
-                let ln = e.local_name();
-                let local_name = std::str::from_utf8(ln.as_ref()).unwrap_or("");
-                if local_name == "colorgroup" || local_name == "m:colorgroup" {
-                    current_color_group_id = None;
-                }
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(format!("XML parse error: {}", e).into()),
-            _ => {}
-        }
+        let before = posed(&mesh);
+        mesh.convert_z_up_to_y_up();
+        mesh.scale_uniform(2.0);
+        let after = posed(&mesh);
+        let expected = Point3::new(before.x, before.z, -before.y) * 2.0;
+        assert!((after - expected).norm() < 1e-5, "{:?} != {:?}", after, expected);
     }
 
-    if !has_colors {
-        face_colors.clear();
+    #[test]
+    fn scene_rejects_missing_index() {
+        let dir = std::env::temp_dir().join(format!("msh_scene_missing_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = write_scene_gltf(&dir);
+        let result = load_gltf_scene(&path, Some(7));
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(result.is_err());
     }
-
-    Ok(MeshWithColors {
-        positions,
-        face_indices,
-        face_colors,
-        texcoords: Vec::new(),
-        texture: None,
-        skeleton: None,
-        joint_indices: Vec::new(),
-        joint_weights: Vec::new(),
-        animations: Vec::new(),
-    })
-}
-
-/// Load mesh from GLB/glTF file (CornerTableF only, for processing)
-pub fn load_mesh_from_glb(
-    path: &PathBuf,
-    mesh_name: Option<&str>,
-) -> Result<CornerTableF, Box<dyn std::error::Error>> {
-    let mesh_data = load_glb_with_colors(path, mesh_name)?;
-    mesh_data.to_corner_table()
 }
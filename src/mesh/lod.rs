@@ -0,0 +1,106 @@
+//! Level-of-detail chains built with quadric edge-collapse decimation.
+//!
+//! Every level is decimated from the input mesh (not from the previous level),
+//! so errors don't accumulate down the chain. Skin, morph targets and
+//! animations are carried by each level, as [`decimate`] keeps them.
+
+use super::decimate::{self, DecimateOptions, DecimateReport, DecimateTarget};
+use super::loader::MeshWithColors;
+
+/// Screen coverage at which the full-detail mesh is needed
+const FULL_DETAIL_COVERAGE: f32 = 0.5;
+
+/// Options controlling LOD generation
+#[derive(Clone, Debug)]
+pub struct LodOptions {
+    /// Fraction of the input's faces kept by each level, finest first
+    pub ratios: Vec<f32>,
+    /// Keep boundary vertices in place
+    pub lock_boundary: bool,
+    /// Keep UV seams intact
+    pub preserve_seams: bool,
+    /// Keep borders between face colors intact
+    pub preserve_color_borders: bool,
+}
+
+/// One level of a LOD chain
+pub struct LodLevel {
+    pub mesh: MeshWithColors,
+    pub ratio: f32,
+    /// Decimation statistics; `None` for a level that keeps the input as is
+    pub report: Option<DecimateReport>,
+}
+
+/// Ratios halving the face count at each level: 1, 0.5, 0.25, ...
+pub fn default_ratios(levels: usize) -> Vec<f32> {
+    (0..levels).map(|i| 0.5f32.powi(i as i32)).collect()
+}
+
+/// Minimum screen coverage of each level, for `MSFT_screencoverage`.
+///
+/// Face count scales with projected area, so a level keeping a fraction `r` of
+/// the faces matches full-detail density at `r` times the full-detail
+/// coverage; each level hands over to the next there. The last level is
+/// never culled.
+pub fn screen_coverage(ratios: &[f32]) -> Vec<f32> {
+    let mut coverage: Vec<f32> = ratios.iter().skip(1).map(|r| FULL_DETAIL_COVERAGE * r).collect();
+    if !ratios.is_empty() {
+        coverage.push(0.0);
+    }
+    coverage
+}
+
+/// Build the LOD chain described by `options.ratios`
+pub fn build_lods(mesh: &MeshWithColors, options: &LodOptions) -> Result<Vec<LodLevel>, Box<dyn std::error::Error>> {
+    if options.ratios.is_empty() {
+        return Err("A LOD chain needs at least one level".into());
+    }
+    for &ratio in &options.ratios {
+        if !(ratio > 0.0 && ratio <= 1.0) {
+            return Err(format!("LOD ratio {} is outside (0, 1]", ratio).into());
+        }
+    }
+    if options.ratios.windows(2).any(|w| w[1] > w[0]) {
+        return Err("LOD ratios must not increase from one level to the next".into());
+    }
+
+    let mut levels = Vec::with_capacity(options.ratios.len());
+    for &ratio in &options.ratios {
+        if ratio >= 1.0 {
+            levels.push(LodLevel { mesh: mesh.clone(), ratio, report: None });
+            continue;
+        }
+        let decimate_options = DecimateOptions {
+            target: DecimateTarget::Ratio(ratio),
+            lock_boundary: options.lock_boundary,
+            preserve_seams: options.preserve_seams,
+            preserve_color_borders: options.preserve_color_borders,
+        };
+        let (decimated, report) = decimate::decimate(mesh, &decimate_options)?;
+        levels.push(LodLevel { mesh: decimated, ratio, report: Some(report) });
+    }
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_hands_over_at_the_next_ratio() {
+        assert_eq!(default_ratios(4), vec![1.0, 0.5, 0.25, 0.125]);
+        assert_eq!(screen_coverage(&[1.0, 0.5, 0.25, 0.1]), vec![0.25, 0.125, 0.05, 0.0]);
+        assert_eq!(screen_coverage(&[1.0]), vec![0.0]);
+    }
+
+    #[test]
+    fn rejects_increasing_ratios() {
+        let options = LodOptions {
+            ratios: vec![1.0, 0.25, 0.5],
+            lock_boundary: false,
+            preserve_seams: false,
+            preserve_color_borders: false,
+        };
+        assert!(build_lods(&MeshWithColors::default(), &options).is_err());
+    }
+}
@@ -1,11 +1,44 @@
 pub mod animation;
 pub mod bvh;
 pub mod bvh_mapping;
+pub mod collada;
+pub mod components;
+pub mod decimate;
+pub mod draco;
+pub mod fbx;
+pub mod format;
+pub mod gltf_compression;
+pub mod gltf_writer;
+pub mod holes;
+pub mod intersect;
 pub mod loader;
+pub mod lod;
+pub mod obj;
+pub mod orient;
+pub mod ply;
 pub mod processing;
 pub mod skeleton;
+pub mod smooth;
+pub mod stdio;
+pub mod stl;
+pub mod subdivide;
+pub mod threemf;
+pub mod topology;
+pub mod transfer;
+pub mod usd;
+pub mod writer;
 
 pub use processing::{
-    check_manifold, fix_holes, remesh_incremental, remesh_pipeline,
-    remesh_voxel, show_stats, VoxelMethod,
+    check_manifold, components, convert, decimate, fix_holes, FixMethod, lod, orient, remesh_incremental, remesh_pipeline,
+    remesh_voxel, show_stats, smooth, subdivide, VoxelMethod,
 };
+pub use components::ComponentFilter;
+pub use decimate::{DecimateOptions, DecimateTarget};
+pub use format::MeshFormat;
+pub use holes::{HoleFillOptions, HoleTriangulation};
+pub use loader::LoadOptions;
+pub use lod::LodOptions;
+pub use smooth::{SmoothMethod, SmoothOptions};
+pub use subdivide::{SubdivideOptions, SubdivisionScheme};
+pub use threemf::ModelUnit;
+pub use writer::WriteOptions;
@@ -0,0 +1,515 @@
+//! Wavefront OBJ reader and writer with MTL materials.
+//!
+//! Reading handles `v`/`vt`/`vn`, polygon faces in every `v/vt/vn` form
+//! (fan-triangulated), negative (relative) indices, `o`/`g` groups that can be
+//! selected by name, and MTL `Kd`/`d`/`map_Kd`. UVs and normals are kept per
+//! corner since OBJ indexes them separately from positions.
+//!
+//! Writing produces `v`/`vt`/`vn`, one `usemtl` group per distinct face color
+//! and a `.mtl` file with `Kd`/`d`. An embedded texture is saved as a PNG next
+//! to the OBJ and referenced via `map_Kd`. UVs use OBJ's bottom-left origin,
+//! so V is flipped relative to the glTF convention used internally.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::loader::{MeshWithColors, TextureData};
+use super::stdio::{self, progress};
+
+/// Color used for faces without a material
+const DEFAULT_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+
+/// A material parsed from an MTL file
+struct ObjMaterial {
+    color: [f32; 4],
+    texture: Option<PathBuf>,
+}
+
+/// One face corner: position index plus optional UV and normal indices (0-based)
+type Corner = (u32, Option<usize>, Option<usize>);
+
+struct ObjFace {
+    corners: [Corner; 3],
+    material: Option<String>,
+    /// Index into the list of `o`/`g` names the face belongs to
+    groups: Vec<usize>,
+}
+
+/// Resolve a 1-based or negative OBJ index against the current element count.
+/// Invalid indices resolve to None.
+fn resolve_index(token: &str, count: usize) -> Option<usize> {
+    let i: i64 = token.parse().ok()?;
+    let resolved = if i > 0 { i - 1 } else { count as i64 + i };
+    (i != 0 && resolved >= 0).then_some(resolved as usize)
+}
+
+/// Load an OBJ file, optionally restricted to the `o`/`g` group named `mesh_name`
+pub fn load_obj(path: &Path, mesh_name: Option<&str>) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let content = stdio::read_to_string(path)?;
+    let parent_dir = path.parent().unwrap_or(Path::new("."));
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut faces: Vec<ObjFace> = Vec::new();
+    let mut materials: HashMap<String, ObjMaterial> = HashMap::new();
+
+    let mut group_names: Vec<String> = Vec::new();
+    let mut current_object: Option<usize> = None;
+    let mut current_group: Option<usize> = None;
+    let mut current_material: Option<String> = None;
+
+    let mut intern_group = |name: &str| match group_names.iter().position(|g| g == name) {
+        Some(i) => i,
+        None => {
+            group_names.push(name.to_string());
+            group_names.len() - 1
+        }
+    };
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let floats = || -> Vec<f32> { rest.split_whitespace().filter_map(|s| s.parse().ok()).collect() };
+
+        match keyword {
+            "v" => {
+                let p = floats();
+                if p.len() >= 3 {
+                    positions.push([p[0], p[1], p[2]]);
+                }
+            }
+            "vt" => {
+                let t = floats();
+                let u = t.first().copied().unwrap_or(0.0);
+                let v = t.get(1).copied().unwrap_or(0.0);
+                uvs.push([u, 1.0 - v]);
+            }
+            "vn" => {
+                let n = floats();
+                if n.len() >= 3 {
+                    normals.push([n[0], n[1], n[2]]);
+                }
+            }
+            "o" => {
+                current_object = Some(intern_group(rest));
+                current_group = None;
+            }
+            "g" => current_group = (!rest.is_empty()).then(|| intern_group(rest)),
+            "usemtl" => current_material = Some(rest.to_string()),
+            "mtllib" => {
+                let mtl_path = parent_dir.join(rest);
+                if mtl_path.exists() {
+                    match parse_mtl(&mtl_path) {
+                        Ok(mtls) => materials.extend(mtls),
+                        Err(e) => eprintln!("Warning: failed to parse MTL file {:?}: {}", mtl_path, e),
+                    }
+                }
+            }
+            "f" => {
+                let corners: Vec<Corner> = rest
+                    .split_whitespace()
+                    .map(|token| {
+                        let mut parts = token.split('/');
+                        let v = parts
+                            .next()
+                            .and_then(|s| resolve_index(s, positions.len()))
+                            .map(|i| i as u32)
+                            .unwrap_or(u32::MAX);
+                        let vt = parts.next().and_then(|s| resolve_index(s, uvs.len()));
+                        let vn = parts.next().and_then(|s| resolve_index(s, normals.len()));
+                        (v, vt, vn)
+                    })
+                    .collect();
+
+                // Fan triangulation for n-gons
+                for i in 1..corners.len().saturating_sub(1) {
+                    faces.push(ObjFace {
+                        corners: [corners[0], corners[i], corners[i + 1]],
+                        material: current_material.clone(),
+                        groups: current_object.into_iter().chain(current_group).collect(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(name) = mesh_name {
+        let Some(selected) = group_names.iter().position(|g| g == name) else {
+            return Err(format!(
+                "Mesh '{}' not found in OBJ file.\nAvailable meshes: {}",
+                name,
+                group_names.join(", ")
+            )
+            .into());
+        };
+        faces.retain(|f| f.groups.contains(&selected));
+        progress!("Loading mesh: {}", name);
+    }
+
+    // Compact positions to the vertices used by the selected faces
+    let mut position_map: Vec<u32> = vec![u32::MAX; positions.len()];
+    let mut mesh = MeshWithColors::default();
+    let remap = |vi: u32, mesh: &mut MeshWithColors, map: &mut Vec<u32>| -> u32 {
+        let Some(slot) = map.get_mut(vi as usize) else {
+            return u32::MAX; // Out-of-range index, kept for validation
+        };
+        if *slot == u32::MAX {
+            *slot = mesh.positions.len() as u32;
+            mesh.positions.push(positions[vi as usize]);
+        }
+        *slot
+    };
+    if mesh_name.is_some() {
+        for face in &faces {
+            let tri = face.corners.map(|c| remap(c.0, &mut mesh, &mut position_map));
+            mesh.face_indices.push(tri);
+        }
+    } else {
+        mesh.positions = positions;
+        mesh.face_indices = faces.iter().map(|f| f.corners.map(|c| c.0)).collect();
+    }
+
+    let any_uv = faces.iter().any(|f| f.corners.iter().any(|c| c.1.is_some_and(|i| i < uvs.len())));
+    if any_uv {
+        mesh.corner_texcoords = faces
+            .iter()
+            .map(|f| f.corners.map(|c| c.1.and_then(|i| uvs.get(i).copied()).unwrap_or([0.0; 2])))
+            .collect();
+    }
+    let any_normal = faces.iter().any(|f| f.corners.iter().any(|c| c.2.is_some_and(|i| i < normals.len())));
+    if any_normal {
+        mesh.corner_normals = faces
+            .iter()
+            .map(|f| f.corners.map(|c| c.2.and_then(|i| normals.get(i).copied()).unwrap_or([0.0; 3])))
+            .collect();
+    }
+
+    if !materials.is_empty() {
+        mesh.face_colors = faces
+            .iter()
+            .map(|f| {
+                f.material
+                    .as_ref()
+                    .and_then(|m| materials.get(m))
+                    .map(|m| m.color)
+                    .unwrap_or(DEFAULT_COLOR)
+            })
+            .collect();
+    }
+
+    // Use the first texture referenced by a material in use
+    let texture_path = faces
+        .iter()
+        .filter_map(|f| f.material.as_ref().and_then(|m| materials.get(m)))
+        .find_map(|m| m.texture.clone());
+    if let Some(texture_path) = texture_path {
+        match image::open(&texture_path) {
+            Ok(img) => {
+                let rgba = img.to_rgba8();
+                mesh.texture = Some(TextureData {
+                    width: rgba.width(),
+                    height: rgba.height(),
+                    pixels: rgba.into_raw(),
+                });
+            }
+            Err(e) => eprintln!("Warning: failed to load texture {:?}: {}", texture_path, e),
+        }
+    }
+
+    Ok(mesh)
+}
+
+/// Parse an MTL file, returning material name → color and diffuse texture
+fn parse_mtl(path: &Path) -> Result<HashMap<String, ObjMaterial>, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path)?;
+    let mtl_dir = path.parent().unwrap_or(Path::new("."));
+    let mut materials = HashMap::new();
+    let mut current_name: Option<String> = None;
+    let mut current = ObjMaterial { color: DEFAULT_COLOR, texture: None };
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match keyword {
+            "newmtl" => {
+                // Save previous material
+                if let Some(prev_name) = current_name.take() {
+                    materials.insert(prev_name, current);
+                }
+                current_name = Some(rest.to_string());
+                current = ObjMaterial { color: DEFAULT_COLOR, texture: None };
+            }
+            "Kd" => {
+                let parts: Vec<f32> = rest
+                    .split_whitespace()
+                    .filter_map(|s| s.parse().ok())
+                    .collect();
+                if parts.len() >= 3 {
+                    current.color = [parts[0], parts[1], parts[2], current.color[3]];
+                }
+            }
+            "d" => {
+                if let Ok(alpha) = rest.parse::<f32>() {
+                    current.color[3] = alpha;
+                }
+            }
+            "Tr" => {
+                if let Ok(transparency) = rest.parse::<f32>() {
+                    current.color[3] = 1.0 - transparency;
+                }
+            }
+            "map_Kd" => {
+                // Options such as `-s 1 1 1` may precede the file name
+                if let Some(file) = rest.split_whitespace().last() {
+                    current.texture = Some(mtl_dir.join(file));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Save last material
+    if let Some(name) = current_name {
+        materials.insert(name, current);
+    }
+
+    Ok(materials)
+}
+
+/// Deduplicated attribute values and, per face corner, the index of its value
+type IndexedAttribute<const N: usize> = (Vec<[f32; N]>, Vec<[u32; 3]>);
+
+/// Index an attribute for `vt`/`vn` output, preferring per-corner values
+fn index_attribute<const N: usize>(
+    mesh: &MeshWithColors,
+    per_vertex: &[[f32; N]],
+    per_corner: &[[[f32; N]; 3]],
+) -> Option<IndexedAttribute<N>> {
+    if !per_corner.is_empty() && per_corner.len() == mesh.face_indices.len() {
+        let mut values: Vec<[f32; N]> = Vec::new();
+        let mut lookup: HashMap<[u32; N], u32> = HashMap::new();
+        let indices = per_corner
+            .iter()
+            .map(|corners| {
+                corners.map(|value| {
+                    *lookup.entry(value.map(f32::to_bits)).or_insert_with(|| {
+                        values.push(value);
+                        values.len() as u32 - 1
+                    })
+                })
+            })
+            .collect();
+        Some((values, indices))
+    } else if !per_vertex.is_empty() && per_vertex.len() == mesh.positions.len() {
+        Some((per_vertex.to_vec(), mesh.face_indices.clone()))
+    } else {
+        None
+    }
+}
+
+/// Write a mesh as OBJ, plus `<stem>.mtl` (and `<stem>.png` for a texture) when it has materials
+pub fn write_obj(mesh: &MeshWithColors, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let uvs = index_attribute(mesh, &mesh.texcoords, &mesh.corner_texcoords);
+    let normals = index_attribute(mesh, &mesh.normals, &mesh.corner_normals);
+    let has_face_colors =
+        !mesh.face_colors.is_empty() && mesh.face_colors.len() == mesh.face_indices.len();
+    let texture = mesh.texture.as_ref().filter(|t| !t.pixels.is_empty());
+
+    let dropped: Vec<&str> = [
+        (!mesh.vertex_colors.is_empty(), "vertex colors"),
+        (mesh.skeleton.is_some(), "skin"),
+        (!mesh.animations.is_empty(), "animations"),
+        (!mesh.morph_targets.is_empty(), "morph targets"),
+    ]
+    .into_iter()
+    .filter_map(|(present, name)| present.then_some(name))
+    .collect();
+    if !dropped.is_empty() {
+        eprintln!("Warning: OBJ output does not store {}; dropping", dropped.join(", "));
+    }
+
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid output file name")?;
+
+    // Group faces by material (first-seen order)
+    let mut materials: Vec<[f32; 4]> = Vec::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    if has_face_colors {
+        for (face_i, color) in mesh.face_colors.iter().enumerate() {
+            match materials.iter().position(|c| c == color) {
+                Some(m) => groups[m].push(face_i),
+                None => {
+                    materials.push(*color);
+                    groups.push(vec![face_i]);
+                }
+            }
+        }
+    } else {
+        if texture.is_some() {
+            materials.push([1.0; 4]);
+        }
+        groups.push((0..mesh.face_indices.len()).collect());
+    }
+
+    // --- Sidecar files ---
+    let texture_name = format!("{}.png", stem);
+    if let Some(tex) = texture {
+        let image = image::RgbaImage::from_raw(tex.width, tex.height, tex.pixels.clone())
+            .ok_or("Texture pixel data does not match its dimensions")?;
+        image.save_with_format(path.with_file_name(&texture_name), image::ImageFormat::Png)?;
+    }
+
+    let mtl_name = format!("{}.mtl", stem);
+    if !materials.is_empty() {
+        let mut mtl = String::from("# Material library written by msh\n");
+        for (i, color) in materials.iter().enumerate() {
+            let _ = writeln!(mtl, "\nnewmtl material_{}", i);
+            let _ = writeln!(mtl, "Kd {} {} {}", color[0], color[1], color[2]);
+            let _ = writeln!(mtl, "d {}", color[3]);
+            if texture.is_some() {
+                let _ = writeln!(mtl, "map_Kd {}", texture_name);
+            }
+        }
+        std::fs::write(path.with_file_name(&mtl_name), mtl)?;
+    }
+
+    // --- OBJ ---
+    let mut obj = String::from("# Written by msh\n");
+    if !materials.is_empty() {
+        let _ = writeln!(obj, "mtllib {}", mtl_name);
+    }
+    for p in &mesh.positions {
+        let _ = writeln!(obj, "v {} {} {}", p[0], p[1], p[2]);
+    }
+    if let Some((values, _)) = &uvs {
+        for uv in values {
+            let _ = writeln!(obj, "vt {} {}", uv[0], 1.0 - uv[1]);
+        }
+    }
+    if let Some((values, _)) = &normals {
+        for nrm in values {
+            let _ = writeln!(obj, "vn {} {} {}", nrm[0], nrm[1], nrm[2]);
+        }
+    }
+
+    let corner = |face_i: usize, k: usize| {
+        let v = mesh.face_indices[face_i][k] as u64 + 1;
+        let vt = uvs.as_ref().map(|(_, idx)| idx[face_i][k] + 1);
+        let vn = normals.as_ref().map(|(_, idx)| idx[face_i][k] + 1);
+        match (vt, vn) {
+            (Some(vt), Some(vn)) => format!("{}/{}/{}", v, vt, vn),
+            (Some(vt), None) => format!("{}/{}", v, vt),
+            (None, Some(vn)) => format!("{}//{}", v, vn),
+            (None, None) => v.to_string(),
+        }
+    };
+    for (m, faces) in groups.iter().enumerate() {
+        if !materials.is_empty() {
+            let _ = writeln!(obj, "usemtl material_{}", m);
+        }
+        for &face_i in faces {
+            let _ = writeln!(obj, "f {} {} {}", corner(face_i, 0), corner(face_i, 1), corner(face_i, 2));
+        }
+    }
+    std::fs::write(path, obj)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::loader::{load_mesh_with_colors, LoadOptions};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("msh_obj_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_uvs_normals_and_relative_indices() {
+        let dir = temp_dir("parse");
+        let path = dir.join("quad.obj");
+        std::fs::write(
+            &path,
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+             vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\nvn 0 0 1\n\
+             f -4/-4/-1 -3/-3/-1 -2/-2/-1 -1/-1/-1\n",
+        )
+        .unwrap();
+        let mesh = load_obj(&path, None).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2], [0, 2, 3]]);
+        // V is flipped into the glTF convention
+        assert_eq!(mesh.corner_texcoords[0], [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0]]);
+        assert_eq!(mesh.corner_normals[1], [[0.0, 0.0, 1.0]; 3]);
+        assert!(mesh.face_colors.is_empty());
+    }
+
+    #[test]
+    fn selects_groups_by_name() {
+        let dir = temp_dir("groups");
+        let path = dir.join("two.obj");
+        std::fs::write(
+            &path,
+            "o first\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n\
+             o second\nv 5 0 0\nv 6 0 0\nv 5 1 0\nf 4 5 6\n",
+        )
+        .unwrap();
+        let second = load_obj(&path, Some("second")).unwrap();
+        let missing = load_obj(&path, Some("third"));
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(second.positions, vec![[5.0, 0.0, 0.0], [6.0, 0.0, 0.0], [5.0, 1.0, 0.0]]);
+        assert_eq!(second.face_indices, vec![[0, 1, 2]]);
+        assert!(missing.is_err_and(|e| e.to_string().contains("first, second")));
+    }
+
+    #[test]
+    fn round_trips_materials_uvs_and_texture() {
+        let mesh = MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            face_indices: vec![[0, 1, 2], [0, 2, 3]],
+            face_colors: vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.5, 1.0, 0.25]],
+            normals: vec![[0.0, 0.0, 1.0]; 4],
+            texcoords: vec![[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]],
+            texture: Some(TextureData { pixels: vec![128; 4 * 4], width: 2, height: 2 }),
+            ..Default::default()
+        };
+        let dir = temp_dir("writer");
+        let path = dir.join("quad.obj");
+        write_obj(&mesh, &path).unwrap();
+
+        let obj = std::fs::read_to_string(&path).unwrap();
+        let mtl = std::fs::read_to_string(dir.join("quad.mtl")).unwrap();
+        assert!(obj.contains("mtllib quad.mtl"));
+        assert!(obj.contains("vt 0 0"));
+        assert!(obj.contains("f 1/1/1 2/2/2 3/3/3"));
+        assert!(mtl.contains("Kd 0 0.5 1\nd 0.25\nmap_Kd quad.png"));
+
+        let loaded = load_mesh_with_colors(&path, &LoadOptions::default()).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(loaded.positions, mesh.positions);
+        assert_eq!(loaded.face_indices, mesh.face_indices);
+        assert_eq!(loaded.face_colors, mesh.face_colors);
+        assert_eq!(loaded.corner_texcoords[1], [[0.0, 1.0], [1.0, 0.0], [0.0, 0.0]]);
+        let texture = loaded.texture.unwrap();
+        assert_eq!((texture.width, texture.height), (2, 2));
+    }
+}
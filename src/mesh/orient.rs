@@ -0,0 +1,319 @@
+//! Consistent face orientation.
+//!
+//! Winding is propagated breadth-first across manifold edges, so every face
+//! in a connected component agrees with its neighbours. Each component is then
+//! turned outward: its signed volume must be positive, unless a ray cast from
+//! it crosses the other closed components an odd number of times, in which
+//! case it is a cavity and faces inward. Vertices are welded by position, so
+//! orientation carries across UV seams.
+
+use super::loader::MeshWithColors;
+use baby_shark::exports::nalgebra::Vector3;
+use std::collections::{HashMap, VecDeque};
+
+type Vec3 = Vector3<f64>;
+
+/// Direction of the parity ray; skewed so it rarely grazes edges of axis-aligned models
+const RAY_DIRECTION: [f64; 3] = [0.3713, 0.5571, 0.7428];
+
+/// What `orient` changed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrientReport {
+    /// Edge-connected components
+    pub components: usize,
+    /// Faces whose winding was reversed
+    pub flipped_faces: usize,
+    /// Components turned inside out to face outward (or inward, for cavities)
+    pub inverted_components: usize,
+    /// Components with no consistent orientation, such as a Möbius strip
+    pub non_orientable: usize,
+}
+
+/// Make the winding of `mesh` consistent, and outward unless `keep_direction` is set.
+///
+/// Corner UVs and normals move with their corners, and normals left pointing
+/// against a reversed face are negated.
+pub fn orient(mesh: &mut MeshWithColors, keep_direction: bool) -> OrientReport {
+    let mut report = OrientReport::default();
+    let positions: Vec<Vec3> = mesh.positions.iter().map(|p| Vec3::new(p[0] as f64, p[1] as f64, p[2] as f64)).collect();
+
+    let mut weld_index: HashMap<[u32; 3], u32> = HashMap::new();
+    let weld: Vec<u32> = mesh
+        .positions
+        .iter()
+        .map(|p| {
+            let next = weld_index.len() as u32;
+            *weld_index.entry(p.map(f32::to_bits)).or_insert(next)
+        })
+        .collect();
+
+    // Faces around each welded edge, with whether they run from the lower to the higher vertex
+    let valid = |face: &[u32; 3]| face.iter().all(|&v| (v as usize) < positions.len());
+    let mut edges: HashMap<(u32, u32), Vec<(usize, bool)>> = HashMap::new();
+    for (f, face) in mesh.face_indices.iter().enumerate().filter(|(_, face)| valid(face)) {
+        for k in 0..3 {
+            let (a, b) = (weld[face[k] as usize], weld[face[(k + 1) % 3] as usize]);
+            if a != b {
+                edges.entry((a.min(b), a.max(b))).or_default().push((f, a < b));
+            }
+        }
+    }
+    let edge_key = |face: &[u32; 3], k: usize| {
+        let (a, b) = (weld[face[k] as usize], weld[face[(k + 1) % 3] as usize]);
+        ((a.min(b), a.max(b)), a < b)
+    };
+
+    // Breadth-first propagation; `flip[f]` is whether face f gets reversed
+    let mut flip: Vec<Option<bool>> = vec![None; mesh.face_indices.len()];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    for seed in 0..mesh.face_indices.len() {
+        if flip[seed].is_some() || !valid(&mesh.face_indices[seed]) {
+            continue;
+        }
+        flip[seed] = Some(false);
+        let mut component = vec![seed];
+        let mut consistent = true;
+        let mut queue = VecDeque::from([seed]);
+        while let Some(f) = queue.pop_front() {
+            let face = mesh.face_indices[f];
+            for k in 0..3 {
+                let (key, forward) = edge_key(&face, k);
+                let Some(around) = edges.get(&key) else { continue };
+                // Non-manifold edges don't say which way their faces should turn
+                if around.len() != 2 {
+                    continue;
+                }
+                let runs_forward = forward != flip[f].unwrap();
+                for &(g, g_forward) in around.iter().filter(|&&(g, _)| g != f) {
+                    // A consistent neighbour runs the other way along the edge
+                    let wanted = g_forward == runs_forward;
+                    match flip[g] {
+                        None => {
+                            flip[g] = Some(wanted);
+                            component.push(g);
+                            queue.push_back(g);
+                        }
+                        Some(existing) if existing != wanted => consistent = false,
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        if !consistent {
+            report.non_orientable += 1;
+        }
+        components.push(component);
+    }
+    report.components = components.len();
+
+    let mut flip: Vec<bool> = flip.into_iter().map(|f| f.unwrap_or(false)).collect();
+    if !keep_direction {
+        let triangle = |f: usize| {
+            let face = mesh.face_indices[f];
+            let [a, b, c] = face.map(|v| positions[v as usize]);
+            if flip[f] { [a, c, b] } else { [a, b, c] }
+        };
+        let closed: Vec<bool> = components
+            .iter()
+            .map(|component| {
+                component.iter().all(|&f| {
+                    (0..3).all(|k| {
+                        let (key, _) = edge_key(&mesh.face_indices[f], k);
+                        edges.get(&key).is_none_or(|around| around.len() == 2)
+                    })
+                })
+            })
+            .collect();
+
+        let mut invert = vec![false; components.len()];
+        for (c, component) in components.iter().enumerate() {
+            let outward = signed_volume(component.iter().map(|&f| triangle(f))).is_none_or(|v| v > 0.0);
+            let cavity = closed[c] && {
+                let origin = component
+                    .iter()
+                    .map(|&f| triangle(f))
+                    .max_by(|a, b| area(a).total_cmp(&area(b)))
+                    .map(|[a, b, c]| (a + b + c) / 3.0)
+                    .unwrap_or_default();
+                let crossings: usize = components
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != c && closed[other])
+                    .flat_map(|(_, faces)| faces.iter())
+                    .filter(|&&f| ray_hits_triangle(origin, &triangle(f)))
+                    .count();
+                crossings % 2 == 1
+            };
+            invert[c] = outward == cavity;
+        }
+        for (component, _) in components.iter().zip(&invert).filter(|(_, invert)| **invert) {
+            report.inverted_components += 1;
+            for &f in component {
+                flip[f] = !flip[f];
+            }
+        }
+    }
+
+    let flipped: Vec<usize> = (0..flip.len()).filter(|&f| flip[f]).collect();
+    report.flipped_faces = flipped.len();
+    for &f in &flipped {
+        mesh.face_indices[f].swap(1, 2);
+        if let Some(uvs) = mesh.corner_texcoords.get_mut(f) {
+            uvs.swap(1, 2);
+        }
+        if let Some(normals) = mesh.corner_normals.get_mut(f) {
+            normals.swap(1, 2);
+        }
+    }
+    fix_normals(mesh, &positions, &flipped);
+    report
+}
+
+/// Negate normals that point against the faces they belong to after flipping
+fn fix_normals(mesh: &mut MeshWithColors, positions: &[Vec3], flipped: &[usize]) {
+    let face_normal = |face: [u32; 3]| {
+        let [a, b, c] = face.map(|v| positions[v as usize]);
+        (b - a).cross(&(c - a))
+    };
+    let against = |normal: [f32; 3], reference: &Vec3| {
+        Vec3::new(normal[0] as f64, normal[1] as f64, normal[2] as f64).dot(reference) < 0.0
+    };
+
+    if mesh.corner_normals.len() == mesh.face_indices.len() {
+        for &f in flipped {
+            let reference = face_normal(mesh.face_indices[f]);
+            for normal in &mut mesh.corner_normals[f] {
+                if against(*normal, &reference) {
+                    *normal = normal.map(|x| -x);
+                }
+            }
+        }
+    }
+
+    if mesh.normals.len() == mesh.positions.len() {
+        let mut touched = vec![false; positions.len()];
+        for &f in flipped {
+            for v in mesh.face_indices[f] {
+                touched[v as usize] = true;
+            }
+        }
+        let mut reference = vec![Vec3::zeros(); positions.len()];
+        for &face in &mesh.face_indices {
+            if face.iter().any(|&v| touched[v as usize]) {
+                let n = face_normal(face);
+                for v in face {
+                    reference[v as usize] += n;
+                }
+            }
+        }
+        for (v, normal) in mesh.normals.iter_mut().enumerate() {
+            if touched[v] && against(*normal, &reference[v]) {
+                *normal = normal.map(|x| -x);
+            }
+        }
+    }
+}
+
+/// Volume enclosed by the triangles, measured from their centroid so open
+/// surfaces get a meaningful sign; `None` when too flat to tell
+fn signed_volume(triangles: impl Iterator<Item = [Vec3; 3]> + Clone) -> Option<f64> {
+    let count = triangles.clone().count();
+    if count == 0 {
+        return None;
+    }
+    let centroid = triangles.clone().map(|[a, b, c]| a + b + c).sum::<Vec3>() / (3 * count) as f64;
+    let (mut volume, mut scale) = (0.0, 0.0);
+    for [a, b, c] in triangles {
+        let (a, b, c) = (a - centroid, b - centroid, c - centroid);
+        volume += a.dot(&b.cross(&c)) / 6.0;
+        scale += area(&[a, b, c]) * a.norm().max(b.norm()).max(c.norm());
+    }
+    (volume.abs() > 1e-6 * scale).then_some(volume)
+}
+
+fn area([a, b, c]: &[Vec3; 3]) -> f64 {
+    (b - a).cross(&(c - a)).norm() / 2.0
+}
+
+/// Whether the ray from `origin` along [`RAY_DIRECTION`] crosses `tri` (Möller–Trumbore)
+fn ray_hits_triangle(origin: Vec3, tri: &[Vec3; 3]) -> bool {
+    let d = Vec3::from(RAY_DIRECTION);
+    let (e1, e2) = (tri[1] - tri[0], tri[2] - tri[0]);
+    let h = d.cross(&e2);
+    let det = e1.dot(&h);
+    if det.abs() <= f64::EPSILON * e1.norm() * e2.norm() {
+        return false;
+    }
+    let s = origin - tri[0];
+    let u = s.dot(&h) / det;
+    let q = s.cross(&e1);
+    let v = d.dot(&q) / det;
+    u >= 0.0 && v >= 0.0 && u + v <= 1.0 && e2.dot(&q) / det > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUBE: [[u32; 3]; 12] = [
+        [0, 2, 1], [1, 2, 3], [4, 5, 6], [5, 7, 6], [0, 1, 4], [1, 5, 4],
+        [2, 6, 3], [3, 6, 7], [0, 4, 2], [2, 4, 6], [1, 3, 5], [3, 7, 5],
+    ];
+
+    fn add_cube(mesh: &mut MeshWithColors, min: f32, size: f32) {
+        let base = mesh.positions.len() as u32;
+        mesh.positions.extend((0..8).map(|i| {
+            [(i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32].map(|x| min + x * size)
+        }));
+        mesh.face_indices.extend(CUBE.iter().map(|f| f.map(|v| v + base)));
+    }
+
+    fn volume(mesh: &MeshWithColors, faces: std::ops::Range<usize>) -> f64 {
+        let p = |v: u32| mesh.positions[v as usize].map(|x| x as f64).into();
+        faces
+            .map(|f| {
+                let [a, b, c]: [Vec3; 3] = mesh.face_indices[f].map(p);
+                a.dot(&b.cross(&c)) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn repairs_mixed_and_inverted_winding() {
+        let mut mesh = MeshWithColors::default();
+        add_cube(&mut mesh, 0.0, 1.0);
+        // Turn the cube inside out, then scramble a few faces back
+        for face in &mut mesh.face_indices {
+            face.swap(1, 2);
+        }
+        for f in [0, 5, 9] {
+            mesh.face_indices[f].swap(1, 2);
+        }
+        mesh.corner_texcoords = (0..12).map(|f| [[f as f32, 0.0], [f as f32, 1.0], [f as f32, 2.0]]).collect();
+        let before = mesh.clone();
+
+        let report = orient(&mut mesh, false);
+        assert_eq!(report.components, 1);
+        assert_eq!(report.flipped_faces, 9);
+        assert!((volume(&mesh, 0..12) - 1.0).abs() < 1e-6);
+        // UVs follow their vertices
+        for f in 0..12 {
+            for k in 0..3 {
+                let old = before.face_indices[f].iter().position(|&v| v == mesh.face_indices[f][k]).unwrap();
+                assert_eq!(mesh.corner_texcoords[f][k], before.corner_texcoords[f][old]);
+            }
+        }
+    }
+
+    #[test]
+    fn cavities_face_inward() {
+        let mut mesh = MeshWithColors::default();
+        add_cube(&mut mesh, -1.5, 3.0);
+        add_cube(&mut mesh, -0.5, 1.0);
+        let report = orient(&mut mesh, false);
+        assert_eq!(report.components, 2);
+        assert_eq!(report.inverted_components, 1);
+        assert!(volume(&mesh, 0..12) > 0.0);
+        assert!(volume(&mesh, 12..24) < 0.0);
+    }
+}
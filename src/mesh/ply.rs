@@ -0,0 +1,616 @@
+//! PLY (Stanford polygon format) reader and writer.
+//!
+//! Supports `ascii`, `binary_little_endian` and `binary_big_endian` files.
+//! The `vertex` element provides positions, optional `red/green/blue/alpha`
+//! colors, `nx/ny/nz` normals and `s/t` (or `u/v`) texture coordinates; the
+//! `face` element provides polygon index lists (fan-triangulated) and optional
+//! per-face colors. Other elements are read and discarded.
+
+use std::io::Write;
+use std::path::Path;
+
+use super::loader::MeshWithColors;
+use super::stdio;
+
+/// Default color for faces without a color when only some faces are colored
+const DEFAULT_FACE_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScalarType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => ScalarType::Int8,
+            "uchar" | "uint8" => ScalarType::UInt8,
+            "short" | "int16" => ScalarType::Int16,
+            "ushort" | "uint16" => ScalarType::UInt16,
+            "int" | "int32" => ScalarType::Int32,
+            "uint" | "uint32" => ScalarType::UInt32,
+            "float" | "float32" => ScalarType::Float32,
+            "double" | "float64" => ScalarType::Float64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::Int8 | ScalarType::UInt8 => 1,
+            ScalarType::Int16 | ScalarType::UInt16 => 2,
+            ScalarType::Int32 | ScalarType::UInt32 | ScalarType::Float32 => 4,
+            ScalarType::Float64 => 8,
+        }
+    }
+
+    /// Scale that maps an integer color channel to [0, 1]
+    fn color_scale(self) -> f32 {
+        match self {
+            ScalarType::UInt8 | ScalarType::Int8 => 255.0,
+            ScalarType::UInt16 | ScalarType::Int16 => 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Property {
+    Scalar { name: String, ty: ScalarType },
+    List { name: String, count_ty: ScalarType, item_ty: ScalarType },
+}
+
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar { name, .. } | Property::List { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+struct Header {
+    encoding: Encoding,
+    elements: Vec<Element>,
+    /// Byte offset of the first element after `end_header`
+    body_offset: usize,
+}
+
+fn parse_header(bytes: &[u8]) -> Result<Header, Box<dyn std::error::Error>> {
+    if !bytes.starts_with(b"ply") {
+        return Err("Not a PLY file (missing 'ply' magic)".into());
+    }
+
+    let mut encoding = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut pos = 0;
+
+    loop {
+        let line_end = bytes[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|i| pos + i)
+            .ok_or("PLY header is missing 'end_header'")?;
+        let line = std::str::from_utf8(&bytes[pos..line_end])
+            .map_err(|_| "PLY header is not valid text")?
+            .trim();
+        pos = line_end + 1;
+
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("format") => {
+                encoding = Some(match parts.next() {
+                    Some("ascii") => Encoding::Ascii,
+                    Some("binary_little_endian") => Encoding::BinaryLittleEndian,
+                    Some("binary_big_endian") => Encoding::BinaryBigEndian,
+                    other => return Err(format!("Unsupported PLY format: {:?}", other).into()),
+                });
+            }
+            Some("element") => {
+                let name = parts.next().ok_or("PLY element is missing a name")?.to_string();
+                let count = parts
+                    .next()
+                    .and_then(|c| c.parse().ok())
+                    .ok_or_else(|| format!("PLY element '{}' has an invalid count", name))?;
+                elements.push(Element { name, count, properties: Vec::new() });
+            }
+            Some("property") => {
+                let element = elements
+                    .last_mut()
+                    .ok_or("PLY property declared before any element")?;
+                let tokens: Vec<&str> = parts.collect();
+                let property = match tokens.as_slice() {
+                    ["list", count_ty, item_ty, name] => Property::List {
+                        name: name.to_string(),
+                        count_ty: ScalarType::parse(count_ty)
+                            .ok_or_else(|| format!("Unknown PLY type: {}", count_ty))?,
+                        item_ty: ScalarType::parse(item_ty)
+                            .ok_or_else(|| format!("Unknown PLY type: {}", item_ty))?,
+                    },
+                    [ty, name] => Property::Scalar {
+                        name: name.to_string(),
+                        ty: ScalarType::parse(ty).ok_or_else(|| format!("Unknown PLY type: {}", ty))?,
+                    },
+                    _ => return Err(format!("Malformed PLY property: {}", line).into()),
+                };
+                element.properties.push(property);
+            }
+            Some("end_header") => break,
+            // ply, comment, obj_info and blank lines
+            _ => {}
+        }
+    }
+
+    Ok(Header {
+        encoding: encoding.ok_or("PLY header is missing 'format'")?,
+        elements,
+        body_offset: pos,
+    })
+}
+
+/// Sequential reader over the PLY body for either encoding
+enum BodyReader<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary { bytes: &'a [u8], pos: usize, big_endian: bool },
+}
+
+impl BodyReader<'_> {
+    /// `count` items from the header, capped at one per remaining body byte
+    /// so a bogus count can't reserve more than the file could hold
+    fn capacity_for(&self, count: usize) -> usize {
+        let remaining = match self {
+            BodyReader::Ascii(tokens) => tokens.size_hint().1.unwrap_or(0),
+            BodyReader::Binary { bytes, pos, .. } => bytes.len().saturating_sub(*pos),
+        };
+        count.min(remaining)
+    }
+
+    fn read(&mut self, ty: ScalarType) -> Result<f64, Box<dyn std::error::Error>> {
+        match self {
+            BodyReader::Ascii(tokens) => {
+                let token = tokens.next().ok_or("Unexpected end of PLY data")?;
+                token
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid PLY value: {}", token).into())
+            }
+            BodyReader::Binary { bytes, pos, big_endian } => {
+                let size = ty.size();
+                let raw = bytes
+                    .get(*pos..*pos + size)
+                    .ok_or("Unexpected end of PLY data")?;
+                *pos += size;
+                let mut buf = [0u8; 8];
+                buf[..size].copy_from_slice(raw);
+                if *big_endian {
+                    buf[..size].reverse();
+                }
+                Ok(match ty {
+                    ScalarType::Int8 => buf[0] as i8 as f64,
+                    ScalarType::UInt8 => buf[0] as f64,
+                    ScalarType::Int16 => i16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    ScalarType::UInt16 => u16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    ScalarType::Int32 => i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::UInt32 => u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::Float32 => f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::Float64 => f64::from_le_bytes(buf),
+                })
+            }
+        }
+    }
+}
+
+/// Load a PLY file
+pub fn load_ply(path: &Path) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let bytes = stdio::read(path)?;
+    parse_ply(&bytes)
+}
+
+/// Parse PLY bytes (any encoding)
+pub fn parse_ply(bytes: &[u8]) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let header = parse_header(bytes)?;
+    let body = &bytes[header.body_offset..];
+    let mut reader = match header.encoding {
+        Encoding::Ascii => BodyReader::Ascii(
+            std::str::from_utf8(body)
+                .map_err(|_| "ASCII PLY body is not valid text")?
+                .split_ascii_whitespace(),
+        ),
+        Encoding::BinaryLittleEndian | Encoding::BinaryBigEndian => BodyReader::Binary {
+            bytes: body,
+            pos: 0,
+            big_endian: header.encoding == Encoding::BinaryBigEndian,
+        },
+    };
+
+    let mut mesh = MeshWithColors::default();
+    let mut has_face_colors = false;
+
+    for element in &header.elements {
+        match element.name.as_str() {
+            "vertex" => read_vertices(element, &mut reader, &mut mesh)?,
+            "face" => has_face_colors |= read_faces(element, &mut reader, &mut mesh)?,
+            _ => {
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        read_property(property, &mut reader)?;
+                    }
+                }
+            }
+        }
+    }
+
+    if !has_face_colors {
+        mesh.face_colors.clear();
+    }
+
+    let vertex_count = mesh.positions.len();
+    if let Some(&bad) = mesh.face_indices.iter().flatten().find(|&&i| i as usize >= vertex_count) {
+        return Err(format!("PLY face references vertex {} but only {} vertices exist", bad, vertex_count).into());
+    }
+
+    Ok(mesh)
+}
+
+/// Read a property and return its values (one for scalars, the items for lists)
+fn read_property(
+    property: &Property,
+    reader: &mut BodyReader<'_>,
+) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+    match property {
+        Property::Scalar { ty, .. } => Ok(vec![reader.read(*ty)?]),
+        Property::List { count_ty, item_ty, .. } => {
+            let count = reader.read(*count_ty)? as usize;
+            (0..count).map(|_| reader.read(*item_ty)).collect()
+        }
+    }
+}
+
+fn read_vertices(
+    element: &Element,
+    reader: &mut BodyReader<'_>,
+    mesh: &mut MeshWithColors,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let find = |names: &[&str]| element.properties.iter().position(|p| names.contains(&p.name()));
+    let scale_of = |idx: usize| match &element.properties[idx] {
+        Property::Scalar { ty, .. } => ty.color_scale(),
+        Property::List { .. } => 1.0,
+    };
+
+    let pos_idx = [find(&["x"]), find(&["y"]), find(&["z"])];
+    if pos_idx.iter().any(Option::is_none) {
+        return Err("PLY vertex element is missing x/y/z".into());
+    }
+    let normal_idx = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+    let has_normals = normal_idx.iter().all(Option::is_some);
+    let color_idx = [
+        find(&["red", "r", "diffuse_red"]),
+        find(&["green", "g", "diffuse_green"]),
+        find(&["blue", "b", "diffuse_blue"]),
+    ];
+    let has_colors = color_idx.iter().all(Option::is_some);
+    let alpha_idx = find(&["alpha", "a", "diffuse_alpha"]);
+    let uv_idx = [
+        find(&["s", "u", "texture_u", "texture_s"]),
+        find(&["t", "v", "texture_v", "texture_t"]),
+    ];
+    let has_uvs = uv_idx.iter().all(Option::is_some);
+
+    mesh.positions.reserve(reader.capacity_for(element.count));
+    let mut values = vec![0.0f64; element.properties.len()];
+    for _ in 0..element.count {
+        for (i, property) in element.properties.iter().enumerate() {
+            values[i] = read_property(property, reader)?.first().copied().unwrap_or(0.0);
+        }
+        let get = |idx: Option<usize>| values[idx.unwrap()] as f32;
+
+        mesh.positions.push(pos_idx.map(get));
+        if has_normals {
+            mesh.normals.push(normal_idx.map(get));
+        }
+        if has_colors {
+            let [r, g, b] = color_idx.map(|idx| get(idx) / scale_of(idx.unwrap()));
+            let a = alpha_idx.map(|idx| get(Some(idx)) / scale_of(idx)).unwrap_or(1.0);
+            mesh.vertex_colors.push([r, g, b, a]);
+        }
+        if has_uvs {
+            // PLY puts the UV origin bottom-left; flip V into the glTF convention
+            let [u, v] = uv_idx.map(get);
+            mesh.texcoords.push([u, 1.0 - v]);
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the face element; returns true if it carried per-face colors
+fn read_faces(
+    element: &Element,
+    reader: &mut BodyReader<'_>,
+    mesh: &mut MeshWithColors,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let find = |names: &[&str]| element.properties.iter().position(|p| names.contains(&p.name()));
+    let indices_idx = find(&["vertex_indices", "vertex_index"])
+        .ok_or("PLY face element is missing vertex_indices")?;
+    let color_idx = [find(&["red"]), find(&["green"]), find(&["blue"])];
+    let has_colors = color_idx.iter().all(Option::is_some);
+    let alpha_idx = find(&["alpha"]);
+    let scale_of = |idx: usize| match &element.properties[idx] {
+        Property::Scalar { ty, .. } => ty.color_scale(),
+        Property::List { .. } => 1.0,
+    };
+
+    mesh.face_indices.reserve(reader.capacity_for(element.count));
+    let mut values: Vec<Vec<f64>> = vec![Vec::new(); element.properties.len()];
+    for _ in 0..element.count {
+        for (i, property) in element.properties.iter().enumerate() {
+            values[i] = read_property(property, reader)?;
+        }
+        let color = if has_colors {
+            let channel = |idx: usize| values[idx].first().copied().unwrap_or(0.0) as f32 / scale_of(idx);
+            let [r, g, b] = color_idx.map(|idx| channel(idx.unwrap()));
+            [r, g, b, alpha_idx.map(channel).unwrap_or(1.0)]
+        } else {
+            DEFAULT_FACE_COLOR
+        };
+
+        // Fan triangulation for n-gons
+        let verts = &values[indices_idx];
+        for i in 1..verts.len().saturating_sub(1) {
+            mesh.face_indices
+                .push([verts[0] as u32, verts[i] as u32, verts[i + 1] as u32]);
+            mesh.face_colors.push(color);
+        }
+    }
+
+    Ok(has_colors)
+}
+
+fn to_u8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Write a mesh as PLY (binary little-endian, or ASCII when `ascii` is set).
+/// Vertex colors, normals, UVs and face colors are written when present.
+pub fn write_ply<W: Write>(
+    mesh: &MeshWithColors,
+    writer: &mut W,
+    ascii: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dropped: Vec<&str> = [
+        (mesh.texture.is_some(), "texture"),
+        (mesh.skeleton.is_some(), "skin"),
+        (!mesh.animations.is_empty(), "animations"),
+        (!mesh.morph_targets.is_empty(), "morph targets"),
+    ]
+    .into_iter()
+    .filter_map(|(present, name)| present.then_some(name))
+    .collect();
+    if !dropped.is_empty() {
+        eprintln!("Warning: PLY output does not store {}; dropping", dropped.join(", "));
+    }
+
+    let split = mesh.with_vertex_attributes();
+    let mesh: &MeshWithColors = &split;
+    let n = mesh.positions.len();
+    let has_normals = mesh.normals.len() == n && n > 0;
+    let has_vertex_colors = mesh.vertex_colors.len() == n && n > 0;
+    let has_uvs = mesh.texcoords.len() == n && n > 0;
+    let has_face_colors =
+        mesh.face_colors.len() == mesh.face_indices.len() && !mesh.face_colors.is_empty();
+
+    writeln!(writer, "ply")?;
+    writeln!(
+        writer,
+        "format {} 1.0",
+        if ascii { "ascii" } else { "binary_little_endian" }
+    )?;
+    writeln!(writer, "comment written by msh")?;
+    writeln!(writer, "element vertex {}", n)?;
+    writeln!(writer, "property float x\nproperty float y\nproperty float z")?;
+    if has_normals {
+        writeln!(writer, "property float nx\nproperty float ny\nproperty float nz")?;
+    }
+    if has_vertex_colors {
+        writeln!(
+            writer,
+            "property uchar red\nproperty uchar green\nproperty uchar blue\nproperty uchar alpha"
+        )?;
+    }
+    if has_uvs {
+        writeln!(writer, "property float s\nproperty float t")?;
+    }
+    writeln!(writer, "element face {}", mesh.face_indices.len())?;
+    writeln!(writer, "property list uchar int vertex_indices")?;
+    if has_face_colors {
+        writeln!(
+            writer,
+            "property uchar red\nproperty uchar green\nproperty uchar blue\nproperty uchar alpha"
+        )?;
+    }
+    writeln!(writer, "end_header")?;
+
+    for i in 0..n {
+        let mut floats: Vec<f32> = mesh.positions[i].to_vec();
+        if has_normals {
+            floats.extend_from_slice(&mesh.normals[i]);
+        }
+        let color = has_vertex_colors.then(|| mesh.vertex_colors[i].map(to_u8));
+        let uv = has_uvs.then(|| [mesh.texcoords[i][0], 1.0 - mesh.texcoords[i][1]]);
+
+        if ascii {
+            let mut fields: Vec<String> = floats.iter().map(|f| f.to_string()).collect();
+            if let Some(c) = color {
+                fields.extend(c.iter().map(|b| b.to_string()));
+            }
+            if let Some(uv) = uv {
+                fields.extend(uv.iter().map(|f| f.to_string()));
+            }
+            writeln!(writer, "{}", fields.join(" "))?;
+        } else {
+            for f in floats {
+                writer.write_all(&f.to_le_bytes())?;
+            }
+            if let Some(c) = color {
+                writer.write_all(&c)?;
+            }
+            if let Some(uv) = uv {
+                writer.write_all(&uv[0].to_le_bytes())?;
+                writer.write_all(&uv[1].to_le_bytes())?;
+            }
+        }
+    }
+
+    for (face_i, tri) in mesh.face_indices.iter().enumerate() {
+        let color = has_face_colors.then(|| mesh.face_colors[face_i].map(to_u8));
+        if ascii {
+            let mut line = format!("3 {} {} {}", tri[0], tri[1], tri[2]);
+            if let Some(c) = color {
+                line.push_str(&format!(" {} {} {} {}", c[0], c[1], c[2], c[3]));
+            }
+            writeln!(writer, "{}", line)?;
+        } else {
+            writer.write_all(&[3u8])?;
+            for &vi in tri {
+                writer.write_all(&(vi as i32).to_le_bytes())?;
+            }
+            if let Some(c) = color {
+                writer.write_all(&c)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASCII_PLY: &str = "\
+ply
+format ascii 1.0
+comment quad with colors
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+property float s
+property float t
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 255 0 0 0 0
+1 0 0 0 255 0 1 0
+1 1 0 0 0 255 1 1
+0 1 0 255 255 255 0 1
+4 0 1 2 3
+";
+
+    #[test]
+    fn parse_ascii_quad() {
+        let mesh = parse_ply(ASCII_PLY.as_bytes()).unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(mesh.vertex_colors[1], [0.0, 1.0, 0.0, 1.0]);
+        // V is flipped into the glTF convention
+        assert_eq!(mesh.texcoords[2], [1.0, 0.0]);
+        assert_eq!(mesh.texcoords[0], [0.0, 1.0]);
+        assert!(mesh.normals.is_empty());
+        assert!(mesh.face_colors.is_empty());
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut mesh = parse_ply(ASCII_PLY.as_bytes()).unwrap();
+        mesh.normals = vec![[0.0, 0.0, 1.0]; 4];
+        mesh.face_colors = vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]];
+
+        let mut buf = Vec::new();
+        write_ply(&mesh, &mut buf, false).unwrap();
+        let loaded = parse_ply(&buf).unwrap();
+
+        assert_eq!(loaded.positions, mesh.positions);
+        assert_eq!(loaded.face_indices, mesh.face_indices);
+        assert_eq!(loaded.normals, mesh.normals);
+        assert_eq!(loaded.vertex_colors, mesh.vertex_colors);
+        assert_eq!(loaded.texcoords, mesh.texcoords);
+        assert_eq!(loaded.face_colors, mesh.face_colors);
+    }
+
+    #[test]
+    fn uvs_match_obj_orientation() {
+        let dir = std::env::temp_dir().join(format!("msh_ply_uv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0.25\nvt 0 1\nf 1/1 2/2 3/3\n";
+        std::fs::write(dir.join("in.obj"), obj).unwrap();
+        let mesh = super::super::obj::load_obj(&dir.join("in.obj"), None).unwrap();
+
+        let mut buf = Vec::new();
+        write_ply(&mesh, &mut buf, true).unwrap();
+        let text = String::from_utf8(buf.clone()).unwrap();
+        // Same s/t as the OBJ's vt lines: both formats put the origin bottom-left
+        assert!(text.contains("\n0 0 0 0 0\n1 0 0 1 0.25\n0 1 0 0 1\n"), "{}", text);
+
+        let loaded = parse_ply(&buf).unwrap();
+        super::super::obj::write_obj(&loaded, &dir.join("out.obj")).unwrap();
+        let written = std::fs::read_to_string(dir.join("out.obj")).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(written.contains("vt 0 0\nvt 1 0.25\nvt 0 1\n"), "{}", written);
+    }
+
+    #[test]
+    fn big_endian_with_extra_element() {
+        let mut data = b"ply\nformat binary_big_endian 1.0\nelement vertex 3\nproperty double x\nproperty double y\nproperty double z\nelement face 1\nproperty list uchar uint vertex_indices\nelement material 1\nproperty float shininess\nend_header\n".to_vec();
+        for v in [[0.0f64, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+            for c in v {
+                data.extend_from_slice(&c.to_be_bytes());
+            }
+        }
+        data.push(3);
+        for i in [0u32, 1, 2] {
+            data.extend_from_slice(&i.to_be_bytes());
+        }
+        data.extend_from_slice(&0.5f32.to_be_bytes());
+
+        let mesh = parse_ply(&data).unwrap();
+        assert_eq!(mesh.positions[1], [1.0, 0.0, 0.0]);
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2]]);
+    }
+
+    #[test]
+    fn huge_declared_counts_fail_cleanly() {
+        let ascii = ASCII_PLY.replace("element vertex 4", "element vertex 4000000000");
+        assert!(parse_ply(ascii.as_bytes()).is_err());
+        let binary = b"ply\nformat binary_little_endian 1.0\nelement vertex 4000000000\nproperty float x\nproperty float y\nproperty float z\nelement face 4000000000\nproperty list uchar int vertex_indices\nend_header\n\0\0\0\0";
+        assert!(parse_ply(binary).is_err());
+    }
+
+    #[test]
+    fn out_of_range_index_is_an_error() {
+        let bad = ASCII_PLY.replace("4 0 1 2 3", "3 0 1 9");
+        assert!(parse_ply(bad.as_bytes()).is_err());
+    }
+}
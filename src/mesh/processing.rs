@@ -1,19 +1,30 @@
 use baby_shark::algo::merge_points::merge_points;
 use baby_shark::exports::nalgebra::Vector3;
-use baby_shark::io::{write_to_file, Builder, IndexedBuilder};
+use baby_shark::io::{Builder, IndexedBuilder};
 use baby_shark::mesh::corner_table::CornerTableF;
 use baby_shark::remeshing::incremental::IncrementalRemesher;
 use baby_shark::remeshing::voxel::{MeshingMethod, VoxelRemesher};
 use std::path::PathBuf;
 
-use super::loader::load_mesh;
+use super::components::{self, ComponentFilter};
+use super::decimate::{self, DecimateOptions};
+use super::loader::{load_mesh, load_mesh_with_colors, LoadOptions, MeshWithColors};
+use super::holes::{self, HoleFillOptions};
+use super::intersect;
+use super::lod::{self, LodOptions};
+use super::orient::{self};
+use super::smooth::{self, SmoothMethod, SmoothOptions};
+use super::subdivide::{self, SubdivideOptions};
+use super::stdio::{self, progress};
+use super::topology::{self, TopologyReport};
+use super::writer::{write_lods, write_mesh, write_mesh_with_colors, WriteOptions};
 
 /// Merge vertices that are closer than tolerance
 pub fn merge_close_vertices(
     mesh: &CornerTableF,
     tolerance: f32,
 ) -> Result<CornerTableF, Box<dyn std::error::Error>> {
-    println!("Merging vertices with tolerance: {}", tolerance);
+    progress!("Merging vertices with tolerance: {}", tolerance);
 
     let vertex_count_before = mesh.count_vertices();
 
@@ -44,7 +55,7 @@ pub fn merge_close_vertices(
     // Merge quantized vertices
     let merged = merge_points(quantized_positions.into_iter());
 
-    println!(
+    progress!(
         "Merged {} vertices into {} unique vertices",
         vertex_count_before,
         merged.points.len()
@@ -92,21 +103,23 @@ pub fn merge_close_vertices(
 pub fn remesh_incremental(
     input: &PathBuf,
     output: &PathBuf,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     iterations: u32,
     target_edge_length: f32,
+    write_options: &WriteOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Loading mesh from {:?}...", input);
-    let mut mesh = load_mesh(input, mesh_name)?;
+    progress!("Loading mesh from {:?}...", input);
+    let source = load_mesh_with_colors(input, load_options)?;
+    let mut mesh = source.to_corner_table()?;
 
     let vertex_count_before = mesh.count_vertices();
     let face_count_before = mesh.count_faces();
 
-    println!(
+    progress!(
         "Before remeshing: {} vertices, {} faces",
         vertex_count_before, face_count_before
     );
-    println!(
+    progress!(
         "Remeshing with {} iterations, target edge length: {}...",
         iterations, target_edge_length
     );
@@ -127,15 +140,15 @@ pub fn remesh_incremental(
     let vertex_count_after = mesh.count_vertices();
     let face_count_after = mesh.count_faces();
 
-    println!(
+    progress!(
         "After remeshing: {} vertices, {} faces",
         vertex_count_after, face_count_after
     );
-    println!("Writing output to {:?}...", output);
+    progress!("Writing output to {:?}...", output);
 
-    write_to_file(&mesh, output).map_err(|e| format!("Failed to write mesh: {:?}", e))?;
+    write_mesh(&mesh, source, output, write_options)?;
 
-    println!("Done!");
+    progress!("Done!");
     Ok(())
 }
 
@@ -143,31 +156,34 @@ pub fn remesh_incremental(
 pub fn remesh_pipeline(
     input: &PathBuf,
     output: &PathBuf,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     voxel_size: f32,
     tolerance: f32,
     no_fix: bool,
     iterations: u32,
     target_edge_length: f32,
+    islands: &ComponentFilter,
+    write_options: &WriteOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Loading mesh from {:?}...", input);
-    let mut mesh = load_mesh(input, mesh_name)?;
+    progress!("Loading mesh from {:?}...", input);
+    let source = remove_islands(load_mesh_with_colors(input, load_options)?, islands)?;
+    let mut mesh = source.to_corner_table()?;
 
     let vertex_count_initial = mesh.count_vertices();
     let face_count_initial = mesh.count_faces();
 
-    println!(
+    progress!(
         "Initial: {} vertices, {} faces",
         vertex_count_initial, face_count_initial
     );
 
     // Step 1: Fix the mesh (unless disabled)
     if !no_fix {
-        println!("\n=== Step 1: Fixing Mesh ===");
+        progress!("\n=== Step 1: Fixing Mesh ===");
 
         // Merge close vertices
         mesh = merge_close_vertices(&mesh, tolerance)?;
-        println!(
+        progress!(
             "After merging: {} vertices, {} faces",
             mesh.count_vertices(),
             mesh.count_faces()
@@ -176,8 +192,8 @@ pub fn remesh_pipeline(
         // Check if mesh needs hole fixing
         let boundary_rings = mesh.boundary_rings();
         if !boundary_rings.is_empty() {
-            println!("Found {} hole(s) in mesh", boundary_rings.len());
-            println!(
+            progress!("Found {} hole(s) in mesh", boundary_rings.len());
+            progress!(
                 "Fixing holes using voxel remeshing (voxel size: {})...",
                 voxel_size
             );
@@ -188,7 +204,7 @@ pub fn remesh_pipeline(
 
             mesh = remesher.remesh(&mesh).ok_or("Voxel remeshing failed")?;
 
-            println!(
+            progress!(
                 "After fixing: {} vertices, {} faces",
                 mesh.count_vertices(),
                 mesh.count_faces()
@@ -196,18 +212,18 @@ pub fn remesh_pipeline(
 
             let boundary_rings_after = mesh.boundary_rings();
             if boundary_rings_after.is_empty() {
-                println!("✓ Mesh is now manifold!");
+                progress!("✓ Mesh is now manifold!");
             } else {
-                println!("⚠ Warning: {} hole(s) remain", boundary_rings_after.len());
+                progress!("⚠ Warning: {} hole(s) remain", boundary_rings_after.len());
             }
         } else {
-            println!("✓ Mesh is already manifold (no holes to fix)");
+            progress!("✓ Mesh is already manifold (no holes to fix)");
         }
     }
 
     // Step 2: Incremental remeshing
-    println!("\n=== Step 2: Incremental Remeshing ===");
-    println!(
+    progress!("\n=== Step 2: Incremental Remeshing ===");
+    progress!(
         "Remeshing with {} iterations, target edge length: {}...",
         iterations, target_edge_length
     );
@@ -230,32 +246,32 @@ pub fn remesh_pipeline(
     let vertex_count_final = mesh.count_vertices();
     let face_count_final = mesh.count_faces();
 
-    println!(
+    progress!(
         "After incremental remeshing: {} vertices, {} faces",
         vertex_count_final, face_count_final
     );
 
     // Final summary
-    println!("\n=== Summary ===");
-    println!(
+    progress!("\n=== Summary ===");
+    progress!(
         "Initial:  {} vertices, {} faces",
         vertex_count_initial, face_count_initial
     );
     if !no_fix {
-        println!(
+        progress!(
             "After fix: {} vertices, {} faces",
             vertex_count_before_incremental, face_count_before_incremental
         );
     }
-    println!(
+    progress!(
         "Final:    {} vertices, {} faces",
         vertex_count_final, face_count_final
     );
 
-    println!("\nWriting output to {:?}...", output);
-    write_to_file(&mesh, output).map_err(|e| format!("Failed to write mesh: {:?}", e))?;
+    progress!("\nWriting output to {:?}...", output);
+    write_mesh(&mesh, source, output, write_options)?;
 
-    println!("Done!");
+    progress!("Done!");
     Ok(())
 }
 
@@ -268,21 +284,23 @@ pub enum VoxelMethod {
 pub fn remesh_voxel(
     input: &PathBuf,
     output: &PathBuf,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     voxel_size: f32,
     method: VoxelMethod,
+    write_options: &WriteOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Loading mesh from {:?}...", input);
-    let mesh = load_mesh(input, mesh_name)?;
+    progress!("Loading mesh from {:?}...", input);
+    let source = load_mesh_with_colors(input, load_options)?;
+    let mesh = source.to_corner_table()?;
 
     let vertex_count_before = mesh.count_vertices();
     let face_count_before = mesh.count_faces();
 
-    println!(
+    progress!(
         "Before remeshing: {} vertices, {} faces",
         vertex_count_before, face_count_before
     );
-    println!(
+    progress!(
         "Voxel remeshing with method: {:?}, voxel size: {}",
         method, voxel_size
     );
@@ -301,7 +319,7 @@ pub fn remesh_voxel(
     let vertex_count_after = remeshed_mesh.count_vertices();
     let face_count_after = remeshed_mesh.count_faces();
 
-    println!(
+    progress!(
         "After remeshing: {} vertices, {} faces",
         vertex_count_after, face_count_after
     );
@@ -310,25 +328,356 @@ pub fn remesh_voxel(
     if matches!(method, VoxelMethod::Manifold) {
         let boundary_rings = remeshed_mesh.boundary_rings();
         if boundary_rings.is_empty() {
-            println!("✓ Output mesh is manifold (watertight)");
+            progress!("✓ Output mesh is manifold (watertight)");
         } else {
-            println!(
+            progress!(
                 "⚠ Warning: {} boundary ring(s) detected",
                 boundary_rings.len()
             );
         }
     }
 
-    println!("Writing output to {:?}...", output);
-    write_to_file(&remeshed_mesh, output).map_err(|e| format!("Failed to write mesh: {:?}", e))?;
+    progress!("Writing output to {:?}...", output);
+    write_mesh(&remeshed_mesh, source, output, write_options)?;
 
-    println!("Done!");
+    progress!("Done!");
     Ok(())
 }
 
-pub fn show_stats(input: &PathBuf, mesh_name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+/// Load a mesh and write it in the format chosen by the output extension,
+/// keeping every attribute the output format can store
+pub fn convert(
+    input: &PathBuf,
+    output: &PathBuf,
+    load_options: &LoadOptions,
+    z_up: bool,
+    scale: Option<f32>,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    progress!("Loading mesh from {:?}...", input);
+    let mut mesh = load_mesh_with_colors(input, load_options)?;
+    progress!(
+        "Loaded {} vertices, {} faces",
+        mesh.positions.len(),
+        mesh.face_indices.len()
+    );
+
+    if z_up {
+        progress!("Converting Z-up to Y-up");
+        mesh.convert_z_up_to_y_up();
+    }
+    if let Some(factor) = scale {
+        progress!("Scaling by {}", factor);
+        mesh.scale_uniform(factor);
+    }
+
+    progress!("Writing output to {:?}...", output);
+    write_mesh_with_colors(&mesh, output, write_options)?;
+
+    progress!("Done!");
+    Ok(())
+}
+
+/// Decimate a mesh to a face budget by quadric edge collapse, reporting the error introduced
+pub fn decimate(
+    input: &PathBuf,
+    output: &PathBuf,
+    load_options: &LoadOptions,
+    options: &DecimateOptions,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    progress!("Loading mesh from {:?}...", input);
+    let mesh = load_mesh_with_colors(input, load_options)?;
+    progress!(
+        "Loaded {} vertices, {} faces",
+        mesh.positions.len(),
+        mesh.face_indices.len()
+    );
+
+    let (result, report) = decimate::decimate(&mesh, options)?;
+    progress!(
+        "Decimated {} faces to {} (target {}), {} vertices",
+        report.faces_before,
+        report.faces_after,
+        report.target_faces,
+        result.positions.len()
+    );
+    if report.skipped_faces > 0 {
+        eprintln!(
+            "Warning: left out {} degenerate or non-manifold face(s) the decimator can't represent",
+            report.skipped_faces
+        );
+    }
+    if report.faces_after > report.target_faces {
+        eprintln!(
+            "Warning: stopped at {} faces; further collapses would move locked vertices or break the topology",
+            report.faces_after
+        );
+    }
+    let relative = if report.diagonal > 0.0 { report.max_error / report.diagonal * 100.0 } else { 0.0 };
+    progress!(
+        "Max geometric error: {:.6} ({:.4}% of the bounding box diagonal)",
+        report.max_error,
+        relative
+    );
+
+    progress!("Writing output to {:?}...", output);
+    write_mesh_with_colors(&result, output, write_options)?;
+
+    progress!("Done!");
+    Ok(())
+}
+
+pub fn lod(
+    input: &PathBuf,
+    output: &PathBuf,
+    load_options: &LoadOptions,
+    options: &LodOptions,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    progress!("Loading mesh from {:?}...", input);
+    let mesh = load_mesh_with_colors(input, load_options)?;
+    progress!(
+        "Loaded {} vertices, {} faces",
+        mesh.positions.len(),
+        mesh.face_indices.len()
+    );
+
+    let levels = lod::build_lods(&mesh, options)?;
+    let coverage = lod::screen_coverage(&options.ratios);
+    for (i, (level, coverage)) in levels.iter().zip(&coverage).enumerate() {
+        let error = match &level.report {
+            Some(report) if report.diagonal > 0.0 => {
+                format!(", max error {:.4}% of the diagonal", report.max_error / report.diagonal * 100.0)
+            }
+            _ => String::new(),
+        };
+        progress!(
+            "LOD{}: {} faces, {} vertices (ratio {}, screen coverage >= {}){}",
+            i,
+            level.mesh.face_indices.len(),
+            level.mesh.positions.len(),
+            level.ratio,
+            coverage,
+            error
+        );
+        if let Some(report) = &level.report
+            && report.skipped_faces > 0
+        {
+            eprintln!(
+                "Warning: LOD{} left out {} degenerate or non-manifold face(s) the decimator can't represent",
+                i, report.skipped_faces
+            );
+        }
+        if let Some(report) = &level.report
+            && report.faces_after > report.target_faces
+        {
+            eprintln!(
+                "Warning: LOD{} stopped at {} faces (target {}); further collapses would move locked vertices or break the topology",
+                i, report.faces_after, report.target_faces
+            );
+        }
+    }
+
+    progress!("Writing output to {:?}...", output);
+    let meshes: Vec<_> = levels.into_iter().map(|level| level.mesh).collect();
+    write_lods(&meshes, &coverage, output, write_options)?;
+
+    progress!("Done!");
+    Ok(())
+}
+
+pub fn smooth(
+    input: &PathBuf,
+    output: &PathBuf,
+    load_options: &LoadOptions,
+    options: &SmoothOptions,
+    tolerance: f32,
+    no_merge: bool,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    options.validate()?;
+    progress!("Loading mesh from {:?}...", input);
+    let source = load_mesh_with_colors(input, load_options)?;
+    let mut mesh = source.to_corner_table()?;
+    progress!("Loaded {} vertices, {} faces", mesh.count_vertices(), mesh.count_faces());
+
+    // Split vertices would smooth as separate boundaries, so weld them first
+    if !no_merge {
+        mesh = merge_close_vertices(&mesh, tolerance)?;
+        progress!(
+            "After merging: {} vertices, {} faces",
+            mesh.count_vertices(),
+            mesh.count_faces()
+        );
+    }
+
+    let method = match options.method {
+        SmoothMethod::Uniform => format!("uniform Laplacian (lambda {})", options.lambda),
+        SmoothMethod::Cotangent => format!("cotangent Laplacian (lambda {})", options.lambda),
+        SmoothMethod::Taubin => format!("Taubin (lambda {}, mu {})", options.lambda, options.mu),
+    };
+    progress!("Smoothing with {} for {} iterations...", method, options.iterations);
+    let report = smooth::smooth(&mut mesh, options);
+    progress!(
+        "{} vertices locked, {} sliding along creases or boundaries",
+        report.locked, report.creased
+    );
+
+    progress!("Writing output to {:?}...", output);
+    write_mesh(&mesh, source, output, write_options)?;
+
+    progress!("Done!");
+    Ok(())
+}
+
+pub fn subdivide(
+    input: &PathBuf,
+    output: &PathBuf,
+    load_options: &LoadOptions,
+    options: &SubdivideOptions,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    progress!("Loading mesh from {:?}...", input);
+    let mesh = load_mesh_with_colors(input, load_options)?;
+    progress!(
+        "Loaded {} vertices, {} faces",
+        mesh.positions.len(),
+        mesh.face_indices.len()
+    );
+
+    progress!("Subdividing {} level(s) with the {:?} scheme...", options.levels, options.scheme);
+    let result = subdivide::subdivide(&mesh, options);
+    progress!(
+        "Subdivided to {} vertices, {} faces",
+        result.positions.len(),
+        result.face_indices.len()
+    );
+
+    progress!("Writing output to {:?}...", output);
+    write_mesh_with_colors(&result, output, write_options)?;
+
+    progress!("Done!");
+    Ok(())
+}
+
+pub fn orient(
+    input: &PathBuf,
+    output: &PathBuf,
+    load_options: &LoadOptions,
+    keep_direction: bool,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    progress!("Loading mesh from {:?}...", input);
+    let mut mesh = load_mesh_with_colors(input, load_options)?;
+    progress!(
+        "Loaded {} vertices, {} faces",
+        mesh.positions.len(),
+        mesh.face_indices.len()
+    );
+
+    progress!("Orienting faces...");
+    let report = orient::orient(&mut mesh, keep_direction);
+    progress!(
+        "Flipped {} of {} faces across {} component(s); {} component(s) turned inside out",
+        report.flipped_faces,
+        mesh.face_indices.len(),
+        report.components,
+        report.inverted_components
+    );
+    if report.non_orientable > 0 {
+        eprintln!(
+            "Warning: {} component(s) are not orientable (e.g. a Möbius strip); their winding stays inconsistent somewhere",
+            report.non_orientable
+        );
+    }
+
+    progress!("Writing output to {:?}...", output);
+    write_mesh_with_colors(&mesh, output, write_options)?;
+
+    progress!("Done!");
+    Ok(())
+}
+
+pub fn components(
+    input: &PathBuf,
+    output: Option<&PathBuf>,
+    split: Option<&str>,
+    load_options: &LoadOptions,
+    filter: &ComponentFilter,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if split.is_some_and(|pattern| !pattern.contains("{}")) {
+        return Err("--split needs a {} in the file name for the component number".into());
+    }
+
+    progress!("Loading mesh from {:?}...", input);
+    let mesh = load_mesh_with_colors(input, load_options)?;
+    let found = components::find_components(&mesh);
+    let keeps = filter.keeps(&found);
+
+    progress!("\n=== Connected Components ===");
+    progress!("Found {} component(s)\n", found.len());
+    for (i, (component, keep)) in found.iter().zip(&keeps).enumerate() {
+        progress!(
+            "  [{}] {} faces, area {:.6}, volume {:.6}{}, bounds ({:.4}, {:.4}, {:.4}) to ({:.4}, {:.4}, {:.4}){}",
+            i,
+            component.faces.len(),
+            component.area,
+            component.volume,
+            if component.closed { "" } else { " (open)" },
+            component.min[0], component.min[1], component.min[2],
+            component.max[0], component.max[1], component.max[2],
+            if *keep { "" } else { " - removed" }
+        );
+    }
+    let kept: Vec<&components::Component> = found.iter().zip(&keeps).filter(|(_, keep)| **keep).map(|(c, _)| c).collect();
+    if filter.is_active() {
+        progress!("\nKeeping {} of {} component(s)", kept.len(), found.len());
+    }
+
+    if kept.is_empty() && (output.is_some() || split.is_some()) {
+        return Err("Every component was filtered out; nothing to write".into());
+    }
+    if let Some(output) = output {
+        let faces: Vec<usize> = kept.iter().flat_map(|c| c.faces.iter().copied()).collect();
+        progress!("Writing output to {:?}...", output);
+        write_mesh_with_colors(&components::extract_faces(&mesh, &faces), output, write_options)?;
+    }
+    if let Some(pattern) = split {
+        for (i, component) in kept.iter().enumerate() {
+            let path = PathBuf::from(pattern.replace("{}", &i.to_string()));
+            progress!("Writing component {} to {:?}...", i, path);
+            write_mesh_with_colors(&components::extract_faces(&mesh, &component.faces), &path, write_options)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Drop the components `filter` rejects, before a processing step sees them
+fn remove_islands(mesh: MeshWithColors, filter: &ComponentFilter) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    if !filter.is_active() {
+        return Ok(mesh);
+    }
+    let found = components::find_components(&mesh);
+    let kept: Vec<&components::Component> = found.iter().zip(filter.keeps(&found)).filter(|(_, keep)| *keep).map(|(c, _)| c).collect();
+    let faces: Vec<usize> = kept.iter().flat_map(|c| c.faces.iter().copied()).collect();
+    if faces.is_empty() {
+        return Err("Component filtering removed the whole mesh".into());
+    }
+    progress!(
+        "Removed {} of {} component(s) ({} faces)",
+        found.len() - kept.len(),
+        found.len(),
+        mesh.face_indices.len() - faces.len()
+    );
+    Ok(components::extract_faces(&mesh, &faces))
+}
+
+pub fn show_stats(input: &PathBuf, load_options: &LoadOptions) -> Result<(), Box<dyn std::error::Error>> {
     println!("Loading mesh from {:?}...", input);
-    let mesh = load_mesh(input, mesh_name)?;
+    let mesh = load_mesh(input, load_options)?;
 
     println!("\n=== Mesh Statistics ===");
     println!("Vertices:  {}", mesh.count_vertices());
@@ -371,59 +720,202 @@ pub fn show_stats(input: &PathBuf, mesh_name: Option<&str>) -> Result<(), Box<dy
 
 pub fn check_manifold(
     input: &PathBuf,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
+    intersections: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Loading mesh from {:?}...", input);
-    let mesh = load_mesh(input, mesh_name)?;
+    let source = load_mesh_with_colors(input, load_options)?;
 
     println!("\n=== Manifold Check ===");
     println!("Analyzing mesh topology...\n");
 
+    // Raw index problems first: the corner table can't represent most of them
+    let topology = topology::analyze(&source);
+    print_topology_report(&source, &topology);
+
+    let mesh = if topology.out_of_range_faces.is_empty() {
+        match source.to_corner_table() {
+            Ok(mesh) => Some(mesh),
+            Err(e) => {
+                println!("✗ Could not build mesh topology: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // Check boundary rings (holes)
-    let boundary_rings = mesh.boundary_rings();
+    let boundary_rings = mesh.as_ref().map(|mesh| mesh.boundary_rings()).unwrap_or_default();
 
-    if boundary_rings.is_empty() {
+    if topology.is_clean() && mesh.is_some() && boundary_rings.is_empty() {
         println!("✓ Mesh is MANIFOLD (watertight)");
         println!("  No holes or boundaries detected.");
     } else {
         println!("✗ Mesh is NOT MANIFOLD");
-        println!(
-            "  Found {} boundary ring(s) (holes):\n",
-            boundary_rings.len()
-        );
+        if let Some(mesh) = &mesh
+            && !boundary_rings.is_empty()
+        {
+            println!(
+                "  Found {} boundary ring(s) (holes):\n",
+                boundary_rings.len()
+            );
+
+            for (i, ring) in boundary_rings.iter().enumerate() {
+                let mut edge_count = 0;
+                mesh.boundary_edges(*ring, |_edge| {
+                    edge_count += 1;
+                    std::ops::ControlFlow::Continue(())
+                });
 
-        for (i, ring) in boundary_rings.iter().enumerate() {
-            let mut edge_count = 0;
-            mesh.boundary_edges(*ring, |_edge| {
-                edge_count += 1;
-                std::ops::ControlFlow::Continue(())
-            });
+                println!("  Hole {}: {} boundary edges", i + 1, edge_count);
+            }
 
-            println!("  Hole {}: {} boundary edges", i + 1, edge_count);
+            println!("\nTo fix these holes, run:");
+            println!("  msh fix {:?} --out <output.obj>", input);
         }
+    }
 
-        println!("\nTo fix these holes, run:");
-        println!("  msh fix {:?} --out <output.obj>", input);
+    if intersections {
+        println!("\n=== Self-Intersection Check ===");
+        let pairs = intersect::self_intersections(&source);
+        if pairs.is_empty() {
+            println!("✓ No self-intersections");
+        } else {
+            println!("✗ {} intersecting face pair(s):", pairs.len());
+            for (f, g) in pairs {
+                let (a, b) = (source.face_indices[f], source.face_indices[g]);
+                println!(
+                    "  face {} [{}, {}, {}] x face {} [{}, {}, {}]",
+                    f, a[0], a[1], a[2], g, b[0], b[1], b[2]
+                );
+            }
+            println!("\nTo see them, run:");
+            println!("  msh view {:?} --highlight-intersections", input);
+        }
     }
 
     Ok(())
 }
 
+/// How many offenders of each kind `check` lists
+const CHECK_SAMPLES: usize = 5;
+
+/// Print each kind of index problem with a few sample locations
+fn print_topology_report(mesh: &MeshWithColors, report: &TopologyReport) {
+    let at = |v: u32| {
+        let p = mesh.positions[v as usize];
+        format!("({:.4}, {:.4}, {:.4})", p[0], p[1], p[2])
+    };
+    let face = |f: usize| {
+        let [a, b, c] = mesh.face_indices[f];
+        format!("face {} [{}, {}, {}]", f, a, b, c)
+    };
+    let section = |count: usize, what: &str, samples: Vec<String>| {
+        if count == 0 {
+            return;
+        }
+        println!("✗ {} {}", count, what);
+        for sample in samples {
+            println!("    {}", sample);
+        }
+        if count > CHECK_SAMPLES {
+            println!("    ... and {} more", count - CHECK_SAMPLES);
+        }
+    };
+
+    section(
+        report.non_manifold_edges.len(),
+        "non-manifold edge(s) shared by 3+ faces",
+        report
+            .non_manifold_edges
+            .iter()
+            .take(CHECK_SAMPLES)
+            .map(|&([a, b], n)| format!("edge {}-{} ({} faces) at {} - {}", a, b, n, at(a), at(b)))
+            .collect(),
+    );
+    section(
+        report.non_manifold_vertices.len(),
+        "non-manifold vertex(es) joining separate fans",
+        report
+            .non_manifold_vertices
+            .iter()
+            .take(CHECK_SAMPLES)
+            .map(|&v| format!("vertex {} at {}", v, at(v)))
+            .collect(),
+    );
+    section(
+        report.duplicate_faces.len(),
+        "duplicate face(s)",
+        report
+            .duplicate_faces
+            .iter()
+            .take(CHECK_SAMPLES)
+            .map(|&(first, dup)| format!("{} repeats face {}", face(dup), first))
+            .collect(),
+    );
+    section(
+        report.degenerate_faces.len(),
+        "degenerate (zero-area) face(s)",
+        report
+            .degenerate_faces
+            .iter()
+            .take(CHECK_SAMPLES)
+            .map(|&f| format!("{} at {}", face(f), at(mesh.face_indices[f][0])))
+            .collect(),
+    );
+    section(
+        report.unreferenced_vertices.len(),
+        "unreferenced vertex(es)",
+        report
+            .unreferenced_vertices
+            .iter()
+            .take(CHECK_SAMPLES)
+            .map(|&v| format!("vertex {} at {}", v, at(v)))
+            .collect(),
+    );
+    section(
+        report.out_of_range_faces.len(),
+        &format!("face(s) with indices past the last vertex ({})", mesh.positions.len()),
+        report.out_of_range_faces.iter().take(CHECK_SAMPLES).map(|&f| face(f)).collect(),
+    );
+    if !report.is_clean() {
+        println!();
+    }
+}
+
+/// How `fix_holes` closes holes
+#[derive(Clone, Debug)]
+pub enum FixMethod {
+    /// Voxel-remesh the whole mesh
+    Voxel,
+    /// Patch each hole on its own, leaving the rest of the mesh untouched
+    Local(HoleFillOptions),
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn fix_holes(
     input: &PathBuf,
     output: &PathBuf,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
+    method: &FixMethod,
     voxel_size: f32,
     tolerance: f32,
     no_merge: bool,
+    islands: &ComponentFilter,
+    write_options: &WriteOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Loading mesh from {:?}...", input);
-    let mut mesh = load_mesh(input, mesh_name)?;
+    progress!("Loading mesh from {:?}...", input);
+    let source = remove_islands(load_mesh_with_colors(input, load_options)?, islands)?;
+    if let FixMethod::Local(options) = method {
+        return fix_holes_locally(source, output, options, write_options);
+    }
+    let mut mesh = source.to_corner_table()?;
 
     let vertex_count_initial = mesh.count_vertices();
     let face_count_initial = mesh.count_faces();
 
-    println!(
+    progress!(
         "Initial: {} vertices, {} faces",
         vertex_count_initial, face_count_initial
     );
@@ -431,7 +923,7 @@ pub fn fix_holes(
     // Merge close vertices first (unless disabled)
     if !no_merge {
         mesh = merge_close_vertices(&mesh, tolerance)?;
-        println!(
+        progress!(
             "After merging: {} vertices, {} faces",
             mesh.count_vertices(),
             mesh.count_faces()
@@ -441,21 +933,21 @@ pub fn fix_holes(
     // Check if mesh needs fixing
     let boundary_rings = mesh.boundary_rings();
     if boundary_rings.is_empty() {
-        println!("Mesh is already manifold (watertight). No fixing needed.");
+        progress!("Mesh is already manifold (watertight). No fixing needed.");
 
-        // Still write the output if we merged vertices
-        if !no_merge && mesh.count_vertices() < vertex_count_initial {
-            println!("Writing merged mesh to {:?}...", output);
-            write_to_file(&mesh, output).map_err(|e| format!("Failed to write mesh: {:?}", e))?;
-            println!("Done!");
+        // Still write the output if we merged vertices, or if it is piped onward
+        if stdio::is_std(output) || (!no_merge && mesh.count_vertices() < vertex_count_initial) {
+            progress!("Writing merged mesh to {:?}...", output);
+            write_mesh(&mesh, source, output, write_options)?;
+            progress!("Done!");
         }
 
         return Ok(());
     }
 
-    println!("Found {} hole(s) in mesh", boundary_rings.len());
-    println!("Fixing holes using voxel remeshing...");
-    println!("Voxel size: {}", voxel_size);
+    progress!("Found {} hole(s) in mesh", boundary_rings.len());
+    progress!("Fixing holes using voxel remeshing...");
+    progress!("Voxel size: {}", voxel_size);
 
     // Use voxel remeshing with Manifold method to close holes
     let mut remesher = VoxelRemesher::default()
@@ -467,7 +959,7 @@ pub fn fix_holes(
     let vertex_count_after = fixed_mesh.count_vertices();
     let face_count_after = fixed_mesh.count_faces();
 
-    println!(
+    progress!(
         "After: {} vertices, {} faces",
         vertex_count_after, face_count_after
     );
@@ -475,17 +967,68 @@ pub fn fix_holes(
     // Verify the result
     let boundary_rings_after = fixed_mesh.boundary_rings();
     if boundary_rings_after.is_empty() {
-        println!("✓ Mesh is now manifold!");
+        progress!("✓ Mesh is now manifold!");
     } else {
-        println!(
+        progress!(
             "⚠ Warning: {} hole(s) remain (may need smaller voxel size)",
             boundary_rings_after.len()
         );
     }
 
-    println!("Writing output to {:?}...", output);
-    write_to_file(&fixed_mesh, output).map_err(|e| format!("Failed to write mesh: {:?}", e))?;
+    progress!("Writing output to {:?}...", output);
+    write_mesh(&fixed_mesh, source, output, write_options)?;
+
+    progress!("Done!");
+    Ok(())
+}
+
+/// Patch holes in the loaded mesh itself, so faces outside the holes keep
+/// their vertices and corner attributes exactly
+fn fix_holes_locally(
+    source: MeshWithColors,
+    output: &PathBuf,
+    options: &HoleFillOptions,
+    write_options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    progress!(
+        "Initial: {} vertices, {} faces",
+        source.positions.len(),
+        source.face_indices.len()
+    );
+    progress!("Filling holes locally ({:?} triangulation)...", options.triangulation);
+    let (filled, report) = holes::fill_holes(&source, options)?;
+    if report.borders > 0 {
+        progress!("Left {} outer border(s) open; pass --fill-border to cap them", report.borders);
+    }
+    if report.filled + report.skipped == 0 {
+        progress!("No holes to fill.");
+        if stdio::is_std(output) {
+            write_mesh_with_colors(&source, output, write_options)?;
+        }
+        return Ok(());
+    }
+
+    progress!(
+        "Filled {} hole(s) with {} faces and {} new vertices",
+        report.filled, report.faces_added, report.vertices_added
+    );
+    progress!(
+        "After: {} vertices, {} faces",
+        filled.positions.len(),
+        filled.face_indices.len()
+    );
+    if report.skipped > 0 {
+        eprintln!(
+            "Warning: left {} hole(s) open (over the edge limit, or with a boundary that touches itself)",
+            report.skipped
+        );
+    } else if report.borders == 0 {
+        progress!("✓ Mesh is now manifold!");
+    }
+
+    progress!("Writing output to {:?}...", output);
+    write_mesh_with_colors(&filled, output, write_options)?;
 
-    println!("Done!");
+    progress!("Done!");
     Ok(())
 }
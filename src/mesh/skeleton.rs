@@ -1,6 +1,7 @@
 use nalgebra::{Matrix4, Quaternion, UnitQuaternion, Vector3};
 
 /// A single joint in a skeleton hierarchy.
+#[derive(Clone)]
 pub struct Joint {
     /// Index in the skeleton's joints array (NOT the glTF node index).
     pub index: usize,
@@ -17,6 +18,7 @@ pub struct Joint {
 }
 
 /// Local transform for a joint node, matching glTF's two representations.
+#[derive(Clone)]
 pub enum JointTransform {
     /// Translation, rotation (quaternion xyzw), and scale.
     Decomposed {
@@ -29,6 +31,7 @@ pub enum JointTransform {
 }
 
 /// A skeleton extracted from a glTF skin.
+#[derive(Clone)]
 pub struct Skeleton {
     /// Ordered list of joints (index in this vec == joint index used in JOINTS_0).
     pub joints: Vec<Joint>,
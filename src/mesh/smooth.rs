@@ -0,0 +1,276 @@
+//! Laplacian mesh smoothing on `CornerTableF`.
+//!
+//! Each pass moves every free vertex a fraction `lambda` of the way towards
+//! the weighted average of its neighbours. Taubin smoothing follows every
+//! such pass with a negative `mu` pass, which cancels the shrinking of plain
+//! Laplacian smoothing.
+//!
+//! Sharp edges (dihedral angle above the feature angle) and open boundaries
+//! are creases: a vertex on exactly two crease edges only slides along them,
+//! and a vertex where creases meet or end stays put.
+
+use baby_shark::exports::nalgebra::Vector3;
+use baby_shark::mesh::corner_table::{CornerTableF, VertexId};
+use std::collections::HashMap;
+
+/// How neighbours are weighted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SmoothMethod {
+    /// Every neighbour counts the same
+    Uniform,
+    /// Cotangent weights, which follow the surface instead of the tessellation
+    Cotangent,
+    /// Uniform weights with alternating `lambda` and `mu` passes
+    Taubin,
+}
+
+/// Options controlling smoothing
+#[derive(Clone, Debug)]
+pub struct SmoothOptions {
+    pub method: SmoothMethod,
+    pub iterations: usize,
+    /// Step towards the neighbour average, in (0, 1]
+    pub lambda: f32,
+    /// Taubin's inflating step; negative, at least as large in magnitude as
+    /// `lambda` and no larger than 1
+    pub mu: f32,
+    /// Keep boundary vertices in place instead of sliding them along the boundary
+    pub lock_boundary: bool,
+    /// Dihedral angle in degrees above which an edge is kept sharp
+    pub feature_angle: Option<f32>,
+}
+
+impl Default for SmoothOptions {
+    fn default() -> Self {
+        SmoothOptions {
+            method: SmoothMethod::Uniform,
+            iterations: 10,
+            lambda: 0.5,
+            mu: -0.53,
+            lock_boundary: false,
+            feature_angle: None,
+        }
+    }
+}
+
+impl SmoothOptions {
+    /// Check `lambda`, and `mu` for Taubin, are steps that converge
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if !(self.lambda > 0.0 && self.lambda <= 1.0) {
+            return Err(format!("Smoothing lambda {} is outside (0, 1]", self.lambda).into());
+        }
+        if self.method == SmoothMethod::Taubin && !(self.mu >= -1.0 && self.mu <= -self.lambda) {
+            return Err(format!("Taubin mu {} is outside [-1, -lambda] = [-1, {}]", self.mu, -self.lambda).into());
+        }
+        Ok(())
+    }
+}
+
+/// How many vertices the creases held back
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmoothReport {
+    /// Vertices that never moved
+    pub locked: usize,
+    /// Vertices that only slid along a crease
+    pub creased: usize,
+}
+
+/// Which neighbours a vertex is averaged over
+enum Stencil {
+    Locked,
+    Crease([usize; 2]),
+    Free(Vec<usize>),
+}
+
+/// Smooth `mesh` in place
+pub fn smooth(mesh: &mut CornerTableF, options: &SmoothOptions) -> SmoothReport {
+    let vertices: Vec<VertexId> = mesh.vertices().collect();
+    let index: HashMap<VertexId, usize> = vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let faces: Vec<[usize; 3]> = mesh
+        .faces()
+        .map(|face| {
+            let (a, b, c) = mesh.face_vertices(face);
+            [index[&a], index[&b], index[&c]]
+        })
+        .collect();
+
+    // Crease edges around each vertex: boundaries, plus sharp edges
+    let cos_feature = options.feature_angle.map(|deg| deg.to_radians().cos());
+    let mut creases: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for edge in mesh.unique_edges() {
+        let sharp = match mesh.edge_faces(edge) {
+            (_, None) => true,
+            (f1, Some(f2)) => match (cos_feature, mesh.face_normal(f1), mesh.face_normal(f2)) {
+                (Some(cos), Some(n1), Some(n2)) => n1.dot(&n2) < cos,
+                _ => false,
+            },
+        };
+        if sharp {
+            let (a, b) = mesh.edge_vertices(edge);
+            let (a, b) = (index[&a], index[&b]);
+            creases[a].push(b);
+            creases[b].push(a);
+        }
+    }
+
+    let mut report = SmoothReport::default();
+    let stencils: Vec<Stencil> = vertices
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let stencil = if options.lock_boundary && mesh.is_vertex_on_boundary(v) {
+                Stencil::Locked
+            } else {
+                match creases[i][..] {
+                    [] => {
+                        let mut neighbours = Vec::new();
+                        mesh.vertices_around_vertex(v, |n| neighbours.push(index[&n]));
+                        Stencil::Free(neighbours)
+                    }
+                    [a, b] => Stencil::Crease([a, b]),
+                    _ => Stencil::Locked,
+                }
+            };
+            match stencil {
+                Stencil::Locked => report.locked += 1,
+                Stencil::Crease(_) => report.creased += 1,
+                Stencil::Free(_) => {}
+            }
+            stencil
+        })
+        .collect();
+
+    let mut positions: Vec<Vector3<f32>> = vertices.iter().map(|&v| *mesh.vertex_position(v)).collect();
+    let steps: &[f32] = match options.method {
+        SmoothMethod::Taubin => &[options.lambda, options.mu],
+        _ => &[options.lambda],
+    };
+    for _ in 0..options.iterations {
+        for &step in steps {
+            let weights = match options.method {
+                SmoothMethod::Cotangent => Some(cotangent_weights(&positions, &faces)),
+                _ => None,
+            };
+            positions = laplacian_step(&positions, &stencils, weights.as_ref(), step);
+        }
+    }
+
+    for (&v, p) in vertices.iter().zip(positions) {
+        mesh[v].set_position(p);
+    }
+    report
+}
+
+/// Move each vertex `step` of the way towards the weighted average of its stencil
+fn laplacian_step(
+    positions: &[Vector3<f32>],
+    stencils: &[Stencil],
+    weights: Option<&HashMap<(usize, usize), f32>>,
+    step: f32,
+) -> Vec<Vector3<f32>> {
+    let weight = |a: usize, b: usize| match weights {
+        Some(weights) => weights.get(&(a.min(b), a.max(b))).copied().unwrap_or(0.0).max(0.0),
+        None => 1.0,
+    };
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| {
+            let neighbours: &[usize] = match &stencils[i] {
+                Stencil::Locked => return p,
+                // A crease is a curve; surface weights don't apply along it
+                Stencil::Crease(pair) => return p + (positions[pair[0]] + positions[pair[1]] - p * 2.0) * 0.5 * step,
+                Stencil::Free(neighbours) => neighbours,
+            };
+            let mut sum = Vector3::zeros();
+            let mut total = 0.0;
+            for &n in neighbours {
+                let w = weight(i, n);
+                sum += positions[n] * w;
+                total += w;
+            }
+            if total <= f32::EPSILON {
+                // Degenerate fan: fall back to uniform weights
+                sum = neighbours.iter().map(|&n| positions[n]).sum();
+                total = neighbours.len() as f32;
+            }
+            if total <= 0.0 { p } else { p + (sum / total - p) * step }
+        })
+        .collect()
+}
+
+/// Cotangent weight of each edge, keyed by its sorted vertex pair
+fn cotangent_weights(positions: &[Vector3<f32>], faces: &[[usize; 3]]) -> HashMap<(usize, usize), f32> {
+    let mut weights = HashMap::new();
+    for face in faces {
+        for k in 0..3 {
+            let (a, b, c) = (face[k], face[(k + 1) % 3], face[(k + 2) % 3]);
+            // Angle at `a` is opposite the edge (b, c)
+            let (u, v) = (positions[b] - positions[a], positions[c] - positions[a]);
+            let sin = u.cross(&v).norm();
+            if sin <= f32::EPSILON {
+                continue;
+            }
+            *weights.entry((b.min(c), b.max(c))).or_insert(0.0) += 0.5 * u.dot(&v) / sin;
+        }
+    }
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube() -> CornerTableF {
+        let positions: Vec<Vector3<f32>> = (0..8)
+            .map(|i| Vector3::new((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32))
+            .collect();
+        let faces = [
+            0, 2, 1, 1, 2, 3, 4, 5, 6, 5, 7, 6, 0, 1, 4, 1, 5, 4, 2, 6, 3, 3, 6, 7, 0, 4, 2, 2, 4, 6, 1, 3, 5, 3, 7, 5,
+        ];
+        CornerTableF::from_vertex_and_face_slices(&positions, &faces)
+    }
+
+    fn extent(mesh: &CornerTableF) -> f32 {
+        let xs: Vec<f32> = mesh.vertices().map(|v| mesh.vertex_position(v).x).collect();
+        xs.iter().copied().fold(f32::NEG_INFINITY, f32::max) - xs.iter().copied().fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn feature_angle_keeps_cube_corners() {
+        let mut mesh = cube();
+        let report = smooth(&mut mesh, &SmoothOptions { feature_angle: Some(30.0), ..Default::default() });
+        assert_eq!(report.locked, 8);
+        assert_eq!(extent(&mesh), 1.0);
+
+        let mut mesh = cube();
+        smooth(&mut mesh, &SmoothOptions::default());
+        assert!(extent(&mesh) < 0.5);
+    }
+
+    #[test]
+    fn taubin_shrinks_less_than_laplacian() {
+        let shrunk = |method| {
+            let mut mesh = cube();
+            smooth(&mut mesh, &SmoothOptions { method, iterations: 5, ..Default::default() });
+            extent(&mesh)
+        };
+        let uniform = shrunk(SmoothMethod::Uniform);
+        assert!(shrunk(SmoothMethod::Taubin) > uniform);
+        assert!(shrunk(SmoothMethod::Cotangent) < 1.0);
+    }
+
+    #[test]
+    fn rejects_diverging_steps() {
+        let taubin = SmoothOptions { method: SmoothMethod::Taubin, ..Default::default() };
+        assert!(taubin.validate().is_ok());
+        for lambda in [0.0, 5.0, f32::NAN] {
+            assert!(SmoothOptions { lambda, ..Default::default() }.validate().is_err());
+        }
+        for mu in [0.5, -0.4, -1.5] {
+            assert!(SmoothOptions { mu, ..taubin.clone() }.validate().is_err());
+        }
+        // mu only matters for Taubin
+        assert!(SmoothOptions { mu: 0.5, ..Default::default() }.validate().is_ok());
+    }
+}
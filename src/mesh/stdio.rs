@@ -0,0 +1,93 @@
+//! `-` as a path: reading meshes from stdin and writing them to stdout.
+//!
+//! Stdin is read once and cached, since some commands load their input more
+//! than once. Once stdout carries mesh data, progress messages printed with
+//! [`progress!`] go to stderr instead so they don't corrupt the output.
+
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STDIN: OnceLock<Vec<u8>> = OnceLock::new();
+static STDOUT_IS_DATA: AtomicBool = AtomicBool::new(false);
+
+/// Whether `path` is `-`, meaning stdin or stdout
+pub fn is_std(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// The whole of stdin, read on first use
+pub fn stdin_bytes() -> std::io::Result<&'static [u8]> {
+    if let Some(bytes) = STDIN.get() {
+        return Ok(bytes);
+    }
+    let mut bytes = Vec::new();
+    std::io::stdin().lock().read_to_end(&mut bytes)?;
+    Ok(STDIN.get_or_init(|| bytes))
+}
+
+/// Read a file, or stdin for `-`
+pub fn read(path: &Path) -> std::io::Result<Vec<u8>> {
+    if is_std(path) {
+        Ok(stdin_bytes()?.to_vec())
+    } else {
+        std::fs::read(path)
+    }
+}
+
+/// Read a text file, or stdin for `-`
+pub fn read_to_string(path: &Path) -> std::io::Result<String> {
+    String::from_utf8(read(path)?)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Create a file for writing, or lock stdout for `-`
+pub fn create(path: &Path) -> std::io::Result<Box<dyn Write>> {
+    if is_std(path) {
+        Ok(Box::new(std::io::BufWriter::new(std::io::stdout().lock())))
+    } else {
+        Ok(Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)))
+    }
+}
+
+/// Write a whole file, or stdout for `-`
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut file = create(path)?;
+    file.write_all(contents.as_ref())?;
+    file.flush()
+}
+
+/// Reserve stdout for output data, sending progress messages to stderr
+pub fn reserve_stdout() {
+    STDOUT_IS_DATA.store(true, Ordering::Relaxed);
+}
+
+/// Whether stdout has been reserved for output data
+pub fn stdout_reserved() -> bool {
+    STDOUT_IS_DATA.load(Ordering::Relaxed)
+}
+
+/// `println!` for progress messages, which moves to stderr while stdout carries data
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if $crate::mesh::stdio::stdout_reserved() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use progress;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_means_standard_streams() {
+        assert!(is_std(Path::new("-")));
+        assert!(!is_std(Path::new("./-")));
+        assert!(!is_std(Path::new("mesh.obj")));
+    }
+}
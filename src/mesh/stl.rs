@@ -0,0 +1,355 @@
+//! STL reader and writer (binary and ASCII).
+//!
+//! Binary vs ASCII is detected from the file contents rather than the extension.
+//! Binary files may carry a per-face color in the two attribute bytes of each
+//! triangle, following the VisCAM/SolidView convention:
+//! bits 0-4 blue, 5-9 green, 10-14 red, bit 15 set when the color is valid.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use super::loader::MeshWithColors;
+use super::stdio;
+
+/// Default color for faces without a valid attribute color
+const DEFAULT_FACE_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+
+/// Size of the binary header plus the triangle count
+const BINARY_HEADER_LEN: usize = 84;
+/// Size of one binary triangle record (normal + 3 vertices + attribute bytes)
+const BINARY_TRIANGLE_LEN: usize = 50;
+/// Bit 15 marks a valid VisCAM/SolidView color
+const COLOR_VALID_BIT: u16 = 0x8000;
+
+/// Load an STL file (binary or ASCII, detected from contents)
+pub fn load_stl(path: &Path) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let bytes = stdio::read(path)?;
+    parse_stl(&bytes)
+}
+
+/// Parse STL bytes, detecting binary vs ASCII from the contents
+pub fn parse_stl(bytes: &[u8]) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    if is_binary_stl(bytes) {
+        parse_binary_stl(bytes)
+    } else {
+        let text = std::str::from_utf8(bytes).map_err(|_| "STL file is neither valid binary nor ASCII")?;
+        parse_ascii_stl(text)
+    }
+}
+
+/// Decide whether the bytes are a binary STL.
+///
+/// Many binary exporters start the 80-byte header with "solid", so the header
+/// alone is not enough: a file whose size matches the declared triangle count
+/// exactly is treated as binary.
+fn is_binary_stl(bytes: &[u8]) -> bool {
+    if matches_binary_size(bytes, bytes.len() as u64) {
+        return true;
+    }
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(bytes.len());
+    !bytes[start..].starts_with(b"solid")
+}
+
+/// Whether a file of `len` bytes starting with `head` has exactly the size its
+/// binary STL triangle count declares
+pub(crate) fn matches_binary_size(head: &[u8], len: u64) -> bool {
+    if head.len() < BINARY_HEADER_LEN {
+        return false;
+    }
+    let count = u32::from_le_bytes([head[80], head[81], head[82], head[83]]) as u64;
+    BINARY_HEADER_LEN as u64 + count * BINARY_TRIANGLE_LEN as u64 == len
+}
+
+fn parse_binary_stl(bytes: &[u8]) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    if bytes.len() < BINARY_HEADER_LEN {
+        return Err("Binary STL is truncated (missing header)".into());
+    }
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    let expected = BINARY_HEADER_LEN + count * BINARY_TRIANGLE_LEN;
+    if bytes.len() < expected {
+        return Err(format!(
+            "Binary STL is truncated: header declares {} triangles ({} bytes), file has {} bytes",
+            count,
+            expected,
+            bytes.len()
+        )
+        .into());
+    }
+
+    let read_f32 = |offset: usize| {
+        f32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+    };
+
+    let mut welder = VertexWelder::default();
+    let mut face_indices: Vec<[u32; 3]> = Vec::with_capacity(count);
+    let mut face_colors: Vec<[f32; 4]> = Vec::with_capacity(count);
+    let mut has_colors = false;
+
+    for i in 0..count {
+        let base = BINARY_HEADER_LEN + i * BINARY_TRIANGLE_LEN;
+        // Skip the 12-byte facet normal; it is recomputed on write
+        let mut tri = [0u32; 3];
+        for (corner, idx) in tri.iter_mut().enumerate() {
+            let v = base + 12 + corner * 12;
+            *idx = welder.add([read_f32(v), read_f32(v + 4), read_f32(v + 8)]);
+        }
+        face_indices.push(tri);
+
+        let attribute = u16::from_le_bytes([bytes[base + 48], bytes[base + 49]]);
+        if attribute & COLOR_VALID_BIT != 0 {
+            has_colors = true;
+            face_colors.push(decode_color(attribute));
+        } else {
+            face_colors.push(DEFAULT_FACE_COLOR);
+        }
+    }
+
+    if !has_colors {
+        face_colors.clear();
+    }
+
+    Ok(MeshWithColors {
+        positions: welder.positions,
+        face_indices,
+        face_colors,
+        ..Default::default()
+    })
+}
+
+fn parse_ascii_stl(text: &str) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let mut welder = VertexWelder::default();
+    let mut face_indices: Vec<[u32; 3]> = Vec::new();
+    let mut facet: Vec<u32> = Vec::with_capacity(3);
+
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if let Some(coords) = line.strip_prefix("vertex") {
+            let parts: Vec<f32> = coords
+                .split_whitespace()
+                .map(|s| s.parse::<f32>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("Invalid vertex on line {}: {}", line_no + 1, line))?;
+            if parts.len() != 3 {
+                return Err(format!("Invalid vertex on line {}: {}", line_no + 1, line).into());
+            }
+            facet.push(welder.add([parts[0], parts[1], parts[2]]));
+        } else if line.starts_with("endloop") {
+            // Fan triangulation for (non-standard) polygonal facets
+            for i in 1..facet.len().saturating_sub(1) {
+                face_indices.push([facet[0], facet[i], facet[i + 1]]);
+            }
+            facet.clear();
+        }
+    }
+
+    Ok(MeshWithColors {
+        positions: welder.positions,
+        face_indices,
+        ..Default::default()
+    })
+}
+
+/// Decode a VisCAM/SolidView 15-bit color
+fn decode_color(attribute: u16) -> [f32; 4] {
+    let b = (attribute & 0x1f) as f32 / 31.0;
+    let g = ((attribute >> 5) & 0x1f) as f32 / 31.0;
+    let r = ((attribute >> 10) & 0x1f) as f32 / 31.0;
+    [r, g, b, 1.0]
+}
+
+/// Encode a color as a VisCAM/SolidView 15-bit color with the valid bit set
+fn encode_color(color: [f32; 4]) -> u16 {
+    let quantize = |c: f32| ((c.clamp(0.0, 1.0) * 31.0).round() as u16) & 0x1f;
+    COLOR_VALID_BIT | (quantize(color[0]) << 10) | (quantize(color[1]) << 5) | quantize(color[2])
+}
+
+/// Merges bit-identical positions so STL's per-triangle vertices become an indexed mesh
+#[derive(Default)]
+struct VertexWelder {
+    positions: Vec<[f32; 3]>,
+    lookup: HashMap<[u32; 3], u32>,
+}
+
+impl VertexWelder {
+    fn add(&mut self, pos: [f32; 3]) -> u32 {
+        // Normalize -0.0 so it welds with 0.0
+        let pos = pos.map(|c| if c == 0.0 { 0.0 } else { c });
+        let key = pos.map(f32::to_bits);
+        *self.lookup.entry(key).or_insert_with(|| {
+            self.positions.push(pos);
+            (self.positions.len() - 1) as u32
+        })
+    }
+}
+
+fn face_normal(mesh: &MeshWithColors, tri: &[u32; 3]) -> [f32; 3] {
+    let p0 = mesh.positions[tri[0] as usize];
+    let p1 = mesh.positions[tri[1] as usize];
+    let p2 = mesh.positions[tri[2] as usize];
+    let e1 = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+    let e2 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+    let n = [
+        e1[1] * e2[2] - e1[2] * e2[1],
+        e1[2] * e2[0] - e1[0] * e2[2],
+        e1[0] * e2[1] - e1[1] * e2[0],
+    ];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if len > 0.0 {
+        [n[0] / len, n[1] / len, n[2] / len]
+    } else {
+        [0.0, 0.0, 0.0]
+    }
+}
+
+/// Write a mesh as STL. Per-face colors are stored in the binary attribute bytes.
+pub fn write_stl<W: Write>(
+    mesh: &MeshWithColors,
+    writer: &mut W,
+    ascii: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dropped: Vec<&str> = [
+        (ascii && !mesh.face_colors.is_empty(), "face colors"),
+        (!mesh.texcoords.is_empty() || !mesh.corner_texcoords.is_empty(), "UVs"),
+        (!mesh.normals.is_empty() || !mesh.corner_normals.is_empty(), "vertex normals"),
+        (mesh.texture.is_some(), "texture"),
+        (!mesh.vertex_colors.is_empty(), "vertex colors"),
+        (mesh.skeleton.is_some(), "skin"),
+        (!mesh.animations.is_empty(), "animations"),
+        (!mesh.morph_targets.is_empty(), "morph targets"),
+    ]
+    .into_iter()
+    .filter_map(|(present, name)| present.then_some(name))
+    .collect();
+    if !dropped.is_empty() {
+        let kind = if ascii { "ASCII STL" } else { "STL" };
+        eprintln!("Warning: {} output does not store {}; dropping", kind, dropped.join(", "));
+    }
+
+    if ascii {
+        write_ascii_stl(mesh, writer)
+    } else {
+        write_binary_stl(mesh, writer)
+    }
+}
+
+fn write_binary_stl<W: Write>(
+    mesh: &MeshWithColors,
+    writer: &mut W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let count = u32::try_from(mesh.face_indices.len()).map_err(|_| "Too many faces for binary STL")?;
+    let has_colors = mesh.face_colors.len() == mesh.face_indices.len() && !mesh.face_colors.is_empty();
+
+    let mut header = [0u8; 80];
+    let label = b"binary STL written by msh";
+    header[..label.len()].copy_from_slice(label);
+    writer.write_all(&header)?;
+    writer.write_all(&count.to_le_bytes())?;
+
+    for (face_i, tri) in mesh.face_indices.iter().enumerate() {
+        for c in face_normal(mesh, tri) {
+            writer.write_all(&c.to_le_bytes())?;
+        }
+        for &vi in tri {
+            for c in mesh.positions[vi as usize] {
+                writer.write_all(&c.to_le_bytes())?;
+            }
+        }
+        let attribute = if has_colors {
+            encode_color(mesh.face_colors[face_i])
+        } else {
+            0
+        };
+        writer.write_all(&attribute.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_ascii_stl<W: Write>(
+    mesh: &MeshWithColors,
+    writer: &mut W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    writeln!(writer, "solid msh")?;
+    for tri in &mesh.face_indices {
+        let n = face_normal(mesh, tri);
+        writeln!(writer, "  facet normal {:e} {:e} {:e}", n[0], n[1], n[2])?;
+        writeln!(writer, "    outer loop")?;
+        for &vi in tri {
+            let p = mesh.positions[vi as usize];
+            writeln!(writer, "      vertex {:e} {:e} {:e}", p[0], p[1], p[2])?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid msh")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> MeshWithColors {
+        MeshWithColors {
+            positions: vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ],
+            face_indices: vec![[0, 1, 2], [0, 2, 3]],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn binary_round_trip_welds_vertices() {
+        let mut buf = Vec::new();
+        write_stl(&quad(), &mut buf, false).unwrap();
+        assert_eq!(buf.len(), BINARY_HEADER_LEN + 2 * BINARY_TRIANGLE_LEN);
+
+        let mesh = parse_stl(&buf).unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2], [0, 2, 3]]);
+        assert!(mesh.face_colors.is_empty());
+    }
+
+    #[test]
+    fn ascii_round_trip() {
+        let mut buf = Vec::new();
+        write_stl(&quad(), &mut buf, true).unwrap();
+        assert!(buf.starts_with(b"solid"));
+
+        let mesh = parse_stl(&buf).unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.face_indices.len(), 2);
+    }
+
+    #[test]
+    fn binary_with_solid_header_is_detected() {
+        let mut buf = Vec::new();
+        write_stl(&quad(), &mut buf, false).unwrap();
+        buf[..5].copy_from_slice(b"solid");
+        assert!(is_binary_stl(&buf));
+        assert_eq!(parse_stl(&buf).unwrap().face_indices.len(), 2);
+    }
+
+    #[test]
+    fn face_colors_round_trip() {
+        let mut mesh = quad();
+        mesh.face_colors = vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]];
+        let mut buf = Vec::new();
+        write_stl(&mesh, &mut buf, false).unwrap();
+
+        let loaded = parse_stl(&buf).unwrap();
+        assert_eq!(loaded.face_colors, mesh.face_colors);
+    }
+
+    #[test]
+    fn color_encoding() {
+        assert_eq!(encode_color([1.0, 0.0, 0.0, 1.0]), 0x8000 | (31 << 10));
+        assert_eq!(decode_color(0x8000 | 31), [0.0, 0.0, 1.0, 1.0]);
+    }
+}
@@ -0,0 +1,342 @@
+//! Loop and midpoint subdivision of triangle meshes.
+//!
+//! Every level splits each triangle into four at its edge midpoints. Loop
+//! subdivision then moves the vertices with the Loop stencils, while
+//! midpoint subdivision leaves the surface as it was. Boundaries,
+//! non-manifold edges and edges sharper than the crease angle are creases:
+//! they are subdivided as curves, and vertices where creases meet or end
+//! stay put.
+//!
+//! The stencils run on positions welded across UV seams, so seams don't tear
+//! the surface. UVs, vertex colors, skin weights and morph deltas are
+//! interpolated linearly per face, so seams stay split in the output; face
+//! colors pass to all four children.
+
+use std::collections::HashMap;
+
+use super::loader::{MeshWithColors, MorphTarget};
+
+/// How new vertex positions are computed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubdivisionScheme {
+    /// Loop's smooth stencils, converging to a C2 surface away from creases
+    Loop,
+    /// Split at edge midpoints without moving the surface
+    Midpoint,
+}
+
+/// Options controlling subdivision
+#[derive(Clone, Debug)]
+pub struct SubdivideOptions {
+    pub scheme: SubdivisionScheme,
+    /// Number of times to subdivide; each multiplies the face count by four
+    pub levels: usize,
+    /// Dihedral angle in degrees above which an edge is kept as a crease
+    pub crease_angle: Option<f32>,
+}
+
+/// Subdivide `mesh` `options.levels` times
+pub fn subdivide(mesh: &MeshWithColors, options: &SubdivideOptions) -> MeshWithColors {
+    let mut result = mesh.with_vertex_attributes().into_owned();
+    // Vertex normals no longer match the moved surface
+    result.normals.clear();
+    let cos_crease = options.crease_angle.map(|deg| deg.to_radians().cos() as f64);
+    for _ in 0..options.levels {
+        result = subdivide_once(&result, options.scheme, cos_crease);
+    }
+    result
+}
+
+/// Edge of the welded mesh, with the vertex opposite it in each incident face
+#[derive(Default)]
+struct WeldedEdge {
+    opposite: Vec<usize>,
+    faces: Vec<usize>,
+}
+
+fn subdivide_once(mesh: &MeshWithColors, scheme: SubdivisionScheme, cos_crease: Option<f64>) -> MeshWithColors {
+    let n = mesh.positions.len();
+
+    // Weld vertices split by attribute seams
+    let mut weld_index: HashMap<[u32; 3], usize> = HashMap::new();
+    let mut welded: Vec<[f64; 3]> = Vec::new();
+    let weld: Vec<usize> = mesh
+        .positions
+        .iter()
+        .map(|p| {
+            *weld_index.entry(p.map(f32::to_bits)).or_insert_with(|| {
+                welded.push(p.map(f64::from));
+                welded.len() - 1
+            })
+        })
+        .collect();
+
+    let face_normals: Vec<[f64; 3]> = mesh
+        .face_indices
+        .iter()
+        .map(|f| {
+            let [a, b, c] = f.map(|i| welded[weld[i as usize]]);
+            normalize(cross(sub(b, a), sub(c, a)))
+        })
+        .collect();
+
+    let mut edges: HashMap<(usize, usize), WeldedEdge> = HashMap::new();
+    for (fi, f) in mesh.face_indices.iter().enumerate() {
+        let w = f.map(|i| weld[i as usize]);
+        for k in 0..3 {
+            let (a, b, c) = (w[k], w[(k + 1) % 3], w[(k + 2) % 3]);
+            let edge = edges.entry((a.min(b), a.max(b))).or_default();
+            edge.opposite.push(c);
+            edge.faces.push(fi);
+        }
+    }
+    let is_crease = |edge: &WeldedEdge| match edge.faces[..] {
+        [f1, f2] => cos_crease.is_some_and(|cos| dot(face_normals[f1], face_normals[f2]) < cos),
+        _ => true,
+    };
+
+    // New positions: edge points, then the moved original vertices
+    let mut edge_points: HashMap<(usize, usize), [f64; 3]> = HashMap::new();
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); welded.len()];
+    let mut creases: Vec<Vec<usize>> = vec![Vec::new(); welded.len()];
+    for (&(a, b), edge) in &edges {
+        let crease = is_crease(edge);
+        let point = match (scheme, &edge.opposite[..]) {
+            (SubdivisionScheme::Loop, &[c, d]) if !crease => add(
+                scale(add(welded[a], welded[b]), 3.0 / 8.0),
+                scale(add(welded[c], welded[d]), 1.0 / 8.0),
+            ),
+            _ => scale(add(welded[a], welded[b]), 0.5),
+        };
+        edge_points.insert((a, b), point);
+        neighbours[a].push(b);
+        neighbours[b].push(a);
+        if crease {
+            creases[a].push(b);
+            creases[b].push(a);
+        }
+    }
+    let moved: Vec<[f64; 3]> = match scheme {
+        SubdivisionScheme::Midpoint => welded.clone(),
+        SubdivisionScheme::Loop => (0..welded.len())
+            .map(|v| match (&creases[v][..], neighbours[v].len()) {
+                (_, 0) => welded[v],
+                ([], valence) => {
+                    let k = valence as f64;
+                    let t = 3.0 / 8.0 + 0.25 * (2.0 * std::f64::consts::PI / k).cos();
+                    let beta = (5.0 / 8.0 - t * t) / k;
+                    let sum = neighbours[v].iter().fold([0.0; 3], |s, &u| add(s, welded[u]));
+                    add(scale(welded[v], 1.0 - k * beta), scale(sum, beta))
+                }
+                (&[a, b], _) => add(scale(welded[v], 0.75), scale(add(welded[a], welded[b]), 0.125)),
+                // Corners and crease ends stay put
+                _ => welded[v],
+            })
+            .collect(),
+    };
+
+    // Output: every input vertex keeps its attributes, plus one vertex per
+    // (unwelded) edge with attributes halfway between its ends
+    let mut result = MeshWithColors {
+        positions: weld.iter().map(|&w| moved[w].map(|x| x as f32)).collect(),
+        vertex_colors: mesh.vertex_colors.clone(),
+        texcoords: mesh.texcoords.clone(),
+        joint_indices: mesh.joint_indices.clone(),
+        joint_weights: mesh.joint_weights.clone(),
+        morph_targets: mesh.morph_targets.clone(),
+        morph_weights: mesh.morph_weights.clone(),
+        texture: mesh.texture.clone(),
+        skeleton: mesh.skeleton.clone(),
+        animations: mesh.animations.clone(),
+        ..Default::default()
+    };
+    let has_colors = mesh.vertex_colors.len() == n;
+    let has_uvs = mesh.texcoords.len() == n;
+    let has_skin = mesh.joint_indices.len() == n && mesh.joint_weights.len() == n;
+    let has_face_colors = mesh.face_colors.len() == mesh.face_indices.len();
+
+    let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut midpoint = |result: &mut MeshWithColors, a: u32, b: u32| -> u32 {
+        let key = (a.min(b), a.max(b));
+        if let Some(&m) = midpoints.get(&key) {
+            return m;
+        }
+        let (i, j) = (a as usize, b as usize);
+        let (wa, wb) = (weld[i], weld[j]);
+        result.positions.push(edge_points[&(wa.min(wb), wa.max(wb))].map(|x| x as f32));
+        if has_colors {
+            result.vertex_colors.push(lerp(mesh.vertex_colors[i], mesh.vertex_colors[j]));
+        }
+        if has_uvs {
+            result.texcoords.push(lerp(mesh.texcoords[i], mesh.texcoords[j]));
+        }
+        if has_skin {
+            let (joints, weights) = blend_skin(
+                (mesh.joint_indices[i], mesh.joint_weights[i]),
+                (mesh.joint_indices[j], mesh.joint_weights[j]),
+            );
+            result.joint_indices.push(joints);
+            result.joint_weights.push(weights);
+        }
+        for (target, source) in result.morph_targets.iter_mut().zip(&mesh.morph_targets) {
+            if source.position_deltas.len() == n {
+                target.position_deltas.push(lerp(source.position_deltas[i], source.position_deltas[j]));
+            }
+        }
+        let m = (result.positions.len() - 1) as u32;
+        midpoints.insert(key, m);
+        m
+    };
+
+    for (fi, &[a, b, c]) in mesh.face_indices.iter().enumerate() {
+        let ab = midpoint(&mut result, a, b);
+        let bc = midpoint(&mut result, b, c);
+        let ca = midpoint(&mut result, c, a);
+        result.face_indices.extend([[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]);
+        if has_face_colors {
+            result.face_colors.extend([mesh.face_colors[fi]; 4]);
+        }
+    }
+    // Morph targets that didn't match the vertex count can't follow the split
+    result.morph_targets.retain(|t: &MorphTarget| t.position_deltas.len() == result.positions.len());
+    if result.morph_targets.is_empty() {
+        result.morph_weights.clear();
+    }
+    result
+}
+
+/// Average two skin influences, keeping the four strongest joints
+fn blend_skin(a: ([u16; 4], [f32; 4]), b: ([u16; 4], [f32; 4])) -> ([u16; 4], [f32; 4]) {
+    let mut influences: Vec<(u16, f32)> = Vec::with_capacity(8);
+    for (joints, weights) in [a, b] {
+        for (&joint, &weight) in joints.iter().zip(&weights) {
+            if weight <= 0.0 {
+                continue;
+            }
+            match influences.iter_mut().find(|(j, _)| *j == joint) {
+                Some((_, w)) => *w += weight * 0.5,
+                None => influences.push((joint, weight * 0.5)),
+            }
+        }
+    }
+    influences.sort_by(|x, y| y.1.total_cmp(&x.1));
+    influences.truncate(4);
+    let total: f32 = influences.iter().map(|(_, w)| w).sum();
+    let mut joints = [0u16; 4];
+    let mut weights = [0.0f32; 4];
+    for (k, (joint, weight)) in influences.into_iter().enumerate() {
+        joints[k] = joint;
+        weights[k] = if total > 0.0 { weight / total } else { 0.0 };
+    }
+    (joints, weights)
+}
+
+fn lerp<const N: usize>(a: [f32; N], b: [f32; N]) -> [f32; N] {
+    std::array::from_fn(|k| (a[k] + b[k]) * 0.5)
+}
+
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], s: f64) -> [f64; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    let len = dot(a, a).sqrt();
+    if len > 0.0 { scale(a, 1.0 / len) } else { a }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit cube with every face its own UV chart, so all edges are seams
+    fn seamed_cube() -> MeshWithColors {
+        let corners = |axis: usize, side: f32| -> [[f32; 3]; 4] {
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]].map(|[a, b]| {
+                let mut p = [0.0; 3];
+                p[axis] = side;
+                p[u] = a;
+                p[v] = b;
+                p
+            })
+        };
+        let mut mesh = MeshWithColors::default();
+        for axis in 0..3 {
+            for side in [0.0, 1.0] {
+                let base = mesh.positions.len() as u32;
+                mesh.positions.extend(corners(axis, side));
+                mesh.texcoords.extend([[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
+                let quad = if side > 0.0 { [[0, 1, 2], [0, 2, 3]] } else { [[0, 2, 1], [0, 3, 2]] };
+                mesh.face_indices.extend(quad.map(|f| f.map(|i| base + i)));
+                mesh.face_colors.extend([[side, 0.0, 0.0, 1.0]; 2]);
+            }
+        }
+        mesh
+    }
+
+    fn extent(mesh: &MeshWithColors) -> f32 {
+        let xs = mesh.positions.iter().map(|p| p[0]);
+        xs.clone().fold(f32::NEG_INFINITY, f32::max) - xs.fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn midpoint_interpolates_attributes() {
+        let mesh = MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+            face_indices: vec![[0, 1, 2]],
+            face_colors: vec![[0.0, 1.0, 0.0, 1.0]],
+            vertex_colors: vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0], [1.0, 1.0, 1.0, 1.0]],
+            texcoords: vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]],
+            ..Default::default()
+        };
+        let options = SubdivideOptions { scheme: SubdivisionScheme::Midpoint, levels: 1, crease_angle: None };
+        let result = subdivide(&mesh, &options);
+
+        assert_eq!(result.positions.len(), 6);
+        assert_eq!(result.face_indices.len(), 4);
+        assert_eq!(result.face_colors, vec![[0.0, 1.0, 0.0, 1.0]; 4]);
+        assert_eq!(result.positions[3], [1.0, 0.0, 0.0]);
+        assert_eq!(result.texcoords[3], [0.5, 0.0]);
+        assert_eq!(result.vertex_colors[3], [0.5, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn loop_smooths_across_seams_but_keeps_creases() {
+        let mesh = seamed_cube();
+        let options = SubdivideOptions { scheme: SubdivisionScheme::Loop, levels: 2, crease_angle: None };
+        let smooth = subdivide(&mesh, &options);
+        assert_eq!(smooth.face_indices.len(), 12 * 16);
+        assert_eq!(smooth.texcoords.len(), smooth.positions.len());
+        assert_eq!(smooth.face_colors.len(), smooth.face_indices.len());
+        // Seams are welded for the stencils, so the cube rounds off instead of
+        // every face shrinking on its own
+        assert!(extent(&smooth) < 1.0 && extent(&smooth) > 0.5);
+
+        let creased = subdivide(&mesh, &SubdivideOptions { crease_angle: Some(30.0), ..options });
+        assert_eq!(extent(&creased), 1.0);
+        assert!(creased.positions.iter().all(|p| p.iter().any(|&x| x.abs() < 1e-6 || (x - 1.0).abs() < 1e-6)));
+    }
+
+    #[test]
+    fn blended_skin_weights_sum_to_one() {
+        let (joints, weights) = blend_skin(([0, 1, 0, 0], [0.5, 0.5, 0.0, 0.0]), ([2, 0, 0, 0], [1.0, 0.0, 0.0, 0.0]));
+        assert_eq!(joints[0], 2);
+        assert_eq!(weights, [0.5, 0.25, 0.25, 0.0]);
+    }
+}
@@ -0,0 +1,745 @@
+//! 3MF package reader and writer.
+//!
+//! Reading follows the start part named in `_rels/.rels`, instances every
+//! `<build>` item through its `<components>` (including production-extension
+//! parts in other model files) with the 3x4 transforms applied, and resolves
+//! `m:colorgroup` and `basematerials` colors per triangle. Coordinates are
+//! converted from the model `unit` to meters.
+//!
+//! Writing produces the OPC container (`[Content_Types].xml`, `_rels/.rels`)
+//! and a single-object `3D/3dmodel.model`. Per-face colors become an
+//! `m:colorgroup` referenced through each triangle's `pid`/`p1`.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use nalgebra::{Matrix4, Point3};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::loader::MeshWithColors;
+use super::stdio::{self, progress};
+
+/// Color for triangles without a material when other triangles have one
+const DEFAULT_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+
+/// Nested components deeper than this are treated as a reference cycle
+const MAX_COMPONENT_DEPTH: usize = 32;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+/// Unit of measurement for 3MF model coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ModelUnit {
+    Micron,
+    #[default]
+    Millimeter,
+    Centimeter,
+    Inch,
+    Foot,
+    Meter,
+}
+
+impl ModelUnit {
+    /// Name used in the `unit` attribute of the 3MF `<model>` element
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ModelUnit::Micron => "micron",
+            ModelUnit::Millimeter => "millimeter",
+            ModelUnit::Centimeter => "centimeter",
+            ModelUnit::Inch => "inch",
+            ModelUnit::Foot => "foot",
+            ModelUnit::Meter => "meter",
+        }
+    }
+
+    /// Parse the `unit` attribute of a `<model>` element
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "micron" => ModelUnit::Micron,
+            "millimeter" => ModelUnit::Millimeter,
+            "centimeter" => ModelUnit::Centimeter,
+            "inch" => ModelUnit::Inch,
+            "foot" => ModelUnit::Foot,
+            "meter" => ModelUnit::Meter,
+            _ => return None,
+        })
+    }
+
+    /// Length of one unit in meters
+    pub fn meters(self) -> f64 {
+        match self {
+            ModelUnit::Micron => 1e-6,
+            ModelUnit::Millimeter => 1e-3,
+            ModelUnit::Centimeter => 1e-2,
+            ModelUnit::Inch => 0.0254,
+            ModelUnit::Foot => 0.3048,
+            ModelUnit::Meter => 1.0,
+        }
+    }
+}
+
+fn hex_color(c: &[f32; 4]) -> String {
+    let [r, g, b, a] = c.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8);
+    if a == 255 {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+    }
+}
+
+/// Parse a #RRGGBB or #RRGGBBAA hex color string to [f32; 4]
+fn parse_hex_color(s: &str) -> Option<[f32; 4]> {
+    let s = s.strip_prefix('#')?;
+    if s.len() != 6 && s.len() != 8 {
+        return None;
+    }
+    let r = u8::from_str_radix(&s[0..2], 16).ok()? as f32 / 255.0;
+    let g = u8::from_str_radix(&s[2..4], 16).ok()? as f32 / 255.0;
+    let b = u8::from_str_radix(&s[4..6], 16).ok()? as f32 / 255.0;
+    let a = if s.len() == 8 {
+        u8::from_str_radix(&s[6..8], 16).ok()? as f32 / 255.0
+    } else {
+        1.0
+    };
+    Some([r, g, b, a])
+}
+
+/// Build the XML for `3D/3dmodel.model`
+fn model_xml(mesh: &MeshWithColors, unit: ModelUnit) -> String {
+    let has_colors =
+        !mesh.face_colors.is_empty() && mesh.face_colors.len() == mesh.face_indices.len();
+
+    // Deduplicate face colors into a palette
+    let mut palette: Vec<[f32; 4]> = Vec::new();
+    let mut face_palette_index: Vec<usize> = Vec::new();
+    if has_colors {
+        for color in &mesh.face_colors {
+            let idx = palette.iter().position(|c| c == color).unwrap_or_else(|| {
+                palette.push(*color);
+                palette.len() - 1
+            });
+            face_palette_index.push(idx);
+        }
+    }
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<model unit=\"{}\" xml:lang=\"en-US\" xmlns=\"http://schemas.microsoft.com/3dmanufacturing/core/2015/02\" xmlns:m=\"http://schemas.microsoft.com/3dmanufacturing/material/2015/02\">",
+        unit.as_str()
+    );
+    xml.push_str("  <metadata name=\"Application\">msh</metadata>\n");
+    xml.push_str("  <resources>\n");
+
+    // Color group is resource 1, the object resource 2
+    let object_id = if has_colors { 2 } else { 1 };
+    if has_colors {
+        xml.push_str("    <m:colorgroup id=\"1\">\n");
+        for color in &palette {
+            let _ = writeln!(xml, "      <m:color color=\"{}\"/>", hex_color(color));
+        }
+        xml.push_str("    </m:colorgroup>\n");
+        let _ = writeln!(
+            xml,
+            "    <object id=\"{}\" type=\"model\" pid=\"1\" pindex=\"0\">",
+            object_id
+        );
+    } else {
+        let _ = writeln!(xml, "    <object id=\"{}\" type=\"model\">", object_id);
+    }
+
+    xml.push_str("      <mesh>\n        <vertices>\n");
+    // Mesh coordinates are meters
+    let scale = unit.meters();
+    for p in &mesh.positions {
+        let [x, y, z] = p.map(|v| (v as f64 / scale) as f32);
+        let _ = writeln!(xml, "          <vertex x=\"{}\" y=\"{}\" z=\"{}\"/>", x, y, z);
+    }
+    xml.push_str("        </vertices>\n        <triangles>\n");
+    for (i, tri) in mesh.face_indices.iter().enumerate() {
+        if has_colors {
+            let _ = writeln!(
+                xml,
+                "          <triangle v1=\"{}\" v2=\"{}\" v3=\"{}\" pid=\"1\" p1=\"{}\"/>",
+                tri[0], tri[1], tri[2], face_palette_index[i]
+            );
+        } else {
+            let _ = writeln!(
+                xml,
+                "          <triangle v1=\"{}\" v2=\"{}\" v3=\"{}\"/>",
+                tri[0], tri[1], tri[2]
+            );
+        }
+    }
+    xml.push_str("        </triangles>\n      </mesh>\n    </object>\n");
+    xml.push_str("  </resources>\n");
+    let _ = writeln!(xml, "  <build>\n    <item objectid=\"{}\"/>\n  </build>", object_id);
+    xml.push_str("</model>\n");
+    xml
+}
+
+/// Write a mesh as a 3MF package
+pub fn write_3mf<W: Write + Seek>(
+    mesh: &MeshWithColors,
+    writer: W,
+    unit: ModelUnit,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dropped: Vec<&str> = [
+        (!mesh.texcoords.is_empty() || !mesh.corner_texcoords.is_empty(), "UVs"),
+        (mesh.texture.is_some(), "texture"),
+        (!mesh.vertex_colors.is_empty(), "vertex colors"),
+        (mesh.skeleton.is_some(), "skin"),
+        (!mesh.animations.is_empty(), "animations"),
+        (!mesh.morph_targets.is_empty(), "morph targets"),
+    ]
+    .into_iter()
+    .filter_map(|(present, name)| present.then_some(name))
+    .collect();
+    if !dropped.is_empty() {
+        eprintln!("Warning: 3MF output does not store {}; dropping", dropped.join(", "));
+    }
+
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = zip::write::SimpleFileOptions::default();
+
+    zip.start_file("[Content_Types].xml", options)?;
+    zip.write_all(CONTENT_TYPES.as_bytes())?;
+    zip.start_file("_rels/.rels", options)?;
+    zip.write_all(RELS.as_bytes())?;
+    zip.start_file("3D/3dmodel.model", options)?;
+    zip.write_all(model_xml(mesh, unit).as_bytes())?;
+    zip.finish()?;
+    Ok(())
+}
+
+// --- Reading ---
+
+/// Reference from a build item or component to an object, possibly in another part
+struct ObjectRef {
+    /// Model part holding the object (`None` for the referencing part itself)
+    path: Option<String>,
+    object_id: String,
+    transform: Matrix4<f64>,
+}
+
+/// An `<object>` resource: a mesh, components, or both
+#[derive(Default)]
+struct Object {
+    positions: Vec<[f32; 3]>,
+    triangles: Vec<[u32; 3]>,
+    colors: Vec<Option<[f32; 4]>>,
+    components: Vec<ObjectRef>,
+}
+
+/// One parsed `.model` part
+struct ModelPart {
+    unit: ModelUnit,
+    objects: HashMap<String, Object>,
+    build: Vec<ObjectRef>,
+}
+
+/// Value of the attribute with the given local name (namespace prefix ignored)
+fn attr(e: &BytesStart, name: &str) -> Option<String> {
+    e.attributes().flatten().find_map(|a| {
+        (a.key.local_name().as_ref() == name.as_bytes())
+            .then(|| String::from_utf8_lossy(&a.value).into_owned())
+    })
+}
+
+/// Parse a 3MF `transform` attribute (`m00 m01 m02 ... m30 m31 m32`, row vectors)
+fn parse_transform(s: &str) -> Result<Matrix4<f64>, Box<dyn std::error::Error>> {
+    let m: Vec<f64> = s
+        .split_whitespace()
+        .map(|v| v.parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid 3MF transform: '{}'", s))?;
+    if m.len() != 12 {
+        return Err(format!("Invalid 3MF transform (expected 12 values): '{}'", s).into());
+    }
+    // Transpose into column-vector form: x' = m00*x + m10*y + m20*z + m30
+    Ok(Matrix4::new(
+        m[0], m[3], m[6], m[9],
+        m[1], m[4], m[7], m[10],
+        m[2], m[5], m[8], m[11],
+        0.0, 0.0, 0.0, 1.0,
+    ))
+}
+
+fn object_ref(e: &BytesStart) -> Result<ObjectRef, Box<dyn std::error::Error>> {
+    Ok(ObjectRef {
+        path: attr(e, "path").map(|p| p.trim_start_matches('/').to_string()),
+        object_id: attr(e, "objectid").ok_or("3MF component or item without objectid")?,
+        transform: match attr(e, "transform") {
+            Some(t) => parse_transform(&t)?,
+            None => Matrix4::identity(),
+        },
+    })
+}
+
+/// Parse one `.model` part
+fn parse_model(xml: &str) -> Result<ModelPart, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut part = ModelPart {
+        unit: ModelUnit::Millimeter,
+        objects: HashMap::new(),
+        build: Vec::new(),
+    };
+
+    // Property groups (color groups and base materials) by resource id
+    let mut groups: HashMap<String, Vec<[f32; 4]>> = HashMap::new();
+    let mut current_group: Option<String> = None;
+    let mut current_object: Option<(String, Object)> = None;
+    // Object-level default property: pid + pindex
+    let mut default_property: (Option<String>, Option<usize>) = (None, None);
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e)) => {
+                let ln = e.local_name();
+                match ln.as_ref() {
+                    b"model" => {
+                        if let Some(unit) = attr(e, "unit") {
+                            part.unit = ModelUnit::parse(&unit).unwrap_or_else(|| {
+                                eprintln!("Warning: unknown 3MF unit '{}', assuming millimeter", unit);
+                                ModelUnit::Millimeter
+                            });
+                        }
+                    }
+                    b"colorgroup" | b"basematerials" => {
+                        let id = attr(e, "id").unwrap_or_default();
+                        groups.entry(id.clone()).or_default();
+                        current_group = Some(id);
+                    }
+                    b"color" | b"base" => {
+                        let key = if ln.as_ref() == b"color" { "color" } else { "displaycolor" };
+                        if let Some(group) = current_group.as_ref().and_then(|g| groups.get_mut(g)) {
+                            group.push(attr(e, key).and_then(|c| parse_hex_color(&c)).unwrap_or(DEFAULT_COLOR));
+                        }
+                    }
+                    b"object" => {
+                        let id = attr(e, "id").ok_or("3MF object without id")?;
+                        default_property = (attr(e, "pid"), attr(e, "pindex").and_then(|v| v.parse().ok()));
+                        current_object = Some((id, Object::default()));
+                    }
+                    b"vertex" => {
+                        if let Some((_, object)) = current_object.as_mut() {
+                            let coord = |name| attr(e, name).and_then(|v| v.parse().ok()).unwrap_or(0.0);
+                            object.positions.push([coord("x"), coord("y"), coord("z")]);
+                        }
+                    }
+                    b"triangle" => {
+                        if let Some((_, object)) = current_object.as_mut() {
+                            let vertex_count = object.positions.len();
+                            let index = |name| {
+                                attr(e, name)
+                                    .and_then(|v| v.parse::<usize>().ok())
+                                    .filter(|&i| i < vertex_count)
+                                    .map_or(u32::MAX, |i| i as u32)
+                            };
+                            object.triangles.push([index("v1"), index("v2"), index("v3")]);
+
+                            // Triangle-level pid/p1, falling back to the object default
+                            let pid = attr(e, "pid").or_else(|| default_property.0.clone());
+                            let pindex = attr(e, "p1").and_then(|v| v.parse().ok()).or(default_property.1);
+                            let color = pid
+                                .and_then(|pid| groups.get(&pid))
+                                .and_then(|group| pindex.and_then(|i: usize| group.get(i).copied()));
+                            object.colors.push(color);
+                        }
+                    }
+                    b"component" => {
+                        if let Some((_, object)) = current_object.as_mut() {
+                            object.components.push(object_ref(e)?);
+                        }
+                    }
+                    b"item" => part.build.push(object_ref(e)?),
+                    _ => {}
+                }
+            }
+            Ok(Event::End(ref e)) => match e.local_name().as_ref() {
+                b"colorgroup" | b"basematerials" => current_group = None,
+                b"object" => {
+                    if let Some((id, object)) = current_object.take() {
+                        part.objects.insert(id, object);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parse error: {}", e).into()),
+            _ => {}
+        }
+    }
+
+    Ok(part)
+}
+
+/// Path of the root model part from `_rels/.rels`, else the first `.model` entry
+fn root_model_path<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if let Ok(mut rels) = archive.by_name("_rels/.rels") {
+        let mut xml = String::new();
+        rels.read_to_string(&mut xml)?;
+        let mut reader = Reader::from_str(&xml);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e))
+                    if e.local_name().as_ref() == b"Relationship"
+                        && attr(e, "Type").is_some_and(|t| t.ends_with("/3dmodel")) =>
+                {
+                    if let Some(target) = attr(e, "Target") {
+                        return Ok(target.trim_start_matches('/').to_string());
+                    }
+                }
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+        }
+    }
+
+    archive
+        .file_names()
+        .find(|name| name.ends_with(".model"))
+        .map(str::to_string)
+        .ok_or_else(|| "No .model file found in 3MF archive".into())
+}
+
+/// Parse `path` and every part its components reference
+fn load_parts<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    path: &str,
+    parts: &mut HashMap<String, ModelPart>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if parts.contains_key(path) {
+        return Ok(());
+    }
+    let mut xml = String::new();
+    archive
+        .by_name(path)
+        .map_err(|_| format!("3MF part '{}' not found in archive", path))?
+        .read_to_string(&mut xml)?;
+    let part = parse_model(&xml)?;
+
+    let referenced: Vec<String> = part
+        .objects
+        .values()
+        .flat_map(|o| &o.components)
+        .chain(&part.build)
+        .filter_map(|r| r.path.clone())
+        .collect();
+    parts.insert(path.to_string(), part);
+    for referenced_path in referenced {
+        load_parts(archive, &referenced_path, parts)?;
+    }
+    Ok(())
+}
+
+/// Accumulates instanced geometry while walking the build
+#[derive(Default)]
+struct Instancer {
+    positions: Vec<[f32; 3]>,
+    face_indices: Vec<[u32; 3]>,
+    face_colors: Vec<Option<[f32; 4]>>,
+}
+
+impl Instancer {
+    fn instance(
+        &mut self,
+        parts: &HashMap<String, ModelPart>,
+        part_path: &str,
+        object: &ObjectRef,
+        parent: &Matrix4<f64>,
+        depth: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err("3MF components are nested too deeply (reference cycle?)".into());
+        }
+        let referencing = parts.get(part_path);
+        let part_path = object.path.as_deref().unwrap_or(part_path);
+        let part = parts.get(part_path);
+        let obj = part
+            .and_then(|p| p.objects.get(&object.object_id))
+            .ok_or_else(|| format!("3MF object {} not found in '{}'", object.object_id, part_path))?;
+        let mut world = parent * object.transform;
+        // Parts may declare their own unit; bring them into the referencing part's
+        if let (Some(from), Some(to)) = (part, referencing)
+            && from.unit != to.unit
+        {
+            world *= Matrix4::new_scaling(from.unit.meters() / to.unit.meters());
+        }
+
+        if !obj.triangles.is_empty() {
+            // Mirroring transforms flip the triangle winding
+            let flip = world.fixed_view::<3, 3>(0, 0).determinant() < 0.0;
+            let offset = self.positions.len() as u32;
+            self.positions.extend(obj.positions.iter().map(|p| {
+                let v = world.transform_point(&Point3::new(p[0] as f64, p[1] as f64, p[2] as f64));
+                [v.x as f32, v.y as f32, v.z as f32]
+            }));
+            self.face_indices.extend(obj.triangles.iter().map(|tri| {
+                let [a, b, c] = tri.map(|i| if i == u32::MAX { i } else { i + offset });
+                if flip { [a, c, b] } else { [a, b, c] }
+            }));
+            self.face_colors.extend_from_slice(&obj.colors);
+        }
+
+        for component in &obj.components {
+            self.instance(parts, part_path, component, &world, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Load a .3mf package, instancing every build item into one mesh in meters
+pub fn load_3mf(path: &Path) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let file = std::io::Cursor::new(stdio::read(path)?);
+    let mut archive = zip::ZipArchive::new(file)?;
+
+    let root = root_model_path(&mut archive)?;
+    let mut parts = HashMap::new();
+    load_parts(&mut archive, &root, &mut parts)?;
+
+    let root_part = &parts[&root];
+    if root_part.build.is_empty() {
+        return Err("3MF model has no build items".into());
+    }
+
+    // Apply the root model's unit last so all transforms stay in model units
+    let scale = root_part.unit.meters();
+    let to_meters = Matrix4::new_scaling(scale);
+    let mut instancer = Instancer::default();
+    for item in &root_part.build {
+        instancer.instance(&parts, &root, item, &to_meters, 0)?;
+    }
+
+    progress!(
+        "Loaded {} build item(s) from {} model part(s), unit: {}",
+        root_part.build.len(),
+        parts.len(),
+        root_part.unit.as_str()
+    );
+
+    let face_colors = if instancer.face_colors.iter().any(Option::is_some) {
+        instancer
+            .face_colors
+            .iter()
+            .map(|c| c.unwrap_or(DEFAULT_COLOR))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(MeshWithColors {
+        positions: instancer.positions,
+        face_indices: instancer.face_indices,
+        face_colors,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::loader::{load_mesh_with_colors, LoadOptions};
+    use crate::mesh::stl;
+
+    fn colored_quad() -> MeshWithColors {
+        MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [1.5, 0.0, 0.0], [1.5, 1.0, 0.0], [0.0, 1.0, 0.25]],
+            face_indices: vec![[0, 1, 2], [0, 2, 3]],
+            face_colors: vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 128.0 / 255.0]],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(hex_color(&[1.0, 0.0, 0.5, 1.0]), "#FF0080");
+        assert_eq!(hex_color(&[0.0, 0.0, 0.0, 0.0]), "#00000000");
+    }
+
+    #[test]
+    fn model_declares_unit_and_colorgroup() {
+        let xml = model_xml(&colored_quad(), ModelUnit::Inch);
+        assert!(xml.contains("unit=\"inch\""));
+        assert!(xml.contains("<m:colorgroup id=\"1\">"));
+        assert!(xml.contains("p1=\"1\""));
+        assert!(xml.contains("<item objectid=\"2\"/>"));
+        // Meters converted to the declared unit
+        assert!(model_xml(&colored_quad(), ModelUnit::Millimeter).contains("<vertex x=\"1500\" y=\"1000\" z=\"0\"/>"));
+    }
+
+    #[test]
+    fn round_trip_through_loader() {
+        let mesh = colored_quad();
+        let path = std::env::temp_dir().join(format!("msh_threemf_{}.3mf", std::process::id()));
+        write_3mf(&mesh, std::fs::File::create(&path).unwrap(), ModelUnit::Millimeter).unwrap();
+
+        let loaded = load_mesh_with_colors(&path, &LoadOptions::default()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.positions, mesh.positions);
+        assert_eq!(loaded.face_indices, mesh.face_indices);
+        assert_eq!(loaded.face_colors, mesh.face_colors);
+    }
+
+    #[test]
+    fn millimeters_survive_stl_round_trip() {
+        let mut mesh = colored_quad();
+        mesh.positions = vec![[0.0, 0.0, 0.0], [0.02, 0.0, 0.0], [0.02, 0.02, 0.0055], [0.0, 0.02, 0.0055]];
+        let path = std::env::temp_dir().join(format!("msh_threemf_stl_{}.3mf", std::process::id()));
+        write_3mf(&mesh, std::fs::File::create(&path).unwrap(), ModelUnit::Millimeter).unwrap();
+        let loaded = load_3mf(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let mut bytes = Vec::new();
+        stl::write_stl(&loaded, &mut bytes, false).unwrap();
+        let from_stl = stl::parse_stl(&bytes).unwrap();
+
+        // A 20 mm part is 0.02 in STL and 20 mm again in 3MF
+        for p in &mesh.positions {
+            assert!(from_stl.positions.iter().any(|q| p.iter().zip(q).all(|(a, b)| (a - b).abs() < 1e-7)));
+        }
+        let xml = model_xml(&from_stl, ModelUnit::Millimeter);
+        assert!(xml.contains("<vertex x=\"20\" y=\"20\" z=\"5.5\"/>"));
+    }
+
+    #[test]
+    fn parses_row_vector_transform() {
+        let m = parse_transform("0 1 0 -1 0 0 0 0 1 5 6 7").unwrap();
+        let p = m.transform_point(&Point3::new(1.0, 0.0, 0.0));
+        assert_eq!([p.x, p.y, p.z], [5.0, 7.0, 7.0]);
+        assert!(parse_transform("1 0 0").is_err());
+    }
+
+    #[test]
+    fn loads_objects_components_and_units() {
+        // Two objects with their own vertex arrays, one assembled from components
+        // of the other, placed by build transforms in centimeters
+        let model = r##"<?xml version="1.0" encoding="UTF-8"?>
+<model unit="centimeter" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">
+  <resources>
+    <basematerials id="1">
+      <base name="red" displaycolor="#FF0000"/>
+      <base name="green" displaycolor="#00FF00FF"/>
+    </basematerials>
+    <object id="2" type="model" pid="1" pindex="0">
+      <mesh>
+        <vertices>
+          <vertex x="0" y="0" z="0"/><vertex x="1" y="0" z="0"/><vertex x="0" y="1" z="0"/>
+        </vertices>
+        <triangles><triangle v1="0" v2="1" v3="2"/></triangles>
+      </mesh>
+    </object>
+    <object id="3" type="model">
+      <mesh>
+        <vertices>
+          <vertex x="0" y="0" z="1"/><vertex x="1" y="0" z="1"/><vertex x="0" y="1" z="1"/>
+        </vertices>
+        <triangles><triangle v1="0" v2="1" v3="2" pid="1" p1="1"/></triangles>
+      </mesh>
+    </object>
+    <object id="4" type="model">
+      <components>
+        <component objectid="2"/>
+        <component objectid="2" transform="1 0 0 0 1 0 0 0 1 0 0 10"/>
+      </components>
+    </object>
+  </resources>
+  <build>
+    <item objectid="4" transform="1 0 0 0 1 0 0 0 1 100 0 0"/>
+    <item objectid="3"/>
+  </build>
+</model>
+"##;
+        let path = std::env::temp_dir().join(format!("msh_threemf_multi_{}.3mf", std::process::id()));
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("_rels/.rels", options).unwrap();
+            zip.write_all(RELS.as_bytes()).unwrap();
+            zip.start_file("3D/3dmodel.model", options).unwrap();
+            zip.write_all(model.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        }
+        let loaded = load_3mf(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.positions.len(), 9);
+        assert_eq!(loaded.face_indices, vec![[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+        // Centimeters to meters, component and item translations composed
+        assert_eq!(loaded.positions[1], [1.01, 0.0, 0.0]);
+        assert_eq!(loaded.positions[5], [1.0, 0.01, 0.1]);
+        assert_eq!(loaded.positions[8], [0.0, 0.01, 0.01]);
+        assert_eq!(
+            loaded.face_colors,
+            vec![[1.0, 0.0, 0.0, 1.0], [1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]]
+        );
+    }
+
+    #[test]
+    fn converts_units_of_referenced_parts() {
+        // A millimeter root placing an object from a centimeter part 10 mm along x
+        let root = r##"<?xml version="1.0" encoding="UTF-8"?>
+<model unit="millimeter" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" xmlns:p="http://schemas.microsoft.com/3dmanufacturing/production/2015/06">
+  <resources>
+    <object id="1" type="model">
+      <components>
+        <component p:path="/3D/Objects/part.model" objectid="1" transform="1 0 0 0 1 0 0 0 1 10 0 0"/>
+      </components>
+    </object>
+  </resources>
+  <build><item objectid="1"/></build>
+</model>
+"##;
+        let part = r##"<?xml version="1.0" encoding="UTF-8"?>
+<model unit="centimeter" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">
+  <resources>
+    <object id="1" type="model">
+      <mesh>
+        <vertices>
+          <vertex x="0" y="0" z="0"/><vertex x="1" y="0" z="0"/><vertex x="0" y="2" z="0"/>
+        </vertices>
+        <triangles><triangle v1="0" v2="1" v3="2"/></triangles>
+      </mesh>
+    </object>
+  </resources>
+</model>
+"##;
+        let path = std::env::temp_dir().join(format!("msh_threemf_parts_{}.3mf", std::process::id()));
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("_rels/.rels", options).unwrap();
+            zip.write_all(RELS.as_bytes()).unwrap();
+            zip.start_file("3D/3dmodel.model", options).unwrap();
+            zip.write_all(root.as_bytes()).unwrap();
+            zip.start_file("3D/Objects/part.model", options).unwrap();
+            zip.write_all(part.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        }
+        let loaded = load_3mf(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let expected = [[0.01, 0.0, 0.0], [0.02, 0.0, 0.0], [0.01, 0.02, 0.0]];
+        for (p, e) in loaded.positions.iter().zip(expected) {
+            assert!(p.iter().zip(e).all(|(a, b)| (a - b).abs() < 1e-7), "{:?} != {:?}", p, e);
+        }
+    }
+}
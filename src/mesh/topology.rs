@@ -0,0 +1,174 @@
+//! Topology checks on raw face indices.
+//!
+//! These run on `MeshWithColors` before a corner table is built, since the
+//! corner table can't represent (and so can't report) edges shared by more
+//! than two faces, bowtie vertices or indices past the end of the vertex list.
+
+use super::loader::MeshWithColors;
+use std::collections::HashMap;
+
+/// Problems found in a mesh's face indices; each list holds every offender
+#[derive(Clone, Debug, Default)]
+pub struct TopologyReport {
+    /// Edges (sorted vertex pairs) shared by three or more faces
+    pub non_manifold_edges: Vec<([u32; 2], usize)>,
+    /// Vertices whose faces form more than one fan (bowties)
+    pub non_manifold_vertices: Vec<u32>,
+    /// Faces using the same three vertices as an earlier face, as (earlier, duplicate)
+    pub duplicate_faces: Vec<(usize, usize)>,
+    /// Faces with a repeated index or zero area
+    pub degenerate_faces: Vec<usize>,
+    /// Vertices no face uses
+    pub unreferenced_vertices: Vec<u32>,
+    /// Faces with an index past the end of the vertex list
+    pub out_of_range_faces: Vec<usize>,
+}
+
+impl TopologyReport {
+    /// True if no problem was found
+    pub fn is_clean(&self) -> bool {
+        self.non_manifold_edges.is_empty()
+            && self.non_manifold_vertices.is_empty()
+            && self.duplicate_faces.is_empty()
+            && self.degenerate_faces.is_empty()
+            && self.unreferenced_vertices.is_empty()
+            && self.out_of_range_faces.is_empty()
+    }
+}
+
+/// Analyze the face indices of `mesh`.
+///
+/// Faces with out-of-range or repeated indices, and duplicates of earlier
+/// faces, are reported once and left out of the edge and vertex checks.
+pub fn analyze(mesh: &MeshWithColors) -> TopologyReport {
+    let mut report = TopologyReport::default();
+    let vertex_count = mesh.positions.len();
+    let mut referenced = vec![false; vertex_count];
+    let mut seen: HashMap<[u32; 3], usize> = HashMap::new();
+    let mut faces: Vec<[u32; 3]> = Vec::with_capacity(mesh.face_indices.len());
+
+    for (i, face) in mesh.face_indices.iter().enumerate() {
+        if face.iter().any(|&v| v as usize >= vertex_count) {
+            report.out_of_range_faces.push(i);
+            continue;
+        }
+        for &v in face {
+            referenced[v as usize] = true;
+        }
+        let repeated = face[0] == face[1] || face[1] == face[2] || face[2] == face[0];
+        let [a, b, c] = face.map(|v| mesh.positions[v as usize]);
+        if repeated || is_zero_area(a, b, c) {
+            report.degenerate_faces.push(i);
+        }
+        if repeated {
+            continue;
+        }
+        let mut key = *face;
+        key.sort_unstable();
+        if let Some(&first) = seen.get(&key) {
+            report.duplicate_faces.push((first, i));
+            continue;
+        }
+        seen.insert(key, i);
+        faces.push(*face);
+    }
+
+    report.unreferenced_vertices = (0..vertex_count as u32).filter(|&v| !referenced[v as usize]).collect();
+
+    let mut edge_faces: HashMap<[u32; 2], usize> = HashMap::new();
+    let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+    for (f, face) in faces.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (face[k], face[(k + 1) % 3]);
+            *edge_faces.entry([a.min(b), a.max(b)]).or_insert(0) += 1;
+            vertex_faces[a as usize].push(f);
+        }
+    }
+    report.non_manifold_edges = edge_faces.into_iter().filter(|&(_, count)| count > 2).collect();
+    report.non_manifold_edges.sort_unstable();
+
+    for (v, around) in vertex_faces.iter().enumerate() {
+        if fan_count(v as u32, around, &faces) > 1 {
+            report.non_manifold_vertices.push(v as u32);
+        }
+    }
+    report
+}
+
+/// Zero area relative to the triangle's size, so tiny but valid faces pass
+fn is_zero_area(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> bool {
+    let sub = |p: [f32; 3], q: [f32; 3]| [p[0] - q[0], p[1] - q[1], p[2] - q[2]];
+    let dot = |p: [f32; 3], q: [f32; 3]| p[0] * q[0] + p[1] * q[1] + p[2] * q[2];
+    let (u, v, w) = (sub(b, a), sub(c, a), sub(c, b));
+    let cross = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+    let longest = dot(u, u).max(dot(v, v)).max(dot(w, w));
+    dot(cross, cross).sqrt() <= f32::EPSILON * longest
+}
+
+/// Number of edge-connected fans the faces around `vertex` form
+fn fan_count(vertex: u32, around: &[usize], faces: &[[u32; 3]]) -> usize {
+    let mut parent: Vec<usize> = (0..around.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    // Faces around the vertex are joined when they share one of its edges
+    let mut first_by_neighbour: HashMap<u32, usize> = HashMap::new();
+    for (i, &f) in around.iter().enumerate() {
+        for &w in faces[f].iter().filter(|&&w| w != vertex) {
+            match first_by_neighbour.get(&w) {
+                Some(&j) => {
+                    let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[ri] = rj;
+                }
+                None => {
+                    first_by_neighbour.insert(w, i);
+                }
+            }
+        }
+    }
+    (0..around.len()).filter(|&i| root(&mut parent, i) == i).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mesh(positions: Vec<[f32; 3]>, face_indices: Vec<[u32; 3]>) -> MeshWithColors {
+        MeshWithColors { positions, face_indices, ..Default::default() }
+    }
+
+    #[test]
+    fn finds_fin_edge_and_bowtie_vertex() {
+        // Three faces on edge 0-1, plus a second fan touching only vertex 0
+        let positions = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.5, 1.0, 0.0],
+            [0.5, -1.0, 0.0],
+            [0.5, 0.0, 1.0],
+            [-1.0, 0.0, 0.0],
+            [-1.0, 1.0, 0.0],
+        ];
+        let faces = vec![[0, 1, 2], [1, 0, 3], [0, 1, 4], [0, 5, 6]];
+        let report = analyze(&mesh(positions, faces));
+        assert_eq!(report.non_manifold_edges, vec![([0, 1], 3)]);
+        assert_eq!(report.non_manifold_vertices, vec![0]);
+        assert!(report.degenerate_faces.is_empty());
+    }
+
+    #[test]
+    fn finds_index_and_face_problems() {
+        let positions = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [2.0, 0.0, 0.0], [9.0, 9.0, 9.0]];
+        let faces = vec![[0, 1, 2], [2, 1, 0], [0, 1, 3], [0, 0, 2], [0, 1, 7]];
+        let report = analyze(&mesh(positions, faces));
+        assert_eq!(report.duplicate_faces, vec![(0, 1)]);
+        assert_eq!(report.degenerate_faces, vec![2, 3]);
+        assert_eq!(report.out_of_range_faces, vec![4]);
+        assert_eq!(report.unreferenced_vertices, vec![4]);
+        assert!(!report.is_clean());
+    }
+}
@@ -0,0 +1,213 @@
+//! Carry mesh attributes over from an input mesh onto processed geometry.
+//!
+//! Remeshing and hole filling produce new vertices and faces, so attributes
+//! are looked up spatially: per-vertex data (UVs, colors, skin weights, morph
+//! target deltas) comes
+//! from the nearest input vertex and face colors from the input face with the
+//! nearest centroid. The texture, skeleton and animations move over unchanged.
+
+use std::collections::HashMap;
+
+use super::loader::{MeshWithColors, MorphTarget};
+
+/// Uniform grid over a point set for nearest-neighbour queries
+struct PointGrid<'a> {
+    points: &'a [[f32; 3]],
+    min: [f32; 3],
+    cell_size: f32,
+    cells: HashMap<[i32; 3], Vec<usize>>,
+    /// Largest cell coordinate span, bounding how far a search can expand
+    max_span: i32,
+}
+
+impl<'a> PointGrid<'a> {
+    fn new(points: &'a [[f32; 3]]) -> Self {
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for p in points {
+            for i in 0..3 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        let extent = (0..3).map(|i| max[i] - min[i]).fold(0.0f32, f32::max);
+        let cells_per_axis = (points.len() as f32).cbrt().max(1.0);
+        let cell_size = if extent > 0.0 { extent / cells_per_axis } else { 1.0 };
+
+        let mut grid = PointGrid {
+            points,
+            min,
+            cell_size,
+            cells: HashMap::new(),
+            max_span: 0,
+        };
+        for (i, p) in points.iter().enumerate() {
+            let cell = grid.cell_of(p);
+            grid.max_span = grid.max_span.max(cell[0]).max(cell[1]).max(cell[2]);
+            grid.cells.entry(cell).or_default().push(i);
+        }
+        grid
+    }
+
+    fn cell_of(&self, p: &[f32; 3]) -> [i32; 3] {
+        [0, 1, 2].map(|i| ((p[i] - self.min[i]) / self.cell_size).floor() as i32)
+    }
+
+    /// Index of the point nearest to `p`, or None if the grid is empty
+    fn nearest(&self, p: &[f32; 3]) -> Option<usize> {
+        let center = self.cell_of(p);
+        let mut best: Option<(usize, f32)> = None;
+        let max_radius = self.max_span + 1 + center.iter().map(|c| c.abs()).max().unwrap_or(0);
+
+        for r in 0..=max_radius {
+            for dx in -r..=r {
+                for dy in -r..=r {
+                    for dz in -r..=r {
+                        // Only visit the shell at Chebyshev distance r
+                        if dx.abs().max(dy.abs()).max(dz.abs()) != r {
+                            continue;
+                        }
+                        let key = [center[0] + dx, center[1] + dy, center[2] + dz];
+                        for &i in self.cells.get(&key).into_iter().flatten() {
+                            let q = self.points[i];
+                            let d = (0..3).map(|k| (q[k] - p[k]).powi(2)).sum::<f32>();
+                            if best.is_none_or(|(_, bd)| d < bd) {
+                                best = Some((i, d));
+                            }
+                        }
+                    }
+                }
+            }
+            // Points in later shells are at least r * cell_size away
+            if let Some((_, d)) = best
+                && d.sqrt() <= r as f32 * self.cell_size
+            {
+                break;
+            }
+        }
+
+        best.map(|(i, _)| i)
+    }
+}
+
+fn face_centroids(mesh: &MeshWithColors) -> Vec<[f32; 3]> {
+    mesh.face_indices
+        .iter()
+        .map(|tri| {
+            let [a, b, c] = tri.map(|i| mesh.positions[i as usize]);
+            [0, 1, 2].map(|k| (a[k] + b[k] + c[k]) / 3.0)
+        })
+        .collect()
+}
+
+/// Copy attributes from `source` onto `target`, whose geometry was derived from it.
+///
+/// Normals are not transferred since they no longer match the new surface.
+pub fn transfer_attributes(target: &mut MeshWithColors, source: MeshWithColors) {
+    let source = if source.has_corner_attributes() {
+        source.with_vertex_attributes().into_owned()
+    } else {
+        source
+    };
+    let n = source.positions.len();
+    let has_morphs = !source.morph_targets.is_empty()
+        && source.morph_targets.iter().all(|t| t.position_deltas.len() == n);
+    let wants_vertex_data = n > 0
+        && (source.texcoords.len() == n
+            || source.vertex_colors.len() == n
+            || (source.joint_indices.len() == n && source.joint_weights.len() == n)
+            || has_morphs);
+
+    if wants_vertex_data {
+        let grid = PointGrid::new(&source.positions);
+        let nearest: Vec<usize> = target
+            .positions
+            .iter()
+            .map(|p| grid.nearest(p).unwrap_or(0))
+            .collect();
+
+        if source.texcoords.len() == n {
+            target.texcoords = nearest.iter().map(|&i| source.texcoords[i]).collect();
+        }
+        if source.vertex_colors.len() == n {
+            target.vertex_colors = nearest.iter().map(|&i| source.vertex_colors[i]).collect();
+        }
+        if source.joint_indices.len() == n && source.joint_weights.len() == n {
+            target.joint_indices = nearest.iter().map(|&i| source.joint_indices[i]).collect();
+            target.joint_weights = nearest.iter().map(|&i| source.joint_weights[i]).collect();
+        }
+        if has_morphs {
+            target.morph_targets = source
+                .morph_targets
+                .iter()
+                .map(|t| MorphTarget {
+                    name: t.name.clone(),
+                    position_deltas: nearest.iter().map(|&i| t.position_deltas[i]).collect(),
+                })
+                .collect();
+            target.morph_weights = source.morph_weights.clone();
+        }
+    }
+
+    if !source.face_colors.is_empty() && source.face_colors.len() == source.face_indices.len() {
+        let source_centroids = face_centroids(&source);
+        let grid = PointGrid::new(&source_centroids);
+        target.face_colors = face_centroids(target)
+            .iter()
+            .map(|c| source.face_colors[grid.nearest(c).unwrap_or(0)])
+            .collect();
+    }
+
+    if !target.joint_indices.is_empty() {
+        target.skeleton = source.skeleton;
+    }
+    target.texture = source.texture;
+    target.animations = source.animations;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let points: Vec<[f32; 3]> = (0..200)
+            .map(|i| {
+                let t = i as f32;
+                [(t * 0.37).sin() * 5.0, (t * 0.11).cos() * 3.0, (t * 0.07).sin()]
+            })
+            .collect();
+        let grid = PointGrid::new(&points);
+        for q in [[0.0, 0.0, 0.0], [4.9, -2.0, 0.5], [10.0, 10.0, 10.0], [-7.0, 0.3, -1.0]] {
+            let brute = (0..points.len())
+                .min_by(|&a, &b| {
+                    let da: f32 = (0..3).map(|k| (points[a][k] - q[k]).powi(2)).sum();
+                    let db: f32 = (0..3).map(|k| (points[b][k] - q[k]).powi(2)).sum();
+                    da.total_cmp(&db)
+                })
+                .unwrap();
+            assert_eq!(grid.nearest(&q), Some(brute));
+        }
+    }
+
+    #[test]
+    fn transfers_uvs_and_face_colors() {
+        let source = MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            face_indices: vec![[0, 1, 2], [0, 2, 3]],
+            face_colors: vec![[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]],
+            texcoords: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+            ..Default::default()
+        };
+        // Same quad split along the other diagonal, vertices reordered
+        let mut target = MeshWithColors {
+            positions: vec![[1.0, 1.0, 0.0], [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            face_indices: vec![[1, 2, 0], [1, 0, 3]],
+            ..Default::default()
+        };
+        transfer_attributes(&mut target, source);
+
+        assert_eq!(target.texcoords, vec![[1.0, 1.0], [0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+        assert_eq!(target.face_colors, vec![[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]]);
+    }
+}
@@ -0,0 +1,1754 @@
+//! USDA (ASCII USD) reader and writer.
+//!
+//! Reading walks the prim hierarchy, baking `Xform` op stacks into every
+//! `Mesh` prim (or only the one named by `--mesh`) and merging them. Meshes
+//! provide `points`, `faceVertexCounts`/`faceVertexIndices` (fan-triangulated),
+//! `normals` and `primvars:st` (kept per corner when face-varying) and
+//! `primvars:displayColor`/`displayOpacity`. Bound `UsdPreviewSurface`
+//! materials, including `GeomSubset` bindings, override the display color and
+//! supply the diffuse texture. A single skinned mesh also loads its UsdSkel
+//! `Skeleton` and `SkelAnimation`. Coordinates are scaled by `metersPerUnit`.
+//!
+//! Writing produces one `Mesh` (under a `SkelRoot` with a `Skeleton` and the
+//! first animation clip when skinned) and a `UsdPreviewSurface` material
+//! reading a PNG sidecar when the mesh has a texture. USD UVs use a
+//! bottom-left origin, so V is flipped relative to the glTF convention.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use nalgebra::{Matrix4, Rotation3, UnitQuaternion, Vector3};
+
+use super::animation::{self, AnimationChannel, AnimationClip, AnimationProperty, Interpolation, Keyframe};
+use super::loader::{MeshWithColors, TextureData};
+use super::skeleton::{Joint, JointTransform, Skeleton};
+use super::stdio::{self, progress};
+
+/// Color used for faces without a display color or material
+const DEFAULT_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+
+// --- Tokenizer ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Punct(char),
+    Num(f64),
+    Str(String),
+    Ident(String),
+    Path(String),
+    Asset(String),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Tok>, Box<dyn std::error::Error>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let until = |i: usize, end: &str| -> Result<(String, usize), Box<dyn std::error::Error>> {
+        let end_chars: Vec<char> = end.chars().collect();
+        let mut j = i;
+        while j + end_chars.len() <= chars.len() {
+            if chars[j..j + end_chars.len()] == end_chars[..] {
+                return Ok((chars[i..j].iter().collect(), j + end_chars.len()));
+            }
+            j += 1;
+        }
+        Err(format!("Unterminated '{}' in USDA file", end).into())
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '(' | ')' | '[' | ']' | '{' | '}' | '=' | ',' | ';' | ':' => {
+                tokens.push(Tok::Punct(c));
+                i += 1;
+            }
+            '"' | '\'' => {
+                let triple: String = [c; 3].iter().collect();
+                if chars[i..].starts_with(&[c, c, c]) {
+                    let (s, next) = until(i + 3, &triple)?;
+                    tokens.push(Tok::Str(s));
+                    i = next;
+                } else {
+                    let mut s = String::new();
+                    i += 1;
+                    loop {
+                        match chars.get(i) {
+                            None => return Err("Unterminated string in USDA file".into()),
+                            Some('\\') => {
+                                match chars.get(i + 1) {
+                                    Some('n') => s.push('\n'),
+                                    Some('t') => s.push('\t'),
+                                    Some(&e) => s.push(e),
+                                    None => {}
+                                }
+                                i += 2;
+                            }
+                            Some(&q) if q == c => {
+                                i += 1;
+                                break;
+                            }
+                            Some(&other) => {
+                                s.push(other);
+                                i += 1;
+                            }
+                        }
+                    }
+                    tokens.push(Tok::Str(s));
+                }
+            }
+            '<' => {
+                let (s, next) = until(i + 1, ">")?;
+                tokens.push(Tok::Path(s));
+                i = next;
+            }
+            '@' => {
+                let (s, next) = if chars[i..].starts_with(&['@', '@', '@']) {
+                    until(i + 3, "@@@")?
+                } else {
+                    until(i + 1, "@")?
+                };
+                tokens.push(Tok::Asset(s));
+                i = next;
+            }
+            c if c.is_ascii_digit() || ((c == '-' || c == '+' || c == '.') && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit() || *n == '.')) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() {
+                    let d = chars[i];
+                    let exponent_sign = (d == '-' || d == '+') && matches!(chars[i - 1], 'e' | 'E');
+                    if d.is_ascii_digit() || d == '.' || d == 'e' || d == 'E' || exponent_sign {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let s: String = chars[start..i].iter().collect();
+                let v = s.parse::<f64>().map_err(|_| format!("Invalid number '{}' in USDA file", s))?;
+                tokens.push(Tok::Num(v));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '!' || c == '-' => {
+                let start = i;
+                while i < chars.len() {
+                    let d = chars[i];
+                    // Namespace separators are part of names; a colon followed by
+                    // whitespace is time-sample punctuation
+                    let namespaced = d == ':' && chars.get(i + 1).is_some_and(|n| n.is_alphanumeric() || *n == '_');
+                    if d.is_alphanumeric() || d == '_' || d == '.' || d == '!' || namespaced || (d == '-' && i == start) {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let s: String = chars[start..i].iter().collect();
+                match s.as_str() {
+                    "inf" => tokens.push(Tok::Num(f64::INFINITY)),
+                    "-inf" => tokens.push(Tok::Num(f64::NEG_INFINITY)),
+                    "nan" => tokens.push(Tok::Num(f64::NAN)),
+                    _ => tokens.push(Tok::Ident(s)),
+                }
+            }
+            other => return Err(format!("Unexpected character '{}' in USDA file", other).into()),
+        }
+    }
+    Ok(tokens)
+}
+
+// --- Parser ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    None,
+    Num(f64),
+    Str(String),
+    Path(String),
+    Asset(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Num(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) | Value::Asset(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Every number inside the value, flattened
+    fn floats(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        fn walk(v: &Value, out: &mut Vec<f64>) {
+            match v {
+                Value::Num(n) => out.push(*n),
+                Value::List(items) => items.iter().for_each(|i| walk(i, out)),
+                _ => {}
+            }
+        }
+        walk(self, &mut out);
+        out
+    }
+
+    fn ints(&self) -> Vec<i64> {
+        self.floats().into_iter().map(|f| f as i64).collect()
+    }
+
+    fn strings(&self) -> Vec<String> {
+        match self {
+            Value::List(items) => items.iter().filter_map(|i| i.as_str().map(String::from)).collect(),
+            Value::Str(s) => vec![s.clone()],
+            _ => Vec::new(),
+        }
+    }
+
+    fn paths(&self) -> Vec<&str> {
+        match self {
+            Value::Path(p) => vec![p.as_str()],
+            Value::List(items) => items.iter().flat_map(|i| i.paths()).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Attribute {
+    name: String,
+    value: Value,
+    /// Time samples (time code, value), in file order
+    samples: Vec<(f64, Value)>,
+    metadata: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone)]
+struct Prim {
+    type_name: String,
+    name: String,
+    path: String,
+    metadata: HashMap<String, Value>,
+    attributes: Vec<Attribute>,
+    children: Vec<Prim>,
+}
+
+impl Prim {
+    fn attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    /// Authored default value, falling back to the first time sample
+    fn value(&self, name: &str) -> Option<&Value> {
+        let attr = self.attribute(name)?;
+        match &attr.value {
+            Value::None => attr.samples.first().map(|(_, v)| v),
+            v => Some(v),
+        }
+    }
+
+    fn floats(&self, name: &str) -> Vec<f64> {
+        self.value(name).map(Value::floats).unwrap_or_default()
+    }
+
+    fn ints(&self, name: &str) -> Vec<i64> {
+        self.value(name).map(Value::ints).unwrap_or_default()
+    }
+
+    fn token(&self, name: &str) -> Option<&str> {
+        self.value(name)?.as_str()
+    }
+
+    fn interpolation(&self, name: &str) -> &str {
+        self.attribute(name)
+            .and_then(|a| a.metadata.get("interpolation"))
+            .and_then(Value::as_str)
+            .unwrap_or("vertex")
+    }
+
+    fn relationship(&self, name: &str) -> Option<&str> {
+        self.attribute(name)?.value.paths().first().copied()
+    }
+}
+
+/// Stage-level metadata and root prims of a USDA layer
+struct Layer {
+    metadata: HashMap<String, Value>,
+    prims: Vec<Prim>,
+}
+
+struct Parser {
+    tokens: Vec<Tok>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Tok, Box<dyn std::error::Error>> {
+        let tok = self.tokens.get(self.pos).cloned().ok_or("Unexpected end of USDA file")?;
+        self.pos += 1;
+        Ok(tok)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Tok::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Box<dyn std::error::Error>> {
+        match self.next()? {
+            Tok::Punct(p) if p == c => Ok(()),
+            other => Err(format!("Expected '{}' in USDA file, found {:?}", c, other).into()),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        match self.next()? {
+            Tok::Ident(s) => Ok(s),
+            other => Err(format!("Expected a name in USDA file, found {:?}", other).into()),
+        }
+    }
+
+    fn peek_ident(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Tok::Ident(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Skip a balanced bracketed group starting at the current opener
+    fn skip_group(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut depth = 0usize;
+        loop {
+            match self.next()? {
+                Tok::Punct('(' | '[' | '{') => depth += 1,
+                Tok::Punct(')' | ']' | '}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Box<dyn std::error::Error>> {
+        match self.next()? {
+            Tok::Num(v) => Ok(Value::Num(v)),
+            Tok::Str(s) => Ok(Value::Str(s)),
+            Tok::Path(p) => Ok(Value::Path(p)),
+            Tok::Asset(a) => {
+                // References may name a prim after the asset path
+                if let Some(Tok::Path(_)) = self.peek() {
+                    self.pos += 1;
+                }
+                Ok(Value::Asset(a))
+            }
+            Tok::Ident(s) => Ok(match s.as_str() {
+                "None" => Value::None,
+                "true" => Value::Num(1.0),
+                "false" => Value::Num(0.0),
+                _ => Value::Str(s),
+            }),
+            Tok::Punct(open @ ('[' | '(')) => {
+                let close = if open == '[' { ']' } else { ')' };
+                let mut items = Vec::new();
+                while !self.eat(close) {
+                    items.push(self.value()?);
+                    self.eat(',');
+                }
+                Ok(Value::List(items))
+            }
+            Tok::Punct('{') => {
+                // Dictionaries are not needed; skip them
+                self.pos -= 1;
+                self.skip_group()?;
+                Ok(Value::None)
+            }
+            other => Err(format!("Unexpected {:?} in USDA value", other).into()),
+        }
+    }
+
+    /// `{ time: value, ... }`
+    fn time_samples(&mut self) -> Result<Vec<(f64, Value)>, Box<dyn std::error::Error>> {
+        self.expect('{')?;
+        let mut samples = Vec::new();
+        while !self.eat('}') {
+            let time = match self.next()? {
+                Tok::Num(t) => t,
+                other => return Err(format!("Expected a time code in USDA file, found {:?}", other).into()),
+            };
+            self.expect(':')?;
+            samples.push((time, self.value()?));
+            self.eat(',');
+        }
+        Ok(samples)
+    }
+
+    /// `( key = value ... )`, with list-op keywords and doc strings ignored
+    fn metadata(&mut self) -> Result<HashMap<String, Value>, Box<dyn std::error::Error>> {
+        let mut map = HashMap::new();
+        if !self.eat('(') {
+            return Ok(map);
+        }
+        while !self.eat(')') {
+            match self.next()? {
+                Tok::Str(_) | Tok::Punct(';' | ',') => {}
+                Tok::Ident(mut key) => {
+                    if matches!(key.as_str(), "prepend" | "append" | "add" | "delete" | "reorder") {
+                        key = self.ident()?;
+                    }
+                    if self.eat('=') {
+                        let value = self.value()?;
+                        map.insert(key, value);
+                    }
+                }
+                other => return Err(format!("Unexpected {:?} in USDA metadata", other).into()),
+            }
+        }
+        Ok(map)
+    }
+
+    fn prim(&mut self, parent_path: &str) -> Result<Prim, Box<dyn std::error::Error>> {
+        let type_name = match self.peek() {
+            Some(Tok::Ident(_)) => self.ident()?,
+            _ => String::new(),
+        };
+        let name = match self.next()? {
+            Tok::Str(s) => s,
+            other => return Err(format!("Expected a prim name in USDA file, found {:?}", other).into()),
+        };
+        let path = format!("{}/{}", parent_path, name);
+        let metadata = self.metadata()?;
+        self.expect('{')?;
+
+        let mut prim = Prim {
+            type_name,
+            name,
+            path,
+            metadata,
+            attributes: Vec::new(),
+            children: Vec::new(),
+        };
+        while !self.eat('}') {
+            match self.peek_ident() {
+                Some("def" | "over" | "class") => {
+                    self.pos += 1;
+                    let child = self.prim(&prim.path)?;
+                    prim.children.push(child);
+                }
+                Some("variantSet") => {
+                    self.pos += 1;
+                    self.next()?;
+                    self.expect('=')?;
+                    self.skip_group()?;
+                }
+                Some(_) => self.property(&mut prim)?,
+                None => {
+                    if !self.eat(';') {
+                        return Err(format!("Unexpected {:?} in USDA prim body", self.peek()).into());
+                    }
+                }
+            }
+        }
+        Ok(prim)
+    }
+
+    fn property(&mut self, prim: &mut Prim) -> Result<(), Box<dyn std::error::Error>> {
+        let mut keyword = self.ident()?;
+        while matches!(
+            keyword.as_str(),
+            "custom" | "uniform" | "varying" | "prepend" | "append" | "add" | "delete" | "reorder"
+        ) {
+            keyword = self.ident()?;
+        }
+        if keyword != "rel" && self.eat('[') {
+            self.expect(']')?;
+        }
+        let full_name = self.ident()?;
+        let (name, is_samples) = match full_name.strip_suffix(".timeSamples") {
+            Some(base) => (base.to_string(), true),
+            None => (full_name, false),
+        };
+
+        let mut value = Value::None;
+        let mut samples = Vec::new();
+        if self.eat('=') {
+            if is_samples {
+                samples = self.time_samples()?;
+            } else {
+                value = self.value()?;
+            }
+        }
+        let metadata = self.metadata()?;
+
+        match prim.attributes.iter_mut().find(|a| a.name == name) {
+            Some(existing) => {
+                if is_samples {
+                    existing.samples = samples;
+                } else {
+                    existing.value = value;
+                    existing.metadata.extend(metadata);
+                }
+            }
+            None => prim.attributes.push(Attribute { name, value, samples, metadata }),
+        }
+        Ok(())
+    }
+
+    fn layer(&mut self) -> Result<Layer, Box<dyn std::error::Error>> {
+        let metadata = self.metadata()?;
+        let mut prims = Vec::new();
+        while self.peek().is_some() {
+            match self.ident()?.as_str() {
+                "def" | "over" | "class" => prims.push(self.prim("")?),
+                other => return Err(format!("Unexpected '{}' at the top level of USDA file", other).into()),
+            }
+        }
+        Ok(Layer { metadata, prims })
+    }
+}
+
+fn parse_layer(text: &str) -> Result<Layer, Box<dyn std::error::Error>> {
+    if !text.trim_start().starts_with("#usda") {
+        if text.starts_with("PXR-USDC") {
+            return Err("Binary USD (usdc) files are not supported; save as .usda".into());
+        }
+        return Err("Not a USDA file (missing #usda header)".into());
+    }
+    Parser { tokens: tokenize(text)?, pos: 0 }.layer()
+}
+
+// --- Transforms ---
+
+fn vec3(values: &[f64]) -> Vector3<f64> {
+    Vector3::new(
+        values.first().copied().unwrap_or(0.0),
+        values.get(1).copied().unwrap_or(0.0),
+        values.get(2).copied().unwrap_or(0.0),
+    )
+}
+
+fn axis_rotation(axis: usize, degrees: f64) -> Rotation3<f64> {
+    let mut v = Vector3::zeros();
+    v[axis] = 1.0;
+    Rotation3::from_axis_angle(&nalgebra::Unit::new_unchecked(v), degrees.to_radians())
+}
+
+/// Matrix from a USD matrix literal, whose rows are nalgebra columns
+fn matrix(values: &[f64]) -> Option<Matrix4<f64>> {
+    (values.len() == 16).then(|| Matrix4::from_column_slice(values))
+}
+
+/// Local transform of a prim from its `xformOpOrder`. The second value is
+/// true when the op stack resets the parent transform.
+fn local_transform(prim: &Prim) -> (Matrix4<f64>, bool) {
+    let Some(order) = prim.value("xformOpOrder").map(Value::strings) else {
+        return (Matrix4::identity(), false);
+    };
+    let mut m = Matrix4::identity();
+    let mut reset = false;
+    for op in order {
+        if op == "!resetXformStack!" {
+            m = Matrix4::identity();
+            reset = true;
+            continue;
+        }
+        let (inverse, op) = match op.strip_prefix("!invert!") {
+            Some(rest) => (true, rest),
+            None => (false, op.as_str()),
+        };
+        let v = prim.floats(op);
+        let kind = op.trim_start_matches("xformOp:").split(':').next().unwrap_or("");
+        let op_matrix = match kind {
+            "translate" => Matrix4::new_translation(&vec3(&v)),
+            "scale" => Matrix4::new_nonuniform_scaling(&vec3(&if v.len() == 1 { vec![v[0]; 3] } else { v })),
+            "rotateX" | "rotateY" | "rotateZ" => {
+                let axis = (kind.as_bytes()[6] - b'X') as usize;
+                axis_rotation(axis, v.first().copied().unwrap_or(0.0)).to_homogeneous()
+            }
+            "orient" if v.len() == 4 => {
+                UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(v[0], v[1], v[2], v[3])).to_homogeneous()
+            }
+            "transform" => matrix(&v).unwrap_or_else(Matrix4::identity),
+            k if k.starts_with("rotate") && k.len() == 9 => {
+                // rotateXYZ applies X first: R = Rz * Ry * Rx
+                let angles = vec3(&v);
+                let mut r = Rotation3::identity();
+                for axis_char in k[6..].bytes() {
+                    let axis = (axis_char - b'X') as usize;
+                    r = axis_rotation(axis, angles[axis]) * r;
+                }
+                r.to_homogeneous()
+            }
+            _ => Matrix4::identity(),
+        };
+        let op_matrix = if inverse {
+            op_matrix.try_inverse().unwrap_or_else(Matrix4::identity)
+        } else {
+            op_matrix
+        };
+        m *= op_matrix;
+    }
+    (m, reset)
+}
+
+/// Scale the translation part of a matrix, converting it to meters
+fn scale_translation(m: &Matrix4<f64>, unit: f64) -> Matrix4<f64> {
+    let mut m = *m;
+    for row in 0..3 {
+        m[(row, 3)] *= unit;
+    }
+    m
+}
+
+fn to_f32(m: &Matrix4<f64>) -> [[f32; 4]; 4] {
+    m.cast::<f32>().into()
+}
+
+// --- Reading ---
+
+/// A Mesh prim with its world transform and the material bound to it or an ancestor
+struct MeshInstance<'a> {
+    prim: &'a Prim,
+    world: Matrix4<f64>,
+    material: Option<String>,
+    skeleton: Option<String>,
+}
+
+/// Index of all prims by path plus the mesh instances found while walking
+struct Stage<'a> {
+    by_path: HashMap<&'a str, &'a Prim>,
+    world: HashMap<&'a str, Matrix4<f64>>,
+    meshes: Vec<MeshInstance<'a>>,
+}
+
+impl<'a> Stage<'a> {
+    fn new(layer: &'a Layer) -> Self {
+        let mut stage = Stage {
+            by_path: HashMap::new(),
+            world: HashMap::new(),
+            meshes: Vec::new(),
+        };
+        for prim in &layer.prims {
+            stage.walk(prim, Matrix4::identity(), None, None);
+        }
+        stage
+    }
+
+    fn walk(&mut self, prim: &'a Prim, parent: Matrix4<f64>, material: Option<&str>, skeleton: Option<&str>) {
+        // Class prims are templates, and inactive prims are pruned
+        if prim.metadata.get("active") == Some(&Value::Num(0.0)) {
+            return;
+        }
+        self.by_path.insert(&prim.path, prim);
+        let (local, reset) = local_transform(prim);
+        let world = if reset { local } else { parent * local };
+        self.world.insert(&prim.path, world);
+        let material = prim.relationship("material:binding").or(material);
+        let skeleton = prim.relationship("skel:skeleton").or(skeleton);
+        if prim.type_name == "Mesh" {
+            self.meshes.push(MeshInstance {
+                prim,
+                world,
+                material: material.map(String::from),
+                skeleton: skeleton.map(String::from),
+            });
+        }
+        for child in &prim.children {
+            self.walk(child, world, material, skeleton);
+        }
+    }
+
+    /// Diffuse color and texture file of a UsdPreviewSurface material
+    fn material(&self, path: &str) -> Option<([f32; 4], Option<String>)> {
+        let material = self.by_path.get(path)?;
+        let mut stack = vec![*material];
+        while let Some(prim) = stack.pop() {
+            if prim.type_name == "Shader" && prim.token("info:id") == Some("UsdPreviewSurface") {
+                let rgb = prim.floats("inputs:diffuseColor");
+                let opacity = prim.value("inputs:opacity").and_then(Value::as_f64).unwrap_or(1.0);
+                let color = if rgb.len() == 3 {
+                    [rgb[0] as f32, rgb[1] as f32, rgb[2] as f32, opacity as f32]
+                } else {
+                    [0.18, 0.18, 0.18, opacity as f32]
+                };
+                let texture = prim
+                    .attribute("inputs:diffuseColor.connect")
+                    .and_then(|a| a.value.paths().first().map(|p| p.split('.').next().unwrap_or(p).to_string()))
+                    .and_then(|shader| self.by_path.get(shader.as_str()).copied())
+                    .and_then(|shader| shader.token("inputs:file").map(String::from));
+                return Some((color, texture));
+            }
+            stack.extend(prim.children.iter());
+        }
+        None
+    }
+}
+
+/// Resolve an indexed or plain primvar to one value per element of its interpolation
+fn primvar<const N: usize>(prim: &Prim, name: &str) -> Option<Vec<[f32; N]>> {
+    let values = prim.floats(name);
+    if values.is_empty() || !values.len().is_multiple_of(N) {
+        return None;
+    }
+    let items: Vec<[f32; N]> = values
+        .chunks_exact(N)
+        .map(|c| std::array::from_fn(|i| c[i] as f32))
+        .collect();
+    let indices = prim.ints(&format!("{}:indices", name));
+    if indices.is_empty() {
+        return Some(items);
+    }
+    indices
+        .iter()
+        .map(|&i| usize::try_from(i).ok().and_then(|i| items.get(i)).copied())
+        .collect()
+}
+
+/// One mesh prim converted to triangles, before merging
+#[derive(Default)]
+struct Part {
+    positions: Vec<[f32; 3]>,
+    faces: Vec<[u32; 3]>,
+    /// Polygon index of each triangle
+    polygons: Vec<usize>,
+    corner_uvs: Option<Vec<[[f32; 2]; 3]>>,
+    corner_normals: Option<Vec<[[f32; 3]; 3]>>,
+    face_colors: Option<Vec<[f32; 4]>>,
+    vertex_colors: Option<Vec<[f32; 4]>>,
+}
+
+/// Per-corner lookup of a primvar: `corner` is the face-vertex index, `point` the point index
+fn corner_value<T: Copy>(interpolation: &str, values: &[T], corner: usize, point: usize, polygon: usize) -> Option<T> {
+    match interpolation {
+        "faceVarying" => values.get(corner),
+        "uniform" => values.get(polygon),
+        "constant" => values.first(),
+        _ => values.get(point),
+    }
+    .copied()
+}
+
+fn read_mesh(
+    instance: &MeshInstance,
+    transform: &Matrix4<f64>,
+    stage: &Stage,
+) -> Result<(Part, Option<String>), Box<dyn std::error::Error>> {
+    let prim = instance.prim;
+    let points = prim.floats("points");
+    let counts = prim.ints("faceVertexCounts");
+    let indices = prim.ints("faceVertexIndices");
+    let positions: Vec<[f32; 3]> = points
+        .chunks_exact(3)
+        .map(|p| {
+            let v = transform.transform_point(&nalgebra::Point3::new(p[0], p[1], p[2]));
+            [v.x as f32, v.y as f32, v.z as f32]
+        })
+        .collect();
+    let normal_matrix = transform
+        .fixed_view::<3, 3>(0, 0)
+        .try_inverse()
+        .unwrap_or_else(nalgebra::Matrix3::identity)
+        .transpose();
+    let flip = (transform.fixed_view::<3, 3>(0, 0).determinant() < 0.0)
+        != (prim.token("orientation") == Some("leftHanded"));
+
+    let uvs = primvar::<2>(prim, "primvars:st").or_else(|| primvar::<2>(prim, "primvars:st0"));
+    let uv_interp = if prim.attribute("primvars:st").is_some() {
+        prim.interpolation("primvars:st")
+    } else {
+        prim.interpolation("primvars:st0")
+    };
+    let normal_name = if prim.attribute("primvars:normals").is_some() { "primvars:normals" } else { "normals" };
+    let normals = primvar::<3>(prim, normal_name);
+    let normal_interp = prim.interpolation(normal_name);
+
+    let mut part = Part {
+        positions,
+        corner_uvs: uvs.as_ref().map(|_| Vec::new()),
+        corner_normals: normals.as_ref().map(|_| Vec::new()),
+        ..Default::default()
+    };
+    let mut corner = 0usize;
+    for (polygon, &count) in counts.iter().enumerate() {
+        let count = usize::try_from(count).map_err(|_| "Negative face vertex count in USDA mesh")?;
+        let face = indices
+            .get(corner..corner + count)
+            .ok_or("faceVertexIndices is shorter than faceVertexCounts requires")?;
+        let face: Vec<(usize, usize)> = face
+            .iter()
+            .enumerate()
+            .map(|(k, &p)| (corner + k, p as usize))
+            .collect();
+        if face.iter().any(|&(_, p)| p >= part.positions.len()) {
+            return Err(format!("USDA mesh {} polygon {} references a missing point", prim.path, polygon).into());
+        }
+        for k in 1..count.saturating_sub(1) {
+            let mut tri = [face[0], face[k], face[k + 1]];
+            if flip {
+                tri.swap(1, 2);
+            }
+            part.faces.push(tri.map(|(_, p)| p as u32));
+            part.polygons.push(polygon);
+            if let (Some(out), Some(values)) = (&mut part.corner_uvs, &uvs) {
+                out.push(tri.map(|(c, p)| {
+                    corner_value(uv_interp, values, c, p, polygon)
+                        .map(|uv| [uv[0], 1.0 - uv[1]])
+                        .unwrap_or([0.0; 2])
+                }));
+            }
+            if let (Some(out), Some(values)) = (&mut part.corner_normals, &normals) {
+                out.push(tri.map(|(c, p)| {
+                    corner_value(normal_interp, values, c, p, polygon)
+                        .map(|n| {
+                            let n = (normal_matrix * Vector3::new(n[0] as f64, n[1] as f64, n[2] as f64)).normalize();
+                            [n.x as f32, n.y as f32, n.z as f32]
+                        })
+                        .unwrap_or([0.0; 3])
+                }));
+            }
+        }
+        corner += count;
+    }
+
+    // displayColor (+ displayOpacity) as face or vertex colors
+    if let Some(colors) = primvar::<3>(prim, "primvars:displayColor") {
+        let opacity = primvar::<1>(prim, "primvars:displayOpacity");
+        let interp = prim.interpolation("primvars:displayColor");
+        let rgba = |i: usize| {
+            let c = colors.get(i).or(colors.first()).copied().unwrap_or([0.85; 3]);
+            let a = opacity.as_ref().and_then(|o| o.get(i).or(o.first())).map(|a| a[0]).unwrap_or(1.0);
+            [c[0], c[1], c[2], a]
+        };
+        match interp {
+            "constant" | "uniform" => {
+                part.face_colors = Some(
+                    part.polygons
+                        .iter()
+                        .map(|&p| rgba(if interp == "uniform" { p } else { 0 }))
+                        .collect(),
+                );
+            }
+            "faceVarying" => {
+                // No per-corner color storage: average the corners around each point
+                let mut sums = vec![([0.0f32; 4], 0u32); part.positions.len()];
+                for (c, &p) in indices.iter().enumerate().take(corner) {
+                    if let Some((sum, n)) = sums.get_mut(p as usize) {
+                        let v = rgba(c);
+                        (0..4).for_each(|k| sum[k] += v[k]);
+                        *n += 1;
+                    }
+                }
+                part.vertex_colors = Some(
+                    sums.iter()
+                        .map(|(sum, n)| if *n > 0 { sum.map(|s| s / *n as f32) } else { DEFAULT_COLOR })
+                        .collect(),
+                );
+            }
+            _ => part.vertex_colors = Some((0..part.positions.len()).map(rgba).collect()),
+        }
+    }
+
+    // Bound materials override the display color, per GeomSubset where present
+    let mut texture = None;
+    let mut material_colors: Vec<Option<[f32; 4]>> = vec![None; counts.len()];
+    let mut any_material = false;
+    if let Some((color, file)) = instance.material.as_deref().and_then(|m| stage.material(m)) {
+        material_colors.iter_mut().for_each(|c| *c = Some(color));
+        texture = file;
+        any_material = true;
+    }
+    for subset in prim.children.iter().filter(|c| c.type_name == "GeomSubset") {
+        if subset.token("elementType").unwrap_or("face") != "face" {
+            continue;
+        }
+        let Some((color, file)) = subset.relationship("material:binding").and_then(|m| stage.material(m)) else {
+            continue;
+        };
+        for i in subset.ints("indices") {
+            if let Some(slot) = usize::try_from(i).ok().and_then(|i| material_colors.get_mut(i)) {
+                *slot = Some(color);
+            }
+        }
+        texture = texture.or(file);
+        any_material = true;
+    }
+    if any_material {
+        let fallback = part.face_colors.take();
+        part.face_colors = Some(
+            part.polygons
+                .iter()
+                .enumerate()
+                .map(|(t, &p)| {
+                    material_colors[p]
+                        .or_else(|| fallback.as_ref().map(|f| f[t]))
+                        .unwrap_or(DEFAULT_COLOR)
+                })
+                .collect(),
+        );
+    }
+
+    Ok((part, texture))
+}
+
+fn merge(parts: Vec<Part>) -> MeshWithColors {
+    let has_uvs = parts.iter().any(|p| p.corner_uvs.is_some());
+    let has_normals = parts.iter().any(|p| p.corner_normals.is_some());
+    let has_face_colors = parts.iter().any(|p| p.face_colors.is_some());
+    let has_vertex_colors = parts.iter().any(|p| p.vertex_colors.is_some());
+
+    let mut mesh = MeshWithColors::default();
+    for part in parts {
+        let offset = mesh.positions.len() as u32;
+        let faces = part.faces.len();
+        let points = part.positions.len();
+        mesh.positions.extend(part.positions);
+        mesh.face_indices.extend(part.faces.iter().map(|f| f.map(|i| i + offset)));
+        if has_uvs {
+            mesh.corner_texcoords.extend(part.corner_uvs.unwrap_or_else(|| vec![[[0.0; 2]; 3]; faces]));
+        }
+        if has_normals {
+            mesh.corner_normals.extend(part.corner_normals.unwrap_or_else(|| vec![[[0.0; 3]; 3]; faces]));
+        }
+        if has_face_colors {
+            mesh.face_colors.extend(part.face_colors.unwrap_or_else(|| vec![DEFAULT_COLOR; faces]));
+        }
+        if has_vertex_colors {
+            mesh.vertex_colors.extend(part.vertex_colors.unwrap_or_else(|| vec![DEFAULT_COLOR; points]));
+        }
+    }
+    mesh
+}
+
+/// Load the Mesh prims of a USDA file (only the one named `mesh_name` when given),
+/// baking their transforms and merging them into one mesh
+pub fn load_usda(path: &Path, mesh_name: Option<&str>) -> Result<MeshWithColors, Box<dyn std::error::Error>> {
+    let text = stdio::read_to_string(path)?;
+    let layer = parse_layer(&text)?;
+    let stage = Stage::new(&layer);
+    let unit = layer.metadata.get("metersPerUnit").and_then(Value::as_f64).unwrap_or(0.01);
+
+    let instances: Vec<&MeshInstance> = match mesh_name {
+        None => stage.meshes.iter().collect(),
+        Some(name) => {
+            let found: Vec<_> = stage
+                .meshes
+                .iter()
+                .filter(|m| m.prim.name == name || m.prim.path == name)
+                .collect();
+            if found.is_empty() {
+                let names: Vec<&str> = stage.meshes.iter().map(|m| m.prim.path.as_str()).collect();
+                return Err(format!(
+                    "Mesh '{}' not found in USDA file.\nAvailable meshes: {}",
+                    name,
+                    names.join(", ")
+                )
+                .into());
+            }
+            found
+        }
+    };
+    if instances.is_empty() {
+        return Err("USDA file contains no Mesh prims".into());
+    }
+    progress!("Loading {} mesh prim(s)", instances.len());
+
+    // UsdSkel: a skinned mesh is posed by its skeleton, not its own transform
+    let skinned = instances.len() == 1
+        && instances[0].skeleton.is_some()
+        && instances[0].prim.attribute("primvars:skel:jointIndices").is_some();
+    if instances.len() > 1 && instances.iter().any(|m| m.skeleton.is_some()) {
+        eprintln!("Warning: skins are only loaded for a single mesh; select one with --mesh");
+    }
+
+    let mut parts = Vec::new();
+    let mut texture_file = None;
+    for instance in &instances {
+        let transform = if skinned {
+            matrix(&instance.prim.floats("primvars:skel:geomBindTransform")).unwrap_or_else(Matrix4::identity)
+        } else {
+            instance.world
+        };
+        let (part, texture) = read_mesh(instance, &(Matrix4::new_scaling(unit) * transform), &stage)?;
+        texture_file = texture_file.or(texture);
+        parts.push(part);
+    }
+    let mut mesh = merge(parts);
+
+    if let Some(file) = texture_file {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let texture_path = dir.join(&file);
+        match image::open(&texture_path) {
+            Ok(img) => {
+                let rgba = img.to_rgba8();
+                mesh.texture = Some(TextureData {
+                    width: rgba.width(),
+                    height: rgba.height(),
+                    pixels: rgba.into_raw(),
+                });
+            }
+            Err(e) => eprintln!("Warning: failed to load texture {:?}: {}", texture_path, e),
+        }
+    }
+
+    if skinned {
+        let instance = instances[0];
+        let skeleton_path = instance.skeleton.as_deref().unwrap_or("");
+        let skeleton_prim = stage
+            .by_path
+            .get(skeleton_path)
+            .ok_or_else(|| format!("Skeleton {} not found in USDA file", skeleton_path))?;
+        let time_codes_per_second = layer
+            .metadata
+            .get("timeCodesPerSecond")
+            .or(layer.metadata.get("framesPerSecond"))
+            .and_then(Value::as_f64)
+            .unwrap_or(24.0);
+        let skel_world = scale_translation(
+            &stage.world.get(skeleton_path).copied().unwrap_or_else(Matrix4::identity),
+            unit,
+        );
+        read_skeleton(&mut mesh, instance.prim, skeleton_prim, &stage, skel_world, unit, time_codes_per_second)?;
+    }
+
+    Ok(mesh)
+}
+
+/// Fill the skeleton, skin weights and animation of a skinned mesh
+fn read_skeleton(
+    mesh: &mut MeshWithColors,
+    mesh_prim: &Prim,
+    skeleton_prim: &Prim,
+    stage: &Stage,
+    skel_world: Matrix4<f64>,
+    unit: f64,
+    time_codes_per_second: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let joint_paths = skeleton_prim.value("joints").map(Value::strings).unwrap_or_default();
+    if joint_paths.is_empty() {
+        return Err(format!("Skeleton {} has no joints", skeleton_prim.path).into());
+    }
+    let matrices = |name: &str| -> Vec<Matrix4<f64>> {
+        skeleton_prim
+            .floats(name)
+            .chunks_exact(16)
+            .map(|m| scale_translation(&Matrix4::from_column_slice(m), unit))
+            .collect()
+    };
+    let bind = matrices("bindTransforms");
+    let rest = matrices("restTransforms");
+
+    // Parents precede children once joints are sorted by depth
+    let mut order: Vec<usize> = (0..joint_paths.len()).collect();
+    order.sort_by_key(|&i| joint_paths[i].matches('/').count());
+    let mut new_index = vec![0usize; joint_paths.len()];
+    for (new, &old) in order.iter().enumerate() {
+        new_index[old] = new;
+    }
+    let by_path: HashMap<&str, usize> = joint_paths.iter().enumerate().map(|(i, p)| (p.as_str(), i)).collect();
+
+    let joints: Vec<Joint> = order
+        .iter()
+        .enumerate()
+        .map(|(new, &old)| {
+            let path = joint_paths[old].as_str();
+            let parent = path.rsplit_once('/').and_then(|(p, _)| by_path.get(p)).map(|&i| new_index[i]);
+            let bind_world = bind.get(old).copied().unwrap_or_else(Matrix4::identity);
+            let local = match rest.get(old) {
+                Some(m) => *m,
+                // Without rest transforms, derive them from the bind pose
+                None => match path.rsplit_once('/').and_then(|(p, _)| by_path.get(p)) {
+                    Some(&p) => {
+                        bind.get(p).and_then(|m| m.try_inverse()).unwrap_or_else(Matrix4::identity) * bind_world
+                    }
+                    None => bind_world,
+                },
+            };
+            // Root joints are placed by the Skeleton prim's own transform
+            let (local, bind_world) = if parent.is_none() {
+                (skel_world * local, skel_world * bind_world)
+            } else {
+                (local, skel_world * bind_world)
+            };
+            let (translation, rotation, scale) = JointTransform::Matrix(to_f32(&local)).decompose();
+            Joint {
+                index: new,
+                node_index: old,
+                name: Some(path.rsplit('/').next().unwrap_or(path).to_string()),
+                parent,
+                inverse_bind_matrix: to_f32(&bind_world.try_inverse().unwrap_or_else(Matrix4::identity)),
+                local_transform: JointTransform::Decomposed { translation, rotation, scale },
+            }
+        })
+        .collect();
+
+    // Influences, optionally indexing a mesh-specific joint subset
+    let element_size = mesh_prim
+        .attribute("primvars:skel:jointIndices")
+        .and_then(|a| a.metadata.get("elementSize"))
+        .and_then(Value::as_f64)
+        .unwrap_or(1.0)
+        .max(1.0) as usize;
+    let constant = mesh_prim.interpolation("primvars:skel:jointIndices") == "constant";
+    let mesh_joints = mesh_prim.value("skel:joints").map(Value::strings);
+    let joint_map = |i: i64| -> u16 {
+        let skel_index = match &mesh_joints {
+            Some(names) => names.get(i as usize).and_then(|n| by_path.get(n.as_str())).copied(),
+            None => usize::try_from(i).ok(),
+        };
+        skel_index.map(|j| new_index.get(j).copied().unwrap_or(0)).unwrap_or(0) as u16
+    };
+    let indices = mesh_prim.ints("primvars:skel:jointIndices");
+    let weights = mesh_prim.floats("primvars:skel:jointWeights");
+    for v in 0..mesh.positions.len() {
+        let start = if constant { 0 } else { v * element_size };
+        let mut influences: Vec<(u16, f32)> = (start..start + element_size)
+            .filter_map(|k| Some((joint_map(*indices.get(k)?), *weights.get(k)? as f32)))
+            .collect();
+        influences.sort_by(|a, b| b.1.total_cmp(&a.1));
+        influences.truncate(4);
+        let total: f32 = influences.iter().map(|(_, w)| w).sum();
+        let mut ji = [0u16; 4];
+        let mut jw = [0.0f32; 4];
+        for (k, &(j, w)) in influences.iter().enumerate() {
+            ji[k] = j;
+            jw[k] = if total > 0.0 { w / total } else { 0.0 };
+        }
+        mesh.joint_indices.push(ji);
+        mesh.joint_weights.push(jw);
+    }
+
+    // Geometry bound with a non-identity geomBindTransform is already in skeleton space
+    let skeleton = Skeleton { joints };
+    let animation = skeleton_prim
+        .relationship("skel:animationSource")
+        .and_then(|p| stage.by_path.get(p).copied())
+        .or_else(|| skeleton_prim.children.iter().find(|c| c.type_name == "SkelAnimation"));
+    if let Some(anim) = animation {
+        let clip = read_animation(anim, &skeleton, &by_path, &new_index, skel_world, unit, time_codes_per_second);
+        if !clip.channels.is_empty() {
+            progress!("Loaded animation: {}", anim.name);
+            mesh.animations.push(clip);
+        }
+    }
+    progress!(
+        "Loaded skeleton with {} joints, {} skinned vertices",
+        skeleton.joints.len(),
+        mesh.joint_indices.len()
+    );
+    mesh.skeleton = Some(skeleton);
+    Ok(())
+}
+
+/// Convert a SkelAnimation's joint-ordered T/R/S samples to animation channels
+fn read_animation(
+    anim: &Prim,
+    skeleton: &Skeleton,
+    by_path: &HashMap<&str, usize>,
+    new_index: &[usize],
+    skel_world: Matrix4<f64>,
+    unit: f64,
+    time_codes_per_second: f64,
+) -> AnimationClip {
+    let anim_joints = anim.value("joints").map(Value::strings).unwrap_or_default();
+    let targets: Vec<Option<usize>> = anim_joints
+        .iter()
+        .map(|p| by_path.get(p.as_str()).map(|&i| new_index[i]))
+        .collect();
+
+    // (time code, per-joint values) for each property; defaults act as a single sample
+    let sampled = |name: &str, width: usize| -> Vec<(f64, Vec<f64>)> {
+        match anim.attribute(name) {
+            Some(a) if !a.samples.is_empty() => a.samples.iter().map(|(t, v)| (*t, v.floats())).collect(),
+            Some(a) if a.value != Value::None => vec![(0.0, a.value.floats())],
+            _ => Vec::new(),
+        }
+        .into_iter()
+        .filter(|(_, v)| v.len() >= width * targets.len())
+        .collect()
+    };
+    let translations = sampled("translations", 3);
+    let rotations = sampled("rotations", 4);
+    let scales = sampled("scales", 3);
+
+    let mut times: Vec<f64> = translations
+        .iter()
+        .chain(&rotations)
+        .chain(&scales)
+        .map(|(t, _)| *t)
+        .collect();
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+    let start = times.first().copied().unwrap_or(0.0);
+
+    // Value of a property at a time code: the latest sample at or before it
+    let at = |samples: &[(f64, Vec<f64>)], t: f64| -> Option<Vec<f64>> {
+        let i = samples.partition_point(|(s, _)| *s <= t);
+        samples.get(i.saturating_sub(1)).map(|(_, v)| v.clone())
+    };
+
+    let mut channels: Vec<AnimationChannel> = Vec::new();
+    let mut keys: HashMap<(usize, usize), Vec<Keyframe>> = HashMap::new();
+    let mut duration = 0.0f32;
+    for &t in &times {
+        let time = ((t - start) / time_codes_per_second) as f32;
+        duration = duration.max(time);
+        let (tr, ro, sc) = (at(&translations, t), at(&rotations, t), at(&scales, t));
+        for (k, target) in targets.iter().enumerate() {
+            let Some(joint) = *target else { continue };
+            let (rest_t, rest_r, rest_s) = skeleton.joints[joint].local_transform.decompose();
+            let mut translation = tr.as_ref().map(|v| [0, 1, 2].map(|i| (v[3 * k + i] * unit) as f32));
+            // USD quaternions are (real, i, j, k)
+            let mut rotation = ro.as_ref().map(|v| [v[4 * k + 1], v[4 * k + 2], v[4 * k + 3], v[4 * k]].map(|c| c as f32));
+            let mut scale = sc.as_ref().map(|v| [0, 1, 2].map(|i| v[3 * k + i] as f32));
+            if skeleton.joints[joint].parent.is_none() {
+                let local = animation::compose_trs(
+                    &translation.unwrap_or(rest_t),
+                    &rotation.unwrap_or(rest_r),
+                    &scale.unwrap_or(rest_s),
+                );
+                let (t2, r2, s2) = JointTransform::Matrix(to_f32(&(skel_world * Matrix4::from(local).cast::<f64>()))).decompose();
+                translation = Some(t2);
+                rotation = Some(r2);
+                scale = Some(s2);
+            }
+            for (slot, value) in [
+                translation.map(|v| v.to_vec()),
+                rotation.map(|v| v.to_vec()),
+                scale.map(|v| v.to_vec()),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                if let Some(value) = value {
+                    keys.entry((joint, slot)).or_default().push(Keyframe {
+                        time,
+                        value,
+                        in_tangent: None,
+                        out_tangent: None,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut entries: Vec<_> = keys.into_iter().collect();
+    entries.sort_by_key(|(k, _)| *k);
+    for ((joint, slot), mut keyframes) in entries {
+        if slot == 1 {
+            // Keep consecutive quaternions in the same hemisphere for slerp
+            for i in 1..keyframes.len() {
+                let dot: f32 = keyframes[i].value.iter().zip(&keyframes[i - 1].value).map(|(a, b)| a * b).sum();
+                if dot < 0.0 {
+                    keyframes[i].value.iter_mut().for_each(|c| *c = -*c);
+                }
+            }
+        }
+        channels.push(AnimationChannel {
+            joint_index: joint,
+            property: [AnimationProperty::Translation, AnimationProperty::Rotation, AnimationProperty::Scale][slot],
+            interpolation: Interpolation::Linear,
+            keyframes,
+        });
+    }
+
+    AnimationClip {
+        name: Some(anim.name.clone()),
+        channels,
+        duration,
+    }
+}
+
+// --- Writing ---
+
+fn fmt_f(v: f32) -> String {
+    let v = if v == 0.0 { 0.0 } else { v };
+    format!("{}", v)
+}
+
+fn fmt_tuple(values: &[f32]) -> String {
+    format!("({})", values.iter().map(|&v| fmt_f(v)).collect::<Vec<_>>().join(", "))
+}
+
+fn fmt_array<I: IntoIterator<Item = String>>(items: I) -> String {
+    format!("[{}]", items.into_iter().collect::<Vec<_>>().join(", "))
+}
+
+/// USD matrix literal: each nalgebra column becomes a row
+fn fmt_matrix(m: &[[f32; 4]; 4]) -> String {
+    format!("({})", m.iter().map(|c| fmt_tuple(c)).collect::<Vec<_>>().join(", "))
+}
+
+/// Prim-safe identifier for a joint or mesh name
+fn usd_name(name: &str, fallback: &str) -> String {
+    let mut s: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if s.is_empty() {
+        s = fallback.to_string();
+    }
+    if s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, '_');
+    }
+    s
+}
+
+/// Joint token paths (`Root/Child/...`), unique among siblings
+fn joint_paths(skeleton: &Skeleton) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::with_capacity(skeleton.joints.len());
+    for (i, joint) in skeleton.joints.iter().enumerate() {
+        let base = usd_name(joint.name.as_deref().unwrap_or(""), &format!("joint{}", i));
+        let prefix = joint.parent.map(|p| format!("{}/", paths[p])).unwrap_or_default();
+        let mut path = format!("{}{}", prefix, base);
+        if paths.contains(&path) {
+            path = format!("{}{}_{}", prefix, base, i);
+        }
+        paths.push(path);
+    }
+    paths
+}
+
+/// Write a mesh as a USDA layer, with a PNG sidecar for its texture
+pub fn write_usda(mesh: &MeshWithColors, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("mesh");
+    let skeleton = mesh.skeleton.as_ref().filter(|_| mesh.joint_indices.len() == mesh.positions.len());
+    let clip = skeleton.and(mesh.animations.first());
+    let time_codes_per_second = 24.0f32;
+
+    // Stdout has nowhere to put the texture file
+    let texture = mesh.texture.as_ref().filter(|_| !stdio::is_std(path));
+    if mesh.texture.is_some() && texture.is_none() {
+        eprintln!("Warning: USDA output to stdout cannot write its texture file; dropping texture");
+    }
+    if !mesh.morph_targets.is_empty() {
+        eprintln!("Warning: USDA output does not store morph targets; dropping");
+    }
+    if mesh.animations.len() > 1 && skeleton.is_some() {
+        eprintln!(
+            "Warning: USDA output stores one animation; dropping {} other clip(s)",
+            mesh.animations.len() - 1
+        );
+    }
+
+    // Key times of the clip, as time codes
+    let mut times: Vec<f32> = clip
+        .map(|c| c.channels.iter().flat_map(|ch| ch.keyframes.iter().map(|k| k.time)).collect())
+        .unwrap_or_default();
+    times.sort_by(f32::total_cmp);
+    times.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+    let mut out = String::new();
+    writeln!(out, "#usda 1.0")?;
+    writeln!(out, "(")?;
+    writeln!(out, "    defaultPrim = \"Root\"")?;
+    writeln!(out, "    metersPerUnit = 1")?;
+    writeln!(out, "    upAxis = \"Y\"")?;
+    if let (Some(first), Some(last)) = (times.first(), times.last()) {
+        writeln!(out, "    startTimeCode = {}", fmt_f(first * time_codes_per_second))?;
+        writeln!(out, "    endTimeCode = {}", fmt_f(last * time_codes_per_second))?;
+    }
+    writeln!(out, "    timeCodesPerSecond = {}", time_codes_per_second)?;
+    writeln!(out, ")")?;
+    writeln!(out)?;
+
+    let root_type = if skeleton.is_some() { "SkelRoot" } else { "Xform" };
+    writeln!(out, "def {} \"Root\"", root_type)?;
+    writeln!(out, "{{")?;
+
+    // Mesh
+    let api = if skeleton.is_some() { "\"SkelBindingAPI\", " } else { "" };
+    let material_api = if texture.is_some() { "\"MaterialBindingAPI\"" } else { "" };
+    let schemas = format!("{}{}", api, material_api);
+    let schemas = schemas.trim_end_matches(", ");
+    if schemas.is_empty() {
+        writeln!(out, "    def Mesh \"{}\"", usd_name(stem, "mesh"))?;
+    } else {
+        writeln!(out, "    def Mesh \"{}\" (", usd_name(stem, "mesh"))?;
+        writeln!(out, "        prepend apiSchemas = [{}]", schemas)?;
+        writeln!(out, "    )")?;
+    }
+    writeln!(out, "    {{")?;
+    writeln!(out, "        uniform token subdivisionScheme = \"none\"")?;
+    writeln!(out, "        int[] faceVertexCounts = {}", fmt_array(mesh.face_indices.iter().map(|_| "3".to_string())))?;
+    writeln!(
+        out,
+        "        int[] faceVertexIndices = {}",
+        fmt_array(mesh.face_indices.iter().flat_map(|f| f.iter().map(|i| i.to_string())))
+    )?;
+    writeln!(out, "        point3f[] points = {}", fmt_array(mesh.positions.iter().map(|p| fmt_tuple(p))))?;
+
+    let corners = mesh.face_indices.len();
+    if mesh.corner_normals.len() == corners && corners > 0 {
+        writeln!(
+            out,
+            "        normal3f[] normals = {} (\n            interpolation = \"faceVarying\"\n        )",
+            fmt_array(mesh.corner_normals.iter().flatten().map(|n| fmt_tuple(n)))
+        )?;
+    } else if mesh.normals.len() == mesh.positions.len() && !mesh.normals.is_empty() {
+        writeln!(
+            out,
+            "        normal3f[] normals = {} (\n            interpolation = \"vertex\"\n        )",
+            fmt_array(mesh.normals.iter().map(|n| fmt_tuple(n)))
+        )?;
+    }
+
+    let flip_v = |uv: &[f32; 2]| fmt_tuple(&[uv[0], 1.0 - uv[1]]);
+    if mesh.corner_texcoords.len() == corners && corners > 0 {
+        writeln!(
+            out,
+            "        texCoord2f[] primvars:st = {} (\n            interpolation = \"faceVarying\"\n        )",
+            fmt_array(mesh.corner_texcoords.iter().flatten().map(flip_v))
+        )?;
+    } else if mesh.texcoords.len() == mesh.positions.len() && !mesh.texcoords.is_empty() {
+        writeln!(
+            out,
+            "        texCoord2f[] primvars:st = {} (\n            interpolation = \"vertex\"\n        )",
+            fmt_array(mesh.texcoords.iter().map(flip_v))
+        )?;
+    }
+
+    let colors = if mesh.vertex_colors.len() == mesh.positions.len() && !mesh.vertex_colors.is_empty() {
+        Some(("vertex", &mesh.vertex_colors))
+    } else if mesh.face_colors.len() == corners && corners > 0 {
+        Some(("uniform", &mesh.face_colors))
+    } else {
+        None
+    };
+    if let Some((interpolation, colors)) = colors {
+        writeln!(
+            out,
+            "        color3f[] primvars:displayColor = {} (\n            interpolation = \"{}\"\n        )",
+            fmt_array(colors.iter().map(|c| fmt_tuple(&c[..3]))),
+            interpolation
+        )?;
+        if colors.iter().any(|c| c[3] < 1.0) {
+            writeln!(
+                out,
+                "        float[] primvars:displayOpacity = {} (\n            interpolation = \"{}\"\n        )",
+                fmt_array(colors.iter().map(|c| fmt_f(c[3]))),
+                interpolation
+            )?;
+        }
+    }
+
+    let paths = skeleton.map(joint_paths).unwrap_or_default();
+    if skeleton.is_some() {
+        writeln!(
+            out,
+            "        int[] primvars:skel:jointIndices = {} (\n            elementSize = 4\n            interpolation = \"vertex\"\n        )",
+            fmt_array(mesh.joint_indices.iter().flatten().map(|j| j.to_string()))
+        )?;
+        writeln!(
+            out,
+            "        float[] primvars:skel:jointWeights = {} (\n            elementSize = 4\n            interpolation = \"vertex\"\n        )",
+            fmt_array(mesh.joint_weights.iter().flatten().map(|&w| fmt_f(w)))
+        )?;
+        writeln!(out, "        matrix4d primvars:skel:geomBindTransform = ((1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (0, 0, 0, 1))")?;
+        writeln!(out, "        rel skel:skeleton = </Root/Skeleton>")?;
+    }
+    if texture.is_some() {
+        writeln!(out, "        rel material:binding = </Root/Materials/Material>")?;
+    }
+    writeln!(out, "    }}")?;
+
+    // Texture material
+    if let Some(texture) = texture {
+        let texture_name = format!("{}_texture.png", stem);
+        let texture_path = path.with_file_name(&texture_name);
+        image::save_buffer(
+            &texture_path,
+            &texture.pixels,
+            texture.width,
+            texture.height,
+            image::ColorType::Rgba8,
+        )?;
+        progress!("Wrote texture to {:?}", texture_path);
+        writeln!(out)?;
+        writeln!(out, "    def Scope \"Materials\"")?;
+        writeln!(out, "    {{")?;
+        writeln!(out, "        def Material \"Material\"")?;
+        writeln!(out, "        {{")?;
+        writeln!(out, "            token outputs:surface.connect = </Root/Materials/Material/Surface.outputs:surface>")?;
+        writeln!(out)?;
+        writeln!(out, "            def Shader \"Surface\"")?;
+        writeln!(out, "            {{")?;
+        writeln!(out, "                uniform token info:id = \"UsdPreviewSurface\"")?;
+        writeln!(out, "                color3f inputs:diffuseColor.connect = </Root/Materials/Material/Texture.outputs:rgb>")?;
+        writeln!(out, "                float inputs:roughness = 0.5")?;
+        writeln!(out, "                token outputs:surface")?;
+        writeln!(out, "            }}")?;
+        writeln!(out)?;
+        writeln!(out, "            def Shader \"PrimvarReader\"")?;
+        writeln!(out, "            {{")?;
+        writeln!(out, "                uniform token info:id = \"UsdPrimvarReader_float2\"")?;
+        writeln!(out, "                token inputs:varname = \"st\"")?;
+        writeln!(out, "                float2 outputs:result")?;
+        writeln!(out, "            }}")?;
+        writeln!(out)?;
+        writeln!(out, "            def Shader \"Texture\"")?;
+        writeln!(out, "            {{")?;
+        writeln!(out, "                uniform token info:id = \"UsdUVTexture\"")?;
+        writeln!(out, "                asset inputs:file = @{}@", texture_name)?;
+        writeln!(out, "                float2 inputs:st.connect = </Root/Materials/Material/PrimvarReader.outputs:result>")?;
+        writeln!(out, "                float3 outputs:rgb")?;
+        writeln!(out, "            }}")?;
+        writeln!(out, "        }}")?;
+        writeln!(out, "    }}")?;
+    }
+
+    // Skeleton and its animation
+    if let Some(skeleton) = skeleton {
+        let bind: Vec<String> = skeleton
+            .joints
+            .iter()
+            .map(|j| {
+                let ibm: Matrix4<f32> = Matrix4::from(j.inverse_bind_matrix);
+                fmt_matrix(&ibm.try_inverse().unwrap_or_else(Matrix4::identity).into())
+            })
+            .collect();
+        let rest: Vec<String> = skeleton
+            .joints
+            .iter()
+            .map(|j| {
+                let (t, r, s) = j.local_transform.decompose();
+                fmt_matrix(&animation::compose_trs(&t, &r, &s))
+            })
+            .collect();
+
+        writeln!(out)?;
+        writeln!(out, "    def Skeleton \"Skeleton\" (")?;
+        writeln!(out, "        prepend apiSchemas = [\"SkelBindingAPI\"]")?;
+        writeln!(out, "    )")?;
+        writeln!(out, "    {{")?;
+        writeln!(out, "        uniform token[] joints = {}", fmt_array(paths.iter().map(|p| format!("\"{}\"", p))))?;
+        writeln!(out, "        uniform matrix4d[] bindTransforms = {}", fmt_array(bind))?;
+        writeln!(out, "        uniform matrix4d[] restTransforms = {}", fmt_array(rest))?;
+        if clip.is_some() {
+            writeln!(out, "        rel skel:animationSource = </Root/Skeleton/Animation>")?;
+        }
+
+        if let Some(clip) = clip {
+            let joint_list = fmt_array(paths.iter().map(|p| format!("\"{}\"", p)));
+            let mut translations = String::new();
+            let mut rotations = String::new();
+            let mut scales = String::new();
+            for &t in &times {
+                let locals = animation::evaluate_animation(clip, skeleton, t);
+                let mut tr = Vec::new();
+                let mut ro = Vec::new();
+                let mut sc = Vec::new();
+                for m in &locals {
+                    let (t, r, s) = JointTransform::Matrix(*m).decompose();
+                    tr.push(fmt_tuple(&t));
+                    // USD quaternions are (real, i, j, k)
+                    ro.push(fmt_tuple(&[r[3], r[0], r[1], r[2]]));
+                    sc.push(fmt_tuple(&s));
+                }
+                let code = fmt_f(t * time_codes_per_second);
+                writeln!(translations, "                {}: {},", code, fmt_array(tr))?;
+                writeln!(rotations, "                {}: {},", code, fmt_array(ro))?;
+                writeln!(scales, "                {}: {},", code, fmt_array(sc))?;
+            }
+            let name = usd_name(clip.name.as_deref().unwrap_or(""), "Animation");
+            writeln!(out)?;
+            writeln!(out, "        def SkelAnimation \"Animation\" (")?;
+            writeln!(out, "            doc = \"{}\"", name)?;
+            writeln!(out, "        )")?;
+            writeln!(out, "        {{")?;
+            writeln!(out, "            uniform token[] joints = {}", joint_list)?;
+            writeln!(out, "            float3[] translations.timeSamples = {{\n{}            }}", translations)?;
+            writeln!(out, "            quatf[] rotations.timeSamples = {{\n{}            }}", rotations)?;
+            writeln!(out, "            half3[] scales.timeSamples = {{\n{}            }}", scales)?;
+            writeln!(out, "        }}")?;
+        }
+        writeln!(out, "    }}")?;
+    }
+
+    writeln!(out, "}}")?;
+    stdio::write(path, out)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("msh_usd_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_xforms_indexed_uvs_and_subset_materials() {
+        let dir = temp_dir("parse");
+        let path = dir.join("scene.usda");
+        std::fs::write(
+            &path,
+            r#"#usda 1.0
+(
+    metersPerUnit = 0.01
+    upAxis = "Y"
+)
+
+def Xform "World"
+{
+    double3 xformOp:translate = (100, 0, 0)
+    uniform token[] xformOpOrder = ["xformOp:translate"]
+
+    def Mesh "Quad" (
+        prepend apiSchemas = ["MaterialBindingAPI"]
+    )
+    {
+        int[] faceVertexCounts = [4, 3]
+        int[] faceVertexIndices = [0, 1, 2, 3, 1, 4, 2]
+        point3f[] points = [(0, 0, 0), (100, 0, 0), (100, 100, 0), (0, 100, 0), (200, 0, 0)]
+        texCoord2f[] primvars:st = [(0, 0), (1, 1)] (
+            interpolation = "faceVarying"
+        )
+        int[] primvars:st:indices = [0, 0, 1, 1, 0, 1, 1]
+        color3f[] primvars:displayColor = [(1, 0, 0), (0, 1, 0)] (
+            interpolation = "uniform"
+        )
+
+        def GeomSubset "Blue" (
+            prepend apiSchemas = ["MaterialBindingAPI"]
+        )
+        {
+            uniform token elementType = "face"
+            int[] indices = [1]
+            rel material:binding = </Looks/Blue>
+        }
+    }
+}
+
+def Scope "Looks"
+{
+    def Material "Blue"
+    {
+        def Shader "Surface"
+        {
+            uniform token info:id = "UsdPreviewSurface"
+            color3f inputs:diffuseColor = (0, 0, 1)
+            float inputs:opacity = 0.5
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let mesh = load_usda(&path, None).unwrap();
+        assert_eq!(mesh.positions.len(), 5);
+        assert_eq!(mesh.face_indices, vec![[0, 1, 2], [0, 2, 3], [1, 4, 2]]);
+        assert_eq!(mesh.positions[0], [1.0, 0.0, 0.0]);
+        assert_eq!(mesh.positions[2], [2.0, 1.0, 0.0]);
+        // st index 1 is (1, 1), which flips to (1, 0)
+        assert_eq!(mesh.corner_texcoords[0], [[0.0, 1.0], [0.0, 1.0], [1.0, 0.0]]);
+        assert_eq!(mesh.face_colors[0], [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(mesh.face_colors[1], [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(mesh.face_colors[2], [0.0, 0.0, 1.0, 0.5]);
+
+        assert!(load_usda(&path, Some("Missing")).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn round_trips_colors_uvs_and_skinning() {
+        let dir = temp_dir("roundtrip");
+        let path = dir.join("skinned.usda");
+
+        let joints = vec![
+            Joint {
+                index: 0,
+                node_index: 0,
+                name: Some("hip".to_string()),
+                parent: None,
+                inverse_bind_matrix: to_f32(&Matrix4::identity()),
+                local_transform: JointTransform::Decomposed {
+                    translation: [0.0, 0.0, 0.0],
+                    rotation: [0.0, 0.0, 0.0, 1.0],
+                    scale: [1.0, 1.0, 1.0],
+                },
+            },
+            Joint {
+                index: 1,
+                node_index: 1,
+                name: Some("knee".to_string()),
+                parent: Some(0),
+                inverse_bind_matrix: to_f32(&Matrix4::new_translation(&Vector3::new(0.0, -1.0, 0.0))),
+                local_transform: JointTransform::Decomposed {
+                    translation: [0.0, 1.0, 0.0],
+                    rotation: [0.0, 0.0, 0.0, 1.0],
+                    scale: [1.0, 1.0, 1.0],
+                },
+            },
+        ];
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let clip = AnimationClip {
+            name: Some("bend".to_string()),
+            channels: vec![AnimationChannel {
+                joint_index: 1,
+                property: AnimationProperty::Rotation,
+                interpolation: Interpolation::Linear,
+                keyframes: vec![
+                    Keyframe { time: 0.0, value: vec![0.0, 0.0, 0.0, 1.0], in_tangent: None, out_tangent: None },
+                    Keyframe { time: 1.0, value: vec![half, 0.0, 0.0, half], in_tangent: None, out_tangent: None },
+                ],
+            }],
+            duration: 1.0,
+        };
+        let mesh = MeshWithColors {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+            face_indices: vec![[0, 1, 2]],
+            face_colors: vec![[0.25, 0.5, 1.0, 1.0]],
+            corner_texcoords: vec![[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]],
+            skeleton: Some(Skeleton { joints }),
+            joint_indices: vec![[0, 0, 0, 0], [0, 0, 0, 0], [1, 0, 0, 0]],
+            joint_weights: vec![[1.0, 0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]],
+            animations: vec![clip],
+            ..Default::default()
+        };
+        write_usda(&mesh, &path).unwrap();
+        let loaded = load_usda(&path, None).unwrap();
+
+        assert_eq!(loaded.positions, mesh.positions);
+        assert_eq!(loaded.face_indices, mesh.face_indices);
+        assert_eq!(loaded.face_colors, mesh.face_colors);
+        assert_eq!(loaded.corner_texcoords, mesh.corner_texcoords);
+        assert_eq!(loaded.joint_indices, mesh.joint_indices);
+
+        let skeleton = loaded.skeleton.as_ref().unwrap();
+        assert_eq!(skeleton.joints.len(), 2);
+        assert_eq!(skeleton.joints[1].parent, Some(0));
+        assert_eq!(skeleton.joints[1].name.as_deref(), Some("knee"));
+        let (t, _, _) = skeleton.joints[1].local_transform.decompose();
+        assert!((t[1] - 1.0).abs() < 1e-5);
+        assert!((skeleton.joints[1].inverse_bind_matrix[3][1] + 1.0).abs() < 1e-5);
+
+        let anim = &loaded.animations[0];
+        assert!((anim.duration - 1.0).abs() < 1e-5);
+        let end = animation::evaluate_animation(anim, skeleton, 1.0);
+        let (_, r, _) = JointTransform::Matrix(end[1]).decompose();
+        assert!((r[0].abs() - half).abs() < 1e-4 && (r[3].abs() - half).abs() < 1e-4);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,120 @@
+use baby_shark::mesh::corner_table::CornerTableF;
+use std::io::Write;
+use std::path::Path;
+
+use super::loader::MeshWithColors;
+use super::transfer::transfer_attributes;
+use super::threemf::{self, ModelUnit};
+use super::{gltf_writer, obj, ply, stdio, stl, usd};
+
+/// Options controlling how meshes are written
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+    /// Write ASCII instead of binary (STL, PLY)
+    pub ascii: bool,
+    /// Unit declared in the model (3MF)
+    pub unit: ModelUnit,
+    /// Output format extension, used when writing to stdout (`-`)
+    pub format: Option<String>,
+}
+
+fn output_extension(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase())
+        .ok_or_else(|| "Output file has no extension".into())
+}
+
+/// Write a processed mesh, choosing the format from the output extension.
+///
+/// `source` is the mesh the geometry was derived from; its attributes are
+/// carried over when the output format can store them.
+pub fn write_mesh(
+    mesh: &CornerTableF,
+    source: MeshWithColors,
+    output: &Path,
+    options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut result = MeshWithColors::from_corner_table(mesh);
+    transfer_attributes(&mut result, source);
+    write_mesh_with_colors(&result, output, options)
+}
+
+/// Write a mesh with its attributes, choosing the format from the output
+/// extension, or from `options.format` when writing to stdout (`-`)
+pub fn write_mesh_with_colors(
+    mesh: &MeshWithColors,
+    output: &Path,
+    options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let to_stdout = stdio::is_std(output);
+    let extension = match &options.format {
+        Some(format) if to_stdout => format.to_lowercase(),
+        None if to_stdout => return Err("Writing to stdout (-) needs an output format; pass --out-format".into()),
+        _ => output_extension(output)?,
+    };
+    match extension.as_str() {
+        "obj" | "gltf" if to_stdout => Err(format!(
+            "{} output writes sidecar files and can't be sent to stdout; pass --out-format glb",
+            extension.to_uppercase()
+        )
+        .into()),
+        "obj" => obj::write_obj(mesh, output),
+        "stl" => {
+            let mut file = stdio::create(output)?;
+            stl::write_stl(mesh, &mut file, options.ascii)?;
+            Ok(file.flush()?)
+        }
+        "ply" => {
+            let mut file = stdio::create(output)?;
+            ply::write_ply(mesh, &mut file, options.ascii)?;
+            Ok(file.flush()?)
+        }
+        "glb" => {
+            let mut file = stdio::create(output)?;
+            gltf_writer::write_glb(mesh, &mut file)?;
+            Ok(file.flush()?)
+        }
+        "gltf" => gltf_writer::write_gltf(mesh, output),
+        // Zip archives need a seekable writer, so stdout gets a finished buffer
+        "3mf" if to_stdout => {
+            let mut buffer = std::io::Cursor::new(Vec::new());
+            threemf::write_3mf(mesh, &mut buffer, options.unit)?;
+            Ok(stdio::write(output, buffer.into_inner())?)
+        }
+        "3mf" => {
+            let file = std::io::BufWriter::new(std::fs::File::create(output)?);
+            threemf::write_3mf(mesh, file, options.unit)
+        }
+        "usda" => usd::write_usda(mesh, output),
+        ext => Err(format!("Unsupported output format: {}", ext).into()),
+    }
+}
+
+/// Write a LOD chain (finest first) as glTF linked with `MSFT_lod`, with the
+/// minimum screen coverage of each level in `coverage`
+pub fn write_lods(
+    levels: &[MeshWithColors],
+    coverage: &[f32],
+    output: &Path,
+    options: &WriteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let to_stdout = stdio::is_std(output);
+    let extension = match &options.format {
+        Some(format) if to_stdout => format.to_lowercase(),
+        None if to_stdout => "glb".to_string(),
+        _ => output_extension(output)?,
+    };
+    match extension.as_str() {
+        "glb" => {
+            let mut file = stdio::create(output)?;
+            gltf_writer::write_lod_glb(levels, coverage, &mut file)?;
+            Ok(file.flush()?)
+        }
+        "gltf" if to_stdout => {
+            Err("GLTF output writes sidecar files and can't be sent to stdout; pass --out-format glb".into())
+        }
+        "gltf" => gltf_writer::write_lod_gltf(levels, coverage, output),
+        ext => Err(format!("LOD chains are written as glTF (.glb or .gltf), not {}", ext).into()),
+    }
+}
@@ -2,7 +2,7 @@
 //! without needing a mesh.
 
 use nalgebra::{Matrix4, Quaternion, UnitQuaternion, Vector3};
-use std::path::PathBuf;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
 use winit::{
@@ -710,11 +710,11 @@ fn compute_bvh_bounds(clip: &BvhClip) -> ([f32; 3], f32) {
 
 /// Open an interactive viewer showing the BVH skeleton as animated stick figures.
 pub fn view_bvh(
-    path: &PathBuf,
+    path: &Path,
     no_vsync: bool,
     configure_state: impl FnOnce(&mut ViewerState),
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let contents = std::fs::read_to_string(path)?;
+    let contents = crate::mesh::stdio::read_to_string(path)?;
     let clip = crate::mesh::bvh::parse_bvh(&contents)
         .map_err(|e| format!("BVH parse error: {}", e))?;
 
@@ -2,12 +2,13 @@ use nalgebra as na;
 use std::path::PathBuf;
 
 use crate::mesh::animation;
-use crate::mesh::loader::load_mesh_with_colors;
+use crate::mesh::loader::{load_mesh_with_colors, LoadOptions};
+use crate::mesh::stdio;
 
 use super::{
     camera::ArcBallCamera,
     mesh_renderer::MeshRenderer,
-    render::extract_render_data,
+    render::{extract_morph_data, extract_render_data},
     state::ViewerState,
 };
 
@@ -16,7 +17,7 @@ use super::{
 pub fn render_to_file(
     input: &PathBuf,
     output: &str,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     width: u32,
     height: u32,
     z_up: bool,
@@ -29,7 +30,7 @@ pub fn render_to_file(
     configure_state: impl FnOnce(&mut ViewerState),
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Load mesh
-    let mut mesh_data = load_mesh_with_colors(input, mesh_name)?;
+    let mut mesh_data = load_mesh_with_colors(input, load_options)?;
 
     if z_up {
         mesh_data.convert_z_up_to_y_up();
@@ -78,6 +79,17 @@ pub fn render_to_file(
         mesh_renderer.set_joint_count(skel_data.joint_matrices.len() as u32);
     }
 
+    // Upload morph targets, weighted by the requested animation frame if any
+    if let Some(morph) = extract_morph_data(&mesh_data) {
+        let weights = match (animation_index, animation_time) {
+            (Some(anim_idx), Some(anim_t)) if anim_idx < mesh_data.animations.len() => {
+                animation::evaluate_morph_weights(&mesh_data.animations[anim_idx], &morph.weights, anim_t)
+            }
+            _ => morph.weights.clone(),
+        };
+        mesh_renderer.load_morph_targets(&device, &morph.deltas, &weights);
+    }
+
     // Apply animation pose if requested
     if let (Some(anim_idx), Some(anim_t)) = (animation_index, animation_time) {
         if let Some(ref skeleton) = mesh_data.skeleton {
@@ -208,21 +220,25 @@ pub fn render_to_file(
     drop(data);
     output_buffer.unmap();
 
+    save_png(output, &img_data, width, height)
+}
+
+/// Save RGBA pixels as a PNG, creating parent directories, or write it to stdout for `-`
+pub fn save_png(output: &str, data: &[u8], width: u32, height: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::path::Path::new(output);
+    if stdio::is_std(path) {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::write_buffer_with_format(&mut png, data, width, height, image::ColorType::Rgba8, image::ImageFormat::Png)?;
+        return Ok(stdio::write(path, png.into_inner())?);
+    }
+
     // Create parent directories if needed
-    if let Some(parent) = std::path::Path::new(output).parent()
+    if let Some(parent) = path.parent()
         && !parent.as_os_str().is_empty() {
             std::fs::create_dir_all(parent)?;
         }
 
-    // Save PNG
-    image::save_buffer(
-        output,
-        &img_data,
-        width,
-        height,
-        image::ColorType::Rgba8,
-    )?;
-
+    image::save_buffer(output, data, width, height, image::ColorType::Rgba8)?;
     Ok(())
 }
 
@@ -46,7 +46,7 @@ pub struct Uniforms {
     pub joint_count: u32,        // number of active joints (0 = no skinning)
     pub base_color: [f32; 4],
     pub light_direction: [f32; 3],
-    pub _pad4: f32,
+    pub morph_target_count: u32, // number of morph targets (0 = no morphing)
 }
 
 /// Mesh renderer handles rendering of 3D meshes
@@ -83,6 +83,12 @@ pub struct MeshRenderer {
     joint_palette_bind_group: wgpu::BindGroup,
     joint_count: u32,
 
+    // Morph target deltas and weights (group 3)
+    morph_bind_group_layout: wgpu::BindGroupLayout,
+    morph_bind_group: wgpu::BindGroup,
+    morph_weights_buffer: wgpu::Buffer,
+    morph_target_count: u32,
+
     // Depth texture
     depth_texture: wgpu::TextureView,
 }
@@ -186,10 +192,34 @@ impl MeshRenderer {
             }],
         });
 
+        // Create morph target bind group layout (group 3): deltas + weights storage buffers
+        let morph_storage_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let morph_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Morph Target Bind Group Layout"),
+                entries: &[morph_storage_entry(0), morph_storage_entry(1)],
+            });
+        let (morph_bind_group, morph_weights_buffer) =
+            Self::create_morph_bind_group(device, &morph_bind_group_layout, &[], &[]);
+
         // Create pipeline layout
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Mesh Pipeline Layout"),
-            bind_group_layouts: &[&bind_group_layout, &texture_bind_group_layout, &joint_palette_bind_group_layout],
+            bind_group_layouts: &[
+                &bind_group_layout,
+                &texture_bind_group_layout,
+                &joint_palette_bind_group_layout,
+                &morph_bind_group_layout,
+            ],
             push_constant_ranges: &[],
         });
 
@@ -337,6 +367,10 @@ impl MeshRenderer {
             joint_palette_buffer,
             joint_palette_bind_group,
             joint_count: 0,
+            morph_bind_group_layout,
+            morph_bind_group,
+            morph_weights_buffer,
+            morph_target_count: 0,
             depth_texture,
         }
     }
@@ -451,6 +485,58 @@ impl MeshRenderer {
         self.joint_count = count;
     }
 
+    /// Create the morph target bind group. Storage buffers may not be empty,
+    /// so both are padded to at least one element.
+    fn create_morph_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        deltas: &[[f32; 4]],
+        weights: &[f32],
+    ) -> (wgpu::BindGroup, wgpu::Buffer) {
+        let deltas: &[[f32; 4]] = if deltas.is_empty() { &[[0.0; 4]] } else { deltas };
+        let mut weight_data = weights.to_vec();
+        weight_data.resize(weights.len().max(4), 0.0);
+
+        let deltas_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Morph Delta Buffer"),
+            contents: bytemuck::cast_slice(deltas),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let weights_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Morph Weight Buffer"),
+            contents: bytemuck::cast_slice(&weight_data),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Morph Target Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: deltas_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: weights_buffer.as_entire_binding() },
+            ],
+        });
+        (bind_group, weights_buffer)
+    }
+
+    /// Upload morph target deltas and their initial weights.
+    /// `deltas` is laid out per triangle-soup vertex: `deltas[vertex * weights.len() + target]`.
+    /// Pass empty slices to disable morphing.
+    pub fn load_morph_targets(&mut self, device: &wgpu::Device, deltas: &[[f32; 4]], weights: &[f32]) {
+        let (bind_group, weights_buffer) =
+            Self::create_morph_bind_group(device, &self.morph_bind_group_layout, deltas, weights);
+        self.morph_bind_group = bind_group;
+        self.morph_weights_buffer = weights_buffer;
+        self.morph_target_count = weights.len() as u32;
+    }
+
+    /// Update the morph target weights (one per target loaded with `load_morph_targets`)
+    pub fn update_morph_weights(&self, queue: &wgpu::Queue, weights: &[f32]) {
+        let count = weights.len().min(self.morph_target_count as usize);
+        if count > 0 {
+            queue.write_buffer(&self.morph_weights_buffer, 0, bytemuck::cast_slice(&weights[..count]));
+        }
+    }
+
     /// Load mesh data with per-vertex colors and optional texture
     #[allow(clippy::too_many_arguments)]
     pub fn load_mesh(
@@ -675,7 +761,7 @@ impl MeshRenderer {
             joint_count: self.joint_count,
             base_color,
             light_direction,
-            _pad4: 0.0,
+            morph_target_count: self.morph_target_count,
         };
 
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
@@ -725,6 +811,7 @@ impl MeshRenderer {
         render_pass.set_bind_group(0, &self.bind_group, &[]);
         render_pass.set_bind_group(1, &self.texture_bind_group, &[]);
         render_pass.set_bind_group(2, &self.joint_palette_bind_group, &[]);
+        render_pass.set_bind_group(3, &self.morph_bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.vertex_buffer.as_ref().unwrap().slice(..));
         render_pass.set_index_buffer(
             self.index_buffer.as_ref().unwrap().slice(..),
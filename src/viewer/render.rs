@@ -11,7 +11,7 @@ use winit::{
 };
 
 use crate::mesh::animation::{self, AnimationClip};
-use crate::mesh::loader::load_mesh_with_colors;
+use crate::mesh::loader::{load_mesh_with_colors, LoadOptions};
 use crate::mesh::skeleton::Skeleton;
 
 use super::{
@@ -34,8 +34,10 @@ struct AnimationState {
     playing: bool,
     /// Playback speed multiplier
     speed: f32,
-    /// The skeleton used to compute joint matrices
-    skeleton: Skeleton,
+    /// The skeleton used to compute joint matrices (None for morph-only animation)
+    skeleton: Option<Skeleton>,
+    /// Default morph target weights (empty if the mesh has no morph targets)
+    morph_weights: Vec<f32>,
     /// Last frame instant for delta-time computation
     last_frame: Instant,
 }
@@ -54,6 +56,7 @@ struct ViewerApp {
     has_vertex_colors: bool,
     texture: Option<crate::mesh::loader::TextureData>,
     skeleton_data: Option<SkeletonRenderData>,
+    morph_data: Option<MorphRenderData>,
     max_dimension: f32,
     mouse_pressed_left: bool,
     mouse_pressed_right: bool,
@@ -75,6 +78,7 @@ impl ViewerApp {
         has_vertex_colors: bool,
         texture: Option<crate::mesh::loader::TextureData>,
         skeleton_data: Option<SkeletonRenderData>,
+        morph_data: Option<MorphRenderData>,
         max_dimension: f32,
         vsync: bool,
         animation: Option<AnimationState>,
@@ -93,6 +97,7 @@ impl ViewerApp {
             has_vertex_colors,
             texture,
             skeleton_data,
+            morph_data,
             max_dimension,
             mouse_pressed_left: false,
             mouse_pressed_right: false,
@@ -139,6 +144,11 @@ impl ApplicationHandler for ViewerApp {
                 mesh_renderer.set_joint_count(skel_data.joint_matrices.len() as u32);
             }
 
+            // Upload morph targets with their default weights
+            if let Some(ref morph) = self.morph_data {
+                mesh_renderer.load_morph_targets(&gpu.device, &morph.deltas, &morph.weights);
+            }
+
             // Create UI renderer
             let ui_renderer = UiRenderer::new(&gpu.device, &gpu.queue, &gpu.config);
 
@@ -333,15 +343,21 @@ impl ApplicationHandler for ViewerApp {
 
                     // Evaluate animation at the current time
                     let clip = &anim.clips[anim.current_clip];
-                    let local_transforms =
-                        animation::evaluate_animation(clip, &anim.skeleton, anim.time);
-                    let joint_matrices =
-                        anim.skeleton.compute_joint_matrices_with_pose(&local_transforms);
-
                     if let (Some(gpu), Some(mesh_renderer)) =
                         (self.gpu.as_ref(), self.mesh_renderer.as_ref())
                     {
-                        mesh_renderer.update_joint_palette(&gpu.queue, &joint_matrices);
+                        if let Some(ref skeleton) = anim.skeleton {
+                            let local_transforms =
+                                animation::evaluate_animation(clip, skeleton, anim.time);
+                            let joint_matrices =
+                                skeleton.compute_joint_matrices_with_pose(&local_transforms);
+                            mesh_renderer.update_joint_palette(&gpu.queue, &joint_matrices);
+                        }
+                        if !anim.morph_weights.is_empty() {
+                            let weights =
+                                animation::evaluate_morph_weights(clip, &anim.morph_weights, anim.time);
+                            mesh_renderer.update_morph_weights(&gpu.queue, &weights);
+                        }
                     }
                 }
 
@@ -439,13 +455,46 @@ pub struct SkeletonRenderData {
     pub joint_matrices: Vec<[[f32; 4]; 4]>,
 }
 
+/// Morph target data laid out for the GPU
+pub struct MorphRenderData {
+    /// Deltas per triangle-soup vertex: `deltas[vertex * weights.len() + target]`
+    pub deltas: Vec<[f32; 4]>,
+    /// Default weight per morph target
+    pub weights: Vec<f32>,
+}
+
+/// Extract morph target deltas in the triangle-soup order of `extract_render_data`
+pub fn extract_morph_data(mesh_data: &crate::mesh::loader::MeshWithColors) -> Option<MorphRenderData> {
+    let n = mesh_data.positions.len();
+    let targets = &mesh_data.morph_targets;
+    if targets.is_empty() || targets.iter().any(|t| t.position_deltas.len() != n) {
+        return None;
+    }
+
+    let mut deltas = Vec::with_capacity(mesh_data.face_indices.len() * 3 * targets.len());
+    for &vi in mesh_data.face_indices.iter().flatten() {
+        for target in targets {
+            let d = target.position_deltas[vi as usize];
+            deltas.push([d[0], d[1], d[2], 0.0]);
+        }
+    }
+    let mut weights = mesh_data.morph_weights.clone();
+    weights.resize(targets.len(), 0.0);
+
+    Some(MorphRenderData { deltas, weights })
+}
+
 /// Extract rendering data from MeshWithColors
 pub fn extract_render_data(
     mesh_data: &crate::mesh::loader::MeshWithColors,
     no_center: bool,
 ) -> (Vec<Vertex>, Vec<u32>, Vec<u32>, bool, f32, Option<SkeletonRenderData>) {
-    let has_vertex_colors = !mesh_data.face_colors.is_empty();
+    let has_per_vertex_colors = mesh_data.vertex_colors.len() == mesh_data.positions.len()
+        && !mesh_data.vertex_colors.is_empty();
+    let has_vertex_colors = has_per_vertex_colors || !mesh_data.face_colors.is_empty();
     let has_uvs = !mesh_data.texcoords.is_empty();
+    let has_corner_uvs = mesh_data.corner_texcoords.len() == mesh_data.face_indices.len()
+        && !mesh_data.corner_texcoords.is_empty();
     let default_color = [0.0f32; 4];
 
     // Calculate bounding box
@@ -476,15 +525,22 @@ pub fn extract_render_data(
     let mut vertex_idx = 0u32;
 
     for (face_i, tri) in mesh_data.face_indices.iter().enumerate() {
-        let color = if has_vertex_colors {
+        let face_color = if !mesh_data.face_colors.is_empty() {
             mesh_data.face_colors[face_i]
         } else {
             default_color
         };
 
-        for &vi in tri {
+        for (k, &vi) in tri.iter().enumerate() {
             let pos = mesh_data.positions[vi as usize];
-            let uv = if has_uvs {
+            let color = if has_per_vertex_colors {
+                mesh_data.vertex_colors[vi as usize]
+            } else {
+                face_color
+            };
+            let uv = if has_corner_uvs {
+                mesh_data.corner_texcoords[face_i][k]
+            } else if has_uvs {
                 mesh_data.texcoords[vi as usize]
             } else {
                 [0.0, 0.0]
@@ -526,26 +582,28 @@ pub fn extract_render_data(
     (vertices, indices, backface_indices, has_vertex_colors, max_dimension, skeleton_data)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn view_mesh_with_bvh(
     input: &PathBuf,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     no_vsync: bool,
     z_up: bool,
     bvh_path: Option<&PathBuf>,
     initial_animation: Option<&str>,
     model_scale: Option<f32>,
     no_center: bool,
+    highlight_intersections: bool,
     configure_state: impl FnOnce(&mut ViewerState),
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Loading mesh from {:?}...", input);
 
     // Load mesh with color data
-    let mut mesh_data = load_mesh_with_colors(input, mesh_name)?;
+    let mut mesh_data = load_mesh_with_colors(input, load_options)?;
 
     // If a BVH file was provided, parse it and map onto the skeleton
     if let Some(bvh_file) = bvh_path {
         if let Some(ref skeleton) = mesh_data.skeleton {
-            let bvh_contents = std::fs::read_to_string(bvh_file)?;
+            let bvh_contents = crate::mesh::stdio::read_to_string(bvh_file)?;
             let bvh_clip = crate::mesh::bvh::parse_bvh(&bvh_contents)
                 .map_err(|e| format!("BVH parse error: {}", e))?;
 
@@ -577,6 +635,12 @@ pub fn view_mesh_with_bvh(
         println!("Converted Z-up to Y-up");
     }
 
+    if highlight_intersections {
+        let pairs = crate::mesh::intersect::self_intersections(&mesh_data);
+        println!("Highlighting {} intersecting face pair(s)", pairs.len());
+        crate::mesh::intersect::highlight_faces(&mut mesh_data, &pairs);
+    }
+
     // Build CornerTableF for stats
     let mesh = mesh_data.to_corner_table()?;
     let vertex_count = mesh.count_vertices();
@@ -608,9 +672,14 @@ pub fn view_mesh_with_bvh(
     let mut state = ViewerState::for_mesh(max_dimension, stats);
     configure_state(&mut state);
 
-    // Create animation state if skeleton and animations are present
-    let animation = if !mesh_data.animations.is_empty() && mesh_data.skeleton.is_some() {
-        let skeleton = mesh_data.skeleton.take().unwrap();
+    let morph_data = extract_morph_data(&mesh_data);
+
+    // Create animation state if animations have a skeleton or morph targets to drive
+    let animation = if !mesh_data.animations.is_empty()
+        && (mesh_data.skeleton.is_some() || morph_data.is_some())
+    {
+        let skeleton = mesh_data.skeleton.take();
+        let morph_weights = morph_data.as_ref().map(|m| m.weights.clone()).unwrap_or_default();
         let clips = std::mem::take(&mut mesh_data.animations);
         let playing = !clips.is_empty();
 
@@ -656,6 +725,7 @@ pub fn view_mesh_with_bvh(
             playing,
             speed: 1.0,
             skeleton,
+            morph_weights,
             last_frame: Instant::now(),
         })
     } else {
@@ -665,7 +735,7 @@ pub fn view_mesh_with_bvh(
     // Create application
     let vsync = !no_vsync;
     let texture = mesh_data.texture;
-    let mut app = ViewerApp::new(state, vertices, indices, backface_indices, has_vertex_colors, texture, skeleton_data, max_dimension, vsync, animation, model_scale.unwrap_or(1.0));
+    let mut app = ViewerApp::new(state, vertices, indices, backface_indices, has_vertex_colors, texture, skeleton_data, morph_data, max_dimension, vsync, animation, model_scale.unwrap_or(1.0));
 
     // Create and run event loop
     let event_loop = EventLoop::new()?;
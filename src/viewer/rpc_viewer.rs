@@ -25,11 +25,11 @@ use super::{
     ui_renderer::UiRenderer,
 };
 #[cfg(feature = "remote")]
-use crate::mesh::loader::load_mesh_with_colors;
+use crate::mesh::loader::{load_mesh_with_colors, LoadOptions};
 #[cfg(feature = "remote")]
 use crate::rpc::spawn_rpc_server;
 #[cfg(feature = "remote")]
-use super::render::extract_render_data;
+use super::render::{extract_morph_data, extract_render_data};
 
 #[cfg(all(feature = "remote", feature = "renderdoc"))]
 use super::renderdoc_helper::RenderDocCapture;
@@ -141,7 +141,10 @@ impl RpcViewerApp {
                     }
                     ViewerCommand::LoadModel { path, mesh_name } => {
                         println!("Loading mesh from {:?}...", path);
-                        match load_mesh_with_colors(&path, mesh_name.as_deref()) {
+                        match load_mesh_with_colors(
+                            &path,
+                            &LoadOptions { mesh_name, ..Default::default() },
+                        ) {
                             Ok(mesh_data) => {
                                 // Update stats from CornerTableF (before moving texture out)
                                 match mesh_data.to_corner_table() {
@@ -161,6 +164,7 @@ impl RpcViewerApp {
                                 // Extract render data
                                 let (vertices, indices, backface_indices, has_vertex_colors, max_dimension, skeleton_data) =
                                     extract_render_data(&mesh_data, false);
+                                let morph_data = extract_morph_data(&mesh_data);
 
                                 self.vertices = vertices;
                                 self.indices = indices;
@@ -178,6 +182,10 @@ impl RpcViewerApp {
                                     } else {
                                         mesh_renderer.set_joint_count(0);
                                     }
+                                    match morph_data {
+                                        Some(morph) => mesh_renderer.load_morph_targets(&gpu.device, &morph.deltas, &morph.weights),
+                                        None => mesh_renderer.load_morph_targets(&gpu.device, &[], &[]),
+                                    }
                                 }
 
                                 // Update camera to frame new mesh
@@ -514,7 +522,7 @@ impl ApplicationHandler for RpcViewerApp {
 #[cfg(feature = "remote")]
 pub fn view_mesh_with_rpc(
     input: Option<&PathBuf>,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     no_vsync: bool,
     z_up: bool,
     configure_state: impl FnOnce(&mut ViewerState),
@@ -522,7 +530,7 @@ pub fn view_mesh_with_rpc(
     let (vertices, indices, backface_indices, has_vertex_colors, texture, max_dimension, stats) = if let Some(input_path) = input {
         println!("Loading mesh from {:?}...", input_path);
 
-        let mut mesh_data = load_mesh_with_colors(input_path, mesh_name)?;
+        let mut mesh_data = load_mesh_with_colors(input_path, load_options)?;
 
         if z_up {
             mesh_data.convert_z_up_to_y_up();
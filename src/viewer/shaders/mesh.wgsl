@@ -12,7 +12,7 @@ struct Uniforms {
     joint_count: u32,       // number of active joints (0 = no skinning)
     base_color: vec4<f32>,
     light_direction: vec3<f32>,
-    _pad4: f32,
+    morph_target_count: u32, // number of morph targets (0 = no morphing)
 };
 
 @group(0) @binding(0)
@@ -29,12 +29,19 @@ struct JointPalette {
 @group(2) @binding(0)
 var<uniform> joint_palette: JointPalette;
 
+// Morph target position deltas, indexed [vertex * morph_target_count + target]
+@group(3) @binding(0)
+var<storage, read> morph_deltas: array<vec4<f32>>;
+@group(3) @binding(1)
+var<storage, read> morph_weights: array<f32>;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec4<f32>,
     @location(2) texcoord: vec2<f32>,
     @location(3) joint_indices: vec4<u32>,
     @location(4) joint_weights: vec4<f32>,
+    @builtin(vertex_index) vertex_index: u32,
 };
 
 struct VertexOutput {
@@ -48,6 +55,16 @@ struct VertexOutput {
 fn vs_main(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
 
+    // Apply morph targets (blend shapes) before skinning
+    var position = in.position;
+    let base = in.vertex_index * uniforms.morph_target_count;
+    for (var t = 0u; t < uniforms.morph_target_count; t = t + 1u) {
+        let weight = morph_weights[t];
+        if weight != 0.0 {
+            position = position + weight * morph_deltas[base + t].xyz;
+        }
+    }
+
     // Apply GPU skinning when joints are present
     var local_pos: vec4<f32>;
     if uniforms.joint_count > 0u {
@@ -56,16 +73,16 @@ fn vs_main(in: VertexInput) -> VertexOutput {
             let weight = in.joint_weights[i];
             if weight > 0.0 {
                 let joint_mat = joint_palette.joints[in.joint_indices[i]];
-                skinned_pos = skinned_pos + weight * (joint_mat * vec4<f32>(in.position, 1.0));
+                skinned_pos = skinned_pos + weight * (joint_mat * vec4<f32>(position, 1.0));
             }
         }
         // If no skinning weights applied (all weights zero), use original position
         if skinned_pos.w == 0.0 {
-            skinned_pos = vec4<f32>(in.position, 1.0);
+            skinned_pos = vec4<f32>(position, 1.0);
         }
         local_pos = skinned_pos;
     } else {
-        local_pos = vec4<f32>(in.position, 1.0);
+        local_pos = vec4<f32>(position, 1.0);
     }
 
     let world_pos = uniforms.model * local_pos;
@@ -2,12 +2,12 @@ use nalgebra as na;
 use std::path::PathBuf;
 
 use crate::mesh::animation;
-use crate::mesh::loader::load_mesh_with_colors;
+use crate::mesh::loader::{load_mesh_with_colors, LoadOptions};
 
 use super::{
     camera::ArcBallCamera,
     mesh_renderer::MeshRenderer,
-    render::extract_render_data,
+    render::{extract_morph_data, extract_render_data},
     state::ViewerState,
 };
 
@@ -30,11 +30,11 @@ pub struct SpriteSheetConfig {
 pub fn render_sprite_sheet(
     input: &PathBuf,
     config: &SpriteSheetConfig,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     z_up: bool,
     configure_state: impl FnOnce(&mut ViewerState),
 ) -> Result<(Vec<u8>, u32, u32), Box<dyn std::error::Error>> {
-    let tiles = render_all_tiles(input, config, mesh_name, z_up, configure_state)?;
+    let tiles = render_all_tiles(input, config, load_options, z_up, configure_state)?;
 
     let num_cols = config.frames.len().max(1) as u32;
     let num_rows = config.angles.len().max(1) as u32;
@@ -67,11 +67,11 @@ pub fn render_frames(
     input: &PathBuf,
     output_dir: &str,
     config: &SpriteSheetConfig,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     z_up: bool,
     configure_state: impl FnOnce(&mut ViewerState),
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let tiles = render_all_tiles(input, config, mesh_name, z_up, configure_state)?;
+    let tiles = render_all_tiles(input, config, load_options, z_up, configure_state)?;
 
     std::fs::create_dir_all(output_dir)?;
 
@@ -97,7 +97,7 @@ pub fn render_frames(
 fn render_all_tiles(
     input: &PathBuf,
     config: &SpriteSheetConfig,
-    mesh_name: Option<&str>,
+    load_options: &LoadOptions,
     z_up: bool,
     configure_state: impl FnOnce(&mut ViewerState),
 ) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error>> {
@@ -105,7 +105,7 @@ fn render_all_tiles(
     let height = config.tile_height;
 
     // Load mesh
-    let mut mesh_data = load_mesh_with_colors(input, mesh_name)?;
+    let mut mesh_data = load_mesh_with_colors(input, load_options)?;
     if z_up {
         mesh_data.convert_z_up_to_y_up();
     }
@@ -165,6 +165,12 @@ fn render_all_tiles(
         mesh_renderer.set_joint_count(skel_data.joint_matrices.len() as u32);
     }
 
+    // Upload morph targets with their default weights
+    let morph_data = extract_morph_data(&mesh_data);
+    if let Some(ref morph) = morph_data {
+        mesh_renderer.load_morph_targets(&device, &morph.deltas, &morph.weights);
+    }
+
     // Set up camera (auto-position based on scaled mesh size)
     let d = max_dimension * config.model_scale * 2.5;
     let eye = na::Point3::new(d * 0.5, d * 0.3, d);
@@ -235,6 +241,13 @@ fn render_all_tiles(
                     mesh_renderer.set_joint_count(joint_matrices.len() as u32);
                 }
             }
+            if let Some(ref morph) = morph_data
+                && config.animation_index < mesh_data.animations.len()
+            {
+                let clip = &mesh_data.animations[config.animation_index];
+                let weights = animation::evaluate_morph_weights(clip, &morph.weights, frame_time);
+                mesh_renderer.update_morph_weights(&queue, &weights);
+            }
 
             // Update uniforms with this angle's model matrix
             mesh_renderer.update_uniforms(